@@ -0,0 +1,259 @@
+#ifndef GUESS_H
+#define GUESS_H
+
+/* Generated by cbindgen from src/capi.rs; do not edit. */
+
+#include <stddef.h>
+#include <stdint.h>
+
+// Result codes returned by [`guess_detect`].
+enum GuessStatus
+#if __STDC_VERSION__ >= 202311L
+  : int32_t
+#endif // __STDC_VERSION__ >= 202311L
+ {
+  // A protocol matched; `out_info` is filled in.
+  GUESS_STATUS_MATCH = 0,
+  // No enabled protocol matched.
+  GUESS_STATUS_NO_MATCH = 1,
+  // More bytes are needed for a verdict.
+  GUESS_STATUS_INCOMPLETE = 2,
+  // The detection budget ran out before a verdict.
+  GUESS_STATUS_BUDGET_EXCEEDED = 3,
+  // A required pointer was null.
+  GUESS_STATUS_INVALID_ARGUMENT = -1,
+};
+#if __STDC_VERSION__ >= 202311L
+typedef enum GuessStatus GuessStatus;
+#else
+typedef int32_t GuessStatus;
+#endif // __STDC_VERSION__ >= 202311L
+
+// Protocol identifiers with stable values across releases and feature sets.
+//
+// New protocols are only ever appended.
+enum GuessProtocol
+#if __STDC_VERSION__ >= 202311L
+  : uint32_t
+#endif // __STDC_VERSION__ >= 202311L
+ {
+  // No protocol.
+  GUESS_PROTOCOL_NONE = 0,
+  // HTTP.
+  GUESS_PROTOCOL_HTTP = 1,
+  // TLS.
+  GUESS_PROTOCOL_TLS = 2,
+  // SSH.
+  GUESS_PROTOCOL_SSH = 3,
+  // DNS.
+  GUESS_PROTOCOL_DNS = 4,
+  // QUIC.
+  GUESS_PROTOCOL_QUIC = 5,
+  // `MySQL`.
+  GUESS_PROTOCOL_MYSQL = 6,
+  // `PostgreSQL`.
+  GUESS_PROTOCOL_POSTGRES = 7,
+  // Redis.
+  GUESS_PROTOCOL_REDIS = 8,
+  // MQTT.
+  GUESS_PROTOCOL_MQTT = 9,
+  // SMTP.
+  GUESS_PROTOCOL_SMTP = 10,
+  // POP3.
+  GUESS_PROTOCOL_POP3 = 11,
+  // IMAP.
+  GUESS_PROTOCOL_IMAP = 12,
+  // FTP.
+  GUESS_PROTOCOL_FTP = 13,
+  // SMB.
+  GUESS_PROTOCOL_SMB = 14,
+  // STUN.
+  GUESS_PROTOCOL_STUN = 15,
+  // SIP.
+  GUESS_PROTOCOL_SIP = 16,
+  // RTSP.
+  GUESS_PROTOCOL_RTSP = 17,
+  // DHCP.
+  GUESS_PROTOCOL_DHCP = 18,
+  // NTP.
+  GUESS_PROTOCOL_NTP = 19,
+  // `gRPC`.
+  GUESS_PROTOCOL_GRPC = 20,
+  // NATS.
+  GUESS_PROTOCOL_NATS = 21,
+  // Git.
+  GUESS_PROTOCOL_GIT = 22,
+  // IPP.
+  GUESS_PROTOCOL_IPP = 23,
+  // JetDirect.
+  GUESS_PROTOCOL_JET_DIRECT = 24,
+  // OPC UA.
+  GUESS_PROTOCOL_OPC_UA = 25,
+  // OpenVPN.
+  GUESS_PROTOCOL_OPEN_VPN = 26,
+  // BitTorrent.
+  GUESS_PROTOCOL_BIT_TORRENT = 27,
+  // BitTorrent DHT.
+  GUESS_PROTOCOL_DHT = 28,
+  // mDNS.
+  GUESS_PROTOCOL_MDNS = 29,
+  // NetBIOS.
+  GUESS_PROTOCOL_NET_BIOS = 30,
+  // TURN.
+  GUESS_PROTOCOL_TURN = 31,
+  // TDS.
+  GUESS_PROTOCOL_TDS = 32,
+  // Riak.
+  GUESS_PROTOCOL_RIAK = 33,
+  // `MySQL` X Protocol.
+  GUESS_PROTOCOL_MYSQLX = 34,
+  // Minecraft.
+  GUESS_PROTOCOL_MINECRAFT = 35,
+  // Source engine query.
+  GUESS_PROTOCOL_SOURCE_QUERY = 36,
+  // Graphite.
+  GUESS_PROTOCOL_GRAPHITE = 37,
+  // `InfluxDB` line protocol.
+  GUESS_PROTOCOL_INFLUX = 38,
+  // Thrift.
+  GUESS_PROTOCOL_THRIFT = 39,
+  // AJP.
+  GUESS_PROTOCOL_AJP = 40,
+  // NVMe/TCP.
+  GUESS_PROTOCOL_NVME_TCP = 41,
+  // DICOM.
+  GUESS_PROTOCOL_DICOM = 42,
+  // DNSCrypt.
+  GUESS_PROTOCOL_DNS_CRYPT = 43,
+  // VXLAN.
+  GUESS_PROTOCOL_VXLAN = 44,
+  // GENEVE.
+  GUESS_PROTOCOL_GENEVE = 45,
+  // Ethereum devp2p.
+  GUESS_PROTOCOL_DEVP2P = 46,
+  // JSON-RPC.
+  GUESS_PROTOCOL_JSON_RPC = 47,
+  // MessagePack-RPC.
+  GUESS_PROTOCOL_MSGPACK_RPC = 48,
+  // LPD.
+  GUESS_PROTOCOL_LPD = 49,
+  // HTTP/3.
+  GUESS_PROTOCOL_HTTP3 = 50,
+  // gQUIC.
+  GUESS_PROTOCOL_GQUIC = 51,
+  // SPDY.
+  GUESS_PROTOCOL_SPDY = 52,
+  // RakNet.
+  GUESS_PROTOCOL_RAK_NET = 53,
+  // MMS.
+  GUESS_PROTOCOL_MMS = 54,
+  // LoRaWAN.
+  GUESS_PROTOCOL_LO_RA_WAN = 55,
+  // WebSocket.
+  GUESS_PROTOCOL_WEB_SOCKET = 56,
+  // Telnet.
+  GUESS_PROTOCOL_TELNET = 57,
+  // RDP.
+  GUESS_PROTOCOL_RDP = 58,
+  // LDAP.
+  GUESS_PROTOCOL_LDAP = 59,
+  // Kerberos.
+  GUESS_PROTOCOL_KERBEROS = 60,
+  // IRC.
+  GUESS_PROTOCOL_IRC = 61,
+  // XMPP.
+  GUESS_PROTOCOL_XMPP = 62,
+  // AMQP.
+  GUESS_PROTOCOL_AMQP = 63,
+  // Memcached.
+  GUESS_PROTOCOL_MEMCACHED = 64,
+  // SOCKS4.
+  GUESS_PROTOCOL_SOCKS4 = 65,
+  // PROXY protocol.
+  GUESS_PROTOCOL_PROXY_PROTOCOL = 66,
+  // RTP.
+  GUESS_PROTOCOL_RTP = 67,
+  // RTCP.
+  GUESS_PROTOCOL_RTCP = 68,
+  // RTMP.
+  GUESS_PROTOCOL_RTMP = 69,
+  // S7comm.
+  GUESS_PROTOCOL_S7 = 70,
+  // ClickHouse.
+  GUESS_PROTOCOL_CLICK_HOUSE = 71,
+  // Tarantool.
+  GUESS_PROTOCOL_TARANTOOL = 72,
+  // Subversion (`svnserve`).
+  GUESS_PROTOCOL_SVN = 73,
+  // ONC RPC (Sun RPC).
+  GUESS_PROTOCOL_SUN_RPC = 74,
+  // ZooKeeper.
+  GUESS_PROTOCOL_ZOOKEEPER = 75,
+  // Mumble.
+  GUESS_PROTOCOL_MUMBLE = 76,
+  // Ident.
+  GUESS_PROTOCOL_IDENT = 77,
+  // SCGI.
+  GUESS_PROTOCOL_SCGI = 78,
+  // ICAP.
+  GUESS_PROTOCOL_ICAP = 79,
+  // RELP.
+  GUESS_PROTOCOL_RELP = 80,
+  // SMPP.
+  GUESS_PROTOCOL_SMPP = 81,
+  // H.225.
+  GUESS_PROTOCOL_H225 = 82,
+};
+#if __STDC_VERSION__ >= 202311L
+typedef enum GuessProtocol GuessProtocol;
+#else
+typedef uint32_t GuessProtocol;
+#endif // __STDC_VERSION__ >= 202311L
+
+// Opaque detector handle; create with [`guess_detector_new`].
+typedef struct GuessDetector GuessDetector;
+
+// Detection result written by [`guess_detect`].
+typedef struct GuessInfo {
+  // Detected protocol, or `None`.
+  GuessProtocol protocol;
+  // Version text (not NUL-terminated), or null if unknown.
+  //
+  // Points into the caller's buffer or static storage, so it is valid for as
+  // long as the buffer passed to [`guess_detect`] is.
+  const uint8_t *version;
+  // Length of `version` in bytes.
+  size_t version_len;
+} GuessInfo;
+
+// Creates a detector with every compiled protocol enabled.
+//
+// Release it with [`guess_detector_free`].
+struct GuessDetector *guess_detector_new(void);
+
+// Releases a detector created by [`guess_detector_new`]. Null is ignored.
+//
+// # Safety
+//
+// `detector` must be null or a pointer returned by [`guess_detector_new`]
+// that has not been freed yet.
+void guess_detector_free(struct GuessDetector *detector);
+
+// Detects the protocol of `len` bytes at `buf` and writes the result to `out_info`.
+//
+// `out_info` is written for every status except `InvalidArgument`.
+//
+// # Safety
+//
+// `detector` must come from [`guess_detector_new`], `buf` must be valid for
+// `len` bytes (it may be null when `len` is 0), and `out_info` must be valid
+// for writes.
+GuessStatus guess_detect(const struct GuessDetector *detector,
+                         const uint8_t *buf,
+                         size_t len,
+                         struct GuessInfo *out_info);
+
+// Returns the lowercase name of a protocol as a static NUL-terminated string.
+const char *guess_protocol_name(GuessProtocol protocol);
+
+#endif  /* GUESS_H */
@@ -0,0 +1,804 @@
+/* src/bin/guess.rs */
+
+//! `guess` command-line tool: classifies bytes from hex, stdin, files, pcap
+//! captures, or whatever a TCP server sends first.
+
+use guess::{
+	DetectionError, DetectionResult, ProtocolDetector, ProtocolInfo, ProtocolMetadata,
+	ProtocolVersion,
+	net::classify_stream,
+	packet::{FrameDetector, PacketError, Segment, Transport},
+};
+use std::{
+	env,
+	fmt::Write as _,
+	fs,
+	io::{self, Read, Write},
+	net::{TcpStream, ToSocketAddrs},
+	process::ExitCode,
+	time::Duration,
+};
+
+/// Usage text printed for `--help` and argument errors.
+const USAGE: &str = "\
+usage: guess [--json] [--timeout MS] [--send HEX] [SOURCE]
+
+sources:
+  hex <HEX>            classify hex-encoded bytes
+  file <PATH>          classify a file (pcap captures are read per packet)
+  pcap <PATH>          classify every packet of a pcap capture
+  connect <HOST:PORT>  connect and classify what the server sends first
+  -                    classify stdin (default)
+
+options:
+  --json               print one JSON object per line
+  --timeout MS         connect/read timeout for `connect` (default 3000)
+  --send HEX           bytes to send after connecting, for client-first protocols
+  -h, --help           print this help";
+
+/// Default timeout for `connect`.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Bytes peeked from a live connection.
+const CONNECT_BUFFER: usize = 4096;
+
+/// Bytes inspected per UDP datagram in a capture (an Ethernet MTU).
+const MAX_DATAGRAM_BYTES: usize = 1500;
+
+/// Size of the pcap global header.
+const PCAP_HEADER_LEN: usize = 24;
+
+/// Size of a pcap per-record header.
+const PCAP_RECORD_LEN: usize = 16;
+
+/// pcap link type for Ethernet.
+const LINKTYPE_ETHERNET: u32 = 1;
+
+/// pcap link types carrying raw IPv4/IPv6 packets.
+const LINKTYPE_RAW: [u32; 4] = [12, 101, 228, 229];
+
+/// Where the bytes to classify come from.
+#[derive(Debug)]
+enum Source {
+	/// Standard input.
+	Stdin,
+	/// Hex-encoded bytes given on the command line.
+	Hex(String),
+	/// A file, read as a pcap capture if it starts with a pcap magic.
+	File(String),
+	/// A pcap capture.
+	Pcap(String),
+	/// A TCP server to connect to.
+	Connect(String),
+}
+
+/// Parsed command line.
+#[derive(Debug)]
+struct Args {
+	/// Input source.
+	source: Source,
+	/// Print JSON lines instead of text.
+	json: bool,
+	/// Timeout for `connect`.
+	timeout: Duration,
+	/// Bytes written after connecting.
+	send: Vec<u8>,
+}
+
+/// Result of classifying one input.
+#[derive(Debug)]
+enum Outcome<'a> {
+	/// A protocol matched, with any metadata read from the payload.
+	Match(ProtocolInfo<'a>, ProtocolMetadata<'a>),
+	/// No enabled protocol matched.
+	Unknown,
+	/// More bytes are needed for a verdict.
+	Incomplete,
+	/// The detection budget ran out before a verdict.
+	BudgetExceeded,
+	/// The server sent nothing before the timeout.
+	Silent,
+	/// The packet carries no TCP or UDP payload.
+	Skipped(PacketError),
+}
+
+impl<'a> Outcome<'a> {
+	/// Converts a detection result on `data`, reading metadata on a match.
+	fn new(result: DetectionResult<Option<ProtocolInfo<'a>>>, data: &'a [u8]) -> Self {
+		match result {
+			Ok(Some(info)) => Self::Match(info, info.protocol.metadata(data)),
+			Err(DetectionError::InsufficientData) => Self::Incomplete,
+			Err(DetectionError::BudgetExceeded) => Self::BudgetExceeded,
+			Ok(None) | Err(_) => Self::Unknown,
+		}
+	}
+}
+
+/// One line of output.
+#[derive(Debug)]
+struct Record<'a> {
+	/// Packet index, for pcap input.
+	packet: Option<usize>,
+	/// Transport header of the packet, if it has one.
+	segment: Option<Segment<'a>>,
+	/// Classification result.
+	outcome: Outcome<'a>,
+}
+
+fn main() -> ExitCode {
+	let args = match parse_args(env::args().skip(1)) {
+		Ok(Some(args)) => args,
+		Ok(None) => {
+			println!("{USAGE}");
+			return ExitCode::SUCCESS;
+		}
+		Err(message) => {
+			eprintln!("guess: {message}\n\n{USAGE}");
+			return ExitCode::from(2);
+		}
+	};
+
+	match run(&args) {
+		Ok(()) => ExitCode::SUCCESS,
+		Err(message) => {
+			eprintln!("guess: {message}");
+			ExitCode::FAILURE
+		}
+	}
+}
+
+/// Parses the command line; `Ok(None)` means help was requested.
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Args>, String> {
+	let mut json = false;
+	let mut timeout = DEFAULT_TIMEOUT;
+	let mut send = Vec::new();
+	let mut source = None;
+
+	while let Some(arg) = args.next() {
+		let mut value = |name: &str| args.next().ok_or_else(|| format!("{name} needs a value"));
+		let next = match arg.as_str() {
+			"-h" | "--help" => return Ok(None),
+			"--json" => {
+				json = true;
+				continue;
+			}
+			"--timeout" => {
+				let ms = value("--timeout")?;
+				let ms = ms.parse().map_err(|_| format!("invalid timeout `{ms}`"))?;
+				timeout = Duration::from_millis(ms);
+				continue;
+			}
+			"--send" => {
+				send = decode_hex(&value("--send")?)?;
+				continue;
+			}
+			"-" => Source::Stdin,
+			"hex" => Source::Hex(value("hex")?),
+			"file" => Source::File(value("file")?),
+			"pcap" => Source::Pcap(value("pcap")?),
+			"connect" => Source::Connect(value("connect")?),
+			other => return Err(format!("unexpected argument `{other}`")),
+		};
+		if source.replace(next).is_some() {
+			return Err("only one source may be given".into());
+		}
+	}
+
+	Ok(Some(Args {
+		source: source.unwrap_or(Source::Stdin),
+		json,
+		timeout,
+		send,
+	}))
+}
+
+/// Classifies the selected source and prints the results.
+fn run(args: &Args) -> Result<(), String> {
+	let detector = ProtocolDetector::builder().all().build();
+	let mut out = io::stdout().lock();
+	let mut emit =
+		|record: &Record<'_>| writeln!(out, "{}", render(record, args.json)).map_err(|e| e.to_string());
+
+	match &args.source {
+		Source::Stdin => {
+			let mut data = Vec::new();
+			io::stdin()
+				.read_to_end(&mut data)
+				.map_err(|e| format!("stdin: {e}"))?;
+			classify_bytes(&detector, &data, &mut emit)
+		}
+		Source::Hex(hex) => {
+			let data = decode_hex(hex)?;
+			classify_bytes(&detector, &data, &mut emit)
+		}
+		Source::File(path) => {
+			let data = fs::read(path).map_err(|e| format!("{path}: {e}"))?;
+			classify_bytes(&detector, &data, &mut emit)
+		}
+		Source::Pcap(path) => {
+			let data = fs::read(path).map_err(|e| format!("{path}: {e}"))?;
+			classify_pcap(&data, &mut emit)
+		}
+		Source::Connect(addr) => {
+			let mut buf = [0u8; CONNECT_BUFFER];
+			let outcome = classify_connect(addr, args, &mut buf)?;
+			emit(&Record {
+				packet: None,
+				segment: None,
+				outcome,
+			})
+		}
+	}
+}
+
+/// Classifies a byte buffer, or each packet if it is a pcap capture.
+fn classify_bytes<F>(detector: &ProtocolDetector, data: &[u8], emit: &mut F) -> Result<(), String>
+where
+	F: FnMut(&Record<'_>) -> Result<(), String>,
+{
+	if pcap_byte_order(data).is_some() {
+		return classify_pcap(data, emit);
+	}
+	emit(&Record {
+		packet: None,
+		segment: None,
+		outcome: Outcome::new(detector.detect_info(data), data),
+	})
+}
+
+/// Classifies every packet of a pcap capture.
+fn classify_pcap<F>(data: &[u8], emit: &mut F) -> Result<(), String>
+where
+	F: FnMut(&Record<'_>) -> Result<(), String>,
+{
+	let detector = FrameDetector::new(
+		ProtocolDetector::builder().tcp().all_tcp().build(),
+		ProtocolDetector::builder()
+			.udp()
+			// Inspect whole datagrams: HTTP/3 needs the entire client Initial.
+			.max_inspect_bytes(MAX_DATAGRAM_BYTES)
+			.dns()
+			.dhcp()
+			.ntp()
+			.http3()
+			.quic()
+			.stun()
+			.sip()
+			.rtsp()
+			.openvpn()
+			.dht()
+			.mdns()
+			.netbios()
+			.turn()
+			.source_query()
+			.graphite()
+			.influx()
+			.dnscrypt()
+			.vxlan()
+			.geneve()
+			.devp2p()
+			.gquic()
+			.raknet()
+			.lorawan()
+			.kerberos()
+			.rtp()
+			.rtcp()
+			.sunrpc()
+			.mumble()
+			.build(),
+	);
+	let (link_type, packets) = parse_pcap(data)?;
+	let raw_ip = LINKTYPE_RAW.contains(&link_type);
+	if link_type != LINKTYPE_ETHERNET && !raw_ip {
+		return Err(format!("unsupported pcap link type {link_type}"));
+	}
+
+	for (index, packet) in packets.into_iter().enumerate() {
+		let mut result = if raw_ip {
+			detector.detect_from_ip(packet)
+		} else {
+			detector.detect_from_frame(packet)
+		};
+		// Tunnel packets are followed by a record for each inner frame.
+		loop {
+			let (record, inner) = match result {
+				Ok(info) => (
+					Record {
+						packet: Some(index),
+						segment: Some(info.segment),
+						outcome: Outcome::new(info.result, info.segment.payload),
+					},
+					detector.detect_encapsulated(&info),
+				),
+				Err(err) => (
+					Record {
+						packet: Some(index),
+						segment: None,
+						outcome: Outcome::Skipped(err),
+					},
+					None,
+				),
+			};
+			emit(&record)?;
+			match inner {
+				Some(next) => result = next,
+				None => break,
+			}
+		}
+	}
+	Ok(())
+}
+
+/// Connects to `addr` and classifies the first bytes the server sends.
+fn classify_connect<'a>(addr: &str, args: &Args, buf: &'a mut [u8]) -> Result<Outcome<'a>, String> {
+	let target = addr
+		.to_socket_addrs()
+		.map_err(|e| format!("{addr}: {e}"))?
+		.next()
+		.ok_or_else(|| format!("{addr}: no addresses"))?;
+	let mut stream =
+		TcpStream::connect_timeout(&target, args.timeout).map_err(|e| format!("{addr}: {e}"))?;
+	if !args.send.is_empty() {
+		stream
+			.write_all(&args.send)
+			.map_err(|e| format!("{addr}: {e}"))?;
+	}
+
+	let detector = ProtocolDetector::builder().tcp().all_tcp().build();
+	match classify_stream(&detector, &stream, &mut *buf, args.timeout).map(|peeked| peeked.peeked) {
+		Ok(len) => {
+			let buf: &'a [u8] = buf;
+			let data = &buf[..len];
+			let info = detector.detect_info(data).ok().flatten();
+			Ok(Outcome::new(Ok(info), data))
+		}
+		Err(e) if e.kind() == io::ErrorKind::TimedOut => Ok(Outcome::Silent),
+		Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => Ok(Outcome::Incomplete),
+		Err(e) => Err(format!("{addr}: {e}")),
+	}
+}
+
+/// Splits a pcap capture into its link type and packet payloads.
+fn parse_pcap(data: &[u8]) -> Result<(u32, Vec<&[u8]>), String> {
+	let big_endian = pcap_byte_order(data).ok_or("not a pcap capture (pcapng is not supported)")?;
+	let read_u32 = |offset: usize| -> Option<u32> {
+		let bytes: [u8; 4] = data.get(offset..offset + 4)?.try_into().ok()?;
+		Some(if big_endian {
+			u32::from_be_bytes(bytes)
+		} else {
+			u32::from_le_bytes(bytes)
+		})
+	};
+
+	let link_type = read_u32(20).ok_or("truncated pcap header")?;
+	let mut packets = Vec::new();
+	let mut offset = PCAP_HEADER_LEN;
+	while offset < data.len() {
+		let captured = read_u32(offset + 8).ok_or("truncated pcap record header")? as usize;
+		let start = offset + PCAP_RECORD_LEN;
+		let packet = data
+			.get(start..start + captured)
+			.ok_or("truncated pcap record")?;
+		packets.push(packet);
+		offset = start + captured;
+	}
+	Ok((link_type, packets))
+}
+
+/// Returns `Some(big_endian)` if `data` starts with a pcap magic number.
+fn pcap_byte_order(data: &[u8]) -> Option<bool> {
+	match data.get(..4)? {
+		[0xA1, 0xB2, 0xC3, 0xD4] | [0xA1, 0xB2, 0x3C, 0x4D] => Some(true),
+		[0xD4, 0xC3, 0xB2, 0xA1] | [0x4D, 0x3C, 0xB2, 0xA1] => Some(false),
+		_ => None,
+	}
+}
+
+/// Decodes hex, ignoring whitespace, `:` separators, and a leading `0x`.
+fn decode_hex(hex: &str) -> Result<Vec<u8>, String> {
+	let hex = hex.strip_prefix("0x").unwrap_or(hex);
+	let digits: Vec<u8> = hex
+		.bytes()
+		.filter(|b| !b.is_ascii_whitespace() && *b != b':')
+		.collect();
+	if !digits.len().is_multiple_of(2) {
+		return Err("hex input has an odd number of digits".into());
+	}
+	digits
+		.chunks(2)
+		.map(|pair| {
+			let pair = std::str::from_utf8(pair).map_err(|_| "invalid hex digit".to_owned())?;
+			u8::from_str_radix(pair, 16).map_err(|_| format!("invalid hex byte `{pair}`"))
+		})
+		.collect()
+}
+
+/// Formats a record as text or as a JSON object.
+fn render(record: &Record<'_>, json: bool) -> String {
+	let (status, info) = match &record.outcome {
+		Outcome::Match(info, _) => ("match", Some(info)),
+		Outcome::Unknown => ("unknown", None),
+		Outcome::Incomplete => ("incomplete", None),
+		Outcome::BudgetExceeded => ("budget_exceeded", None),
+		Outcome::Silent => ("silent", None),
+		Outcome::Skipped(_) => ("skipped", None),
+	};
+	let protocol = info.map(|info| format!("{:?}", info.protocol).to_ascii_lowercase());
+	let version = info.and_then(|info| version_text(&info.version));
+	let metadata = match &record.outcome {
+		Outcome::Match(_, metadata) => metadata_fields(metadata),
+		_ => Vec::new(),
+	};
+
+	let mut line = String::new();
+	if json {
+		line.push('{');
+		if let Some(index) = record.packet {
+			let _ = write!(line, "\"packet\":{index},");
+		}
+		if let Some(segment) = &record.segment {
+			let _ = write!(
+				line,
+				"\"transport\":\"{}\",\"src_port\":{},\"dst_port\":{},",
+				transport_name(segment.transport),
+				segment.src_port,
+				segment.dst_port
+			);
+		}
+		let _ = write!(line, "\"status\":\"{status}\",\"protocol\":");
+		push_json_str(&mut line, protocol.as_deref());
+		line.push_str(",\"version\":");
+		push_json_str(&mut line, version.as_deref());
+		if !metadata.is_empty() {
+			line.push_str(",\"metadata\":{");
+			for (i, (key, value)) in metadata.iter().enumerate() {
+				if i > 0 {
+					line.push(',');
+				}
+				let _ = write!(line, "\"{key}\":");
+				push_json_str(&mut line, Some(value));
+			}
+			line.push('}');
+		}
+		if let Outcome::Skipped(err) = &record.outcome {
+			line.push_str(",\"error\":");
+			push_json_str(&mut line, Some(&err.to_string()));
+		}
+		line.push('}');
+	} else {
+		if let Some(index) = record.packet {
+			let _ = write!(line, "#{index} ");
+		}
+		if let Some(segment) = &record.segment {
+			let _ = write!(
+				line,
+				"{} {} -> {}: ",
+				transport_name(segment.transport),
+				segment.src_port,
+				segment.dst_port
+			);
+		}
+		match (&record.outcome, protocol, version) {
+			(_, Some(protocol), Some(version)) => {
+				let _ = write!(line, "{protocol} {version}");
+			}
+			(_, Some(protocol), None) => line.push_str(&protocol),
+			(Outcome::Skipped(err), ..) => {
+				let _ = write!(line, "skipped ({err})");
+			}
+			_ => line.push_str(status),
+		}
+		for (key, value) in &metadata {
+			let _ = write!(line, " {key}={value}");
+		}
+	}
+	line
+}
+
+/// Returns the version as display text, if known.
+fn version_text(version: &ProtocolVersion<'_>) -> Option<String> {
+	match version {
+		ProtocolVersion::Http(v)
+		| ProtocolVersion::Tls(v)
+		| ProtocolVersion::Ssh(v)
+		| ProtocolVersion::Amqp(v)
+		| ProtocolVersion::Tds(v)
+		| ProtocolVersion::Tarantool(v) => Some((*v).to_owned()),
+		ProtocolVersion::Redis(v) | ProtocolVersion::Spdy(v) => Some(v.to_string()),
+		_ => None,
+	}
+}
+
+/// Returns the metadata as key/value pairs for display.
+fn metadata_fields(metadata: &ProtocolMetadata<'_>) -> Vec<(&'static str, String)> {
+	match metadata {
+		ProtocolMetadata::OpcUa { message_type } => vec![("message_type", (*message_type).to_owned())],
+		ProtocolMetadata::BitTorrent {
+			reserved,
+			info_hash,
+		} => vec![
+			("reserved", format!("{reserved:016x}")),
+			(
+				"info_hash",
+				info_hash.iter().map(|b| format!("{b:02x}")).collect(),
+			),
+		],
+		ProtocolMetadata::Tds {
+			major,
+			minor,
+			build,
+			sub_build,
+		} => vec![("version", format!("{major}.{minor}.{build}.{sub_build}"))],
+		ProtocolMetadata::Minecraft {
+			protocol_version,
+			server_address,
+		} => {
+			let mut fields = vec![("protocol_version", protocol_version.to_string())];
+			if let Some(address) = server_address {
+				fields.push(("server_address", (*address).to_owned()));
+			}
+			fields
+		}
+		ProtocolMetadata::Thrift {
+			compact,
+			framed,
+			method,
+		} => {
+			let encoding = if *compact { "compact" } else { "binary" };
+			let mut fields = vec![
+				("encoding", encoding.to_owned()),
+				("framed", framed.to_string()),
+			];
+			if let Some(method) = method {
+				fields.push(("method", (*method).to_owned()));
+			}
+			fields
+		}
+		ProtocolMetadata::Vxlan { vni } => vec![("vni", vni.to_string())],
+		ProtocolMetadata::Geneve { vni, protocol_type } => vec![
+			("vni", vni.to_string()),
+			("protocol_type", format!("{protocol_type:#06x}")),
+		],
+		ProtocolMetadata::Gquic { version } => vec![("version", (*version).to_owned())],
+		ProtocolMetadata::WebSocket {
+			subprotocols,
+			inner,
+		} => {
+			let mut fields = vec![("upgrade", "websocket".to_owned())];
+			if let Some(subprotocols) = subprotocols {
+				fields.push(("subprotocols", (*subprotocols).to_owned()));
+			}
+			if let Some(inner) = inner {
+				fields.push(("inner", format!("{inner:?}").to_ascii_lowercase()));
+			}
+			fields
+		}
+		ProtocolMetadata::RakNet {
+			packet_id,
+			client_guid,
+		} => {
+			let mut fields = vec![("packet_id", format!("{packet_id:#04x}"))];
+			if let Some(guid) = client_guid {
+				fields.push(("client_guid", format!("{guid:016x}")));
+			}
+			fields
+		}
+		ProtocolMetadata::Rdp {
+			cookie,
+			requested_protocols,
+		} => {
+			let mut fields = Vec::new();
+			if let Some(cookie) = cookie {
+				fields.push(("cookie", (*cookie).to_owned()));
+			}
+			if let Some(protocols) = requested_protocols {
+				fields.push(("requested_protocols", format!("{protocols:#x}")));
+			}
+			fields
+		}
+		ProtocolMetadata::LoRaWan { gateway_eui } => {
+			vec![("gateway_eui", format!("{gateway_eui:016x}"))]
+		}
+		ProtocolMetadata::ProxyProtocol {
+			version,
+			header_len,
+		} => vec![
+			("version", version.to_string()),
+			("header_len", header_len.to_string()),
+		],
+		ProtocolMetadata::SunRpc {
+			program,
+			version,
+			procedure,
+		} => vec![
+			("program", program.to_string()),
+			("version", version.to_string()),
+			("procedure", procedure.to_string()),
+		],
+		ProtocolMetadata::TorSuspect { confidence } => vec![
+			("tor_suspect", "true".to_owned()),
+			("confidence", confidence.to_string()),
+		],
+		_ => Vec::new(),
+	}
+}
+
+/// Lowercase name of a transport.
+fn transport_name(transport: Transport) -> &'static str {
+	match transport {
+		Transport::Tcp => "tcp",
+		Transport::Udp => "udp",
+	}
+}
+
+/// Appends a JSON string literal, or `null`.
+fn push_json_str(out: &mut String, value: Option<&str>) {
+	let Some(value) = value else {
+		out.push_str("null");
+		return;
+	};
+	out.push('"');
+	for c in value.chars() {
+		match c {
+			'"' => out.push_str("\\\""),
+			'\\' => out.push_str("\\\\"),
+			'\n' => out.push_str("\\n"),
+			'\r' => out.push_str("\\r"),
+			'\t' => out.push_str("\\t"),
+			c if c.is_control() => {
+				let _ = write!(out, "\\u{:04x}", c as u32);
+			}
+			c => out.push(c),
+		}
+	}
+	out.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use guess::Protocol;
+
+	/// Wraps Ethernet frames in a little-endian pcap capture.
+	fn pcap(frames: &[&[u8]]) -> Vec<u8> {
+		let mut data = vec![0xD4, 0xC3, 0xB2, 0xA1, 2, 0, 4, 0];
+		data.extend_from_slice(&[0; 8]);
+		data.extend_from_slice(&65535u32.to_le_bytes());
+		data.extend_from_slice(&LINKTYPE_ETHERNET.to_le_bytes());
+		for frame in frames {
+			data.extend_from_slice(&[0; 8]);
+			data.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+			data.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+			data.extend_from_slice(frame);
+		}
+		data
+	}
+
+	/// Builds an Ethernet/IPv4/TCP frame carrying `payload`.
+	fn tcp_frame(dst_port: u16, payload: &[u8]) -> Vec<u8> {
+		let mut frame = vec![0u8; 12];
+		frame.extend_from_slice(&[0x08, 0x00]);
+		let mut ip = vec![
+			0x45, 0, 0, 0, 0, 0, 0, 0, 64, 6, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+		];
+		ip[2..4].copy_from_slice(&((40 + payload.len()) as u16).to_be_bytes());
+		frame.extend_from_slice(&ip);
+		let mut tcp = vec![0u8; 20];
+		tcp[0..2].copy_from_slice(&40000u16.to_be_bytes());
+		tcp[2..4].copy_from_slice(&dst_port.to_be_bytes());
+		tcp[12] = 0x50;
+		frame.extend_from_slice(&tcp);
+		frame.extend_from_slice(payload);
+		frame
+	}
+
+	/// Collects rendered lines for a byte buffer.
+	fn lines(data: &[u8], json: bool) -> Vec<String> {
+		let detector = ProtocolDetector::builder().all().build();
+		let mut lines = Vec::new();
+		classify_bytes(&detector, data, &mut |record: &Record<'_>| {
+			lines.push(render(record, json));
+			Ok(())
+		})
+		.unwrap();
+		lines
+	}
+
+	// ── Correct paths ──
+
+	#[test]
+	fn decode_hex_accepts_separators() {
+		assert_eq!(decode_hex("0x16 03:01").unwrap(), [0x16, 0x03, 0x01]);
+	}
+
+	#[test]
+	fn parse_args_reads_options_and_source() {
+		let args = ["--json", "--timeout", "500", "connect", "localhost:22"].map(String::from);
+		let args = parse_args(args.into_iter()).unwrap().unwrap();
+		assert!(args.json);
+		assert_eq!(args.timeout, Duration::from_millis(500));
+		assert!(matches!(args.source, Source::Connect(ref a) if a == "localhost:22"));
+	}
+
+	#[test]
+	fn raw_bytes_render_as_text_and_json() {
+		assert_eq!(lines(b"SSH-2.0-OpenSSH_9.6\r\n", false), ["ssh 2.0"]);
+		assert_eq!(
+			lines(b"SSH-2.0-OpenSSH_9.6\r\n", true),
+			[r#"{"status":"match","protocol":"ssh","version":"2.0"}"#]
+		);
+	}
+
+	#[test]
+	fn pcap_input_is_classified_per_packet() {
+		let http = tcp_frame(80, b"GET / HTTP/1.1\r\n");
+		let arp = [0u8; 42];
+		let mut arp = arp.to_vec();
+		arp[12..14].copy_from_slice(&[0x08, 0x06]);
+		let out = lines(&pcap(&[&http, &arp]), false);
+		assert_eq!(out[0], "#0 tcp 40000 -> 80: http 1.1");
+		assert_eq!(out[1], "#1 skipped (unsupported ethertype 0x0806)");
+	}
+
+	#[test]
+	#[cfg(feature = "opcua")]
+	fn metadata_is_rendered() {
+		let hello = b"HELF\x20\x00\x00\x00\x00\x00\x00\x00\x00\x00\x01\x00\x00\x00\x01\x00\
+			\x00\x00\x00\x00\x00\x00\x00\x00\xff\xff\xff\xff";
+		assert_eq!(lines(hello, false), ["opcua message_type=HEL"]);
+		assert_eq!(
+			lines(hello, true),
+			[r#"{"status":"match","protocol":"opcua","version":null,"metadata":{"message_type":"HEL"}}"#]
+		);
+	}
+
+	#[test]
+	fn json_strings_are_escaped() {
+		let mut out = String::new();
+		push_json_str(&mut out, Some("a\"b\\\u{1}"));
+		assert_eq!(out, r#""a\"b\\\u0001""#);
+	}
+
+	#[test]
+	fn connect_reports_server_banner() {
+		let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+		let addr = listener.local_addr().unwrap().to_string();
+		let server = std::thread::spawn(move || {
+			let (mut conn, _) = listener.accept().unwrap();
+			conn.write_all(b"SSH-2.0-test\r\n").unwrap();
+		});
+		let args = parse_args(["connect", &addr].map(String::from).into_iter())
+			.unwrap()
+			.unwrap();
+		let mut buf = [0u8; 64];
+		let outcome = classify_connect(&addr, &args, &mut buf).unwrap();
+		assert!(matches!(outcome, Outcome::Match(info, _) if info.protocol == Protocol::Ssh));
+		server.join().unwrap();
+	}
+
+	// ── Error paths ──
+
+	#[test]
+	fn decode_hex_rejects_bad_input() {
+		assert!(decode_hex("abc").is_err());
+		assert!(decode_hex("zz").is_err());
+	}
+
+	#[test]
+	fn parse_args_rejects_two_sources() {
+		let args = ["hex", "00", "file", "x"].map(String::from);
+		assert!(parse_args(args.into_iter()).is_err());
+	}
+
+	#[test]
+	fn truncated_pcap_is_an_error() {
+		let mut data = pcap(&[&tcp_frame(80, b"GET")]);
+		data.truncate(data.len() - 1);
+		assert!(parse_pcap(&data).is_err());
+	}
+
+	#[test]
+	fn incomplete_input_is_reported() {
+		assert_eq!(lines(b"SS", false), ["incomplete"]);
+	}
+}
@@ -0,0 +1,132 @@
+/* src/bpf.rs */
+
+use crate::{
+	ProtocolDetector,
+	signature::{self, Check, Layer, Signature},
+};
+use std::fmt::Write as _;
+
+/// Builds a libpcap filter expression that keeps packets which may start one of
+/// the detector's enabled protocols.
+///
+/// The expression compares the first bytes of the TCP or UDP payload against
+/// each protocol's fixed signature, falling back to well-known ports for
+/// protocols that have none (DNS, DHCP, NTP). It is a lossy prefilter:
+/// matching packets still need the full detector, and some packets the
+/// detector would match fail it, e.g. HTTP requests whose method is not one
+/// of the common methods the signature lists (`PROPFIND`, extension
+/// methods). Payload offsets use `tcp[]`/`udp[]`, which libpcap evaluates
+/// for IPv4 only, and TCP segments without payload never match.
+///
+/// Returns `None` if no protocols are enabled.
+#[must_use]
+pub fn filter_expression<T>(detector: &ProtocolDetector<T>) -> Option<String> {
+	let mut tcp = Vec::new();
+	let mut udp = Vec::new();
+
+	for (_, signature) in signature::enabled(detector) {
+		let (layer, clause) = match *signature {
+			Signature::Payload(layer, checks) => (layer, payload_clause(layer, checks)),
+			Signature::Port(layer, port) => (layer, format!("port {port}")),
+		};
+		let clauses = match layer {
+			Layer::Tcp => &mut tcp,
+			Layer::Udp => &mut udp,
+		};
+		if !clauses.contains(&clause) {
+			clauses.push(clause);
+		}
+	}
+
+	let parts: Vec<String> = [("tcp", tcp), ("udp", udp)]
+		.into_iter()
+		.filter(|(_, clauses)| !clauses.is_empty())
+		.map(|(name, clauses)| format!("({name} and ({}))", clauses.join(" or ")))
+		.collect();
+	(!parts.is_empty()).then(|| parts.join(" or "))
+}
+
+/// Renders the checks of one signature, joined with `and`.
+fn payload_clause(layer: Layer, checks: &[Check]) -> String {
+	let rendered: Vec<String> = checks
+		.iter()
+		.map(|check| render_check(layer, check))
+		.collect();
+	if rendered.len() == 1 {
+		rendered.concat()
+	} else {
+		format!("({})", rendered.join(" and "))
+	}
+}
+
+/// Renders a single payload comparison.
+fn render_check(layer: Layer, check: &Check) -> String {
+	let mut out = match layer {
+		Layer::Tcp if check.offset == 0 => format!("tcp[((tcp[12:1] & 0xf0) >> 2):{}]", check.len),
+		Layer::Tcp => format!(
+			"tcp[((tcp[12:1] & 0xf0) >> 2) + {}:{}]",
+			check.offset, check.len
+		),
+		Layer::Udp => format!("udp[{}:{}]", 8 + check.offset, check.len),
+	};
+	let width = usize::from(check.len) * 2;
+	let full = u32::MAX >> (32 - u32::from(check.len) * 8);
+	if check.mask != full {
+		let _ = write!(out, " & {:#0w$x}", check.mask, w = width + 2);
+	}
+	let _ = write!(out, " = {:#0w$x}", check.value, w = width + 2);
+	out
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// ── Correct paths ──
+
+	#[test]
+	#[cfg(feature = "ssh")]
+	fn single_protocol_produces_one_clause() {
+		let detector = ProtocolDetector::builder().ssh().build();
+		assert_eq!(
+			filter_expression(&detector).as_deref(),
+			Some("(tcp and (tcp[((tcp[12:1] & 0xf0) >> 2):4] = 0x5353482d))")
+		);
+	}
+
+	#[test]
+	#[cfg(all(feature = "quic", feature = "dns"))]
+	fn udp_signatures_use_masks_and_ports() {
+		let detector = ProtocolDetector::builder().quic().dns().build();
+		assert_eq!(
+			filter_expression(&detector).as_deref(),
+			Some("(tcp and (port 53)) or (udp and (port 53 or udp[8:1] & 0xc0 = 0xc0))")
+		);
+	}
+
+	#[test]
+	#[cfg(all(feature = "smtp", feature = "ftp"))]
+	fn shared_prefixes_are_emitted_once() {
+		let detector = ProtocolDetector::builder().smtp().ftp().build();
+		let filter = filter_expression(&detector).unwrap();
+		assert_eq!(filter.matches("= 0x32323020").count(), 1);
+	}
+
+	#[test]
+	#[cfg(feature = "mysql")]
+	fn multi_byte_checks_are_combined_with_and() {
+		let detector = ProtocolDetector::builder().mysql().build();
+		let filter = filter_expression(&detector).unwrap();
+		assert!(filter.contains(
+			"(tcp[((tcp[12:1] & 0xf0) >> 2) + 3:1] = 0x00 and tcp[((tcp[12:1] & 0xf0) >> 2) + 4:1] = 0x0a)"
+		));
+	}
+
+	// ── Error paths ──
+
+	#[test]
+	fn no_protocols_yields_no_filter() {
+		let detector = ProtocolDetector::builder().build();
+		assert_eq!(filter_expression(&detector), None);
+	}
+}
@@ -0,0 +1,186 @@
+/* src/budget.rs */
+use crate::DetectionError;
+#[cfg(feature = "alloc")]
+use crate::Protocol;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+/// Upper bound on the work of a single detection call.
+///
+/// Once any limit is reached, detection stops with
+/// [`DetectionError::BudgetExceeded`]. Limits are checked before each probe,
+/// so a probe that has started always finishes, but no further probe runs.
+/// The default budget is unlimited.
+///
+/// ```
+/// use guess::{DetectionBudget, ProtocolDetector};
+///
+/// let detector = ProtocolDetector::builder()
+///     .all()
+///     .budget(DetectionBudget::new().probes(8).bytes(4096))
+///     .build();
+/// let _ = detector.detect(b"GET / HTTP/1.1\r\n");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DetectionBudget {
+	/// Most probes run per call.
+	probes: Option<usize>,
+	/// Most bytes handed to probes per call, summed over all probes.
+	bytes: Option<usize>,
+	/// Longest wall-clock time spent per call.
+	#[cfg(feature = "std")]
+	time: Option<Duration>,
+}
+
+impl DetectionBudget {
+	/// Creates an unlimited budget.
+	#[must_use]
+	pub const fn new() -> Self {
+		Self {
+			probes: None,
+			bytes: None,
+			#[cfg(feature = "std")]
+			time: None,
+		}
+	}
+
+	/// Limits the number of protocol probes run per call.
+	#[must_use]
+	pub const fn probes(mut self, probes: usize) -> Self {
+		self.probes = Some(probes);
+		self
+	}
+
+	/// Limits the bytes handed to probes per call, summed over all probes.
+	///
+	/// Each probe is charged the inspected window, so this bounds the work of
+	/// linear parsers regardless of how many protocols are enabled.
+	#[must_use]
+	pub const fn bytes(mut self, bytes: usize) -> Self {
+		self.bytes = Some(bytes);
+		self
+	}
+
+	/// Limits the wall-clock time spent per call.
+	#[cfg(feature = "std")]
+	#[must_use]
+	pub const fn time(mut self, time: Duration) -> Self {
+		self.time = Some(time);
+		self
+	}
+
+	/// Starts metering one detection call.
+	pub(crate) fn start(&self) -> Meter {
+		Meter {
+			probes: self.probes,
+			bytes: self.bytes,
+			#[cfg(feature = "std")]
+			deadline: self.time.and_then(|time| Instant::now().checked_add(time)),
+			#[cfg(feature = "alloc")]
+			pending: None,
+		}
+	}
+}
+
+/// Remaining budget of one detection call.
+#[derive(Debug)]
+#[allow(dead_code)]
+pub(crate) struct Meter {
+	/// Probes left.
+	probes: Option<usize>,
+	/// Bytes left.
+	bytes: Option<usize>,
+	/// Instant after which no further probe starts.
+	#[cfg(feature = "std")]
+	deadline: Option<Instant>,
+	/// Probed protocols that returned incomplete, in probe order, when
+	/// recording.
+	#[cfg(feature = "alloc")]
+	pending: Option<Vec<Protocol>>,
+}
+
+impl Meter {
+	/// Charges one probe over `len` bytes.
+	///
+	/// # Errors
+	///
+	/// Returns `BudgetExceeded` if the probe would exceed any limit.
+	#[allow(dead_code)]
+	pub(crate) fn charge(&mut self, len: usize) -> Result<(), DetectionError> {
+		if let Some(probes) = &mut self.probes {
+			*probes = probes
+				.checked_sub(1)
+				.ok_or(DetectionError::BudgetExceeded)?;
+		}
+		if let Some(bytes) = &mut self.bytes {
+			*bytes = bytes
+				.checked_sub(len)
+				.ok_or(DetectionError::BudgetExceeded)?;
+		}
+		#[cfg(feature = "std")]
+		if self
+			.deadline
+			.is_some_and(|deadline| Instant::now() >= deadline)
+		{
+			return Err(DetectionError::BudgetExceeded);
+		}
+		Ok(())
+	}
+
+	/// Starts recording the protocols whose probes return incomplete.
+	#[cfg(feature = "alloc")]
+	pub(crate) fn record_pending(mut self) -> Self {
+		self.pending = Some(Vec::new());
+		self
+	}
+
+	/// Records `protocol` as undecided if recording.
+	#[cfg(feature = "alloc")]
+	pub(crate) fn note_pending(&mut self, protocol: Protocol) {
+		if let Some(pending) = &mut self.pending {
+			pending.push(protocol);
+		}
+	}
+
+	/// Returns the recorded undecided protocols.
+	#[cfg(feature = "alloc")]
+	pub(crate) fn take_pending(&mut self) -> Vec<Protocol> {
+		self.pending.take().unwrap_or_default()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_unlimited_budget_never_runs_out() {
+		let mut meter = DetectionBudget::new().start();
+		for _ in 0..1000 {
+			assert_eq!(meter.charge(usize::MAX), Ok(()));
+		}
+	}
+
+	#[test]
+	fn test_probe_and_byte_limits() {
+		let mut meter = DetectionBudget::new().probes(2).start();
+		assert_eq!(meter.charge(64), Ok(()));
+		assert_eq!(meter.charge(64), Ok(()));
+		assert_eq!(meter.charge(64), Err(DetectionError::BudgetExceeded));
+
+		let mut meter = DetectionBudget::new().bytes(100).start();
+		assert_eq!(meter.charge(64), Ok(()));
+		assert_eq!(meter.charge(64), Err(DetectionError::BudgetExceeded));
+	}
+
+	#[test]
+	#[cfg(feature = "std")]
+	fn test_time_limit() {
+		let mut meter = DetectionBudget::new().time(Duration::ZERO).start();
+		assert_eq!(meter.charge(1), Err(DetectionError::BudgetExceeded));
+		let mut meter = DetectionBudget::new().time(Duration::from_secs(60)).start();
+		assert_eq!(meter.charge(1), Ok(()));
+	}
+}
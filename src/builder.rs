@@ -1,8 +1,10 @@
 /* src/builder.rs */
 use crate::{
-	ProtocolDetector, Unknown,
+	DetectionBudget, Protocol, ProtocolDetector, Unknown,
 	detector::{ProtocolSet, ProtocolVersionSet},
 };
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
 use core::marker::PhantomData;
 
 /// Builder for `ProtocolDetector`.
@@ -12,8 +14,13 @@ pub struct ProtocolDetectorBuilder<Transport = Unknown> {
 	pub(crate) enabled: ProtocolSet,
 	/// Maximum bytes to inspect.
 	pub(crate) max_inspect_bytes: usize,
+	/// Work limit per detection call.
+	pub(crate) budget: DetectionBudget,
 	/// Expected protocol versions.
 	pub(crate) expected_versions: ProtocolVersionSet,
+	/// Custom detection order carried over from an existing detector.
+	#[cfg(feature = "alloc")]
+	pub(crate) priority_order: Option<Vec<Protocol>>,
 	/// Transport type marker.
 	pub(crate) _transport: PhantomData<Transport>,
 }
@@ -24,7 +31,10 @@ impl<T> ProtocolDetectorBuilder<T> {
 		Self {
 			enabled: ProtocolSet::default(),
 			max_inspect_bytes: crate::MAX_INSPECT_BYTES,
+			budget: DetectionBudget::new(),
 			expected_versions: ProtocolVersionSet::default(),
+			#[cfg(feature = "alloc")]
+			priority_order: None,
 			_transport: PhantomData,
 		}
 	}
@@ -109,6 +119,258 @@ impl<T> ProtocolDetectorBuilder<T> {
 		{
 			self.enabled.stun = true;
 		}
+		#[cfg(feature = "grpc")]
+		{
+			self.enabled.grpc = true;
+		}
+		#[cfg(feature = "nats")]
+		{
+			self.enabled.nats = true;
+		}
+		#[cfg(feature = "git")]
+		{
+			self.enabled.git = true;
+		}
+		#[cfg(feature = "ipp")]
+		{
+			self.enabled.ipp = true;
+		}
+		#[cfg(feature = "jetdirect")]
+		{
+			self.enabled.jetdirect = true;
+		}
+		#[cfg(feature = "opcua")]
+		{
+			self.enabled.opcua = true;
+		}
+		#[cfg(feature = "openvpn")]
+		{
+			self.enabled.openvpn = true;
+		}
+		#[cfg(feature = "bittorrent")]
+		{
+			self.enabled.bittorrent = true;
+		}
+		#[cfg(feature = "dht")]
+		{
+			self.enabled.dht = true;
+		}
+		#[cfg(feature = "mdns")]
+		{
+			self.enabled.mdns = true;
+		}
+		#[cfg(feature = "netbios")]
+		{
+			self.enabled.netbios = true;
+		}
+		#[cfg(feature = "turn")]
+		{
+			self.enabled.turn = true;
+		}
+		#[cfg(feature = "tds")]
+		{
+			self.enabled.tds = true;
+		}
+		#[cfg(feature = "riak")]
+		{
+			self.enabled.riak = true;
+		}
+		#[cfg(feature = "mysqlx")]
+		{
+			self.enabled.mysqlx = true;
+		}
+		#[cfg(feature = "minecraft")]
+		{
+			self.enabled.minecraft = true;
+		}
+		#[cfg(feature = "source_query")]
+		{
+			self.enabled.source_query = true;
+		}
+		#[cfg(feature = "graphite")]
+		{
+			self.enabled.graphite = true;
+		}
+		#[cfg(feature = "influx")]
+		{
+			self.enabled.influx = true;
+		}
+		#[cfg(feature = "thrift")]
+		{
+			self.enabled.thrift = true;
+		}
+		#[cfg(feature = "ajp")]
+		{
+			self.enabled.ajp = true;
+		}
+		#[cfg(feature = "nvme_tcp")]
+		{
+			self.enabled.nvme_tcp = true;
+		}
+		#[cfg(feature = "dicom")]
+		{
+			self.enabled.dicom = true;
+		}
+		#[cfg(feature = "dnscrypt")]
+		{
+			self.enabled.dnscrypt = true;
+		}
+		#[cfg(feature = "vxlan")]
+		{
+			self.enabled.vxlan = true;
+		}
+		#[cfg(feature = "geneve")]
+		{
+			self.enabled.geneve = true;
+		}
+		#[cfg(feature = "devp2p")]
+		{
+			self.enabled.devp2p = true;
+		}
+		#[cfg(feature = "jsonrpc")]
+		{
+			self.enabled.jsonrpc = true;
+		}
+		#[cfg(feature = "msgpack_rpc")]
+		{
+			self.enabled.msgpack_rpc = true;
+		}
+		#[cfg(feature = "lpd")]
+		{
+			self.enabled.lpd = true;
+		}
+		#[cfg(feature = "http3")]
+		{
+			self.enabled.http3 = true;
+		}
+		#[cfg(feature = "gquic")]
+		{
+			self.enabled.gquic = true;
+		}
+		#[cfg(feature = "spdy")]
+		{
+			self.enabled.spdy = true;
+		}
+		#[cfg(feature = "raknet")]
+		{
+			self.enabled.raknet = true;
+		}
+		#[cfg(feature = "mms")]
+		{
+			self.enabled.mms = true;
+		}
+		#[cfg(feature = "lorawan")]
+		{
+			self.enabled.lorawan = true;
+		}
+		#[cfg(feature = "websocket")]
+		{
+			self.enabled.websocket = true;
+		}
+		#[cfg(feature = "telnet")]
+		{
+			self.enabled.telnet = true;
+		}
+		#[cfg(feature = "rdp")]
+		{
+			self.enabled.rdp = true;
+		}
+		#[cfg(feature = "ldap")]
+		{
+			self.enabled.ldap = true;
+		}
+		#[cfg(feature = "kerberos")]
+		{
+			self.enabled.kerberos = true;
+		}
+		#[cfg(feature = "irc")]
+		{
+			self.enabled.irc = true;
+		}
+		#[cfg(feature = "xmpp")]
+		{
+			self.enabled.xmpp = true;
+		}
+		#[cfg(feature = "amqp")]
+		{
+			self.enabled.amqp = true;
+		}
+		#[cfg(feature = "memcached")]
+		{
+			self.enabled.memcached = true;
+		}
+		#[cfg(feature = "socks4")]
+		{
+			self.enabled.socks4 = true;
+		}
+		#[cfg(feature = "proxyproto")]
+		{
+			self.enabled.proxyproto = true;
+		}
+		#[cfg(feature = "rtp")]
+		{
+			self.enabled.rtp = true;
+		}
+		#[cfg(feature = "rtcp")]
+		{
+			self.enabled.rtcp = true;
+		}
+		#[cfg(feature = "rtmp")]
+		{
+			self.enabled.rtmp = true;
+		}
+		#[cfg(feature = "s7")]
+		{
+			self.enabled.s7 = true;
+		}
+		#[cfg(feature = "clickhouse")]
+		{
+			self.enabled.clickhouse = true;
+		}
+		#[cfg(feature = "tarantool")]
+		{
+			self.enabled.tarantool = true;
+		}
+		#[cfg(feature = "svn")]
+		{
+			self.enabled.svn = true;
+		}
+		#[cfg(feature = "sunrpc")]
+		{
+			self.enabled.sunrpc = true;
+		}
+		#[cfg(feature = "zookeeper")]
+		{
+			self.enabled.zookeeper = true;
+		}
+		#[cfg(feature = "mumble")]
+		{
+			self.enabled.mumble = true;
+		}
+		#[cfg(feature = "ident")]
+		{
+			self.enabled.ident = true;
+		}
+		#[cfg(feature = "scgi")]
+		{
+			self.enabled.scgi = true;
+		}
+		#[cfg(feature = "icap")]
+		{
+			self.enabled.icap = true;
+		}
+		#[cfg(feature = "relp")]
+		{
+			self.enabled.relp = true;
+		}
+		#[cfg(feature = "smpp")]
+		{
+			self.enabled.smpp = true;
+		}
+		#[cfg(feature = "h225")]
+		{
+			self.enabled.h225 = true;
+		}
 		self
 	}
 
@@ -148,13 +410,30 @@ impl<T> ProtocolDetectorBuilder<T> {
 		self
 	}
 
+	/// Sets the maximum bytes to inspect.
+	#[must_use]
+	pub fn max_inspect_bytes(mut self, bytes: usize) -> Self {
+		self.max_inspect_bytes = bytes;
+		self
+	}
+
+	/// Sets the work limit per detection call.
+	#[must_use]
+	pub fn budget(mut self, budget: DetectionBudget) -> Self {
+		self.budget = budget;
+		self
+	}
+
 	/// Switches to TCP transport.
 	#[must_use]
 	pub fn tcp(self) -> ProtocolDetectorBuilder<crate::Tcp> {
 		ProtocolDetectorBuilder {
 			enabled: self.enabled,
 			max_inspect_bytes: self.max_inspect_bytes,
+			budget: self.budget,
 			expected_versions: self.expected_versions,
+			#[cfg(feature = "alloc")]
+			priority_order: self.priority_order,
 			_transport: PhantomData,
 		}
 	}
@@ -165,7 +444,10 @@ impl<T> ProtocolDetectorBuilder<T> {
 		ProtocolDetectorBuilder {
 			enabled: self.enabled,
 			max_inspect_bytes: self.max_inspect_bytes,
+			budget: self.budget,
 			expected_versions: self.expected_versions,
+			#[cfg(feature = "alloc")]
+			priority_order: self.priority_order,
 			_transport: PhantomData,
 		}
 	}
@@ -230,24 +512,328 @@ impl<T> ProtocolDetectorBuilder<T> {
 		{
 			self.enabled.rtsp = true;
 		}
+		#[cfg(feature = "grpc")]
+		{
+			self.enabled.grpc = true;
+		}
+		#[cfg(feature = "nats")]
+		{
+			self.enabled.nats = true;
+		}
+		#[cfg(feature = "git")]
+		{
+			self.enabled.git = true;
+		}
+		#[cfg(feature = "ipp")]
+		{
+			self.enabled.ipp = true;
+		}
+		#[cfg(feature = "jetdirect")]
+		{
+			self.enabled.jetdirect = true;
+		}
+		#[cfg(feature = "opcua")]
+		{
+			self.enabled.opcua = true;
+		}
+		#[cfg(feature = "openvpn")]
+		{
+			self.enabled.openvpn = true;
+		}
+		#[cfg(feature = "bittorrent")]
+		{
+			self.enabled.bittorrent = true;
+		}
+		#[cfg(feature = "netbios")]
+		{
+			self.enabled.netbios = true;
+		}
+		#[cfg(feature = "tds")]
+		{
+			self.enabled.tds = true;
+		}
+		#[cfg(feature = "riak")]
+		{
+			self.enabled.riak = true;
+		}
+		#[cfg(feature = "mysqlx")]
+		{
+			self.enabled.mysqlx = true;
+		}
+		#[cfg(feature = "minecraft")]
+		{
+			self.enabled.minecraft = true;
+		}
+		#[cfg(feature = "graphite")]
+		{
+			self.enabled.graphite = true;
+		}
+		#[cfg(feature = "thrift")]
+		{
+			self.enabled.thrift = true;
+		}
+		#[cfg(feature = "ajp")]
+		{
+			self.enabled.ajp = true;
+		}
+		#[cfg(feature = "nvme_tcp")]
+		{
+			self.enabled.nvme_tcp = true;
+		}
+		#[cfg(feature = "dicom")]
+		{
+			self.enabled.dicom = true;
+		}
+		#[cfg(feature = "devp2p")]
+		{
+			self.enabled.devp2p = true;
+		}
+		#[cfg(feature = "jsonrpc")]
+		{
+			self.enabled.jsonrpc = true;
+		}
+		#[cfg(feature = "msgpack_rpc")]
+		{
+			self.enabled.msgpack_rpc = true;
+		}
+		#[cfg(feature = "lpd")]
+		{
+			self.enabled.lpd = true;
+		}
+		#[cfg(feature = "spdy")]
+		{
+			self.enabled.spdy = true;
+		}
+		#[cfg(feature = "mms")]
+		{
+			self.enabled.mms = true;
+		}
+		#[cfg(feature = "websocket")]
+		{
+			self.enabled.websocket = true;
+		}
+		#[cfg(feature = "telnet")]
+		{
+			self.enabled.telnet = true;
+		}
+		#[cfg(feature = "rdp")]
+		{
+			self.enabled.rdp = true;
+		}
+		#[cfg(feature = "ldap")]
+		{
+			self.enabled.ldap = true;
+		}
+		#[cfg(feature = "kerberos")]
+		{
+			self.enabled.kerberos = true;
+		}
+		#[cfg(feature = "irc")]
+		{
+			self.enabled.irc = true;
+		}
+		#[cfg(feature = "xmpp")]
+		{
+			self.enabled.xmpp = true;
+		}
+		#[cfg(feature = "amqp")]
+		{
+			self.enabled.amqp = true;
+		}
+		#[cfg(feature = "memcached")]
+		{
+			self.enabled.memcached = true;
+		}
+		#[cfg(feature = "socks4")]
+		{
+			self.enabled.socks4 = true;
+		}
+		#[cfg(feature = "proxyproto")]
+		{
+			self.enabled.proxyproto = true;
+		}
+		#[cfg(feature = "rtmp")]
+		{
+			self.enabled.rtmp = true;
+		}
+		#[cfg(feature = "s7")]
+		{
+			self.enabled.s7 = true;
+		}
+		#[cfg(feature = "clickhouse")]
+		{
+			self.enabled.clickhouse = true;
+		}
+		#[cfg(feature = "tarantool")]
+		{
+			self.enabled.tarantool = true;
+		}
+		#[cfg(feature = "svn")]
+		{
+			self.enabled.svn = true;
+		}
+		#[cfg(feature = "sunrpc")]
+		{
+			self.enabled.sunrpc = true;
+		}
+		#[cfg(feature = "zookeeper")]
+		{
+			self.enabled.zookeeper = true;
+		}
+		#[cfg(feature = "mumble")]
+		{
+			self.enabled.mumble = true;
+		}
+		#[cfg(feature = "ident")]
+		{
+			self.enabled.ident = true;
+		}
+		#[cfg(feature = "scgi")]
+		{
+			self.enabled.scgi = true;
+		}
+		#[cfg(feature = "icap")]
+		{
+			self.enabled.icap = true;
+		}
+		#[cfg(feature = "relp")]
+		{
+			self.enabled.relp = true;
+		}
+		#[cfg(feature = "smpp")]
+		{
+			self.enabled.smpp = true;
+		}
+		#[cfg(feature = "h225")]
+		{
+			self.enabled.h225 = true;
+		}
 		self
 	}
 
-	#[cfg(feature = "http")]
-	/// Enables HTTP.
+	/// Enables mail protocols (SMTP, IMAP, POP3).
 	#[must_use]
-	pub fn http(mut self) -> Self {
-		self.enabled.http = true;
+	#[allow(unused_mut)]
+	pub fn mail(mut self) -> Self {
+		#[cfg(feature = "smtp")]
+		{
+			self.enabled.smtp = true;
+		}
+		#[cfg(feature = "imap")]
+		{
+			self.enabled.imap = true;
+		}
+		#[cfg(feature = "pop3")]
+		{
+			self.enabled.pop3 = true;
+		}
 		self
 	}
 
-	#[cfg(feature = "tls")]
-	/// Enables TLS.
+	/// Enables voice and video protocols (SIP, H.225, RTSP, STUN, TURN, RTP,
+	/// RTCP, Mumble).
 	#[must_use]
-	pub fn tls(mut self) -> Self {
-		self.enabled.tls = true;
-		self
-	}
+	#[allow(unused_mut)]
+	pub fn voip(mut self) -> Self {
+		#[cfg(feature = "sip")]
+		{
+			self.enabled.sip = true;
+		}
+		#[cfg(feature = "h225")]
+		{
+			self.enabled.h225 = true;
+		}
+		#[cfg(feature = "rtsp")]
+		{
+			self.enabled.rtsp = true;
+		}
+		#[cfg(feature = "stun")]
+		{
+			self.enabled.stun = true;
+		}
+		#[cfg(feature = "turn")]
+		{
+			self.enabled.turn = true;
+		}
+		#[cfg(feature = "rtp")]
+		{
+			self.enabled.rtp = true;
+		}
+		#[cfg(feature = "rtcp")]
+		{
+			self.enabled.rtcp = true;
+		}
+		#[cfg(feature = "mumble")]
+		{
+			self.enabled.mumble = true;
+		}
+		self
+	}
+
+	/// Enables IoT messaging protocols (MQTT, LoRaWAN).
+	#[must_use]
+	#[allow(unused_mut)]
+	pub fn iot(mut self) -> Self {
+		#[cfg(feature = "mqtt")]
+		{
+			self.enabled.mqtt = true;
+		}
+		#[cfg(feature = "lorawan")]
+		{
+			self.enabled.lorawan = true;
+		}
+		self
+	}
+
+	/// Enables Windows networking protocols (SMB, NetBIOS, RDP).
+	#[must_use]
+	#[allow(unused_mut)]
+	pub fn windows(mut self) -> Self {
+		#[cfg(feature = "smb")]
+		{
+			self.enabled.smb = true;
+		}
+		#[cfg(feature = "netbios")]
+		{
+			self.enabled.netbios = true;
+		}
+		#[cfg(feature = "rdp")]
+		{
+			self.enabled.rdp = true;
+		}
+		self
+	}
+
+	/// Enables media streaming protocols (RTSP, RTMP).
+	#[must_use]
+	#[allow(unused_mut)]
+	pub fn streaming(mut self) -> Self {
+		#[cfg(feature = "rtsp")]
+		{
+			self.enabled.rtsp = true;
+		}
+		#[cfg(feature = "rtmp")]
+		{
+			self.enabled.rtmp = true;
+		}
+		self
+	}
+
+	#[cfg(feature = "http")]
+	/// Enables HTTP.
+	#[must_use]
+	pub fn http(mut self) -> Self {
+		self.enabled.http = true;
+		self
+	}
+
+	#[cfg(feature = "tls")]
+	/// Enables TLS.
+	#[must_use]
+	pub fn tls(mut self) -> Self {
+		self.enabled.tls = true;
+		self
+	}
 
 	#[cfg(feature = "ssh")]
 	/// Enables SSH.
@@ -385,14 +971,558 @@ impl<T> ProtocolDetectorBuilder<T> {
 		self
 	}
 
+	#[cfg(feature = "grpc")]
+	/// Enables `gRPC`.
+	#[must_use]
+	pub fn grpc(mut self) -> Self {
+		self.enabled.grpc = true;
+		self
+	}
+
+	#[cfg(feature = "nats")]
+	/// Enables NATS.
+	#[must_use]
+	pub fn nats(mut self) -> Self {
+		self.enabled.nats = true;
+		self
+	}
+
+	#[cfg(feature = "git")]
+	/// Enables Git.
+	#[must_use]
+	pub fn git(mut self) -> Self {
+		self.enabled.git = true;
+		self
+	}
+
+	#[cfg(feature = "ipp")]
+	/// Enables IPP.
+	#[must_use]
+	pub fn ipp(mut self) -> Self {
+		self.enabled.ipp = true;
+		self
+	}
+
+	#[cfg(feature = "jetdirect")]
+	/// Enables JetDirect.
+	#[must_use]
+	pub fn jetdirect(mut self) -> Self {
+		self.enabled.jetdirect = true;
+		self
+	}
+
+	#[cfg(feature = "opcua")]
+	/// Enables OPC UA.
+	#[must_use]
+	pub fn opcua(mut self) -> Self {
+		self.enabled.opcua = true;
+		self
+	}
+
+	#[cfg(feature = "openvpn")]
+	/// Enables OpenVPN.
+	#[must_use]
+	pub fn openvpn(mut self) -> Self {
+		self.enabled.openvpn = true;
+		self
+	}
+
+	#[cfg(feature = "bittorrent")]
+	/// Enables BitTorrent.
+	#[must_use]
+	pub fn bittorrent(mut self) -> Self {
+		self.enabled.bittorrent = true;
+		self
+	}
+
+	#[cfg(feature = "dht")]
+	/// Enables BitTorrent DHT.
+	#[must_use]
+	pub fn dht(mut self) -> Self {
+		self.enabled.dht = true;
+		self
+	}
+
+	#[cfg(feature = "mdns")]
+	/// Enables mDNS.
+	#[must_use]
+	pub fn mdns(mut self) -> Self {
+		self.enabled.mdns = true;
+		self
+	}
+
+	#[cfg(feature = "netbios")]
+	/// Enables NetBIOS.
+	#[must_use]
+	pub fn netbios(mut self) -> Self {
+		self.enabled.netbios = true;
+		self
+	}
+
+	#[cfg(feature = "turn")]
+	/// Enables TURN.
+	#[must_use]
+	pub fn turn(mut self) -> Self {
+		self.enabled.turn = true;
+		self
+	}
+
+	#[cfg(feature = "tds")]
+	/// Enables TDS.
+	#[must_use]
+	pub fn tds(mut self) -> Self {
+		self.enabled.tds = true;
+		self
+	}
+
+	#[cfg(feature = "riak")]
+	/// Enables Riak.
+	#[must_use]
+	pub fn riak(mut self) -> Self {
+		self.enabled.riak = true;
+		self
+	}
+
+	#[cfg(feature = "mysqlx")]
+	/// Enables `MySQL` X Protocol.
+	#[must_use]
+	pub fn mysqlx(mut self) -> Self {
+		self.enabled.mysqlx = true;
+		self
+	}
+
+	#[cfg(feature = "minecraft")]
+	/// Enables Minecraft.
+	#[must_use]
+	pub fn minecraft(mut self) -> Self {
+		self.enabled.minecraft = true;
+		self
+	}
+
+	#[cfg(feature = "source_query")]
+	/// Enables Source engine query.
+	#[must_use]
+	pub fn source_query(mut self) -> Self {
+		self.enabled.source_query = true;
+		self
+	}
+
+	#[cfg(feature = "graphite")]
+	/// Enables Graphite.
+	#[must_use]
+	pub fn graphite(mut self) -> Self {
+		self.enabled.graphite = true;
+		self
+	}
+
+	#[cfg(feature = "influx")]
+	/// Enables `InfluxDB` line protocol.
+	#[must_use]
+	pub fn influx(mut self) -> Self {
+		self.enabled.influx = true;
+		self
+	}
+
+	#[cfg(feature = "thrift")]
+	/// Enables Thrift.
+	#[must_use]
+	pub fn thrift(mut self) -> Self {
+		self.enabled.thrift = true;
+		self
+	}
+
+	#[cfg(feature = "ajp")]
+	/// Enables AJP.
+	#[must_use]
+	pub fn ajp(mut self) -> Self {
+		self.enabled.ajp = true;
+		self
+	}
+
+	#[cfg(feature = "nvme_tcp")]
+	/// Enables NVMe/TCP.
+	#[must_use]
+	pub fn nvme_tcp(mut self) -> Self {
+		self.enabled.nvme_tcp = true;
+		self
+	}
+
+	#[cfg(feature = "dicom")]
+	/// Enables DICOM.
+	#[must_use]
+	pub fn dicom(mut self) -> Self {
+		self.enabled.dicom = true;
+		self
+	}
+
+	#[cfg(feature = "dnscrypt")]
+	/// Enables DNSCrypt.
+	#[must_use]
+	pub fn dnscrypt(mut self) -> Self {
+		self.enabled.dnscrypt = true;
+		self
+	}
+
+	#[cfg(feature = "vxlan")]
+	/// Enables VXLAN.
+	#[must_use]
+	pub fn vxlan(mut self) -> Self {
+		self.enabled.vxlan = true;
+		self
+	}
+
+	#[cfg(feature = "geneve")]
+	/// Enables GENEVE.
+	#[must_use]
+	pub fn geneve(mut self) -> Self {
+		self.enabled.geneve = true;
+		self
+	}
+
+	#[cfg(feature = "devp2p")]
+	/// Enables Ethereum devp2p.
+	///
+	/// RLPx handshakes are matched on their first three bytes, a heuristic
+	/// since everything after the size is encrypted. discv4 packets are
+	/// matched on their whole layout, with the packet type at offset 97, so
+	/// they need a [`max_inspect_bytes`](Self::max_inspect_bytes) covering the
+	/// datagram (up to 1280 bytes).
+	#[must_use]
+	pub fn devp2p(mut self) -> Self {
+		self.enabled.devp2p = true;
+		self
+	}
+
+	#[cfg(feature = "jsonrpc")]
+	/// Enables JSON-RPC.
+	#[must_use]
+	pub fn jsonrpc(mut self) -> Self {
+		self.enabled.jsonrpc = true;
+		self
+	}
+
+	#[cfg(feature = "msgpack_rpc")]
+	/// Enables MessagePack-RPC.
+	#[must_use]
+	pub fn msgpack_rpc(mut self) -> Self {
+		self.enabled.msgpack_rpc = true;
+		self
+	}
+
+	#[cfg(feature = "lpd")]
+	/// Enables LPD.
+	#[must_use]
+	pub fn lpd(mut self) -> Self {
+		self.enabled.lpd = true;
+		self
+	}
+
+	#[cfg(feature = "http3")]
+	/// Enables HTTP/3.
+	#[must_use]
+	pub fn http3(mut self) -> Self {
+		self.enabled.http3 = true;
+		self
+	}
+
+	#[cfg(feature = "gquic")]
+	/// Enables gQUIC.
+	#[must_use]
+	pub fn gquic(mut self) -> Self {
+		self.enabled.gquic = true;
+		self
+	}
+
+	#[cfg(feature = "spdy")]
+	/// Enables SPDY.
+	#[must_use]
+	pub fn spdy(mut self) -> Self {
+		self.enabled.spdy = true;
+		self
+	}
+
+	#[cfg(feature = "raknet")]
+	/// Enables RakNet.
+	#[must_use]
+	pub fn raknet(mut self) -> Self {
+		self.enabled.raknet = true;
+		self
+	}
+
+	#[cfg(feature = "mms")]
+	/// Enables MMS.
+	#[must_use]
+	pub fn mms(mut self) -> Self {
+		self.enabled.mms = true;
+		self
+	}
+
+	#[cfg(feature = "lorawan")]
+	/// Enables LoRaWAN.
+	#[must_use]
+	pub fn lorawan(mut self) -> Self {
+		self.enabled.lorawan = true;
+		self
+	}
+
+	#[cfg(feature = "websocket")]
+	/// Enables WebSocket.
+	#[must_use]
+	pub fn websocket(mut self) -> Self {
+		self.enabled.websocket = true;
+		self
+	}
+
+	#[cfg(feature = "telnet")]
+	/// Enables Telnet.
+	#[must_use]
+	pub fn telnet(mut self) -> Self {
+		self.enabled.telnet = true;
+		self
+	}
+
+	#[cfg(feature = "rdp")]
+	/// Enables RDP.
+	#[must_use]
+	pub fn rdp(mut self) -> Self {
+		self.enabled.rdp = true;
+		self
+	}
+
+	#[cfg(feature = "ldap")]
+	/// Enables LDAP.
+	#[must_use]
+	pub fn ldap(mut self) -> Self {
+		self.enabled.ldap = true;
+		self
+	}
+
+	#[cfg(feature = "kerberos")]
+	/// Enables Kerberos.
+	#[must_use]
+	pub fn kerberos(mut self) -> Self {
+		self.enabled.kerberos = true;
+		self
+	}
+
+	#[cfg(feature = "irc")]
+	/// Enables IRC.
+	#[must_use]
+	pub fn irc(mut self) -> Self {
+		self.enabled.irc = true;
+		self
+	}
+
+	#[cfg(feature = "xmpp")]
+	/// Enables XMPP.
+	#[must_use]
+	pub fn xmpp(mut self) -> Self {
+		self.enabled.xmpp = true;
+		self
+	}
+
+	#[cfg(feature = "amqp")]
+	/// Enables AMQP.
+	#[must_use]
+	pub fn amqp(mut self) -> Self {
+		self.enabled.amqp = true;
+		self
+	}
+
+	#[cfg(feature = "memcached")]
+	/// Enables Memcached.
+	#[must_use]
+	pub fn memcached(mut self) -> Self {
+		self.enabled.memcached = true;
+		self
+	}
+
+	#[cfg(feature = "socks4")]
+	/// Enables SOCKS4.
+	#[must_use]
+	pub fn socks4(mut self) -> Self {
+		self.enabled.socks4 = true;
+		self
+	}
+
+	#[cfg(feature = "proxyproto")]
+	/// Enables PROXY protocol.
+	#[must_use]
+	pub fn proxyproto(mut self) -> Self {
+		self.enabled.proxyproto = true;
+		self
+	}
+
+	#[cfg(feature = "rtp")]
+	/// Enables RTP.
+	#[must_use]
+	pub fn rtp(mut self) -> Self {
+		self.enabled.rtp = true;
+		self
+	}
+
+	#[cfg(feature = "rtcp")]
+	/// Enables RTCP.
+	#[must_use]
+	pub fn rtcp(mut self) -> Self {
+		self.enabled.rtcp = true;
+		self
+	}
+
+	#[cfg(feature = "rtmp")]
+	/// Enables RTMP.
+	#[must_use]
+	pub fn rtmp(mut self) -> Self {
+		self.enabled.rtmp = true;
+		self
+	}
+
+	#[cfg(feature = "s7")]
+	/// Enables S7comm.
+	#[must_use]
+	pub fn s7(mut self) -> Self {
+		self.enabled.s7 = true;
+		self
+	}
+
+	#[cfg(feature = "clickhouse")]
+	/// Enables ClickHouse.
+	#[must_use]
+	pub fn clickhouse(mut self) -> Self {
+		self.enabled.clickhouse = true;
+		self
+	}
+
+	#[cfg(feature = "tarantool")]
+	/// Enables Tarantool.
+	#[must_use]
+	pub fn tarantool(mut self) -> Self {
+		self.enabled.tarantool = true;
+		self
+	}
+
+	#[cfg(feature = "svn")]
+	/// Enables Subversion (`svnserve`).
+	#[must_use]
+	pub fn svn(mut self) -> Self {
+		self.enabled.svn = true;
+		self
+	}
+
+	#[cfg(feature = "sunrpc")]
+	/// Enables ONC RPC (Sun RPC).
+	#[must_use]
+	pub fn sunrpc(mut self) -> Self {
+		self.enabled.sunrpc = true;
+		self
+	}
+
+	#[cfg(feature = "zookeeper")]
+	/// Enables ZooKeeper.
+	#[must_use]
+	pub fn zookeeper(mut self) -> Self {
+		self.enabled.zookeeper = true;
+		self
+	}
+
+	#[cfg(feature = "mumble")]
+	/// Enables Mumble.
+	#[must_use]
+	pub fn mumble(mut self) -> Self {
+		self.enabled.mumble = true;
+		self
+	}
+
+	#[cfg(feature = "ident")]
+	/// Enables Ident.
+	#[must_use]
+	pub fn ident(mut self) -> Self {
+		self.enabled.ident = true;
+		self
+	}
+
+	#[cfg(feature = "scgi")]
+	/// Enables SCGI.
+	#[must_use]
+	pub fn scgi(mut self) -> Self {
+		self.enabled.scgi = true;
+		self
+	}
+
+	#[cfg(feature = "icap")]
+	/// Enables ICAP.
+	#[must_use]
+	pub fn icap(mut self) -> Self {
+		self.enabled.icap = true;
+		self
+	}
+
+	#[cfg(feature = "relp")]
+	/// Enables RELP.
+	#[must_use]
+	pub fn relp(mut self) -> Self {
+		self.enabled.relp = true;
+		self
+	}
+
+	#[cfg(feature = "smpp")]
+	/// Enables SMPP.
+	#[must_use]
+	pub fn smpp(mut self) -> Self {
+		self.enabled.smpp = true;
+		self
+	}
+
+	#[cfg(feature = "h225")]
+	/// Enables H.225.
+	#[must_use]
+	pub fn h225(mut self) -> Self {
+		self.enabled.h225 = true;
+		self
+	}
+
+	/// Disables a protocol enabled earlier, e.g. `all().without(Protocol::Redis)`.
+	#[must_use]
+	pub fn without(mut self, protocol: Protocol) -> Self {
+		self.enabled.set(protocol, false);
+		self
+	}
+
+	/// Disables every protocol in `protocols`.
+	#[must_use]
+	pub fn exclude(mut self, protocols: &[Protocol]) -> Self {
+		for protocol in protocols {
+			self.enabled.set(*protocol, false);
+		}
+		self
+	}
+
 	/// Builds the detector.
+	///
+	/// A custom order taken over by [`ProtocolDetector::to_builder`] is kept:
+	/// disabled protocols are dropped from it and newly enabled ones are
+	/// appended.
 	#[must_use]
 	pub fn build(self) -> ProtocolDetector<T> {
+		#[cfg(feature = "alloc")]
+		let priority_order = self.priority_order.map(|order| {
+			let mut order: Vec<Protocol> = order
+				.into_iter()
+				.filter(|protocol| self.enabled.contains(*protocol))
+				.collect();
+			for protocol in Protocol::ALL {
+				if self.enabled.contains(*protocol) && !order.contains(protocol) {
+					order.push(*protocol);
+				}
+			}
+			order
+		});
 		ProtocolDetector {
 			enabled: self.enabled,
-			#[cfg(feature = "std")]
-			priority_order: None,
+			#[cfg(feature = "alloc")]
+			priority_order,
 			max_inspect_bytes: self.max_inspect_bytes,
+			budget: self.budget,
 			expected_versions: self.expected_versions,
 			_transport: self._transport,
 		}
@@ -416,6 +1546,36 @@ mod tests {
 		assert_eq!(detector.detect(data).unwrap(), Some(Protocol::Http));
 	}
 
+	#[test]
+	#[cfg(feature = "http")]
+	fn max_inspect_bytes_limits_window() {
+		let detector = ProtocolDetectorBuilder::<Unknown>::new()
+			.http()
+			.max_inspect_bytes(3)
+			.build();
+		assert_eq!(
+			detector.detect(b"GET / HTTP/1.1\r\n"),
+			Err(DetectionError::InsufficientData)
+		);
+	}
+
+	#[test]
+	#[cfg(feature = "devp2p")]
+	fn devp2p_discv4_needs_window_covering_datagram() {
+		let mut ping = [0u8; 98 + 20];
+		ping[96] = 0x01;
+		ping[97] = 0x01;
+		ping[98] = 0xC0 + 19;
+		ping[99] = 0x04;
+		let detector = ProtocolDetectorBuilder::<Unknown>::new().devp2p().build();
+		assert_eq!(detector.detect(&ping), Ok(None));
+		let detector = ProtocolDetectorBuilder::<Unknown>::new()
+			.devp2p()
+			.max_inspect_bytes(1280)
+			.build();
+		assert_eq!(detector.detect(&ping), Ok(Some(Protocol::Devp2p)));
+	}
+
 	#[test]
 	#[cfg(feature = "http")]
 	fn all_build_detects_http() {
@@ -438,6 +1598,43 @@ mod tests {
 		);
 	}
 
+	#[test]
+	#[cfg(all(feature = "http", feature = "ssh", feature = "redis"))]
+	fn without_disables_selected_protocols() {
+		let detector = ProtocolDetectorBuilder::<Unknown>::new()
+			.all()
+			.without(Protocol::Redis)
+			.exclude(&[Protocol::Ssh])
+			.build();
+		let redis = detector.detect(b"*1\r\n$4\r\nPING\r\n");
+		assert_ne!(redis.ok().flatten(), Some(Protocol::Redis));
+		let ssh = detector.detect(b"SSH-2.0-OpenSSH_8.9\r\n");
+		assert_ne!(ssh.ok().flatten(), Some(Protocol::Ssh));
+		assert_eq!(
+			detector.detect(b"GET / HTTP/1.1\r\n").unwrap(),
+			Some(Protocol::Http)
+		);
+	}
+
+	#[test]
+	#[cfg(all(feature = "smtp", feature = "http"))]
+	fn mail_preset_enables_only_mail_protocols() {
+		let detector = ProtocolDetectorBuilder::<Unknown>::new().mail().build();
+		assert_eq!(
+			detector.detect(b"EHLO client.example.com\r\n").unwrap(),
+			Some(Protocol::Smtp)
+		);
+		assert_eq!(detector.detect(b"GET / HTTP/1.1\r\n").unwrap(), None);
+	}
+
+	#[test]
+	#[cfg(feature = "sip")]
+	fn voip_preset_detects_sip() {
+		let detector = ProtocolDetectorBuilder::<Unknown>::new().voip().build();
+		let data = b"INVITE sip:bob@example.com SIP/2.0\r\n";
+		assert_eq!(detector.detect(data).unwrap(), Some(Protocol::Sip));
+	}
+
 	#[test]
 	#[cfg(feature = "http")]
 	fn tcp_marker_compiles() {
@@ -0,0 +1,648 @@
+/* src/capi.rs */
+
+#![allow(unsafe_code)]
+
+use crate::{DetectionError, Protocol, ProtocolDetector, ProtocolVersion};
+use core::{ffi::c_char, ptr, slice};
+
+/// Opaque detector handle; create with [`guess_detector_new`].
+#[derive(Debug)]
+pub struct GuessDetector {
+	/// Detector with every compiled protocol enabled.
+	inner: ProtocolDetector,
+}
+
+/// Protocol identifiers with stable values across releases and feature sets.
+///
+/// New protocols are only ever appended.
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuessProtocol {
+	/// No protocol.
+	None = 0,
+	/// HTTP.
+	Http = 1,
+	/// TLS.
+	Tls = 2,
+	/// SSH.
+	Ssh = 3,
+	/// DNS.
+	Dns = 4,
+	/// QUIC.
+	Quic = 5,
+	/// `MySQL`.
+	Mysql = 6,
+	/// `PostgreSQL`.
+	Postgres = 7,
+	/// Redis.
+	Redis = 8,
+	/// MQTT.
+	Mqtt = 9,
+	/// SMTP.
+	Smtp = 10,
+	/// POP3.
+	Pop3 = 11,
+	/// IMAP.
+	Imap = 12,
+	/// FTP.
+	Ftp = 13,
+	/// SMB.
+	Smb = 14,
+	/// STUN.
+	Stun = 15,
+	/// SIP.
+	Sip = 16,
+	/// RTSP.
+	Rtsp = 17,
+	/// DHCP.
+	Dhcp = 18,
+	/// NTP.
+	Ntp = 19,
+	/// `gRPC`.
+	Grpc = 20,
+	/// NATS.
+	Nats = 21,
+	/// Git.
+	Git = 22,
+	/// IPP.
+	Ipp = 23,
+	/// JetDirect.
+	JetDirect = 24,
+	/// OPC UA.
+	OpcUa = 25,
+	/// OpenVPN.
+	OpenVpn = 26,
+	/// BitTorrent.
+	BitTorrent = 27,
+	/// BitTorrent DHT.
+	Dht = 28,
+	/// mDNS.
+	Mdns = 29,
+	/// NetBIOS.
+	NetBios = 30,
+	/// TURN.
+	Turn = 31,
+	/// TDS.
+	Tds = 32,
+	/// Riak.
+	Riak = 33,
+	/// `MySQL` X Protocol.
+	Mysqlx = 34,
+	/// Minecraft.
+	Minecraft = 35,
+	/// Source engine query.
+	SourceQuery = 36,
+	/// Graphite.
+	Graphite = 37,
+	/// `InfluxDB` line protocol.
+	Influx = 38,
+	/// Thrift.
+	Thrift = 39,
+	/// AJP.
+	Ajp = 40,
+	/// NVMe/TCP.
+	NvmeTcp = 41,
+	/// DICOM.
+	Dicom = 42,
+	/// DNSCrypt.
+	DnsCrypt = 43,
+	/// VXLAN.
+	Vxlan = 44,
+	/// GENEVE.
+	Geneve = 45,
+	/// Ethereum devp2p.
+	Devp2p = 46,
+	/// JSON-RPC.
+	JsonRpc = 47,
+	/// MessagePack-RPC.
+	MsgpackRpc = 48,
+	/// LPD.
+	Lpd = 49,
+	/// HTTP/3.
+	Http3 = 50,
+	/// gQUIC.
+	Gquic = 51,
+	/// SPDY.
+	Spdy = 52,
+	/// RakNet.
+	RakNet = 53,
+	/// MMS.
+	Mms = 54,
+	/// LoRaWAN.
+	LoRaWan = 55,
+	/// WebSocket.
+	WebSocket = 56,
+	/// Telnet.
+	Telnet = 57,
+	/// RDP.
+	Rdp = 58,
+	/// LDAP.
+	Ldap = 59,
+	/// Kerberos.
+	Kerberos = 60,
+	/// IRC.
+	Irc = 61,
+	/// XMPP.
+	Xmpp = 62,
+	/// AMQP.
+	Amqp = 63,
+	/// Memcached.
+	Memcached = 64,
+	/// SOCKS4.
+	Socks4 = 65,
+	/// PROXY protocol.
+	ProxyProtocol = 66,
+	/// RTP.
+	Rtp = 67,
+	/// RTCP.
+	Rtcp = 68,
+	/// RTMP.
+	Rtmp = 69,
+	/// S7comm.
+	S7 = 70,
+	/// ClickHouse.
+	ClickHouse = 71,
+	/// Tarantool.
+	Tarantool = 72,
+	/// Subversion (`svnserve`).
+	Svn = 73,
+	/// ONC RPC (Sun RPC).
+	SunRpc = 74,
+	/// ZooKeeper.
+	Zookeeper = 75,
+	/// Mumble.
+	Mumble = 76,
+	/// Ident.
+	Ident = 77,
+	/// SCGI.
+	Scgi = 78,
+	/// ICAP.
+	Icap = 79,
+	/// RELP.
+	Relp = 80,
+	/// SMPP.
+	Smpp = 81,
+	/// H.225.
+	H225 = 82,
+}
+
+impl From<Protocol> for GuessProtocol {
+	fn from(protocol: Protocol) -> Self {
+		match protocol {
+			#[cfg(feature = "http")]
+			Protocol::Http => Self::Http,
+			#[cfg(feature = "tls")]
+			Protocol::Tls => Self::Tls,
+			#[cfg(feature = "ssh")]
+			Protocol::Ssh => Self::Ssh,
+			#[cfg(feature = "dns")]
+			Protocol::Dns => Self::Dns,
+			#[cfg(feature = "quic")]
+			Protocol::Quic => Self::Quic,
+			#[cfg(feature = "mysql")]
+			Protocol::Mysql => Self::Mysql,
+			#[cfg(feature = "postgres")]
+			Protocol::Postgres => Self::Postgres,
+			#[cfg(feature = "redis")]
+			Protocol::Redis => Self::Redis,
+			#[cfg(feature = "mqtt")]
+			Protocol::Mqtt => Self::Mqtt,
+			#[cfg(feature = "smtp")]
+			Protocol::Smtp => Self::Smtp,
+			#[cfg(feature = "pop3")]
+			Protocol::Pop3 => Self::Pop3,
+			#[cfg(feature = "imap")]
+			Protocol::Imap => Self::Imap,
+			#[cfg(feature = "ftp")]
+			Protocol::Ftp => Self::Ftp,
+			#[cfg(feature = "smb")]
+			Protocol::Smb => Self::Smb,
+			#[cfg(feature = "stun")]
+			Protocol::Stun => Self::Stun,
+			#[cfg(feature = "sip")]
+			Protocol::Sip => Self::Sip,
+			#[cfg(feature = "rtsp")]
+			Protocol::Rtsp => Self::Rtsp,
+			#[cfg(feature = "dhcp")]
+			Protocol::Dhcp => Self::Dhcp,
+			#[cfg(feature = "ntp")]
+			Protocol::Ntp => Self::Ntp,
+			#[cfg(feature = "grpc")]
+			Protocol::Grpc => Self::Grpc,
+			#[cfg(feature = "nats")]
+			Protocol::Nats => Self::Nats,
+			#[cfg(feature = "git")]
+			Protocol::Git => Self::Git,
+			#[cfg(feature = "ipp")]
+			Protocol::Ipp => Self::Ipp,
+			#[cfg(feature = "jetdirect")]
+			Protocol::JetDirect => Self::JetDirect,
+			#[cfg(feature = "opcua")]
+			Protocol::OpcUa => Self::OpcUa,
+			#[cfg(feature = "openvpn")]
+			Protocol::OpenVpn => Self::OpenVpn,
+			#[cfg(feature = "bittorrent")]
+			Protocol::BitTorrent => Self::BitTorrent,
+			#[cfg(feature = "dht")]
+			Protocol::Dht => Self::Dht,
+			#[cfg(feature = "mdns")]
+			Protocol::Mdns => Self::Mdns,
+			#[cfg(feature = "netbios")]
+			Protocol::NetBios => Self::NetBios,
+			#[cfg(feature = "turn")]
+			Protocol::Turn => Self::Turn,
+			#[cfg(feature = "tds")]
+			Protocol::Tds => Self::Tds,
+			#[cfg(feature = "riak")]
+			Protocol::Riak => Self::Riak,
+			#[cfg(feature = "mysqlx")]
+			Protocol::Mysqlx => Self::Mysqlx,
+			#[cfg(feature = "minecraft")]
+			Protocol::Minecraft => Self::Minecraft,
+			#[cfg(feature = "source_query")]
+			Protocol::SourceQuery => Self::SourceQuery,
+			#[cfg(feature = "graphite")]
+			Protocol::Graphite => Self::Graphite,
+			#[cfg(feature = "influx")]
+			Protocol::Influx => Self::Influx,
+			#[cfg(feature = "thrift")]
+			Protocol::Thrift => Self::Thrift,
+			#[cfg(feature = "ajp")]
+			Protocol::Ajp => Self::Ajp,
+			#[cfg(feature = "nvme_tcp")]
+			Protocol::NvmeTcp => Self::NvmeTcp,
+			#[cfg(feature = "dicom")]
+			Protocol::Dicom => Self::Dicom,
+			#[cfg(feature = "dnscrypt")]
+			Protocol::DnsCrypt => Self::DnsCrypt,
+			#[cfg(feature = "vxlan")]
+			Protocol::Vxlan => Self::Vxlan,
+			#[cfg(feature = "geneve")]
+			Protocol::Geneve => Self::Geneve,
+			#[cfg(feature = "devp2p")]
+			Protocol::Devp2p => Self::Devp2p,
+			#[cfg(feature = "jsonrpc")]
+			Protocol::JsonRpc => Self::JsonRpc,
+			#[cfg(feature = "msgpack_rpc")]
+			Protocol::MsgpackRpc => Self::MsgpackRpc,
+			#[cfg(feature = "lpd")]
+			Protocol::Lpd => Self::Lpd,
+			#[cfg(feature = "http3")]
+			Protocol::Http3 => Self::Http3,
+			#[cfg(feature = "gquic")]
+			Protocol::Gquic => Self::Gquic,
+			#[cfg(feature = "spdy")]
+			Protocol::Spdy => Self::Spdy,
+			#[cfg(feature = "raknet")]
+			Protocol::RakNet => Self::RakNet,
+			#[cfg(feature = "mms")]
+			Protocol::Mms => Self::Mms,
+			#[cfg(feature = "lorawan")]
+			Protocol::LoRaWan => Self::LoRaWan,
+			#[cfg(feature = "websocket")]
+			Protocol::WebSocket => Self::WebSocket,
+			#[cfg(feature = "telnet")]
+			Protocol::Telnet => Self::Telnet,
+			#[cfg(feature = "rdp")]
+			Protocol::Rdp => Self::Rdp,
+			#[cfg(feature = "ldap")]
+			Protocol::Ldap => Self::Ldap,
+			#[cfg(feature = "kerberos")]
+			Protocol::Kerberos => Self::Kerberos,
+			#[cfg(feature = "irc")]
+			Protocol::Irc => Self::Irc,
+			#[cfg(feature = "xmpp")]
+			Protocol::Xmpp => Self::Xmpp,
+			#[cfg(feature = "amqp")]
+			Protocol::Amqp => Self::Amqp,
+			#[cfg(feature = "memcached")]
+			Protocol::Memcached => Self::Memcached,
+			#[cfg(feature = "socks4")]
+			Protocol::Socks4 => Self::Socks4,
+			#[cfg(feature = "proxyproto")]
+			Protocol::ProxyProtocol => Self::ProxyProtocol,
+			#[cfg(feature = "rtp")]
+			Protocol::Rtp => Self::Rtp,
+			#[cfg(feature = "rtcp")]
+			Protocol::Rtcp => Self::Rtcp,
+			#[cfg(feature = "rtmp")]
+			Protocol::Rtmp => Self::Rtmp,
+			#[cfg(feature = "s7")]
+			Protocol::S7 => Self::S7,
+			#[cfg(feature = "clickhouse")]
+			Protocol::ClickHouse => Self::ClickHouse,
+			#[cfg(feature = "tarantool")]
+			Protocol::Tarantool => Self::Tarantool,
+			#[cfg(feature = "svn")]
+			Protocol::Svn => Self::Svn,
+			#[cfg(feature = "sunrpc")]
+			Protocol::SunRpc => Self::SunRpc,
+			#[cfg(feature = "zookeeper")]
+			Protocol::Zookeeper => Self::Zookeeper,
+			#[cfg(feature = "mumble")]
+			Protocol::Mumble => Self::Mumble,
+			#[cfg(feature = "ident")]
+			Protocol::Ident => Self::Ident,
+			#[cfg(feature = "scgi")]
+			Protocol::Scgi => Self::Scgi,
+			#[cfg(feature = "icap")]
+			Protocol::Icap => Self::Icap,
+			#[cfg(feature = "relp")]
+			Protocol::Relp => Self::Relp,
+			#[cfg(feature = "smpp")]
+			Protocol::Smpp => Self::Smpp,
+			#[cfg(feature = "h225")]
+			Protocol::H225 => Self::H225,
+			#[allow(unreachable_patterns)]
+			_ => Self::None,
+		}
+	}
+}
+
+/// Result codes returned by [`guess_detect`].
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuessStatus {
+	/// A protocol matched; `out_info` is filled in.
+	Match = 0,
+	/// No enabled protocol matched.
+	NoMatch = 1,
+	/// More bytes are needed for a verdict.
+	Incomplete = 2,
+	/// The detection budget ran out before a verdict.
+	BudgetExceeded = 3,
+	/// A required pointer was null.
+	InvalidArgument = -1,
+}
+
+/// Detection result written by [`guess_detect`].
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct GuessInfo {
+	/// Detected protocol, or `None`.
+	pub protocol: GuessProtocol,
+	/// Version text (not NUL-terminated), or null if unknown.
+	///
+	/// Points into the caller's buffer or static storage, so it is valid for as
+	/// long as the buffer passed to [`guess_detect`] is.
+	pub version: *const u8,
+	/// Length of `version` in bytes.
+	pub version_len: usize,
+}
+
+/// Creates a detector with every compiled protocol enabled.
+///
+/// Release it with [`guess_detector_free`].
+#[unsafe(no_mangle)]
+pub extern "C" fn guess_detector_new() -> *mut GuessDetector {
+	let inner = ProtocolDetector::builder().all().build();
+	Box::into_raw(Box::new(GuessDetector { inner }))
+}
+
+/// Releases a detector created by [`guess_detector_new`]. Null is ignored.
+///
+/// # Safety
+///
+/// `detector` must be null or a pointer returned by [`guess_detector_new`]
+/// that has not been freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn guess_detector_free(detector: *mut GuessDetector) {
+	if !detector.is_null() {
+		// SAFETY: the caller guarantees the pointer came from `guess_detector_new`.
+		drop(unsafe { Box::from_raw(detector) });
+	}
+}
+
+/// Detects the protocol of `len` bytes at `buf` and writes the result to `out_info`.
+///
+/// `out_info` is written for every status except `InvalidArgument`.
+///
+/// # Safety
+///
+/// `detector` must come from [`guess_detector_new`], `buf` must be valid for
+/// `len` bytes (it may be null when `len` is 0), and `out_info` must be valid
+/// for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn guess_detect(
+	detector: *const GuessDetector,
+	buf: *const u8,
+	len: usize,
+	out_info: *mut GuessInfo,
+) -> GuessStatus {
+	if detector.is_null() || out_info.is_null() || (buf.is_null() && len != 0) {
+		return GuessStatus::InvalidArgument;
+	}
+	let data = if len == 0 {
+		&[][..]
+	} else {
+		// SAFETY: the caller guarantees `buf` is valid for `len` bytes.
+		unsafe { slice::from_raw_parts(buf, len) }
+	};
+	// SAFETY: checked for null above; the caller guarantees validity.
+	let detector = unsafe { &(*detector).inner };
+
+	let mut info = GuessInfo {
+		protocol: GuessProtocol::None,
+		version: ptr::null(),
+		version_len: 0,
+	};
+	let status = match detector.detect_info(data) {
+		Ok(Some(found)) => {
+			info.protocol = found.protocol.into();
+			if let Some(version) = version_bytes(&found.version) {
+				info.version = version.as_ptr();
+				info.version_len = version.len();
+			}
+			GuessStatus::Match
+		}
+		Ok(None) | Err(DetectionError::ProtocolNotEnabled(_)) => GuessStatus::NoMatch,
+		Err(DetectionError::InsufficientData) => GuessStatus::Incomplete,
+		Err(DetectionError::BudgetExceeded) => GuessStatus::BudgetExceeded,
+	};
+	// SAFETY: checked for null above; the caller guarantees validity.
+	unsafe { out_info.write(info) };
+	status
+}
+
+/// Returns the lowercase name of a protocol as a static NUL-terminated string.
+#[unsafe(no_mangle)]
+pub extern "C" fn guess_protocol_name(protocol: GuessProtocol) -> *const c_char {
+	let name = match protocol {
+		GuessProtocol::None => c"none",
+		GuessProtocol::Http => c"http",
+		GuessProtocol::Tls => c"tls",
+		GuessProtocol::Ssh => c"ssh",
+		GuessProtocol::Dns => c"dns",
+		GuessProtocol::Quic => c"quic",
+		GuessProtocol::Mysql => c"mysql",
+		GuessProtocol::Postgres => c"postgres",
+		GuessProtocol::Redis => c"redis",
+		GuessProtocol::Mqtt => c"mqtt",
+		GuessProtocol::Smtp => c"smtp",
+		GuessProtocol::Pop3 => c"pop3",
+		GuessProtocol::Imap => c"imap",
+		GuessProtocol::Ftp => c"ftp",
+		GuessProtocol::Smb => c"smb",
+		GuessProtocol::Stun => c"stun",
+		GuessProtocol::Sip => c"sip",
+		GuessProtocol::Rtsp => c"rtsp",
+		GuessProtocol::Dhcp => c"dhcp",
+		GuessProtocol::Ntp => c"ntp",
+		GuessProtocol::Grpc => c"grpc",
+		GuessProtocol::Nats => c"nats",
+		GuessProtocol::Git => c"git",
+		GuessProtocol::Ipp => c"ipp",
+		GuessProtocol::JetDirect => c"jetdirect",
+		GuessProtocol::OpcUa => c"opcua",
+		GuessProtocol::OpenVpn => c"openvpn",
+		GuessProtocol::BitTorrent => c"bittorrent",
+		GuessProtocol::Dht => c"dht",
+		GuessProtocol::Mdns => c"mdns",
+		GuessProtocol::NetBios => c"netbios",
+		GuessProtocol::Turn => c"turn",
+		GuessProtocol::Tds => c"tds",
+		GuessProtocol::Riak => c"riak",
+		GuessProtocol::Mysqlx => c"mysqlx",
+		GuessProtocol::Minecraft => c"minecraft",
+		GuessProtocol::SourceQuery => c"source_query",
+		GuessProtocol::Graphite => c"graphite",
+		GuessProtocol::Influx => c"influx",
+		GuessProtocol::Thrift => c"thrift",
+		GuessProtocol::Ajp => c"ajp",
+		GuessProtocol::NvmeTcp => c"nvme_tcp",
+		GuessProtocol::Dicom => c"dicom",
+		GuessProtocol::DnsCrypt => c"dnscrypt",
+		GuessProtocol::Vxlan => c"vxlan",
+		GuessProtocol::Geneve => c"geneve",
+		GuessProtocol::Devp2p => c"devp2p",
+		GuessProtocol::JsonRpc => c"jsonrpc",
+		GuessProtocol::MsgpackRpc => c"msgpack_rpc",
+		GuessProtocol::Lpd => c"lpd",
+		GuessProtocol::Http3 => c"http3",
+		GuessProtocol::Gquic => c"gquic",
+		GuessProtocol::Spdy => c"spdy",
+		GuessProtocol::RakNet => c"raknet",
+		GuessProtocol::Mms => c"mms",
+		GuessProtocol::LoRaWan => c"lorawan",
+		GuessProtocol::WebSocket => c"websocket",
+		GuessProtocol::Telnet => c"telnet",
+		GuessProtocol::Rdp => c"rdp",
+		GuessProtocol::Ldap => c"ldap",
+		GuessProtocol::Kerberos => c"kerberos",
+		GuessProtocol::Irc => c"irc",
+		GuessProtocol::Xmpp => c"xmpp",
+		GuessProtocol::Amqp => c"amqp",
+		GuessProtocol::Memcached => c"memcached",
+		GuessProtocol::Socks4 => c"socks4",
+		GuessProtocol::ProxyProtocol => c"proxyproto",
+		GuessProtocol::Rtp => c"rtp",
+		GuessProtocol::Rtcp => c"rtcp",
+		GuessProtocol::Rtmp => c"rtmp",
+		GuessProtocol::S7 => c"s7",
+		GuessProtocol::ClickHouse => c"clickhouse",
+		GuessProtocol::Tarantool => c"tarantool",
+		GuessProtocol::Svn => c"svn",
+		GuessProtocol::SunRpc => c"sunrpc",
+		GuessProtocol::Zookeeper => c"zookeeper",
+		GuessProtocol::Mumble => c"mumble",
+		GuessProtocol::Ident => c"ident",
+		GuessProtocol::Scgi => c"scgi",
+		GuessProtocol::Icap => c"icap",
+		GuessProtocol::Relp => c"relp",
+		GuessProtocol::Smpp => c"smpp",
+		GuessProtocol::H225 => c"h225",
+	};
+	name.as_ptr()
+}
+
+/// Returns the version as bytes borrowed from the input or static storage.
+fn version_bytes<'a>(version: &ProtocolVersion<'a>) -> Option<&'a [u8]> {
+	match version {
+		ProtocolVersion::Http(v)
+		| ProtocolVersion::Tls(v)
+		| ProtocolVersion::Ssh(v)
+		| ProtocolVersion::Amqp(v)
+		| ProtocolVersion::Tds(v)
+		| ProtocolVersion::Tarantool(v) => Some(v.as_bytes()),
+		ProtocolVersion::Redis(2) | ProtocolVersion::Spdy(2) => Some(b"2"),
+		ProtocolVersion::Redis(3) | ProtocolVersion::Spdy(3) => Some(b"3"),
+		ProtocolVersion::Redis(_) | ProtocolVersion::Spdy(_) | ProtocolVersion::Unknown => None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use core::ffi::CStr;
+
+	/// Runs `guess_detect` on `data` with a fresh detector.
+	fn detect(data: &[u8]) -> (GuessStatus, GuessInfo) {
+		let detector = guess_detector_new();
+		let mut info = GuessInfo {
+			protocol: GuessProtocol::Http,
+			version: ptr::null(),
+			version_len: 0,
+		};
+		let status = unsafe { guess_detect(detector, data.as_ptr(), data.len(), &raw mut info) };
+		unsafe { guess_detector_free(detector) };
+		(status, info)
+	}
+
+	// ── Correct paths ──
+
+	#[test]
+	#[cfg(feature = "ssh")]
+	fn detect_reports_protocol_and_version() {
+		let data = b"SSH-2.0-OpenSSH_9.6\r\n";
+		let (status, info) = detect(data);
+		assert_eq!(status, GuessStatus::Match);
+		assert_eq!(info.protocol, GuessProtocol::Ssh);
+		let version = unsafe { slice::from_raw_parts(info.version, info.version_len) };
+		assert_eq!(version, b"2.0");
+	}
+
+	#[test]
+	fn protocol_names_are_nul_terminated() {
+		let name = unsafe { CStr::from_ptr(guess_protocol_name(GuessProtocol::Postgres)) };
+		assert_eq!(name.to_str(), Ok("postgres"));
+	}
+
+	#[test]
+	fn protocol_values_are_stable() {
+		assert_eq!(GuessProtocol::None as u32, 0);
+		assert_eq!(GuessProtocol::Http as u32, 1);
+		assert_eq!(GuessProtocol::Ntp as u32, 19);
+	}
+
+	// ── Error paths ──
+
+	#[test]
+	fn unknown_data_clears_out_info() {
+		let (status, info) = detect(&[0xFF; 64]);
+		assert_eq!(status, GuessStatus::NoMatch);
+		assert_eq!(info.protocol, GuessProtocol::None);
+		assert!(info.version.is_null());
+	}
+
+	#[test]
+	fn null_arguments_are_rejected() {
+		let detector = guess_detector_new();
+		let mut info = detect(b"").1;
+		unsafe {
+			assert_eq!(
+				guess_detect(ptr::null(), ptr::null(), 0, &raw mut info),
+				GuessStatus::InvalidArgument
+			);
+			assert_eq!(
+				guess_detect(detector, ptr::null(), 4, &raw mut info),
+				GuessStatus::InvalidArgument
+			);
+			assert_eq!(
+				guess_detect(detector, ptr::null(), 0, ptr::null_mut()),
+				GuessStatus::InvalidArgument
+			);
+			guess_detector_free(detector);
+			guess_detector_free(ptr::null_mut());
+		}
+	}
+}
@@ -1,26 +1,112 @@
 /* src/chain.rs */
 
-#[cfg(feature = "std")]
-use crate::{Protocol, ProtocolDetector, Unknown};
+#[cfg(feature = "alloc")]
+use crate::{
+	DetectionBudget, Protocol, ProtocolDetector, Tcp, Udp, Unknown, detector::ProtocolVersionSet,
+};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use thiserror::Error;
+
+/// Errors reported by [`ProtocolChainBuilder::try_build`].
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum ChainError {
+	/// The chain contains no protocols.
+	#[error("detection chain is empty")]
+	Empty,
+	/// A protocol was added more than once.
+	#[error("protocol {0:?} appears more than once in the chain")]
+	Duplicate(Protocol),
+	/// A protocol is not carried over the chain's transport.
+	#[error("protocol {protocol:?} does not run over {transport}")]
+	TransportMismatch {
+		/// The offending protocol.
+		protocol: Protocol,
+		/// The chain's transport (`"TCP"` or `"UDP"`).
+		transport: &'static str,
+	},
+}
+
+/// Transport selected with [`ProtocolChainBuilder::tcp`] or [`ProtocolChainBuilder::udp`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Layer {
+	/// TCP streams.
+	Tcp,
+	/// UDP datagrams.
+	Udp,
+}
 
 /// Builder for creating a custom detection chain with a specific order.
-#[cfg(feature = "std")]
-#[derive(Debug, Default, Clone)]
-pub struct ProtocolChainBuilder {
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+pub struct ProtocolChainBuilder<Transport = Unknown> {
 	/// Ordered list of protocols to check.
 	order: Vec<Protocol>,
 	/// Maximum bytes to inspect.
 	max_inspect_bytes: usize,
+	/// Work limit per detection call.
+	budget: DetectionBudget,
+	/// Expected protocol versions.
+	expected_versions: ProtocolVersionSet,
+	/// Transport checked by `try_build`, if one was chosen.
+	layer: Option<Layer>,
+	/// Transport type marker.
+	_transport: PhantomData<Transport>,
 }
 
-#[cfg(feature = "std")]
+#[cfg(feature = "alloc")]
+impl Default for ProtocolChainBuilder {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+#[cfg(feature = "alloc")]
 impl ProtocolChainBuilder {
 	/// Creates a new empty `ProtocolChainBuilder`.
 	#[must_use]
 	pub fn new() -> Self {
+		Self::from_slice(&[])
+	}
+
+	/// Builds the chain from a slice of protocols.
+	#[must_use]
+	pub fn from_slice(protocols: &[Protocol]) -> Self {
 		Self {
-			order: Vec::new(),
+			order: protocols.to_vec(),
 			max_inspect_bytes: crate::MAX_INSPECT_BYTES,
+			budget: DetectionBudget::new(),
+			expected_versions: ProtocolVersionSet::default(),
+			layer: None,
+			_transport: PhantomData,
+		}
+	}
+}
+
+#[cfg(feature = "alloc")]
+impl<T> ProtocolChainBuilder<T> {
+	/// Switches to TCP transport; `try_build` rejects UDP-only protocols.
+	#[must_use]
+	pub fn tcp(self) -> ProtocolChainBuilder<Tcp> {
+		self.with_layer(Layer::Tcp)
+	}
+
+	/// Switches to UDP transport; `try_build` rejects TCP-only protocols.
+	#[must_use]
+	pub fn udp(self) -> ProtocolChainBuilder<Udp> {
+		self.with_layer(Layer::Udp)
+	}
+
+	/// Moves the chain to another transport marker.
+	fn with_layer<U>(self, layer: Layer) -> ProtocolChainBuilder<U> {
+		ProtocolChainBuilder {
+			order: self.order,
+			max_inspect_bytes: self.max_inspect_bytes,
+			budget: self.budget,
+			expected_versions: self.expected_versions,
+			layer: Some(layer),
+			_transport: PhantomData,
 		}
 	}
 
@@ -31,6 +117,13 @@ impl ProtocolChainBuilder {
 		self
 	}
 
+	/// Sets the work limit per detection call.
+	#[must_use]
+	pub fn budget(mut self, budget: DetectionBudget) -> Self {
+		self.budget = budget;
+		self
+	}
+
 	/// Adds HTTP to the detection chain.
 	#[cfg(feature = "http")]
 	#[must_use]
@@ -103,155 +196,1010 @@ impl ProtocolChainBuilder {
 		self
 	}
 
-	/// Adds all compiled TCP protocols in the default optimized order.
+	/// Expects a specific HTTP version, adding HTTP to the chain if it is not already present.
+	#[cfg(feature = "http")]
 	#[must_use]
-	pub fn all_tcp(mut self) -> Self {
-		let _ = &mut self;
-		#[cfg(feature = "ssh")]
-		{
-			self.order.push(Protocol::Ssh);
-		}
-		#[cfg(feature = "tls")]
-		{
-			self.order.push(Protocol::Tls);
-		}
-		#[cfg(feature = "http")]
-		{
-			self.order.push(Protocol::Http);
-		}
-		#[cfg(feature = "redis")]
-		{
-			self.order.push(Protocol::Redis);
-		}
-		#[cfg(feature = "mysql")]
-		{
-			self.order.push(Protocol::Mysql);
-		}
-		#[cfg(feature = "postgres")]
-		{
-			self.order.push(Protocol::Postgres);
-		}
-		#[cfg(feature = "mqtt")]
-		{
-			self.order.push(Protocol::Mqtt);
-		}
-		#[cfg(feature = "smtp")]
-		{
-			self.order.push(Protocol::Smtp);
-		}
-		#[cfg(feature = "pop3")]
-		{
-			self.order.push(Protocol::Pop3);
-		}
-		#[cfg(feature = "imap")]
-		{
-			self.order.push(Protocol::Imap);
-		}
-		#[cfg(feature = "ftp")]
-		{
-			self.order.push(Protocol::Ftp);
-		}
-		#[cfg(feature = "smb")]
-		{
-			self.order.push(Protocol::Smb);
-		}
-		#[cfg(feature = "sip")]
-		{
-			self.order.push(Protocol::Sip);
-		}
-		#[cfg(feature = "rtsp")]
-		{
-			self.order.push(Protocol::Rtsp);
+	pub fn http_version(mut self, version: &'static str) -> Self {
+		self.expected_versions.http = Some(version);
+		self.ensure(Protocol::Http)
+	}
+
+	/// Expects a specific TLS version, adding TLS to the chain if it is not already present.
+	#[cfg(feature = "tls")]
+	#[must_use]
+	pub fn tls_version(mut self, version: &'static str) -> Self {
+		self.expected_versions.tls = Some(version);
+		self.ensure(Protocol::Tls)
+	}
+
+	/// Expects a specific SSH version, adding SSH to the chain if it is not already present.
+	#[cfg(feature = "ssh")]
+	#[must_use]
+	pub fn ssh_version(mut self, version: &'static str) -> Self {
+		self.expected_versions.ssh = Some(version);
+		self.ensure(Protocol::Ssh)
+	}
+
+	/// Expects a specific Redis RESP version, adding Redis to the chain if it is not already present.
+	#[cfg(feature = "redis")]
+	#[must_use]
+	pub fn redis_version(mut self, version: u8) -> Self {
+		self.expected_versions.redis = Some(version);
+		self.ensure(Protocol::Redis)
+	}
+
+	/// Appends `protocol` unless the chain already contains it.
+	#[allow(dead_code)]
+	fn ensure(mut self, protocol: Protocol) -> Self {
+		if !self.order.contains(&protocol) {
+			self.order.push(protocol);
 		}
 		self
 	}
 
-	/// Adds all compiled UDP protocols.
+	/// Adds SMTP to the detection chain.
+	#[cfg(feature = "smtp")]
 	#[must_use]
-	pub fn all_udp(mut self) -> Self {
-		let _ = &mut self;
-		#[cfg(feature = "dns")]
-		{
-			self.order.push(Protocol::Dns);
-		}
-		#[cfg(feature = "quic")]
-		{
-			self.order.push(Protocol::Quic);
-		}
-		#[cfg(feature = "dhcp")]
-		{
-			self.order.push(Protocol::Dhcp);
-		}
-		#[cfg(feature = "ntp")]
-		{
-			self.order.push(Protocol::Ntp);
-		}
-		#[cfg(feature = "stun")]
-		{
-			self.order.push(Protocol::Stun);
-		}
-		#[cfg(feature = "sip")]
-		{
-			self.order.push(Protocol::Sip);
-		}
-		#[cfg(feature = "rtsp")]
-		{
-			self.order.push(Protocol::Rtsp);
-		}
+	pub fn smtp(mut self) -> Self {
+		self.order.push(Protocol::Smtp);
 		self
 	}
 
-	/// Adds all compiled database protocols.
+	/// Adds POP3 to the detection chain.
+	#[cfg(feature = "pop3")]
 	#[must_use]
-	pub fn all_db(mut self) -> Self {
-		let _ = &mut self;
-		#[cfg(feature = "redis")]
-		{
-			self.order.push(Protocol::Redis);
-		}
-		#[cfg(feature = "mysql")]
-		{
-			self.order.push(Protocol::Mysql);
-		}
-		#[cfg(feature = "postgres")]
-		{
-			self.order.push(Protocol::Postgres);
-		}
+	pub fn pop3(mut self) -> Self {
+		self.order.push(Protocol::Pop3);
 		self
 	}
 
-	/// Adds all compiled Web protocols (HTTP, TLS, QUIC).
+	/// Adds IMAP to the detection chain.
+	#[cfg(feature = "imap")]
 	#[must_use]
-	pub fn all_web(mut self) -> Self {
-		let _ = &mut self;
-		#[cfg(feature = "http")]
-		{
-			self.order.push(Protocol::Http);
-		}
-		#[cfg(feature = "tls")]
-		{
-			self.order.push(Protocol::Tls);
-		}
-		#[cfg(feature = "quic")]
-		{
-			self.order.push(Protocol::Quic);
-		}
+	pub fn imap(mut self) -> Self {
+		self.order.push(Protocol::Imap);
 		self
 	}
 
-	/// Builds the chain from a slice of protocols.
+	/// Adds FTP to the detection chain.
+	#[cfg(feature = "ftp")]
 	#[must_use]
-	pub fn from_slice(protocols: &[Protocol]) -> Self {
-		Self {
-			order: protocols.to_vec(),
-			max_inspect_bytes: crate::MAX_INSPECT_BYTES,
+	pub fn ftp(mut self) -> Self {
+		self.order.push(Protocol::Ftp);
+		self
+	}
+
+	/// Adds SMB to the detection chain.
+	#[cfg(feature = "smb")]
+	#[must_use]
+	pub fn smb(mut self) -> Self {
+		self.order.push(Protocol::Smb);
+		self
+	}
+
+	/// Adds STUN to the detection chain.
+	#[cfg(feature = "stun")]
+	#[must_use]
+	pub fn stun(mut self) -> Self {
+		self.order.push(Protocol::Stun);
+		self
+	}
+
+	/// Adds SIP to the detection chain.
+	#[cfg(feature = "sip")]
+	#[must_use]
+	pub fn sip(mut self) -> Self {
+		self.order.push(Protocol::Sip);
+		self
+	}
+
+	/// Adds RTSP to the detection chain.
+	#[cfg(feature = "rtsp")]
+	#[must_use]
+	pub fn rtsp(mut self) -> Self {
+		self.order.push(Protocol::Rtsp);
+		self
+	}
+
+	/// Adds DHCP to the detection chain.
+	#[cfg(feature = "dhcp")]
+	#[must_use]
+	pub fn dhcp(mut self) -> Self {
+		self.order.push(Protocol::Dhcp);
+		self
+	}
+
+	/// Adds NTP to the detection chain.
+	#[cfg(feature = "ntp")]
+	#[must_use]
+	pub fn ntp(mut self) -> Self {
+		self.order.push(Protocol::Ntp);
+		self
+	}
+
+	/// Adds `gRPC` to the detection chain.
+	#[cfg(feature = "grpc")]
+	#[must_use]
+	pub fn grpc(mut self) -> Self {
+		self.order.push(Protocol::Grpc);
+		self
+	}
+
+	/// Adds NATS to the detection chain.
+	#[cfg(feature = "nats")]
+	#[must_use]
+	pub fn nats(mut self) -> Self {
+		self.order.push(Protocol::Nats);
+		self
+	}
+
+	/// Adds Git to the detection chain.
+	#[cfg(feature = "git")]
+	#[must_use]
+	pub fn git(mut self) -> Self {
+		self.order.push(Protocol::Git);
+		self
+	}
+
+	/// Adds IPP to the detection chain.
+	#[cfg(feature = "ipp")]
+	#[must_use]
+	pub fn ipp(mut self) -> Self {
+		self.order.push(Protocol::Ipp);
+		self
+	}
+
+	/// Adds JetDirect to the detection chain.
+	#[cfg(feature = "jetdirect")]
+	#[must_use]
+	pub fn jetdirect(mut self) -> Self {
+		self.order.push(Protocol::JetDirect);
+		self
+	}
+
+	/// Adds OPC UA to the detection chain.
+	#[cfg(feature = "opcua")]
+	#[must_use]
+	pub fn opcua(mut self) -> Self {
+		self.order.push(Protocol::OpcUa);
+		self
+	}
+
+	/// Adds OpenVPN to the detection chain.
+	#[cfg(feature = "openvpn")]
+	#[must_use]
+	pub fn openvpn(mut self) -> Self {
+		self.order.push(Protocol::OpenVpn);
+		self
+	}
+
+	/// Adds BitTorrent to the detection chain.
+	#[cfg(feature = "bittorrent")]
+	#[must_use]
+	pub fn bittorrent(mut self) -> Self {
+		self.order.push(Protocol::BitTorrent);
+		self
+	}
+
+	/// Adds BitTorrent DHT to the detection chain.
+	#[cfg(feature = "dht")]
+	#[must_use]
+	pub fn dht(mut self) -> Self {
+		self.order.push(Protocol::Dht);
+		self
+	}
+
+	/// Adds mDNS to the detection chain.
+	#[cfg(feature = "mdns")]
+	#[must_use]
+	pub fn mdns(mut self) -> Self {
+		self.order.push(Protocol::Mdns);
+		self
+	}
+
+	/// Adds NetBIOS to the detection chain.
+	#[cfg(feature = "netbios")]
+	#[must_use]
+	pub fn netbios(mut self) -> Self {
+		self.order.push(Protocol::NetBios);
+		self
+	}
+
+	/// Adds TURN to the detection chain.
+	#[cfg(feature = "turn")]
+	#[must_use]
+	pub fn turn(mut self) -> Self {
+		self.order.push(Protocol::Turn);
+		self
+	}
+
+	/// Adds TDS to the detection chain.
+	#[cfg(feature = "tds")]
+	#[must_use]
+	pub fn tds(mut self) -> Self {
+		self.order.push(Protocol::Tds);
+		self
+	}
+
+	/// Adds Riak to the detection chain.
+	#[cfg(feature = "riak")]
+	#[must_use]
+	pub fn riak(mut self) -> Self {
+		self.order.push(Protocol::Riak);
+		self
+	}
+
+	/// Adds `MySQL` X Protocol to the detection chain.
+	#[cfg(feature = "mysqlx")]
+	#[must_use]
+	pub fn mysqlx(mut self) -> Self {
+		self.order.push(Protocol::Mysqlx);
+		self
+	}
+
+	/// Adds Minecraft to the detection chain.
+	#[cfg(feature = "minecraft")]
+	#[must_use]
+	pub fn minecraft(mut self) -> Self {
+		self.order.push(Protocol::Minecraft);
+		self
+	}
+
+	/// Adds Source engine query to the detection chain.
+	#[cfg(feature = "source_query")]
+	#[must_use]
+	pub fn source_query(mut self) -> Self {
+		self.order.push(Protocol::SourceQuery);
+		self
+	}
+
+	/// Adds Graphite to the detection chain.
+	#[cfg(feature = "graphite")]
+	#[must_use]
+	pub fn graphite(mut self) -> Self {
+		self.order.push(Protocol::Graphite);
+		self
+	}
+
+	/// Adds `InfluxDB` line protocol to the detection chain.
+	#[cfg(feature = "influx")]
+	#[must_use]
+	pub fn influx(mut self) -> Self {
+		self.order.push(Protocol::Influx);
+		self
+	}
+
+	/// Adds Thrift to the detection chain.
+	#[cfg(feature = "thrift")]
+	#[must_use]
+	pub fn thrift(mut self) -> Self {
+		self.order.push(Protocol::Thrift);
+		self
+	}
+
+	/// Adds AJP to the detection chain.
+	#[cfg(feature = "ajp")]
+	#[must_use]
+	pub fn ajp(mut self) -> Self {
+		self.order.push(Protocol::Ajp);
+		self
+	}
+
+	/// Adds NVMe/TCP to the detection chain.
+	#[cfg(feature = "nvme_tcp")]
+	#[must_use]
+	pub fn nvme_tcp(mut self) -> Self {
+		self.order.push(Protocol::NvmeTcp);
+		self
+	}
+
+	/// Adds DICOM to the detection chain.
+	#[cfg(feature = "dicom")]
+	#[must_use]
+	pub fn dicom(mut self) -> Self {
+		self.order.push(Protocol::Dicom);
+		self
+	}
+
+	/// Adds DNSCrypt to the detection chain.
+	#[cfg(feature = "dnscrypt")]
+	#[must_use]
+	pub fn dnscrypt(mut self) -> Self {
+		self.order.push(Protocol::DnsCrypt);
+		self
+	}
+
+	/// Adds VXLAN to the detection chain.
+	#[cfg(feature = "vxlan")]
+	#[must_use]
+	pub fn vxlan(mut self) -> Self {
+		self.order.push(Protocol::Vxlan);
+		self
+	}
+
+	/// Adds GENEVE to the detection chain.
+	#[cfg(feature = "geneve")]
+	#[must_use]
+	pub fn geneve(mut self) -> Self {
+		self.order.push(Protocol::Geneve);
+		self
+	}
+
+	/// Adds Ethereum devp2p to the detection chain.
+	///
+	/// RLPx handshakes are matched on their first three bytes; discv4 packets
+	/// need a [`max_inspect_bytes`](Self::max_inspect_bytes) covering the
+	/// datagram (up to 1280 bytes).
+	#[cfg(feature = "devp2p")]
+	#[must_use]
+	pub fn devp2p(mut self) -> Self {
+		self.order.push(Protocol::Devp2p);
+		self
+	}
+
+	/// Adds JSON-RPC to the detection chain.
+	#[cfg(feature = "jsonrpc")]
+	#[must_use]
+	pub fn jsonrpc(mut self) -> Self {
+		self.order.push(Protocol::JsonRpc);
+		self
+	}
+
+	/// Adds MessagePack-RPC to the detection chain.
+	#[cfg(feature = "msgpack_rpc")]
+	#[must_use]
+	pub fn msgpack_rpc(mut self) -> Self {
+		self.order.push(Protocol::MsgpackRpc);
+		self
+	}
+
+	/// Adds LPD to the detection chain.
+	#[cfg(feature = "lpd")]
+	#[must_use]
+	pub fn lpd(mut self) -> Self {
+		self.order.push(Protocol::Lpd);
+		self
+	}
+
+	/// Adds HTTP/3 to the detection chain.
+	#[cfg(feature = "http3")]
+	#[must_use]
+	pub fn http3(mut self) -> Self {
+		self.order.push(Protocol::Http3);
+		self
+	}
+
+	/// Adds gQUIC to the detection chain.
+	#[cfg(feature = "gquic")]
+	#[must_use]
+	pub fn gquic(mut self) -> Self {
+		self.order.push(Protocol::Gquic);
+		self
+	}
+
+	/// Adds SPDY to the detection chain.
+	#[cfg(feature = "spdy")]
+	#[must_use]
+	pub fn spdy(mut self) -> Self {
+		self.order.push(Protocol::Spdy);
+		self
+	}
+
+	/// Adds RakNet to the detection chain.
+	#[cfg(feature = "raknet")]
+	#[must_use]
+	pub fn raknet(mut self) -> Self {
+		self.order.push(Protocol::RakNet);
+		self
+	}
+
+	/// Adds MMS to the detection chain.
+	#[cfg(feature = "mms")]
+	#[must_use]
+	pub fn mms(mut self) -> Self {
+		self.order.push(Protocol::Mms);
+		self
+	}
+
+	/// Adds LoRaWAN to the detection chain.
+	#[cfg(feature = "lorawan")]
+	#[must_use]
+	pub fn lorawan(mut self) -> Self {
+		self.order.push(Protocol::LoRaWan);
+		self
+	}
+
+	/// Adds WebSocket to the detection chain.
+	///
+	/// Handshakes are probed at this position; bare frames are probed after
+	/// the rest of the chain.
+	#[cfg(feature = "websocket")]
+	#[must_use]
+	pub fn websocket(mut self) -> Self {
+		self.order.push(Protocol::WebSocket);
+		self
+	}
+
+	/// Adds Telnet to the detection chain.
+	#[cfg(feature = "telnet")]
+	#[must_use]
+	pub fn telnet(mut self) -> Self {
+		self.order.push(Protocol::Telnet);
+		self
+	}
+
+	/// Adds RDP to the detection chain.
+	#[cfg(feature = "rdp")]
+	#[must_use]
+	pub fn rdp(mut self) -> Self {
+		self.order.push(Protocol::Rdp);
+		self
+	}
+
+	/// Adds LDAP to the detection chain.
+	#[cfg(feature = "ldap")]
+	#[must_use]
+	pub fn ldap(mut self) -> Self {
+		self.order.push(Protocol::Ldap);
+		self
+	}
+
+	/// Adds Kerberos to the detection chain.
+	#[cfg(feature = "kerberos")]
+	#[must_use]
+	pub fn kerberos(mut self) -> Self {
+		self.order.push(Protocol::Kerberos);
+		self
+	}
+
+	/// Adds IRC to the detection chain.
+	#[cfg(feature = "irc")]
+	#[must_use]
+	pub fn irc(mut self) -> Self {
+		self.order.push(Protocol::Irc);
+		self
+	}
+
+	/// Adds XMPP to the detection chain.
+	#[cfg(feature = "xmpp")]
+	#[must_use]
+	pub fn xmpp(mut self) -> Self {
+		self.order.push(Protocol::Xmpp);
+		self
+	}
+
+	/// Adds AMQP to the detection chain.
+	#[cfg(feature = "amqp")]
+	#[must_use]
+	pub fn amqp(mut self) -> Self {
+		self.order.push(Protocol::Amqp);
+		self
+	}
+
+	/// Adds Memcached to the detection chain.
+	#[cfg(feature = "memcached")]
+	#[must_use]
+	pub fn memcached(mut self) -> Self {
+		self.order.push(Protocol::Memcached);
+		self
+	}
+
+	/// Adds SOCKS4 to the detection chain.
+	#[cfg(feature = "socks4")]
+	#[must_use]
+	pub fn socks4(mut self) -> Self {
+		self.order.push(Protocol::Socks4);
+		self
+	}
+
+	/// Adds PROXY protocol to the detection chain.
+	#[cfg(feature = "proxyproto")]
+	#[must_use]
+	pub fn proxyproto(mut self) -> Self {
+		self.order.push(Protocol::ProxyProtocol);
+		self
+	}
+
+	/// Adds RTP to the detection chain.
+	#[cfg(feature = "rtp")]
+	#[must_use]
+	pub fn rtp(mut self) -> Self {
+		self.order.push(Protocol::Rtp);
+		self
+	}
+
+	/// Adds RTCP to the detection chain.
+	#[cfg(feature = "rtcp")]
+	#[must_use]
+	pub fn rtcp(mut self) -> Self {
+		self.order.push(Protocol::Rtcp);
+		self
+	}
+
+	/// Adds RTMP to the detection chain.
+	#[cfg(feature = "rtmp")]
+	#[must_use]
+	pub fn rtmp(mut self) -> Self {
+		self.order.push(Protocol::Rtmp);
+		self
+	}
+
+	/// Adds S7comm to the detection chain.
+	#[cfg(feature = "s7")]
+	#[must_use]
+	pub fn s7(mut self) -> Self {
+		self.order.push(Protocol::S7);
+		self
+	}
+
+	/// Adds ClickHouse to the detection chain.
+	#[cfg(feature = "clickhouse")]
+	#[must_use]
+	pub fn clickhouse(mut self) -> Self {
+		self.order.push(Protocol::ClickHouse);
+		self
+	}
+
+	/// Adds Tarantool to the detection chain.
+	#[cfg(feature = "tarantool")]
+	#[must_use]
+	pub fn tarantool(mut self) -> Self {
+		self.order.push(Protocol::Tarantool);
+		self
+	}
+
+	/// Adds Subversion (`svnserve`) to the detection chain.
+	#[cfg(feature = "svn")]
+	#[must_use]
+	pub fn svn(mut self) -> Self {
+		self.order.push(Protocol::Svn);
+		self
+	}
+
+	/// Adds ONC RPC (Sun RPC) to the detection chain.
+	#[cfg(feature = "sunrpc")]
+	#[must_use]
+	pub fn sunrpc(mut self) -> Self {
+		self.order.push(Protocol::SunRpc);
+		self
+	}
+
+	/// Adds ZooKeeper to the detection chain.
+	#[cfg(feature = "zookeeper")]
+	#[must_use]
+	pub fn zookeeper(mut self) -> Self {
+		self.order.push(Protocol::Zookeeper);
+		self
+	}
+
+	/// Adds Mumble to the detection chain.
+	#[cfg(feature = "mumble")]
+	#[must_use]
+	pub fn mumble(mut self) -> Self {
+		self.order.push(Protocol::Mumble);
+		self
+	}
+
+	/// Adds Ident to the detection chain.
+	#[cfg(feature = "ident")]
+	#[must_use]
+	pub fn ident(mut self) -> Self {
+		self.order.push(Protocol::Ident);
+		self
+	}
+
+	/// Adds SCGI to the detection chain.
+	#[cfg(feature = "scgi")]
+	#[must_use]
+	pub fn scgi(mut self) -> Self {
+		self.order.push(Protocol::Scgi);
+		self
+	}
+
+	/// Adds ICAP to the detection chain.
+	#[cfg(feature = "icap")]
+	#[must_use]
+	pub fn icap(mut self) -> Self {
+		self.order.push(Protocol::Icap);
+		self
+	}
+
+	/// Adds RELP to the detection chain.
+	#[cfg(feature = "relp")]
+	#[must_use]
+	pub fn relp(mut self) -> Self {
+		self.order.push(Protocol::Relp);
+		self
+	}
+
+	/// Adds SMPP to the detection chain.
+	#[cfg(feature = "smpp")]
+	#[must_use]
+	pub fn smpp(mut self) -> Self {
+		self.order.push(Protocol::Smpp);
+		self
+	}
+
+	/// Adds H.225 to the detection chain.
+	#[cfg(feature = "h225")]
+	#[must_use]
+	pub fn h225(mut self) -> Self {
+		self.order.push(Protocol::H225);
+		self
+	}
+
+	/// Adds all compiled TCP protocols in detection order.
+	#[must_use]
+	pub fn all_tcp(self) -> Self {
+		self.extend_preset(&[
+			#[cfg(feature = "proxyproto")]
+			Protocol::ProxyProtocol,
+			#[cfg(feature = "ssh")]
+			Protocol::Ssh,
+			#[cfg(feature = "sip")]
+			Protocol::Sip,
+			#[cfg(feature = "rtsp")]
+			Protocol::Rtsp,
+			#[cfg(feature = "icap")]
+			Protocol::Icap,
+			#[cfg(feature = "imap")]
+			Protocol::Imap,
+			#[cfg(feature = "telnet")]
+			Protocol::Telnet,
+			#[cfg(feature = "tls")]
+			Protocol::Tls,
+			#[cfg(feature = "grpc")]
+			Protocol::Grpc,
+			#[cfg(feature = "ipp")]
+			Protocol::Ipp,
+			#[cfg(feature = "websocket")]
+			Protocol::WebSocket,
+			#[cfg(feature = "http")]
+			Protocol::Http,
+			#[cfg(feature = "spdy")]
+			Protocol::Spdy,
+			#[cfg(feature = "netbios")]
+			Protocol::NetBios,
+			#[cfg(feature = "irc")]
+			Protocol::Irc,
+			#[cfg(feature = "ident")]
+			Protocol::Ident,
+			#[cfg(feature = "relp")]
+			Protocol::Relp,
+			#[cfg(feature = "xmpp")]
+			Protocol::Xmpp,
+			#[cfg(feature = "smtp")]
+			Protocol::Smtp,
+			#[cfg(feature = "ftp")]
+			Protocol::Ftp,
+			#[cfg(feature = "git")]
+			Protocol::Git,
+			#[cfg(feature = "svn")]
+			Protocol::Svn,
+			#[cfg(feature = "jetdirect")]
+			Protocol::JetDirect,
+			#[cfg(feature = "lpd")]
+			Protocol::Lpd,
+			#[cfg(feature = "mysql")]
+			Protocol::Mysql,
+			#[cfg(feature = "mysqlx")]
+			Protocol::Mysqlx,
+			#[cfg(feature = "postgres")]
+			Protocol::Postgres,
+			#[cfg(feature = "tds")]
+			Protocol::Tds,
+			#[cfg(feature = "clickhouse")]
+			Protocol::ClickHouse,
+			#[cfg(feature = "thrift")]
+			Protocol::Thrift,
+			#[cfg(feature = "ajp")]
+			Protocol::Ajp,
+			#[cfg(feature = "scgi")]
+			Protocol::Scgi,
+			#[cfg(feature = "nvme_tcp")]
+			Protocol::NvmeTcp,
+			#[cfg(feature = "dicom")]
+			Protocol::Dicom,
+			#[cfg(feature = "jsonrpc")]
+			Protocol::JsonRpc,
+			#[cfg(feature = "msgpack_rpc")]
+			Protocol::MsgpackRpc,
+			#[cfg(feature = "redis")]
+			Protocol::Redis,
+			#[cfg(feature = "memcached")]
+			Protocol::Memcached,
+			#[cfg(feature = "tarantool")]
+			Protocol::Tarantool,
+			#[cfg(feature = "zookeeper")]
+			Protocol::Zookeeper,
+			#[cfg(feature = "smpp")]
+			Protocol::Smpp,
+			#[cfg(feature = "nats")]
+			Protocol::Nats,
+			#[cfg(feature = "amqp")]
+			Protocol::Amqp,
+			#[cfg(feature = "mqtt")]
+			Protocol::Mqtt,
+			#[cfg(feature = "opcua")]
+			Protocol::OpcUa,
+			#[cfg(feature = "mms")]
+			Protocol::Mms,
+			#[cfg(feature = "s7")]
+			Protocol::S7,
+			#[cfg(feature = "rdp")]
+			Protocol::Rdp,
+			#[cfg(feature = "h225")]
+			Protocol::H225,
+			#[cfg(feature = "ldap")]
+			Protocol::Ldap,
+			#[cfg(feature = "kerberos")]
+			Protocol::Kerberos,
+			#[cfg(feature = "sunrpc")]
+			Protocol::SunRpc,
+			#[cfg(feature = "rtmp")]
+			Protocol::Rtmp,
+			#[cfg(feature = "pop3")]
+			Protocol::Pop3,
+			#[cfg(feature = "smb")]
+			Protocol::Smb,
+			#[cfg(feature = "riak")]
+			Protocol::Riak,
+			#[cfg(feature = "openvpn")]
+			Protocol::OpenVpn,
+			#[cfg(feature = "bittorrent")]
+			Protocol::BitTorrent,
+			#[cfg(feature = "minecraft")]
+			Protocol::Minecraft,
+			#[cfg(feature = "graphite")]
+			Protocol::Graphite,
+			#[cfg(feature = "socks4")]
+			Protocol::Socks4,
+			#[cfg(feature = "devp2p")]
+			Protocol::Devp2p,
+			#[cfg(feature = "mumble")]
+			Protocol::Mumble,
+		])
+	}
+
+	/// Adds all compiled UDP protocols in detection order.
+	#[must_use]
+	pub fn all_udp(self) -> Self {
+		self.extend_preset(&[
+			#[cfg(feature = "sip")]
+			Protocol::Sip,
+			#[cfg(feature = "rtsp")]
+			Protocol::Rtsp,
+			#[cfg(feature = "vxlan")]
+			Protocol::Vxlan,
+			#[cfg(feature = "geneve")]
+			Protocol::Geneve,
+			#[cfg(feature = "mdns")]
+			Protocol::Mdns,
+			#[cfg(feature = "netbios")]
+			Protocol::NetBios,
+			#[cfg(feature = "dnscrypt")]
+			Protocol::DnsCrypt,
+			#[cfg(feature = "dns")]
+			Protocol::Dns,
+			#[cfg(feature = "dhcp")]
+			Protocol::Dhcp,
+			#[cfg(feature = "ntp")]
+			Protocol::Ntp,
+			#[cfg(feature = "http3")]
+			Protocol::Http3,
+			#[cfg(feature = "quic")]
+			Protocol::Quic,
+			#[cfg(feature = "gquic")]
+			Protocol::Gquic,
+			#[cfg(feature = "raknet")]
+			Protocol::RakNet,
+			#[cfg(feature = "lorawan")]
+			Protocol::LoRaWan,
+			#[cfg(feature = "kerberos")]
+			Protocol::Kerberos,
+			#[cfg(feature = "sunrpc")]
+			Protocol::SunRpc,
+			#[cfg(feature = "turn")]
+			Protocol::Turn,
+			#[cfg(feature = "stun")]
+			Protocol::Stun,
+			#[cfg(feature = "openvpn")]
+			Protocol::OpenVpn,
+			#[cfg(feature = "dht")]
+			Protocol::Dht,
+			#[cfg(feature = "source_query")]
+			Protocol::SourceQuery,
+			#[cfg(feature = "graphite")]
+			Protocol::Graphite,
+			#[cfg(feature = "influx")]
+			Protocol::Influx,
+			#[cfg(feature = "rtcp")]
+			Protocol::Rtcp,
+			#[cfg(feature = "rtp")]
+			Protocol::Rtp,
+			#[cfg(feature = "devp2p")]
+			Protocol::Devp2p,
+			#[cfg(feature = "mumble")]
+			Protocol::Mumble,
+		])
+	}
+
+	/// Adds all compiled database protocols.
+	#[must_use]
+	pub fn all_db(self) -> Self {
+		self.extend_preset(&[
+			#[cfg(feature = "mysql")]
+			Protocol::Mysql,
+			#[cfg(feature = "mysqlx")]
+			Protocol::Mysqlx,
+			#[cfg(feature = "postgres")]
+			Protocol::Postgres,
+			#[cfg(feature = "tds")]
+			Protocol::Tds,
+			#[cfg(feature = "clickhouse")]
+			Protocol::ClickHouse,
+			#[cfg(feature = "redis")]
+			Protocol::Redis,
+			#[cfg(feature = "tarantool")]
+			Protocol::Tarantool,
+			#[cfg(feature = "riak")]
+			Protocol::Riak,
+		])
+	}
+
+	/// Adds all compiled Web protocols (WebSocket, HTTP, TLS, HTTP/3, QUIC).
+	#[must_use]
+	pub fn all_web(self) -> Self {
+		self.extend_preset(&[
+			#[cfg(feature = "tls")]
+			Protocol::Tls,
+			#[cfg(feature = "websocket")]
+			Protocol::WebSocket,
+			#[cfg(feature = "http")]
+			Protocol::Http,
+			#[cfg(feature = "http3")]
+			Protocol::Http3,
+			#[cfg(feature = "quic")]
+			Protocol::Quic,
+		])
+	}
+
+	/// Adds all compiled mail protocols (SMTP, IMAP, POP3).
+	#[must_use]
+	pub fn mail(self) -> Self {
+		self.extend_preset(&[
+			#[cfg(feature = "imap")]
+			Protocol::Imap,
+			#[cfg(feature = "smtp")]
+			Protocol::Smtp,
+			#[cfg(feature = "pop3")]
+			Protocol::Pop3,
+		])
+	}
+
+	/// Adds all compiled voice and video protocols (SIP, H.225, RTSP, STUN,
+	/// TURN, RTP, RTCP, Mumble).
+	#[must_use]
+	pub fn voip(self) -> Self {
+		self.extend_preset(&[
+			#[cfg(feature = "sip")]
+			Protocol::Sip,
+			#[cfg(feature = "rtsp")]
+			Protocol::Rtsp,
+			#[cfg(feature = "h225")]
+			Protocol::H225,
+			#[cfg(feature = "turn")]
+			Protocol::Turn,
+			#[cfg(feature = "stun")]
+			Protocol::Stun,
+			#[cfg(feature = "rtcp")]
+			Protocol::Rtcp,
+			#[cfg(feature = "rtp")]
+			Protocol::Rtp,
+			#[cfg(feature = "mumble")]
+			Protocol::Mumble,
+		])
+	}
+
+	/// Adds all compiled IoT messaging protocols (MQTT, LoRaWAN).
+	#[must_use]
+	pub fn iot(self) -> Self {
+		self.extend_preset(&[
+			#[cfg(feature = "lorawan")]
+			Protocol::LoRaWan,
+			#[cfg(feature = "mqtt")]
+			Protocol::Mqtt,
+		])
+	}
+
+	/// Adds all compiled Windows networking protocols (SMB, NetBIOS, RDP).
+	#[must_use]
+	pub fn windows(self) -> Self {
+		self.extend_preset(&[
+			#[cfg(feature = "netbios")]
+			Protocol::NetBios,
+			#[cfg(feature = "rdp")]
+			Protocol::Rdp,
+			#[cfg(feature = "smb")]
+			Protocol::Smb,
+		])
+	}
+
+	/// Adds all compiled media streaming protocols (RTSP, RTMP).
+	#[must_use]
+	pub fn streaming(self) -> Self {
+		self.extend_preset(&[
+			#[cfg(feature = "rtsp")]
+			Protocol::Rtsp,
+			#[cfg(feature = "rtmp")]
+			Protocol::Rtmp,
+		])
+	}
+
+	/// Appends the protocols of a preset in detection order, the order
+	/// [`ProtocolDetector`] probes them in, skipping those already in the chain.
+	fn extend_preset(mut self, protocols: &[Protocol]) -> Self {
+		for protocol in Protocol::DETECTION_ORDER {
+			if protocols.contains(protocol) && !self.order.contains(protocol) {
+				self.order.push(*protocol);
+			}
 		}
+		self
+	}
+
+	/// Removes a protocol from the chain, e.g. `all_tcp().without(Protocol::Redis)`.
+	#[must_use]
+	pub fn without(self, protocol: Protocol) -> Self {
+		self.exclude(&[protocol])
+	}
+
+	/// Removes every protocol in `protocols` from the chain.
+	#[must_use]
+	pub fn exclude(mut self, protocols: &[Protocol]) -> Self {
+		self.order.retain(|protocol| !protocols.contains(protocol));
+		self
 	}
 
 	/// Builds the detector.
 	#[must_use]
-	pub fn build(self) -> ProtocolDetector<Unknown> {
-		ProtocolDetector::with_order(self.order, self.max_inspect_bytes)
+	pub fn build(self) -> ProtocolDetector<T> {
+		ProtocolDetector::with_order(
+			self.order,
+			self.max_inspect_bytes,
+			self.budget,
+			self.expected_versions,
+		)
+	}
+
+	/// Builds the detector after validating the chain.
+	///
+	/// # Errors
+	///
+	/// Returns `Empty` if no protocols were added, `Duplicate` if a protocol
+	/// was added twice (presets skip protocols already in the chain), and
+	/// `TransportMismatch` if a protocol does not run over the transport
+	/// chosen with [`tcp`](Self::tcp) or [`udp`](Self::udp).
+	pub fn try_build(self) -> Result<ProtocolDetector<T>, ChainError> {
+		if self.order.is_empty() {
+			return Err(ChainError::Empty);
+		}
+		for (i, protocol) in self.order.iter().enumerate() {
+			if self.order[..i].contains(protocol) {
+				return Err(ChainError::Duplicate(*protocol));
+			}
+			let mismatch = match self.layer {
+				Some(Layer::Tcp) if !protocol.supports_tcp() => Some("TCP"),
+				Some(Layer::Udp) if !protocol.supports_udp() => Some("UDP"),
+				_ => None,
+			};
+			if let Some(transport) = mismatch {
+				return Err(ChainError::TransportMismatch {
+					protocol: *protocol,
+					transport,
+				});
+			}
+		}
+		Ok(self.build())
 	}
 }
 
@@ -261,6 +1209,8 @@ mod tests {
 	use super::*;
 	#[allow(unused_imports)]
 	use crate::DetectionError;
+	#[allow(unused_imports)]
+	use alloc::string::ToString;
 
 	// ── Correct paths ──
 
@@ -275,6 +1225,50 @@ mod tests {
 		assert_eq!(detector.detect(http_data).unwrap(), Some(Protocol::Http));
 	}
 
+	#[test]
+	fn presets_agree_with_builder() {
+		let mut rr = [0u8; 32];
+		rr[..4].copy_from_slice(&[0x81, 0xC9, 0x00, 0x07]);
+		rr[4..8].copy_from_slice(&[0x12, 0x34, 0x56, 0x78]);
+		let corpus: &[&[u8]] = &[
+			b"GET / HTTP/1.1\r\n",
+			b"GET /ws HTTP/1.1\r\nUpgrade: websocket\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\r\n",
+			b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n\x00\x00\x00\x04\x00\x00\x00\x00\x00",
+			b"SSH-2.0-OpenSSH_8.9\r\n",
+			b"USER alice\r\n",
+			b"PASS x\r\n",
+			b"EHLO mail.example.com\r\n",
+			b"a001 LOGIN alice secret\r\n",
+			b"*1\r\n$4\r\nPING\r\n",
+			b"\x16\x03\x01\x00\x2A\x01\x00\x00\x26\x03\x03",
+			b"\xFF\xFB\x01\xFF\xFB\x03\xFF\xFD\x18\xFF\xFD\x1F",
+			b"\x81\x85\x37\xFA\x21\x3D\x7F\x9F\x4D\x51\x58",
+			b"\x00\x00\x00\x0F\x09\x0A\x05users\x12\x05alice",
+			b"\x10\x00\x00\x00\x04\x0A\x05PLAIN\x12\x06\x00root\x00",
+			b"\x12\x34\x01\x00\x00\x01\x00\x00\x00\x00\x00\x00\x07example\x03com\x00\x00\x01\x00\x01",
+			b"\x00\x01\x00\x00\x21\x12\xA4\x42\x5A\x5A\x5A\x5A\x5A\x5A\x5A\x5A\x5A\x5A\x5A\x5A",
+			b"\x6A\x81\xB4\x30\x81\xB1\xA1\x03\x02\x01\x05\xA2\x03\x02\x01\x0A\xA3\x15\x30\x13",
+			b"\x02\xC5\x3C\x00\xAA\x55\x5A\x00\x00\x00\x01\x01{\"stat\":{}}",
+			&rr,
+		];
+		for preset in [ProtocolChainBuilder::all_tcp, ProtocolChainBuilder::all_udp] {
+			let chain = preset(ProtocolChainBuilder::new());
+			let others: Vec<Protocol> = Protocol::ALL
+				.iter()
+				.copied()
+				.filter(|protocol| !chain.order.contains(protocol))
+				.collect();
+			let chain = chain.build();
+			let builder = crate::ProtocolDetectorBuilder::<crate::Unknown>::new()
+				.all()
+				.exclude(&others)
+				.build();
+			for data in corpus {
+				assert_eq!(chain.detect(data), builder.detect(data), "{data:02X?}");
+			}
+		}
+	}
+
 	#[test]
 	#[cfg(feature = "http")]
 	fn all_tcp_detects_http() {
@@ -319,6 +1313,40 @@ mod tests {
 		assert_eq!(detector.detect(data).unwrap(), Some(Protocol::Http));
 	}
 
+	#[test]
+	#[cfg(all(feature = "smb", feature = "mqtt", feature = "rtsp"))]
+	fn presets_compose_without_duplicates() {
+		let detector = ProtocolChainBuilder::new()
+			.windows()
+			.iot()
+			.streaming()
+			.try_build()
+			.unwrap();
+		assert_eq!(
+			detector.detect(b"\xffSMB\x72\x00\x00\x00").unwrap(),
+			Some(Protocol::Smb)
+		);
+	}
+
+	#[test]
+	#[cfg(feature = "rtsp")]
+	fn overlapping_presets_skip_protocols_already_added() {
+		// Both presets include RTSP.
+		let detector = ProtocolChainBuilder::new()
+			.voip()
+			.streaming()
+			.try_build()
+			.unwrap();
+		assert_eq!(
+			detector
+				.detect(b"OPTIONS rtsp://example.com/media RTSP/1.0\r\n")
+				.unwrap(),
+			Some(Protocol::Rtsp)
+		);
+		let result = ProtocolChainBuilder::new().rtsp().streaming().try_build();
+		assert!(result.is_ok());
+	}
+
 	#[test]
 	#[cfg(feature = "http")]
 	fn from_slice_creates_chain() {
@@ -338,8 +1366,168 @@ mod tests {
 		assert_eq!(detector.detect(data).unwrap(), Some(Protocol::Http));
 	}
 
+	#[test]
+	#[cfg(all(feature = "smtp", feature = "ftp"))]
+	fn chain_orders_shared_greeting_protocols() {
+		let data = b"220 mail.example.com ESMTP Postfix\r\n";
+		let smtp_first = ProtocolChainBuilder::new().smtp().ftp().build();
+		assert_eq!(smtp_first.detect(data).unwrap(), Some(Protocol::Smtp));
+		let ftp_first = ProtocolChainBuilder::new().ftp().smtp().build();
+		assert_eq!(ftp_first.detect(data).unwrap(), Some(Protocol::Ftp));
+	}
+
+	#[test]
+	#[cfg(feature = "ntp")]
+	fn ntp_method_adds_ntp() {
+		let detector = ProtocolChainBuilder::new().ntp().build();
+		let mut data = [0u8; 48];
+		data[0] = 0x23;
+		data[47] = 0x01;
+		assert_eq!(detector.detect(&data).unwrap(), Some(Protocol::Ntp));
+	}
+
+	#[test]
+	#[cfg(all(feature = "ssh", feature = "http"))]
+	fn try_build_accepts_valid_chain() {
+		let detector = ProtocolChainBuilder::new()
+			.tcp()
+			.ssh()
+			.http()
+			.try_build()
+			.unwrap();
+		let data = b"SSH-2.0-OpenSSH_8.9\r\n";
+		assert_eq!(detector.detect(data).unwrap(), Some(Protocol::Ssh));
+	}
+
+	#[test]
+	#[cfg(all(feature = "dns", feature = "sip"))]
+	fn try_build_accepts_dual_transport_protocols() {
+		assert!(
+			ProtocolChainBuilder::new()
+				.tcp()
+				.dns()
+				.sip()
+				.try_build()
+				.is_ok()
+		);
+		assert!(
+			ProtocolChainBuilder::new()
+				.udp()
+				.dns()
+				.sip()
+				.try_build()
+				.is_ok()
+		);
+	}
+
+	#[test]
+	#[cfg(all(feature = "ssh", feature = "http", feature = "redis"))]
+	fn without_removes_protocols_and_keeps_order() {
+		let detector = ProtocolChainBuilder::new()
+			.ssh()
+			.redis()
+			.http()
+			.without(Protocol::Redis)
+			.try_build()
+			.unwrap();
+		assert_eq!(detector.detect(b"*1\r\n$4\r\nPING\r\n").unwrap(), None);
+		assert_eq!(
+			detector.detect(b"GET / HTTP/1.1\r\n").unwrap(),
+			Some(Protocol::Http)
+		);
+
+		let empty = ProtocolChainBuilder::new().ssh().exclude(&[Protocol::Ssh]);
+		assert_eq!(empty.try_build().unwrap_err(), ChainError::Empty);
+	}
+
+	#[test]
+	#[cfg(all(feature = "ssh", feature = "http"))]
+	fn version_filter_applies_in_chain_order() {
+		let detector = ProtocolChainBuilder::new()
+			.ssh()
+			.http()
+			.http_version("1.1")
+			.build();
+		assert_eq!(
+			detector.detect(b"GET / HTTP/1.1\r\n").unwrap(),
+			Some(Protocol::Http)
+		);
+		assert_eq!(detector.detect(b"GET / HTTP/1.0\r\n").unwrap(), None);
+	}
+
+	#[test]
+	#[cfg(feature = "tls")]
+	fn version_method_adds_missing_protocol_once() {
+		let detector = ProtocolChainBuilder::new()
+			.tls_version("1.3")
+			.tls_version("1.2")
+			.try_build()
+			.unwrap();
+		let hello = [
+			0x16, 0x03, 0x01, 0x00, 0x2E, 0x01, 0x00, 0x00, 0x2A, 0x03, 0x03,
+		];
+		assert_eq!(detector.detect(&hello).unwrap(), Some(Protocol::Tls));
+	}
+
 	// ── Error paths ──
 
+	#[test]
+	#[cfg(all(feature = "http", feature = "tls"))]
+	fn budget_applies_to_chain_order() {
+		let detector = ProtocolChainBuilder::new()
+			.tls()
+			.http()
+			.budget(DetectionBudget::new().probes(1))
+			.build();
+		assert_eq!(
+			detector.detect(b"GET / HTTP/1.1\r\n"),
+			Err(DetectionError::BudgetExceeded)
+		);
+	}
+
+	#[test]
+	fn try_build_rejects_empty_chain() {
+		assert_eq!(
+			ProtocolChainBuilder::new().try_build().unwrap_err(),
+			ChainError::Empty
+		);
+	}
+
+	#[test]
+	#[cfg(all(feature = "http", feature = "tls"))]
+	fn try_build_rejects_duplicates() {
+		let result = ProtocolChainBuilder::new().http().tls().http().try_build();
+		assert_eq!(result.unwrap_err(), ChainError::Duplicate(Protocol::Http));
+	}
+
+	#[test]
+	#[cfg(all(feature = "http", feature = "quic"))]
+	fn try_build_rejects_transport_mismatch() {
+		let err = ProtocolChainBuilder::new()
+			.tcp()
+			.http()
+			.quic()
+			.try_build()
+			.unwrap_err();
+		assert_eq!(
+			err,
+			ChainError::TransportMismatch {
+				protocol: Protocol::Quic,
+				transport: "TCP",
+			}
+		);
+		assert_eq!(err.to_string(), "protocol Quic does not run over TCP");
+
+		let err = ProtocolChainBuilder::new().udp().http().try_build();
+		assert!(matches!(
+			err,
+			Err(ChainError::TransportMismatch {
+				protocol: Protocol::Http,
+				..
+			})
+		));
+	}
+
 	#[test]
 	fn empty_chain_returns_none() {
 		let detector = ProtocolChainBuilder::new().build();
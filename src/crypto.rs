@@ -0,0 +1,370 @@
+/* src/crypto.rs */
+
+/// SHA-256 round constants: the first 32 bits of the fractional parts of the
+/// cube roots of the first 64 primes.
+const SHA256_CONSTANTS: [u32; 64] = [
+	0x428a_2f98,
+	0x7137_4491,
+	0xb5c0_fbcf,
+	0xe9b5_dba5,
+	0x3956_c25b,
+	0x59f1_11f1,
+	0x923f_82a4,
+	0xab1c_5ed5,
+	0xd807_aa98,
+	0x1283_5b01,
+	0x2431_85be,
+	0x550c_7dc3,
+	0x72be_5d74,
+	0x80de_b1fe,
+	0x9bdc_06a7,
+	0xc19b_f174,
+	0xe49b_69c1,
+	0xefbe_4786,
+	0x0fc1_9dc6,
+	0x240c_a1cc,
+	0x2de9_2c6f,
+	0x4a74_84aa,
+	0x5cb0_a9dc,
+	0x76f9_88da,
+	0x983e_5152,
+	0xa831_c66d,
+	0xb003_27c8,
+	0xbf59_7fc7,
+	0xc6e0_0bf3,
+	0xd5a7_9147,
+	0x06ca_6351,
+	0x1429_2967,
+	0x27b7_0a85,
+	0x2e1b_2138,
+	0x4d2c_6dfc,
+	0x5338_0d13,
+	0x650a_7354,
+	0x766a_0abb,
+	0x81c2_c92e,
+	0x9272_2c85,
+	0xa2bf_e8a1,
+	0xa81a_664b,
+	0xc24b_8b70,
+	0xc76c_51a3,
+	0xd192_e819,
+	0xd699_0624,
+	0xf40e_3585,
+	0x106a_a070,
+	0x19a4_c116,
+	0x1e37_6c08,
+	0x2748_774c,
+	0x34b0_bcb5,
+	0x391c_0cb3,
+	0x4ed8_aa4a,
+	0x5b9c_ca4f,
+	0x682e_6ff3,
+	0x748f_82ee,
+	0x78a5_636f,
+	0x84c8_7814,
+	0x8cc7_0208,
+	0x90be_fffa,
+	0xa450_6ceb,
+	0xbef9_a3f7,
+	0xc671_78f2,
+];
+
+/// The AES S-box.
+const AES_SBOX: [u8; 256] = [
+	0x63, 0x7c, 0x77, 0x7b, 0xf2, 0x6b, 0x6f, 0xc5, 0x30, 0x01, 0x67, 0x2b, 0xfe, 0xd7, 0xab, 0x76,
+	0xca, 0x82, 0xc9, 0x7d, 0xfa, 0x59, 0x47, 0xf0, 0xad, 0xd4, 0xa2, 0xaf, 0x9c, 0xa4, 0x72, 0xc0,
+	0xb7, 0xfd, 0x93, 0x26, 0x36, 0x3f, 0xf7, 0xcc, 0x34, 0xa5, 0xe5, 0xf1, 0x71, 0xd8, 0x31, 0x15,
+	0x04, 0xc7, 0x23, 0xc3, 0x18, 0x96, 0x05, 0x9a, 0x07, 0x12, 0x80, 0xe2, 0xeb, 0x27, 0xb2, 0x75,
+	0x09, 0x83, 0x2c, 0x1a, 0x1b, 0x6e, 0x5a, 0xa0, 0x52, 0x3b, 0xd6, 0xb3, 0x29, 0xe3, 0x2f, 0x84,
+	0x53, 0xd1, 0x00, 0xed, 0x20, 0xfc, 0xb1, 0x5b, 0x6a, 0xcb, 0xbe, 0x39, 0x4a, 0x4c, 0x58, 0xcf,
+	0xd0, 0xef, 0xaa, 0xfb, 0x43, 0x4d, 0x33, 0x85, 0x45, 0xf9, 0x02, 0x7f, 0x50, 0x3c, 0x9f, 0xa8,
+	0x51, 0xa3, 0x40, 0x8f, 0x92, 0x9d, 0x38, 0xf5, 0xbc, 0xb6, 0xda, 0x21, 0x10, 0xff, 0xf3, 0xd2,
+	0xcd, 0x0c, 0x13, 0xec, 0x5f, 0x97, 0x44, 0x17, 0xc4, 0xa7, 0x7e, 0x3d, 0x64, 0x5d, 0x19, 0x73,
+	0x60, 0x81, 0x4f, 0xdc, 0x22, 0x2a, 0x90, 0x88, 0x46, 0xee, 0xb8, 0x14, 0xde, 0x5e, 0x0b, 0xdb,
+	0xe0, 0x32, 0x3a, 0x0a, 0x49, 0x06, 0x24, 0x5c, 0xc2, 0xd3, 0xac, 0x62, 0x91, 0x95, 0xe4, 0x79,
+	0xe7, 0xc8, 0x37, 0x6d, 0x8d, 0xd5, 0x4e, 0xa9, 0x6c, 0x56, 0xf4, 0xea, 0x65, 0x7a, 0xae, 0x08,
+	0xba, 0x78, 0x25, 0x2e, 0x1c, 0xa6, 0xb4, 0xc6, 0xe8, 0xdd, 0x74, 0x1f, 0x4b, 0xbd, 0x8b, 0x8a,
+	0x70, 0x3e, 0xb5, 0x66, 0x48, 0x03, 0xf6, 0x0e, 0x61, 0x35, 0x57, 0xb9, 0x86, 0xc1, 0x1d, 0x9e,
+	0xe1, 0xf8, 0x98, 0x11, 0x69, 0xd9, 0x8e, 0x94, 0x9b, 0x1e, 0x87, 0xe9, 0xce, 0x55, 0x28, 0xdf,
+	0x8c, 0xa1, 0x89, 0x0d, 0xbf, 0xe6, 0x42, 0x68, 0x41, 0x99, 0x2d, 0x0f, 0xb0, 0x54, 0xbb, 0x16,
+];
+
+/// Round constants of the AES-128 key schedule.
+const AES_RCON: [u8; 10] = [0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0x1b, 0x36];
+
+/// Streaming SHA-256.
+#[derive(Debug, Clone)]
+struct Sha256 {
+	/// Chaining state.
+	state: [u32; 8],
+	/// Partial block awaiting compression.
+	block: [u8; 64],
+	/// Bytes buffered in `block`.
+	buffered: usize,
+	/// Total input length in bytes.
+	length: u64,
+}
+
+impl Sha256 {
+	/// Creates a hasher with the standard initial state.
+	const fn new() -> Self {
+		Self {
+			state: [
+				0x6a09_e667,
+				0xbb67_ae85,
+				0x3c6e_f372,
+				0xa54f_f53a,
+				0x510e_527f,
+				0x9b05_688c,
+				0x1f83_d9ab,
+				0x5be0_cd19,
+			],
+			block: [0; 64],
+			buffered: 0,
+			length: 0,
+		}
+	}
+
+	/// Appends `data` to the input.
+	fn update(&mut self, mut data: &[u8]) {
+		self.length = self.length.wrapping_add(data.len() as u64);
+		while !data.is_empty() {
+			let take = (64 - self.buffered).min(data.len());
+			self.block[self.buffered..self.buffered + take].copy_from_slice(&data[..take]);
+			self.buffered += take;
+			data = &data[take..];
+			if self.buffered == 64 {
+				self.compress();
+				self.buffered = 0;
+			}
+		}
+	}
+
+	/// Pads the input and returns the digest.
+	fn finalize(mut self) -> [u8; 32] {
+		let bits = self.length.wrapping_mul(8);
+		self.update(&[0x80]);
+		while self.buffered != 56 {
+			self.update(&[0]);
+		}
+		self.update(&bits.to_be_bytes());
+		let mut digest = [0; 32];
+		for (chunk, word) in digest.chunks_exact_mut(4).zip(self.state) {
+			chunk.copy_from_slice(&word.to_be_bytes());
+		}
+		digest
+	}
+
+	/// Compresses the buffered block into the state.
+	fn compress(&mut self) {
+		let mut w = [0u32; 64];
+		for (word, chunk) in w.iter_mut().zip(self.block.chunks_exact(4)) {
+			*word = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+		}
+		for i in 16..64 {
+			let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+			let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+			w[i] = w[i - 16]
+				.wrapping_add(s0)
+				.wrapping_add(w[i - 7])
+				.wrapping_add(s1);
+		}
+
+		let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = self.state;
+		for (constant, word) in SHA256_CONSTANTS.iter().zip(w) {
+			let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+			let choice = (e & f) ^ (!e & g);
+			let t1 = h
+				.wrapping_add(s1)
+				.wrapping_add(choice)
+				.wrapping_add(*constant)
+				.wrapping_add(word);
+			let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+			let majority = (a & b) ^ (a & c) ^ (b & c);
+			let t2 = s0.wrapping_add(majority);
+			h = g;
+			g = f;
+			f = e;
+			e = d.wrapping_add(t1);
+			d = c;
+			c = b;
+			b = a;
+			a = t1.wrapping_add(t2);
+		}
+		for (state, value) in self.state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+			*state = state.wrapping_add(value);
+		}
+	}
+}
+
+/// Computes HMAC-SHA256 over the concatenation of `parts`.
+fn hmac_sha256(key: &[u8], parts: &[&[u8]]) -> [u8; 32] {
+	let mut block = [0u8; 64];
+	if key.len() > 64 {
+		let mut hasher = Sha256::new();
+		hasher.update(key);
+		block[..32].copy_from_slice(&hasher.finalize());
+	} else {
+		block[..key.len()].copy_from_slice(key);
+	}
+
+	let mut inner = Sha256::new();
+	inner.update(&block.map(|b| b ^ 0x36));
+	for part in parts {
+		inner.update(part);
+	}
+	let mut outer = Sha256::new();
+	outer.update(&block.map(|b| b ^ 0x5c));
+	outer.update(&inner.finalize());
+	outer.finalize()
+}
+
+/// HKDF-Extract with SHA-256 (RFC 5869).
+pub(crate) fn hkdf_extract(salt: &[u8], ikm: &[u8]) -> [u8; 32] {
+	hmac_sha256(salt, &[ikm])
+}
+
+/// HKDF-Expand-Label from TLS 1.3 (RFC 8446, section 7.1) with an empty
+/// context, filling `out` (at most 32 bytes, a single HKDF block).
+pub(crate) fn hkdf_expand_label(secret: &[u8; 32], label: &[u8], out: &mut [u8]) {
+	debug_assert!(out.len() <= 32 && label.len() <= 249);
+	let length = (out.len() as u16).to_be_bytes();
+	let label_len = [(b"tls13 ".len() + label.len()) as u8];
+	let block = hmac_sha256(secret, &[&length, &label_len, b"tls13 ", label, &[0], &[1]]);
+	out.copy_from_slice(&block[..out.len()]);
+}
+
+/// AES-128 with an expanded key, encrypt direction only.
+#[derive(Debug, Clone)]
+pub(crate) struct Aes128 {
+	/// The eleven round keys.
+	round_keys: [[u8; 16]; 11],
+}
+
+impl Aes128 {
+	/// Expands `key` into the round keys.
+	pub(crate) fn new(key: &[u8; 16]) -> Self {
+		let mut round_keys = [[0u8; 16]; 11];
+		round_keys[0] = *key;
+		for round in 1..11 {
+			let previous = round_keys[round - 1];
+			let mut word = [previous[13], previous[14], previous[15], previous[12]];
+			for byte in &mut word {
+				*byte = AES_SBOX[usize::from(*byte)];
+			}
+			word[0] ^= AES_RCON[round - 1];
+			let next = &mut round_keys[round];
+			for i in 0..16 {
+				let feed = if i < 4 { word[i] } else { next[i - 4] };
+				next[i] = previous[i] ^ feed;
+			}
+		}
+		Self { round_keys }
+	}
+
+	/// Encrypts one block in place.
+	pub(crate) fn encrypt_block(&self, block: &mut [u8; 16]) {
+		add_round_key(block, &self.round_keys[0]);
+		for round_key in &self.round_keys[1..10] {
+			sub_bytes_shift_rows(block);
+			mix_columns(block);
+			add_round_key(block, round_key);
+		}
+		sub_bytes_shift_rows(block);
+		add_round_key(block, &self.round_keys[10]);
+	}
+}
+
+/// XORs a round key into the state.
+fn add_round_key(block: &mut [u8; 16], round_key: &[u8; 16]) {
+	for (byte, key) in block.iter_mut().zip(round_key) {
+		*byte ^= key;
+	}
+}
+
+/// Applies SubBytes and ShiftRows to the column-major state.
+fn sub_bytes_shift_rows(block: &mut [u8; 16]) {
+	let state = *block;
+	for column in 0..4 {
+		for row in 0..4 {
+			block[column * 4 + row] = AES_SBOX[usize::from(state[((column + row) % 4) * 4 + row])];
+		}
+	}
+}
+
+/// Applies MixColumns to the column-major state.
+fn mix_columns(block: &mut [u8; 16]) {
+	for column in block.chunks_exact_mut(4) {
+		let [a, b, c, d] = [column[0], column[1], column[2], column[3]];
+		let all = a ^ b ^ c ^ d;
+		column[0] ^= all ^ double(a ^ b);
+		column[1] ^= all ^ double(b ^ c);
+		column[2] ^= all ^ double(c ^ d);
+		column[3] ^= all ^ double(d ^ a);
+	}
+}
+
+/// Multiplies by x in GF(2^8).
+const fn double(byte: u8) -> u8 {
+	(byte << 1) ^ if byte & 0x80 == 0 { 0 } else { 0x1b }
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Decodes a hex string.
+	fn bytes<const N: usize>(hex: &str) -> [u8; N] {
+		let mut out = [0u8; N];
+		for (byte, pair) in out.iter_mut().zip(hex.as_bytes().chunks_exact(2)) {
+			*byte = u8::from_str_radix(core::str::from_utf8(pair).unwrap(), 16).unwrap();
+		}
+		out
+	}
+
+	#[test]
+	fn test_sha256() {
+		let mut hasher = Sha256::new();
+		hasher.update(b"abc");
+		assert_eq!(
+			hasher.finalize(),
+			bytes("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+		);
+		let mut hasher = Sha256::new();
+		hasher.update(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq");
+		assert_eq!(
+			hasher.finalize(),
+			bytes("248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1")
+		);
+	}
+
+	#[test]
+	fn test_aes128_block() {
+		// FIPS-197, appendix C.1.
+		let cipher = Aes128::new(&bytes("000102030405060708090a0b0c0d0e0f"));
+		let mut block = bytes("00112233445566778899aabbccddeeff");
+		cipher.encrypt_block(&mut block);
+		assert_eq!(block, bytes("69c4e0d86a7b0430d8cdb78070b4c55a"));
+	}
+
+	#[test]
+	fn test_quic_v1_initial_keys() {
+		// RFC 9001, appendix A.1.
+		let initial = hkdf_extract(
+			&bytes::<20>("38762cf7f55934b34d179ae6a4c80cadccbb7f0a"),
+			&bytes::<8>("8394c8f03e515708"),
+		);
+		let mut client = [0u8; 32];
+		hkdf_expand_label(&initial, b"client in", &mut client);
+		assert_eq!(
+			client,
+			bytes("c00cf151ca5be075ed0ebfb5c80323c42d6b7db67881289af4008f1f6c357aea")
+		);
+		let mut key = [0u8; 16];
+		hkdf_expand_label(&client, b"quic key", &mut key);
+		assert_eq!(key, bytes("1f369613dd76d5467730efcbe3b1a22d"));
+		let mut hp = [0u8; 16];
+		hkdf_expand_label(&client, b"quic hp", &mut hp);
+		assert_eq!(hp, bytes("9f50449e04a0e810283a1e9933adedd2"));
+	}
+}
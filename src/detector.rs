@@ -1,7 +1,10 @@
 /* src/detector.rs */
 use crate::{
-	DetectionError, DetectionResult, DetectionStatus, Protocol, ProtocolInfo, ProtocolVersion,
+	DetectionBudget, DetectionError, DetectionResult, DetectionStatus, Protocol, ProtocolInfo,
+	ProtocolVersion, budget::Meter,
 };
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
 use core::marker::PhantomData;
 
 /// Marker for TCP transport.
@@ -21,10 +24,12 @@ pub struct ProtocolDetector<Transport = Unknown> {
 	#[allow(dead_code)]
 	pub(crate) enabled: ProtocolSet,
 	/// Optional custom priority order for detection.
-	#[cfg(feature = "std")]
+	#[cfg(feature = "alloc")]
 	pub(crate) priority_order: Option<Vec<Protocol>>,
 	/// Maximum bytes to inspect.
 	pub(crate) max_inspect_bytes: usize,
+	/// Work limit per detection call.
+	pub(crate) budget: DetectionBudget,
 	/// Version constraints for detection.
 	#[allow(dead_code)]
 	pub(crate) expected_versions: ProtocolVersionSet,
@@ -92,6 +97,195 @@ pub(crate) struct ProtocolSet {
 	/// STUN enabled.
 	#[cfg(feature = "stun")]
 	pub stun: bool,
+	/// `gRPC` enabled.
+	#[cfg(feature = "grpc")]
+	pub grpc: bool,
+	/// NATS enabled.
+	#[cfg(feature = "nats")]
+	pub nats: bool,
+	/// Git enabled.
+	#[cfg(feature = "git")]
+	pub git: bool,
+	/// IPP enabled.
+	#[cfg(feature = "ipp")]
+	pub ipp: bool,
+	/// JetDirect enabled.
+	#[cfg(feature = "jetdirect")]
+	pub jetdirect: bool,
+	/// OPC UA enabled.
+	#[cfg(feature = "opcua")]
+	pub opcua: bool,
+	/// OpenVPN enabled.
+	#[cfg(feature = "openvpn")]
+	pub openvpn: bool,
+	/// BitTorrent enabled.
+	#[cfg(feature = "bittorrent")]
+	pub bittorrent: bool,
+	/// BitTorrent DHT enabled.
+	#[cfg(feature = "dht")]
+	pub dht: bool,
+	/// mDNS enabled.
+	#[cfg(feature = "mdns")]
+	pub mdns: bool,
+	/// NetBIOS enabled.
+	#[cfg(feature = "netbios")]
+	pub netbios: bool,
+	/// TURN enabled.
+	#[cfg(feature = "turn")]
+	pub turn: bool,
+	/// TDS enabled.
+	#[cfg(feature = "tds")]
+	pub tds: bool,
+	/// Riak enabled.
+	#[cfg(feature = "riak")]
+	pub riak: bool,
+	/// `MySQL` X Protocol enabled.
+	#[cfg(feature = "mysqlx")]
+	pub mysqlx: bool,
+	/// Minecraft enabled.
+	#[cfg(feature = "minecraft")]
+	pub minecraft: bool,
+	/// Source engine query enabled.
+	#[cfg(feature = "source_query")]
+	pub source_query: bool,
+	/// Graphite enabled.
+	#[cfg(feature = "graphite")]
+	pub graphite: bool,
+	/// `InfluxDB` line protocol enabled.
+	#[cfg(feature = "influx")]
+	pub influx: bool,
+	/// Thrift enabled.
+	#[cfg(feature = "thrift")]
+	pub thrift: bool,
+	/// AJP enabled.
+	#[cfg(feature = "ajp")]
+	pub ajp: bool,
+	/// NVMe/TCP enabled.
+	#[cfg(feature = "nvme_tcp")]
+	pub nvme_tcp: bool,
+	/// DICOM enabled.
+	#[cfg(feature = "dicom")]
+	pub dicom: bool,
+	/// DNSCrypt enabled.
+	#[cfg(feature = "dnscrypt")]
+	pub dnscrypt: bool,
+	/// VXLAN enabled.
+	#[cfg(feature = "vxlan")]
+	pub vxlan: bool,
+	/// GENEVE enabled.
+	#[cfg(feature = "geneve")]
+	pub geneve: bool,
+	/// Ethereum devp2p enabled.
+	#[cfg(feature = "devp2p")]
+	pub devp2p: bool,
+	/// JSON-RPC enabled.
+	#[cfg(feature = "jsonrpc")]
+	pub jsonrpc: bool,
+	/// MessagePack-RPC enabled.
+	#[cfg(feature = "msgpack_rpc")]
+	pub msgpack_rpc: bool,
+	/// LPD enabled.
+	#[cfg(feature = "lpd")]
+	pub lpd: bool,
+	/// HTTP/3 enabled.
+	#[cfg(feature = "http3")]
+	pub http3: bool,
+	/// gQUIC enabled.
+	#[cfg(feature = "gquic")]
+	pub gquic: bool,
+	/// SPDY enabled.
+	#[cfg(feature = "spdy")]
+	pub spdy: bool,
+	/// RakNet enabled.
+	#[cfg(feature = "raknet")]
+	pub raknet: bool,
+	/// MMS enabled.
+	#[cfg(feature = "mms")]
+	pub mms: bool,
+	/// LoRaWAN enabled.
+	#[cfg(feature = "lorawan")]
+	pub lorawan: bool,
+	/// WebSocket enabled.
+	#[cfg(feature = "websocket")]
+	pub websocket: bool,
+	/// Telnet enabled.
+	#[cfg(feature = "telnet")]
+	pub telnet: bool,
+	/// RDP enabled.
+	#[cfg(feature = "rdp")]
+	pub rdp: bool,
+	/// LDAP enabled.
+	#[cfg(feature = "ldap")]
+	pub ldap: bool,
+	/// Kerberos enabled.
+	#[cfg(feature = "kerberos")]
+	pub kerberos: bool,
+	/// IRC enabled.
+	#[cfg(feature = "irc")]
+	pub irc: bool,
+	/// XMPP enabled.
+	#[cfg(feature = "xmpp")]
+	pub xmpp: bool,
+	/// AMQP enabled.
+	#[cfg(feature = "amqp")]
+	pub amqp: bool,
+	/// Memcached enabled.
+	#[cfg(feature = "memcached")]
+	pub memcached: bool,
+	/// SOCKS4 enabled.
+	#[cfg(feature = "socks4")]
+	pub socks4: bool,
+	/// PROXY protocol enabled.
+	#[cfg(feature = "proxyproto")]
+	pub proxyproto: bool,
+	/// RTP enabled.
+	#[cfg(feature = "rtp")]
+	pub rtp: bool,
+	/// RTCP enabled.
+	#[cfg(feature = "rtcp")]
+	pub rtcp: bool,
+	/// RTMP enabled.
+	#[cfg(feature = "rtmp")]
+	pub rtmp: bool,
+	/// S7comm enabled.
+	#[cfg(feature = "s7")]
+	pub s7: bool,
+	/// ClickHouse enabled.
+	#[cfg(feature = "clickhouse")]
+	pub clickhouse: bool,
+	/// Tarantool enabled.
+	#[cfg(feature = "tarantool")]
+	pub tarantool: bool,
+	/// Subversion (`svnserve`) enabled.
+	#[cfg(feature = "svn")]
+	pub svn: bool,
+	/// ONC RPC (Sun RPC) enabled.
+	#[cfg(feature = "sunrpc")]
+	pub sunrpc: bool,
+	/// ZooKeeper enabled.
+	#[cfg(feature = "zookeeper")]
+	pub zookeeper: bool,
+	/// Mumble enabled.
+	#[cfg(feature = "mumble")]
+	pub mumble: bool,
+	/// Ident enabled.
+	#[cfg(feature = "ident")]
+	pub ident: bool,
+	/// SCGI enabled.
+	#[cfg(feature = "scgi")]
+	pub scgi: bool,
+	/// ICAP enabled.
+	#[cfg(feature = "icap")]
+	pub icap: bool,
+	/// RELP enabled.
+	#[cfg(feature = "relp")]
+	pub relp: bool,
+	/// SMPP enabled.
+	#[cfg(feature = "smpp")]
+	pub smpp: bool,
+	/// H.225 enabled.
+	#[cfg(feature = "h225")]
+	pub h225: bool,
 }
 
 /// A set of expected protocol versions.
@@ -111,281 +305,455 @@ pub(crate) struct ProtocolVersionSet {
 	pub redis: Option<u8>,
 }
 
+impl ProtocolSet {
+	/// Returns `true` if the protocol is enabled.
+	#[allow(dead_code, clippy::trivially_copy_pass_by_ref, clippy::unused_self)]
+	pub(crate) fn contains(&self, protocol: Protocol) -> bool {
+		match protocol {
+			#[cfg(feature = "http")]
+			Protocol::Http => self.http,
+			#[cfg(feature = "tls")]
+			Protocol::Tls => self.tls,
+			#[cfg(feature = "ssh")]
+			Protocol::Ssh => self.ssh,
+			#[cfg(feature = "dns")]
+			Protocol::Dns => self.dns,
+			#[cfg(feature = "quic")]
+			Protocol::Quic => self.quic,
+			#[cfg(feature = "mysql")]
+			Protocol::Mysql => self.mysql,
+			#[cfg(feature = "postgres")]
+			Protocol::Postgres => self.postgres,
+			#[cfg(feature = "redis")]
+			Protocol::Redis => self.redis,
+			#[cfg(feature = "mqtt")]
+			Protocol::Mqtt => self.mqtt,
+			#[cfg(feature = "smtp")]
+			Protocol::Smtp => self.smtp,
+			#[cfg(feature = "pop3")]
+			Protocol::Pop3 => self.pop3,
+			#[cfg(feature = "imap")]
+			Protocol::Imap => self.imap,
+			#[cfg(feature = "ftp")]
+			Protocol::Ftp => self.ftp,
+			#[cfg(feature = "smb")]
+			Protocol::Smb => self.smb,
+			#[cfg(feature = "stun")]
+			Protocol::Stun => self.stun,
+			#[cfg(feature = "sip")]
+			Protocol::Sip => self.sip,
+			#[cfg(feature = "rtsp")]
+			Protocol::Rtsp => self.rtsp,
+			#[cfg(feature = "dhcp")]
+			Protocol::Dhcp => self.dhcp,
+			#[cfg(feature = "ntp")]
+			Protocol::Ntp => self.ntp,
+			#[cfg(feature = "grpc")]
+			Protocol::Grpc => self.grpc,
+			#[cfg(feature = "nats")]
+			Protocol::Nats => self.nats,
+			#[cfg(feature = "git")]
+			Protocol::Git => self.git,
+			#[cfg(feature = "ipp")]
+			Protocol::Ipp => self.ipp,
+			#[cfg(feature = "jetdirect")]
+			Protocol::JetDirect => self.jetdirect,
+			#[cfg(feature = "opcua")]
+			Protocol::OpcUa => self.opcua,
+			#[cfg(feature = "openvpn")]
+			Protocol::OpenVpn => self.openvpn,
+			#[cfg(feature = "bittorrent")]
+			Protocol::BitTorrent => self.bittorrent,
+			#[cfg(feature = "dht")]
+			Protocol::Dht => self.dht,
+			#[cfg(feature = "mdns")]
+			Protocol::Mdns => self.mdns,
+			#[cfg(feature = "netbios")]
+			Protocol::NetBios => self.netbios,
+			#[cfg(feature = "turn")]
+			Protocol::Turn => self.turn,
+			#[cfg(feature = "tds")]
+			Protocol::Tds => self.tds,
+			#[cfg(feature = "riak")]
+			Protocol::Riak => self.riak,
+			#[cfg(feature = "mysqlx")]
+			Protocol::Mysqlx => self.mysqlx,
+			#[cfg(feature = "minecraft")]
+			Protocol::Minecraft => self.minecraft,
+			#[cfg(feature = "source_query")]
+			Protocol::SourceQuery => self.source_query,
+			#[cfg(feature = "graphite")]
+			Protocol::Graphite => self.graphite,
+			#[cfg(feature = "influx")]
+			Protocol::Influx => self.influx,
+			#[cfg(feature = "thrift")]
+			Protocol::Thrift => self.thrift,
+			#[cfg(feature = "ajp")]
+			Protocol::Ajp => self.ajp,
+			#[cfg(feature = "nvme_tcp")]
+			Protocol::NvmeTcp => self.nvme_tcp,
+			#[cfg(feature = "dicom")]
+			Protocol::Dicom => self.dicom,
+			#[cfg(feature = "dnscrypt")]
+			Protocol::DnsCrypt => self.dnscrypt,
+			#[cfg(feature = "vxlan")]
+			Protocol::Vxlan => self.vxlan,
+			#[cfg(feature = "geneve")]
+			Protocol::Geneve => self.geneve,
+			#[cfg(feature = "devp2p")]
+			Protocol::Devp2p => self.devp2p,
+			#[cfg(feature = "jsonrpc")]
+			Protocol::JsonRpc => self.jsonrpc,
+			#[cfg(feature = "msgpack_rpc")]
+			Protocol::MsgpackRpc => self.msgpack_rpc,
+			#[cfg(feature = "lpd")]
+			Protocol::Lpd => self.lpd,
+			#[cfg(feature = "http3")]
+			Protocol::Http3 => self.http3,
+			#[cfg(feature = "gquic")]
+			Protocol::Gquic => self.gquic,
+			#[cfg(feature = "spdy")]
+			Protocol::Spdy => self.spdy,
+			#[cfg(feature = "raknet")]
+			Protocol::RakNet => self.raknet,
+			#[cfg(feature = "mms")]
+			Protocol::Mms => self.mms,
+			#[cfg(feature = "lorawan")]
+			Protocol::LoRaWan => self.lorawan,
+			#[cfg(feature = "websocket")]
+			Protocol::WebSocket => self.websocket,
+			#[cfg(feature = "telnet")]
+			Protocol::Telnet => self.telnet,
+			#[cfg(feature = "rdp")]
+			Protocol::Rdp => self.rdp,
+			#[cfg(feature = "ldap")]
+			Protocol::Ldap => self.ldap,
+			#[cfg(feature = "kerberos")]
+			Protocol::Kerberos => self.kerberos,
+			#[cfg(feature = "irc")]
+			Protocol::Irc => self.irc,
+			#[cfg(feature = "xmpp")]
+			Protocol::Xmpp => self.xmpp,
+			#[cfg(feature = "amqp")]
+			Protocol::Amqp => self.amqp,
+			#[cfg(feature = "memcached")]
+			Protocol::Memcached => self.memcached,
+			#[cfg(feature = "socks4")]
+			Protocol::Socks4 => self.socks4,
+			#[cfg(feature = "proxyproto")]
+			Protocol::ProxyProtocol => self.proxyproto,
+			#[cfg(feature = "rtp")]
+			Protocol::Rtp => self.rtp,
+			#[cfg(feature = "rtcp")]
+			Protocol::Rtcp => self.rtcp,
+			#[cfg(feature = "rtmp")]
+			Protocol::Rtmp => self.rtmp,
+			#[cfg(feature = "s7")]
+			Protocol::S7 => self.s7,
+			#[cfg(feature = "clickhouse")]
+			Protocol::ClickHouse => self.clickhouse,
+			#[cfg(feature = "tarantool")]
+			Protocol::Tarantool => self.tarantool,
+			#[cfg(feature = "svn")]
+			Protocol::Svn => self.svn,
+			#[cfg(feature = "sunrpc")]
+			Protocol::SunRpc => self.sunrpc,
+			#[cfg(feature = "zookeeper")]
+			Protocol::Zookeeper => self.zookeeper,
+			#[cfg(feature = "mumble")]
+			Protocol::Mumble => self.mumble,
+			#[cfg(feature = "ident")]
+			Protocol::Ident => self.ident,
+			#[cfg(feature = "scgi")]
+			Protocol::Scgi => self.scgi,
+			#[cfg(feature = "icap")]
+			Protocol::Icap => self.icap,
+			#[cfg(feature = "relp")]
+			Protocol::Relp => self.relp,
+			#[cfg(feature = "smpp")]
+			Protocol::Smpp => self.smpp,
+			#[cfg(feature = "h225")]
+			Protocol::H225 => self.h225,
+			#[allow(unreachable_patterns)]
+			_ => false,
+		}
+	}
+
+	/// Enables or disables the protocol.
+	#[allow(
+		dead_code,
+		unused_variables,
+		clippy::needless_pass_by_ref_mut,
+		clippy::unused_self
+	)]
+	pub(crate) fn set(&mut self, protocol: Protocol, enabled: bool) {
+		match protocol {
+			#[cfg(feature = "http")]
+			Protocol::Http => self.http = enabled,
+			#[cfg(feature = "tls")]
+			Protocol::Tls => self.tls = enabled,
+			#[cfg(feature = "ssh")]
+			Protocol::Ssh => self.ssh = enabled,
+			#[cfg(feature = "dns")]
+			Protocol::Dns => self.dns = enabled,
+			#[cfg(feature = "quic")]
+			Protocol::Quic => self.quic = enabled,
+			#[cfg(feature = "mysql")]
+			Protocol::Mysql => self.mysql = enabled,
+			#[cfg(feature = "postgres")]
+			Protocol::Postgres => self.postgres = enabled,
+			#[cfg(feature = "redis")]
+			Protocol::Redis => self.redis = enabled,
+			#[cfg(feature = "mqtt")]
+			Protocol::Mqtt => self.mqtt = enabled,
+			#[cfg(feature = "smtp")]
+			Protocol::Smtp => self.smtp = enabled,
+			#[cfg(feature = "pop3")]
+			Protocol::Pop3 => self.pop3 = enabled,
+			#[cfg(feature = "imap")]
+			Protocol::Imap => self.imap = enabled,
+			#[cfg(feature = "ftp")]
+			Protocol::Ftp => self.ftp = enabled,
+			#[cfg(feature = "smb")]
+			Protocol::Smb => self.smb = enabled,
+			#[cfg(feature = "stun")]
+			Protocol::Stun => self.stun = enabled,
+			#[cfg(feature = "sip")]
+			Protocol::Sip => self.sip = enabled,
+			#[cfg(feature = "rtsp")]
+			Protocol::Rtsp => self.rtsp = enabled,
+			#[cfg(feature = "dhcp")]
+			Protocol::Dhcp => self.dhcp = enabled,
+			#[cfg(feature = "ntp")]
+			Protocol::Ntp => self.ntp = enabled,
+			#[cfg(feature = "grpc")]
+			Protocol::Grpc => self.grpc = enabled,
+			#[cfg(feature = "nats")]
+			Protocol::Nats => self.nats = enabled,
+			#[cfg(feature = "git")]
+			Protocol::Git => self.git = enabled,
+			#[cfg(feature = "ipp")]
+			Protocol::Ipp => self.ipp = enabled,
+			#[cfg(feature = "jetdirect")]
+			Protocol::JetDirect => self.jetdirect = enabled,
+			#[cfg(feature = "opcua")]
+			Protocol::OpcUa => self.opcua = enabled,
+			#[cfg(feature = "openvpn")]
+			Protocol::OpenVpn => self.openvpn = enabled,
+			#[cfg(feature = "bittorrent")]
+			Protocol::BitTorrent => self.bittorrent = enabled,
+			#[cfg(feature = "dht")]
+			Protocol::Dht => self.dht = enabled,
+			#[cfg(feature = "mdns")]
+			Protocol::Mdns => self.mdns = enabled,
+			#[cfg(feature = "netbios")]
+			Protocol::NetBios => self.netbios = enabled,
+			#[cfg(feature = "turn")]
+			Protocol::Turn => self.turn = enabled,
+			#[cfg(feature = "tds")]
+			Protocol::Tds => self.tds = enabled,
+			#[cfg(feature = "riak")]
+			Protocol::Riak => self.riak = enabled,
+			#[cfg(feature = "mysqlx")]
+			Protocol::Mysqlx => self.mysqlx = enabled,
+			#[cfg(feature = "minecraft")]
+			Protocol::Minecraft => self.minecraft = enabled,
+			#[cfg(feature = "source_query")]
+			Protocol::SourceQuery => self.source_query = enabled,
+			#[cfg(feature = "graphite")]
+			Protocol::Graphite => self.graphite = enabled,
+			#[cfg(feature = "influx")]
+			Protocol::Influx => self.influx = enabled,
+			#[cfg(feature = "thrift")]
+			Protocol::Thrift => self.thrift = enabled,
+			#[cfg(feature = "ajp")]
+			Protocol::Ajp => self.ajp = enabled,
+			#[cfg(feature = "nvme_tcp")]
+			Protocol::NvmeTcp => self.nvme_tcp = enabled,
+			#[cfg(feature = "dicom")]
+			Protocol::Dicom => self.dicom = enabled,
+			#[cfg(feature = "dnscrypt")]
+			Protocol::DnsCrypt => self.dnscrypt = enabled,
+			#[cfg(feature = "vxlan")]
+			Protocol::Vxlan => self.vxlan = enabled,
+			#[cfg(feature = "geneve")]
+			Protocol::Geneve => self.geneve = enabled,
+			#[cfg(feature = "devp2p")]
+			Protocol::Devp2p => self.devp2p = enabled,
+			#[cfg(feature = "jsonrpc")]
+			Protocol::JsonRpc => self.jsonrpc = enabled,
+			#[cfg(feature = "msgpack_rpc")]
+			Protocol::MsgpackRpc => self.msgpack_rpc = enabled,
+			#[cfg(feature = "lpd")]
+			Protocol::Lpd => self.lpd = enabled,
+			#[cfg(feature = "http3")]
+			Protocol::Http3 => self.http3 = enabled,
+			#[cfg(feature = "gquic")]
+			Protocol::Gquic => self.gquic = enabled,
+			#[cfg(feature = "spdy")]
+			Protocol::Spdy => self.spdy = enabled,
+			#[cfg(feature = "raknet")]
+			Protocol::RakNet => self.raknet = enabled,
+			#[cfg(feature = "mms")]
+			Protocol::Mms => self.mms = enabled,
+			#[cfg(feature = "lorawan")]
+			Protocol::LoRaWan => self.lorawan = enabled,
+			#[cfg(feature = "websocket")]
+			Protocol::WebSocket => self.websocket = enabled,
+			#[cfg(feature = "telnet")]
+			Protocol::Telnet => self.telnet = enabled,
+			#[cfg(feature = "rdp")]
+			Protocol::Rdp => self.rdp = enabled,
+			#[cfg(feature = "ldap")]
+			Protocol::Ldap => self.ldap = enabled,
+			#[cfg(feature = "kerberos")]
+			Protocol::Kerberos => self.kerberos = enabled,
+			#[cfg(feature = "irc")]
+			Protocol::Irc => self.irc = enabled,
+			#[cfg(feature = "xmpp")]
+			Protocol::Xmpp => self.xmpp = enabled,
+			#[cfg(feature = "amqp")]
+			Protocol::Amqp => self.amqp = enabled,
+			#[cfg(feature = "memcached")]
+			Protocol::Memcached => self.memcached = enabled,
+			#[cfg(feature = "socks4")]
+			Protocol::Socks4 => self.socks4 = enabled,
+			#[cfg(feature = "proxyproto")]
+			Protocol::ProxyProtocol => self.proxyproto = enabled,
+			#[cfg(feature = "rtp")]
+			Protocol::Rtp => self.rtp = enabled,
+			#[cfg(feature = "rtcp")]
+			Protocol::Rtcp => self.rtcp = enabled,
+			#[cfg(feature = "rtmp")]
+			Protocol::Rtmp => self.rtmp = enabled,
+			#[cfg(feature = "s7")]
+			Protocol::S7 => self.s7 = enabled,
+			#[cfg(feature = "clickhouse")]
+			Protocol::ClickHouse => self.clickhouse = enabled,
+			#[cfg(feature = "tarantool")]
+			Protocol::Tarantool => self.tarantool = enabled,
+			#[cfg(feature = "svn")]
+			Protocol::Svn => self.svn = enabled,
+			#[cfg(feature = "sunrpc")]
+			Protocol::SunRpc => self.sunrpc = enabled,
+			#[cfg(feature = "zookeeper")]
+			Protocol::Zookeeper => self.zookeeper = enabled,
+			#[cfg(feature = "mumble")]
+			Protocol::Mumble => self.mumble = enabled,
+			#[cfg(feature = "ident")]
+			Protocol::Ident => self.ident = enabled,
+			#[cfg(feature = "scgi")]
+			Protocol::Scgi => self.scgi = enabled,
+			#[cfg(feature = "icap")]
+			Protocol::Icap => self.icap = enabled,
+			#[cfg(feature = "relp")]
+			Protocol::Relp => self.relp = enabled,
+			#[cfg(feature = "smpp")]
+			Protocol::Smpp => self.smpp = enabled,
+			#[cfg(feature = "h225")]
+			Protocol::H225 => self.h225 = enabled,
+			#[allow(unreachable_patterns)]
+			_ => {}
+		}
+	}
+}
+
 impl<Transport> ProtocolDetector<Transport> {
 	/// Detects the protocol and returns its information.
 	///
 	/// # Errors
 	///
-	/// Returns `InsufficientData` if more bytes are needed to confirm a protocol.
-	#[allow(unused_variables, unused_mut)]
+	/// Returns `InsufficientData` if more bytes are needed to confirm a protocol,
+	/// and `BudgetExceeded` if the detector's budget ran out first.
 	pub fn detect_info<'a>(&self, data: &'a [u8]) -> DetectionResult<Option<ProtocolInfo<'a>>> {
+		#[cfg(feature = "metrics")]
+		let start = std::time::Instant::now();
+		let result = self.probe_all(data, &mut self.budget.start());
+		#[cfg(feature = "tracing")]
+		trace_summary(data.len(), &result);
+		#[cfg(feature = "metrics")]
+		record_metrics(start, &result);
+		result
+	}
+
+	/// Probes every enabled protocol in detection order.
+	fn probe_all<'a>(
+		&self,
+		data: &'a [u8],
+		meter: &mut Meter,
+	) -> DetectionResult<Option<ProtocolInfo<'a>>> {
 		let limit = data.len().min(self.max_inspect_bytes);
 		let data = &data[..limit];
 
-		let mut any_incomplete = false;
-
-		#[cfg(feature = "std")]
+		#[cfg(feature = "alloc")]
 		if let Some(order) = &self.priority_order {
-			for protocol in order {
-				match self.check_protocol(*protocol, data) {
-					(DetectionStatus::Match, version) => {
-						return Ok(Some(ProtocolInfo {
-							protocol: *protocol,
-							version,
-						}));
-					}
-					(DetectionStatus::Incomplete, _) => any_incomplete = true,
-					(DetectionStatus::NoMatch, _) => {}
-				}
-			}
-			return if any_incomplete {
-				Err(DetectionError::InsufficientData)
-			} else {
-				Ok(None)
-			};
+			return self.probe_in_order(order, data, meter);
 		}
+		self.probe_in_order(Protocol::DETECTION_ORDER, data, meter)
+	}
 
-		// Default detection logic (no Vec allocation)
-		#[cfg(feature = "ssh")]
-		if self.enabled.ssh {
-			match self.check_protocol(Protocol::Ssh, data) {
-				(DetectionStatus::Match, version) => {
-					return Ok(Some(ProtocolInfo {
-						protocol: Protocol::Ssh,
-						version,
-					}));
-				}
-				(DetectionStatus::Incomplete, _) => any_incomplete = true,
-				(DetectionStatus::NoMatch, _) => {}
-			}
-		}
-		#[cfg(feature = "sip")]
-		if self.enabled.sip {
-			match self.check_protocol(Protocol::Sip, data) {
-				(DetectionStatus::Match, version) => {
-					return Ok(Some(ProtocolInfo {
-						protocol: Protocol::Sip,
-						version,
-					}));
-				}
-				(DetectionStatus::Incomplete, _) => any_incomplete = true,
-				(DetectionStatus::NoMatch, _) => {}
-			}
-		}
-		#[cfg(feature = "rtsp")]
-		if self.enabled.rtsp {
-			match self.check_protocol(Protocol::Rtsp, data) {
-				(DetectionStatus::Match, version) => {
-					return Ok(Some(ProtocolInfo {
-						protocol: Protocol::Rtsp,
-						version,
-					}));
-				}
-				(DetectionStatus::Incomplete, _) => any_incomplete = true,
-				(DetectionStatus::NoMatch, _) => {}
-			}
-		}
-		#[cfg(feature = "imap")]
-		if self.enabled.imap {
-			match self.check_protocol(Protocol::Imap, data) {
-				(DetectionStatus::Match, version) => {
-					return Ok(Some(ProtocolInfo {
-						protocol: Protocol::Imap,
-						version,
-					}));
-				}
-				(DetectionStatus::Incomplete, _) => any_incomplete = true,
-				(DetectionStatus::NoMatch, _) => {}
-			}
-		}
-		#[cfg(feature = "tls")]
-		if self.enabled.tls {
-			match self.check_protocol(Protocol::Tls, data) {
-				(DetectionStatus::Match, version) => {
-					return Ok(Some(ProtocolInfo {
-						protocol: Protocol::Tls,
-						version,
-					}));
-				}
-				(DetectionStatus::Incomplete, _) => any_incomplete = true,
-				(DetectionStatus::NoMatch, _) => {}
-			}
-		}
-		#[cfg(feature = "http")]
-		if self.enabled.http {
-			match self.check_protocol(Protocol::Http, data) {
-				(DetectionStatus::Match, version) => {
-					return Ok(Some(ProtocolInfo {
-						protocol: Protocol::Http,
-						version,
-					}));
-				}
-				(DetectionStatus::Incomplete, _) => any_incomplete = true,
-				(DetectionStatus::NoMatch, _) => {}
-			}
-		}
-		#[cfg(feature = "dns")]
-		if self.enabled.dns {
-			match self.check_protocol(Protocol::Dns, data) {
-				(DetectionStatus::Match, version) => {
-					return Ok(Some(ProtocolInfo {
-						protocol: Protocol::Dns,
-						version,
-					}));
-				}
-				(DetectionStatus::Incomplete, _) => any_incomplete = true,
-				(DetectionStatus::NoMatch, _) => {}
-			}
-		}
-		#[cfg(feature = "smtp")]
-		if self.enabled.smtp {
-			match self.check_protocol(Protocol::Smtp, data) {
-				(DetectionStatus::Match, version) => {
-					return Ok(Some(ProtocolInfo {
-						protocol: Protocol::Smtp,
-						version,
-					}));
-				}
-				(DetectionStatus::Incomplete, _) => any_incomplete = true,
-				(DetectionStatus::NoMatch, _) => {}
-			}
-		}
-		#[cfg(feature = "ftp")]
-		if self.enabled.ftp {
-			match self.check_protocol(Protocol::Ftp, data) {
-				(DetectionStatus::Match, version) => {
-					return Ok(Some(ProtocolInfo {
-						protocol: Protocol::Ftp,
-						version,
-					}));
-				}
-				(DetectionStatus::Incomplete, _) => any_incomplete = true,
-				(DetectionStatus::NoMatch, _) => {}
-			}
-		}
-		#[cfg(feature = "dhcp")]
-		if self.enabled.dhcp {
-			match self.check_protocol(Protocol::Dhcp, data) {
-				(DetectionStatus::Match, version) => {
-					return Ok(Some(ProtocolInfo {
-						protocol: Protocol::Dhcp,
-						version,
-					}));
-				}
-				(DetectionStatus::Incomplete, _) => any_incomplete = true,
-				(DetectionStatus::NoMatch, _) => {}
-			}
-		}
-		#[cfg(feature = "ntp")]
-		if self.enabled.ntp {
-			match self.check_protocol(Protocol::Ntp, data) {
-				(DetectionStatus::Match, version) => {
-					return Ok(Some(ProtocolInfo {
-						protocol: Protocol::Ntp,
-						version,
-					}));
-				}
-				(DetectionStatus::Incomplete, _) => any_incomplete = true,
-				(DetectionStatus::NoMatch, _) => {}
-			}
-		}
-		#[cfg(feature = "quic")]
-		if self.enabled.quic {
-			match self.check_protocol(Protocol::Quic, data) {
-				(DetectionStatus::Match, version) => {
-					return Ok(Some(ProtocolInfo {
-						protocol: Protocol::Quic,
-						version,
-					}));
-				}
-				(DetectionStatus::Incomplete, _) => any_incomplete = true,
-				(DetectionStatus::NoMatch, _) => {}
-			}
-		}
-		#[cfg(feature = "mysql")]
-		if self.enabled.mysql {
-			match self.check_protocol(Protocol::Mysql, data) {
-				(DetectionStatus::Match, version) => {
-					return Ok(Some(ProtocolInfo {
-						protocol: Protocol::Mysql,
-						version,
-					}));
-				}
-				(DetectionStatus::Incomplete, _) => any_incomplete = true,
-				(DetectionStatus::NoMatch, _) => {}
-			}
-		}
-		#[cfg(feature = "postgres")]
-		if self.enabled.postgres {
-			match self.check_protocol(Protocol::Postgres, data) {
-				(DetectionStatus::Match, version) => {
-					return Ok(Some(ProtocolInfo {
-						protocol: Protocol::Postgres,
-						version,
-					}));
-				}
-				(DetectionStatus::Incomplete, _) => any_incomplete = true,
-				(DetectionStatus::NoMatch, _) => {}
+	/// Probes the enabled protocols of `order` in turn over data already cut
+	/// to the inspection window.
+	///
+	/// Two protocols deviate from the order. A WebSocket probe on data that
+	/// does not open with a `GET` handshake looks for bare frames, which are
+	/// probed after every other protocol. An HTTP/2 preface is held back as
+	/// undecided while gRPC, probed ahead of HTTP, is still waiting for its
+	/// HEADERS frame and the window has room left.
+	#[allow(unused_variables, unused_mut, clippy::needless_pass_by_ref_mut)]
+	fn probe_in_order<'a>(
+		&self,
+		order: &[Protocol],
+		data: &'a [u8],
+		meter: &mut Meter,
+	) -> DetectionResult<Option<ProtocolInfo<'a>>> {
+		let mut any_incomplete = false;
+		#[cfg(all(feature = "grpc", feature = "http"))]
+		let mut grpc_pending = false;
+		#[cfg(feature = "websocket")]
+		let mut websocket_frame = false;
+
+		for &protocol in order {
+			if !self.enabled.contains(protocol) {
+				continue;
 			}
-		}
-		#[cfg(feature = "redis")]
-		if self.enabled.redis {
-			match self.check_protocol(Protocol::Redis, data) {
-				(DetectionStatus::Match, version) => {
-					return Ok(Some(ProtocolInfo {
-						protocol: Protocol::Redis,
-						version,
-					}));
-				}
-				(DetectionStatus::Incomplete, _) => any_incomplete = true,
-				(DetectionStatus::NoMatch, _) => {}
+			#[cfg(feature = "websocket")]
+			if protocol == Protocol::WebSocket && data.first() != Some(&b'G') {
+				websocket_frame = true;
+				continue;
 			}
-		}
-		#[cfg(feature = "mqtt")]
-		if self.enabled.mqtt {
-			match self.check_protocol(Protocol::Mqtt, data) {
-				(DetectionStatus::Match, version) => {
-					return Ok(Some(ProtocolInfo {
-						protocol: Protocol::Mqtt,
-						version,
-					}));
+			match self.metered(meter, protocol, data)? {
+				#[cfg(all(feature = "grpc", feature = "http"))]
+				(DetectionStatus::Match, _)
+					if protocol == Protocol::Http
+						&& grpc_pending
+						&& data.starts_with(b"PRI ")
+						&& data.len() < self.max_inspect_bytes =>
+				{
+					any_incomplete = true;
+					#[cfg(feature = "alloc")]
+					meter.note_pending(protocol);
 				}
-				(DetectionStatus::Incomplete, _) => any_incomplete = true,
-				(DetectionStatus::NoMatch, _) => {}
-			}
-		}
-		#[cfg(feature = "pop3")]
-		if self.enabled.pop3 {
-			match self.check_protocol(Protocol::Pop3, data) {
 				(DetectionStatus::Match, version) => {
-					return Ok(Some(ProtocolInfo {
-						protocol: Protocol::Pop3,
-						version,
-					}));
+					return Ok(Some(ProtocolInfo { protocol, version }));
 				}
-				(DetectionStatus::Incomplete, _) => any_incomplete = true,
-				(DetectionStatus::NoMatch, _) => {}
-			}
-		}
-		#[cfg(feature = "smb")]
-		if self.enabled.smb {
-			match self.check_protocol(Protocol::Smb, data) {
-				(DetectionStatus::Match, version) => {
-					return Ok(Some(ProtocolInfo {
-						protocol: Protocol::Smb,
-						version,
-					}));
+				(DetectionStatus::Incomplete, _) => {
+					any_incomplete = true;
+					#[cfg(all(feature = "grpc", feature = "http"))]
+					if protocol == Protocol::Grpc {
+						grpc_pending = true;
+					}
 				}
-				(DetectionStatus::Incomplete, _) => any_incomplete = true,
 				(DetectionStatus::NoMatch, _) => {}
 			}
 		}
-		#[cfg(feature = "stun")]
-		if self.enabled.stun {
-			match self.check_protocol(Protocol::Stun, data) {
+
+		#[cfg(feature = "websocket")]
+		if websocket_frame {
+			match self.metered(meter, Protocol::WebSocket, data)? {
 				(DetectionStatus::Match, version) => {
 					return Ok(Some(ProtocolInfo {
-						protocol: Protocol::Stun,
+						protocol: Protocol::WebSocket,
 						version,
 					}));
 				}
@@ -401,6 +769,23 @@ impl<Transport> ProtocolDetector<Transport> {
 		}
 	}
 
+	/// Charges `meter` for one probe, then checks the protocol.
+	#[allow(dead_code)]
+	fn metered<'a>(
+		&self,
+		meter: &mut Meter,
+		protocol: Protocol,
+		data: &'a [u8],
+	) -> DetectionResult<(DetectionStatus, ProtocolVersion<'a>)> {
+		meter.charge(data.len())?;
+		let result = self.check_protocol(protocol, data);
+		#[cfg(feature = "alloc")]
+		if result.0 == DetectionStatus::Incomplete {
+			meter.note_pending(protocol);
+		}
+		Ok(result)
+	}
+
 	/// Internal helper to check a single protocol with version constraints.
 	#[allow(dead_code, clippy::collapsible_if, clippy::unused_self)]
 	fn check_protocol<'a>(
@@ -408,13 +793,18 @@ impl<Transport> ProtocolDetector<Transport> {
 		protocol: Protocol,
 		data: &'a [u8],
 	) -> (DetectionStatus, ProtocolVersion<'a>) {
+		#[cfg(feature = "tracing")]
+		let _span = tracing::trace_span!("probe", protocol = ?protocol).entered();
 		let (status, version) = protocol.probe_info(data);
+		#[cfg(feature = "defmt")]
+		defmt::trace!("guess: probe {} -> {}", protocol, status);
 		if status == DetectionStatus::Match {
 			match (protocol, &version) {
 				#[cfg(feature = "http")]
 				(Protocol::Http, ProtocolVersion::Http(v)) => {
 					if let Some(expected) = self.expected_versions.http {
 						if *v != expected {
+							reject!("version_mismatch");
 							return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
 						}
 					}
@@ -423,6 +813,7 @@ impl<Transport> ProtocolDetector<Transport> {
 				(Protocol::Tls, ProtocolVersion::Tls(v)) => {
 					if let Some(expected) = self.expected_versions.tls {
 						if *v != expected {
+							reject!("version_mismatch");
 							return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
 						}
 					}
@@ -431,6 +822,7 @@ impl<Transport> ProtocolDetector<Transport> {
 				(Protocol::Ssh, ProtocolVersion::Ssh(v)) => {
 					if let Some(expected) = self.expected_versions.ssh {
 						if *v != expected {
+							reject!("version_mismatch");
 							return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
 						}
 					}
@@ -439,6 +831,7 @@ impl<Transport> ProtocolDetector<Transport> {
 				(Protocol::Redis, ProtocolVersion::Redis(v)) => {
 					if let Some(expected) = self.expected_versions.redis {
 						if *v != expected {
+							reject!("version_mismatch");
 							return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
 						}
 					}
@@ -449,6 +842,121 @@ impl<Transport> ProtocolDetector<Transport> {
 		(status, version)
 	}
 
+	/// Detects the protocol, probing `hint` first when it is enabled.
+	///
+	/// An HTTP hint is probed together with the protocols ordered ahead of
+	/// HTTP because they refine it (gRPC, IPP, WebSocket), so a hinted port
+	/// reports them as it would without the hint.
+	#[cfg(feature = "packet")]
+	pub(crate) fn detect_info_hinted<'a>(
+		&self,
+		data: &'a [u8],
+		hint: Option<Protocol>,
+	) -> DetectionResult<Option<ProtocolInfo<'a>>> {
+		#[cfg(feature = "metrics")]
+		let start = std::time::Instant::now();
+		let mut meter = self.budget.start();
+		let result = match hint.filter(|p| self.enabled.contains(*p)) {
+			Some(protocol) => {
+				let limit = data.len().min(self.max_inspect_bytes);
+				let order = match protocol {
+					#[cfg(feature = "http")]
+					Protocol::Http => HTTP_FAMILY,
+					#[allow(unreachable_patterns)]
+					_ => core::slice::from_ref(&protocol),
+				};
+				match self.probe_in_order(order, &data[..limit], &mut meter) {
+					Ok(Some(info)) => Ok(Some(info)),
+					Err(DetectionError::BudgetExceeded) => Err(DetectionError::BudgetExceeded),
+					Ok(None) | Err(_) => self.probe_all(data, &mut meter),
+				}
+			}
+			None => self.probe_all(data, &mut meter),
+		};
+		#[cfg(feature = "tracing")]
+		trace_summary(data.len(), &result);
+		#[cfg(feature = "metrics")]
+		record_metrics(start, &result);
+		result
+	}
+
+	/// Detects the protocol like [`detect_info`](Self::detect_info), but reports
+	/// which protocols are still undecided when the data is insufficient.
+	///
+	/// # Errors
+	///
+	/// Returns [`IncompleteDetection`](crate::IncompleteDetection) listing the
+	/// pending protocols, in the order they were probed, and how many more
+	/// bytes each needs at minimum. If the budget ran out, the listing stops
+	/// at the last probe it allowed and `budget_exceeded` is set.
+	#[cfg(feature = "alloc")]
+	pub fn detect_verbose<'a>(
+		&self,
+		data: &'a [u8],
+	) -> Result<Option<ProtocolInfo<'a>>, crate::IncompleteDetection> {
+		#[cfg(feature = "metrics")]
+		let start = std::time::Instant::now();
+		let mut meter = self.budget.start().record_pending();
+		let result = self.probe_all(data, &mut meter);
+		#[cfg(feature = "tracing")]
+		trace_summary(data.len(), &result);
+		#[cfg(feature = "metrics")]
+		record_metrics(start, &result);
+		let budget_exceeded = match result {
+			Ok(found) => return Ok(found),
+			Err(error) => error == DetectionError::BudgetExceeded,
+		};
+		let limit = data.len().min(self.max_inspect_bytes);
+		let pending = meter
+			.take_pending()
+			.into_iter()
+			.map(|protocol| crate::PendingProtocol {
+				protocol,
+				needed: protocol.min_bytes().saturating_sub(limit).max(1),
+			})
+			.collect();
+		Err(crate::IncompleteDetection {
+			pending,
+			window_full: limit >= self.max_inspect_bytes,
+			budget_exceeded,
+		})
+	}
+
+	/// Returns a builder preloaded with this detector's configuration.
+	///
+	/// Use it to adjust a long-lived detector's policy, e.g.
+	/// `detector.to_builder().without(Protocol::Redis).build()`.
+	#[must_use]
+	pub fn to_builder(&self) -> crate::ProtocolDetectorBuilder<Transport> {
+		crate::ProtocolDetectorBuilder {
+			enabled: self.enabled,
+			max_inspect_bytes: self.max_inspect_bytes,
+			budget: self.budget,
+			expected_versions: self.expected_versions.clone(),
+			#[cfg(feature = "alloc")]
+			priority_order: self.priority_order.clone(),
+			_transport: PhantomData,
+		}
+	}
+
+	/// Detects the protocol, falling back to the [`ml`](crate::ml) classifier's
+	/// best guess when no enabled detector matches.
+	///
+	/// # Errors
+	///
+	/// Returns the errors of [`detect_info`](Self::detect_info); the classifier
+	/// only runs on a definite no-match.
+	#[cfg(feature = "ml")]
+	pub fn detect_or_classify<'a>(
+		&self,
+		data: &'a [u8],
+	) -> DetectionResult<Option<crate::ml::Classification<'a>>> {
+		Ok(match self.detect_info(data)? {
+			Some(info) => Some(crate::ml::Classification::Protocol(info)),
+			None => crate::ml::classify(data).map(crate::ml::Classification::Guess),
+		})
+	}
+
 	/// Backwards compatible detect method.
 	///
 	/// # Errors
@@ -461,64 +969,73 @@ impl<Transport> ProtocolDetector<Transport> {
 	}
 
 	/// Internal constructor for custom chains.
-	#[cfg(feature = "std")]
+	#[cfg(feature = "alloc")]
 	#[allow(unused_mut)]
-	pub(crate) fn with_order(order: Vec<Protocol>, max_inspect_bytes: usize) -> Self {
+	pub(crate) fn with_order(
+		order: Vec<Protocol>,
+		max_inspect_bytes: usize,
+		budget: DetectionBudget,
+		expected_versions: ProtocolVersionSet,
+	) -> Self {
 		let mut enabled = ProtocolSet::default();
 		for p in &order {
-			match p {
-				#[cfg(feature = "http")]
-				Protocol::Http => enabled.http = true,
-				#[cfg(feature = "tls")]
-				Protocol::Tls => enabled.tls = true,
-				#[cfg(feature = "ssh")]
-				Protocol::Ssh => enabled.ssh = true,
-				#[cfg(feature = "dns")]
-				Protocol::Dns => enabled.dns = true,
-				#[cfg(feature = "quic")]
-				Protocol::Quic => enabled.quic = true,
-				#[cfg(feature = "mysql")]
-				Protocol::Mysql => enabled.mysql = true,
-				#[cfg(feature = "postgres")]
-				Protocol::Postgres => enabled.postgres = true,
-				#[cfg(feature = "redis")]
-				Protocol::Redis => enabled.redis = true,
-				#[cfg(feature = "mqtt")]
-				Protocol::Mqtt => enabled.mqtt = true,
-				#[cfg(feature = "smtp")]
-				Protocol::Smtp => enabled.smtp = true,
-				#[cfg(feature = "pop3")]
-				Protocol::Pop3 => enabled.pop3 = true,
-				#[cfg(feature = "imap")]
-				Protocol::Imap => enabled.imap = true,
-				#[cfg(feature = "ftp")]
-				Protocol::Ftp => enabled.ftp = true,
-				#[cfg(feature = "smb")]
-				Protocol::Smb => enabled.smb = true,
-				#[cfg(feature = "stun")]
-				Protocol::Stun => enabled.stun = true,
-				#[cfg(feature = "sip")]
-				Protocol::Sip => enabled.sip = true,
-				#[cfg(feature = "rtsp")]
-				Protocol::Rtsp => enabled.rtsp = true,
-				#[cfg(feature = "dhcp")]
-				Protocol::Dhcp => enabled.dhcp = true,
-				#[cfg(feature = "ntp")]
-				Protocol::Ntp => enabled.ntp = true,
-				#[allow(unreachable_patterns)]
-				_ => {}
-			}
+			enabled.set(*p, true);
 		}
 		Self {
 			enabled,
 			priority_order: Some(order),
 			max_inspect_bytes,
-			expected_versions: ProtocolVersionSet::default(),
+			budget,
+			expected_versions,
 			_transport: PhantomData,
 		}
 	}
 }
 
+/// HTTP and the protocols probed ahead of it because they refine it, in
+/// detection order.
+#[cfg(all(feature = "packet", feature = "http"))]
+const HTTP_FAMILY: &[Protocol] = &[
+	#[cfg(feature = "grpc")]
+	Protocol::Grpc,
+	#[cfg(feature = "ipp")]
+	Protocol::Ipp,
+	#[cfg(feature = "websocket")]
+	Protocol::WebSocket,
+	Protocol::Http,
+];
+
+/// Emits the summary event for one detection call.
+#[cfg(feature = "tracing")]
+fn trace_summary(len: usize, result: &DetectionResult<Option<ProtocolInfo<'_>>>) {
+	match result {
+		Ok(Some(info)) => tracing::debug!(
+			bytes = len,
+			protocol = ?info.protocol,
+			version = ?info.version,
+			"detected"
+		),
+		Ok(None) => tracing::debug!(bytes = len, "no protocol matched"),
+		Err(error) => tracing::debug!(bytes = len, error = ?error, "detection inconclusive"),
+	}
+}
+
+/// Records the match counter and duration histogram for one detection call.
+#[cfg(feature = "metrics")]
+fn record_metrics(start: std::time::Instant, result: &DetectionResult<Option<ProtocolInfo<'_>>>) {
+	let outcome = match result {
+		Ok(Some(info)) => {
+			let protocol = format!("{:?}", info.protocol).to_ascii_lowercase();
+			metrics::counter!("guess.detect.match", "protocol" => protocol).increment(1);
+			"match"
+		}
+		Ok(None) => "no_match",
+		Err(DetectionError::BudgetExceeded) => "budget_exceeded",
+		Err(_) => "incomplete",
+	};
+	metrics::histogram!("guess.detect.duration", "outcome" => outcome).record(start.elapsed());
+}
+
 impl ProtocolDetector<Unknown> {
 	/// Creates a new builder.
 	#[must_use]
@@ -527,7 +1044,7 @@ impl ProtocolDetector<Unknown> {
 	}
 
 	/// Creates a new custom chain builder to define a specific detection order.
-	#[cfg(feature = "std")]
+	#[cfg(feature = "alloc")]
 	#[must_use]
 	pub fn chain() -> crate::ProtocolChainBuilder {
 		crate::ProtocolChainBuilder::new()
@@ -544,6 +1061,9 @@ mod tests {
 	use super::*;
 	#[allow(unused_imports)]
 	use crate::ProtocolDetectorBuilder;
+	#[cfg(feature = "alloc")]
+	#[allow(unused_imports)]
+	use alloc::string::ToString;
 
 	// ── Correct paths ──
 
@@ -638,8 +1158,309 @@ mod tests {
 		assert_eq!(detector.detect(&data).unwrap(), Some(Protocol::Http));
 	}
 
+	#[test]
+	#[cfg(all(feature = "http", feature = "tls"))]
+	fn to_builder_keeps_configuration() {
+		let detector = ProtocolDetectorBuilder::<Unknown>::new()
+			.http_version("1.1")
+			.tls()
+			.build();
+		let rebuilt = detector.to_builder().without(Protocol::Tls).build();
+		assert_eq!(
+			rebuilt.detect(b"GET / HTTP/1.1\r\n").unwrap(),
+			Some(Protocol::Http)
+		);
+		assert_eq!(rebuilt.detect(b"GET / HTTP/1.0\r\n").unwrap(), None);
+		let hello = [
+			0x16, 0x03, 0x01, 0x00, 0x2E, 0x01, 0x00, 0x00, 0x2A, 0x03, 0x03,
+		];
+		assert_eq!(rebuilt.detect(&hello).unwrap(), None);
+	}
+
+	#[test]
+	#[cfg(all(feature = "alloc", feature = "smtp", feature = "ftp", feature = "ssh"))]
+	fn to_builder_preserves_chain_order() {
+		let detector = crate::ProtocolChainBuilder::new().ftp().smtp().build();
+		let rebuilt = detector.to_builder().ssh().build();
+		assert_eq!(
+			rebuilt.detect(b"220 mail.example.com ESMTP\r\n").unwrap(),
+			Some(Protocol::Ftp)
+		);
+		assert_eq!(
+			rebuilt.detect(b"SSH-2.0-OpenSSH_8.9\r\n").unwrap(),
+			Some(Protocol::Ssh)
+		);
+	}
+
+	#[test]
+	#[cfg(all(feature = "ml", feature = "http"))]
+	fn detect_or_classify_falls_back_to_guess() {
+		use crate::ml::{Classification, TrafficClass};
+
+		let detector = ProtocolDetectorBuilder::<Unknown>::new().http().build();
+		assert!(matches!(
+			detector.detect_or_classify(b"GET / HTTP/1.1\r\n"),
+			Ok(Some(Classification::Protocol(ProtocolInfo {
+				protocol: Protocol::Http,
+				..
+			})))
+		));
+		let Ok(Some(Classification::Guess(guess))) =
+			detector.detect_or_classify(b"hello there, how are you today?\n")
+		else {
+			panic!("expected a guess");
+		};
+		assert_eq!(guess.class, TrafficClass::Text);
+	}
+
+	#[test]
+	#[cfg(all(feature = "http", feature = "websocket"))]
+	fn websocket_upgrade_wins_over_http() {
+		let detector = ProtocolDetectorBuilder::<Unknown>::new()
+			.http()
+			.websocket()
+			.build();
+		let upgrade = b"GET /ws HTTP/1.1\r\nUpgrade: websocket\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\r\n";
+		assert_eq!(detector.detect(upgrade).unwrap(), Some(Protocol::WebSocket));
+		assert_eq!(
+			detector
+				.detect(b"GET / HTTP/1.1\r\nHost: example.com\r\n\r\n")
+				.unwrap(),
+			Some(Protocol::Http)
+		);
+	}
+
+	#[test]
+	#[cfg(all(feature = "proxyproto", feature = "http"))]
+	fn proxy_header_can_be_skipped() {
+		let detector = ProtocolDetectorBuilder::<Unknown>::new()
+			.proxyproto()
+			.http()
+			.build();
+		let data = b"PROXY TCP4 192.168.0.1 192.168.0.11 56324 80\r\nGET / HTTP/1.1\r\n";
+		assert_eq!(
+			detector.detect(data).unwrap(),
+			Some(Protocol::ProxyProtocol)
+		);
+		let crate::ProtocolMetadata::ProxyProtocol { header_len, .. } =
+			Protocol::ProxyProtocol.metadata(data)
+		else {
+			panic!("expected PROXY protocol metadata");
+		};
+		assert_eq!(
+			detector.detect(&data[header_len..]).unwrap(),
+			Some(Protocol::Http)
+		);
+	}
+
+	#[test]
+	#[cfg(all(feature = "s7", feature = "rdp"))]
+	fn s7_and_rdp_share_tpkt_framing() {
+		let detector = ProtocolDetectorBuilder::<Unknown>::new().rdp().s7().build();
+		let s7 =
+			b"\x03\x00\x00\x16\x11\xE0\x00\x00\x00\x01\x00\xC0\x01\x0A\xC1\x02\x01\x00\xC2\x02\x01\x02";
+		assert_eq!(detector.detect(s7).unwrap(), Some(Protocol::S7));
+		let rdp = b"\x03\x00\x00\x13\x0E\xE0\x00\x00\x00\x00\x00\x01\x00\x08\x00\x03\x00\x00\x00";
+		assert_eq!(detector.detect(rdp).unwrap(), Some(Protocol::Rdp));
+	}
+
+	#[test]
+	#[cfg(all(feature = "riak", feature = "stun", feature = "rdp"))]
+	fn riak_does_not_take_stun_or_rdp() {
+		let detector = ProtocolDetectorBuilder::<Unknown>::new().all().build();
+		let stun = b"\x00\x01\x00\x00\x21\x12\xA4\x42\x5A\x5A\x5A\x5A\x5A\x5A\x5A\x5A\x5A\x5A\x5A\x5A";
+		assert_eq!(detector.detect(stun).unwrap(), Some(Protocol::Stun));
+		let rdp = b"\x03\x00\x00\x0B\x06\xE0\x00\x00\x00\x00\x00";
+		assert_eq!(detector.detect(rdp).unwrap(), Some(Protocol::Rdp));
+		let riak = b"\x00\x00\x00\x0F\x09\x0A\x05users\x12\x05alice";
+		assert_eq!(detector.detect(riak).unwrap(), Some(Protocol::Riak));
+	}
+
+	#[test]
+	#[cfg(all(feature = "mysqlx", feature = "dicom", feature = "msgpack_rpc"))]
+	fn mysqlx_does_not_take_dicom_or_msgpack_rpc() {
+		let detector = ProtocolDetectorBuilder::<Unknown>::new().all().build();
+		let mut dicom = [0u8; 74];
+		dicom[..10].copy_from_slice(&[0x01, 0x00, 0x00, 0x00, 0x00, 0xCD, 0x00, 0x01, 0x00, 0x00]);
+		dicom[10..26].copy_from_slice(b"ANY-SCP         ");
+		dicom[26..42].copy_from_slice(b"STORESCU        ");
+		assert_eq!(detector.detect(&dicom).unwrap(), Some(Protocol::Dicom));
+		let msgpack = b"\x94\x00\xCE\x00\x01\x11\x70\xB1nvim_get_api_info\x90";
+		assert_eq!(
+			detector.detect(msgpack).unwrap(),
+			Some(Protocol::MsgpackRpc)
+		);
+		let mysqlx = b"\x10\x00\x00\x00\x04\x0A\x05PLAIN\x12\x06\x00root\x00";
+		assert_eq!(detector.detect(mysqlx).unwrap(), Some(Protocol::Mysqlx));
+	}
+
+	#[test]
+	#[cfg(all(feature = "websocket", feature = "rtcp", feature = "lorawan"))]
+	fn websocket_frames_do_not_take_rtcp_or_lorawan() {
+		let mut rr = [0u8; 32];
+		rr[..4].copy_from_slice(&[0x81, 0xC9, 0x00, 0x07]);
+		rr[4..8].copy_from_slice(&[0x12, 0x34, 0x56, 0x78]);
+		rr[8..12].copy_from_slice(&[0x9A, 0xBC, 0xDE, 0xF0]);
+		let push = b"\x02\xC5\x3C\x00\xAA\x55\x5A\x00\x00\x00\x01\x01{\"stat\":{\"time\":\"2024-01-01 00:00:00 GMT\"}}";
+		let detector = ProtocolDetectorBuilder::<Unknown>::new().all().build();
+		assert_eq!(detector.detect(&rr).unwrap(), Some(Protocol::Rtcp));
+		assert_eq!(detector.detect(push).unwrap(), Some(Protocol::LoRaWan));
+		let detector = ProtocolDetectorBuilder::<Unknown>::new()
+			.udp()
+			.all()
+			.build();
+		assert_eq!(detector.detect(&rr).unwrap(), Some(Protocol::Rtcp));
+		assert_eq!(detector.detect(push).unwrap(), Some(Protocol::LoRaWan));
+	}
+
+	#[test]
+	#[cfg(all(feature = "telnet", feature = "tls"))]
+	fn telnet_negotiation_is_not_taken_by_tls() {
+		let negotiation = b"\xFF\xFB\x01\xFF\xFB\x03\xFF\xFD\x18\xFF\xFD\x1F";
+		let detector = ProtocolDetectorBuilder::<Unknown>::new().all().build();
+		assert_eq!(
+			detector.detect(negotiation).unwrap(),
+			Some(Protocol::Telnet)
+		);
+		let detector = ProtocolDetectorBuilder::<Unknown>::new()
+			.tcp()
+			.all()
+			.build();
+		assert_eq!(
+			detector.detect(negotiation).unwrap(),
+			Some(Protocol::Telnet)
+		);
+	}
+
+	#[test]
+	#[cfg(all(feature = "alloc", feature = "telnet", feature = "tls"))]
+	fn telnet_negotiation_is_not_taken_by_tls_in_chain() {
+		let negotiation = b"\xFF\xFB\x01\xFF\xFB\x03\xFF\xFD\x18\xFF\xFD\x1F";
+		let detector = crate::ProtocolChainBuilder::new().all_tcp().build();
+		assert_eq!(
+			detector.detect(negotiation).unwrap(),
+			Some(Protocol::Telnet)
+		);
+	}
+
+	#[test]
+	#[cfg(all(feature = "alloc", feature = "turn", feature = "kerberos"))]
+	fn kerberos_as_req_is_not_taken_by_turn() {
+		let as_req =
+			b"\x6A\x81\xB4\x30\x81\xB1\xA1\x03\x02\x01\x05\xA2\x03\x02\x01\x0A\xA3\x15\x30\x13";
+		let detector = ProtocolDetectorBuilder::<Unknown>::new().all().build();
+		assert_eq!(detector.detect(as_req).unwrap(), Some(Protocol::Kerberos));
+		let detector = crate::ProtocolChainBuilder::new().all_udp().build();
+		assert_eq!(detector.detect(as_req).unwrap(), Some(Protocol::Kerberos));
+	}
+
+	#[test]
+	#[cfg(all(feature = "grpc", feature = "http"))]
+	fn grpc_waits_for_headers_after_h2_preface() {
+		// Preface, empty SETTINGS, then HEADERS with a literal content type.
+		let request = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n\x00\x00\x00\x04\x00\x00\x00\x00\x00\
+			\x00\x00\x14\x01\x04\x00\x00\x00\x01\x83\x86\x5f\x10application/grpc";
+		let detector = ProtocolDetectorBuilder::<Unknown>::new()
+			.grpc()
+			.http()
+			.max_inspect_bytes(512)
+			.build();
+		assert_eq!(
+			detector.detect(&request[..33]),
+			Err(DetectionError::InsufficientData)
+		);
+		assert_eq!(detector.detect(request).unwrap(), Some(Protocol::Grpc));
+		// A plain HTTP/2 request stays HTTP once its HEADERS frame is in view.
+		let h2 = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n\x00\x00\x00\x04\x00\x00\x00\x00\x00\
+			\x00\x00\x03\x01\x04\x00\x00\x00\x01\x82\x86\x84";
+		assert_eq!(detector.detect(h2).unwrap(), Some(Protocol::Http));
+		// HTTP once the window fills before any HEADERS frame.
+		let mut updates = [0u8; 72];
+		updates[..33].copy_from_slice(&h2[..33]);
+		for frame in updates[33..].chunks_exact_mut(13) {
+			frame.copy_from_slice(b"\x00\x00\x04\x08\x00\x00\x00\x00\x00\x00\x0F\x00\x01");
+		}
+		let detector = ProtocolDetectorBuilder::<Unknown>::new()
+			.grpc()
+			.http()
+			.build();
+		assert_eq!(detector.detect(&updates).unwrap(), Some(Protocol::Http));
+	}
+
 	// ── Error paths ──
 
+	#[test]
+	#[cfg(all(feature = "http", feature = "ssh"))]
+	fn budget_stops_detection_between_probes() {
+		// SSH is probed before HTTP.
+		let data = b"GET / HTTP/1.1\r\n";
+		let detector = ProtocolDetectorBuilder::<Unknown>::new()
+			.http()
+			.ssh()
+			.budget(DetectionBudget::new().probes(1))
+			.build();
+		assert_eq!(detector.detect(data), Err(DetectionError::BudgetExceeded));
+		let detector = detector
+			.to_builder()
+			.budget(DetectionBudget::new().bytes(2 * data.len()))
+			.build();
+		assert_eq!(detector.detect(data), Ok(Some(Protocol::Http)));
+	}
+
+	#[test]
+	#[cfg(all(feature = "alloc", feature = "http", feature = "ssh", feature = "tls"))]
+	fn detect_verbose_lists_pending_protocols() {
+		let detector = ProtocolDetectorBuilder::<Unknown>::new()
+			.http()
+			.ssh()
+			.tls()
+			.build();
+		let err = detector.detect_verbose(b"SSH-").unwrap_err();
+		// SSH is probed before TLS.
+		let pending: Vec<Protocol> = err.pending.iter().map(|p| p.protocol).collect();
+		assert_eq!(pending, [Protocol::Ssh, Protocol::Tls]);
+		assert_eq!(err.pending[0].needed, 1);
+		assert!(!err.window_full);
+		assert!(!err.budget_exceeded);
+		assert_eq!(
+			err.to_string(),
+			"insufficient data: 2 protocol(s) still pending"
+		);
+
+		let found = detector.detect_verbose(b"SSH-2.0-OpenSSH_8.9\r\n").unwrap();
+		assert_eq!(found.map(|info| info.protocol), Some(Protocol::Ssh));
+	}
+
+	#[test]
+	#[cfg(all(feature = "alloc", feature = "ssh", feature = "tls"))]
+	fn detect_verbose_stops_at_budget() {
+		let detector = ProtocolDetectorBuilder::<Unknown>::new()
+			.ssh()
+			.tls()
+			.budget(DetectionBudget::new().probes(1))
+			.build();
+		let err = detector.detect_verbose(b"SSH-").unwrap_err();
+		let pending: Vec<Protocol> = err.pending.iter().map(|p| p.protocol).collect();
+		assert_eq!(pending, [Protocol::Ssh]);
+		assert!(err.budget_exceeded);
+		assert_eq!(
+			err.to_string(),
+			"detection budget exceeded: 1 protocol(s) still pending"
+		);
+	}
+
+	#[test]
+	#[cfg(all(feature = "alloc", feature = "ssh"))]
+	fn detect_verbose_flags_full_window() {
+		let mut detector = ProtocolDetectorBuilder::<Unknown>::new().ssh().build();
+		detector.max_inspect_bytes = 6;
+		let err = detector
+			.detect_verbose(b"SSH-1.99-OpenSSH\r\n")
+			.unwrap_err();
+		assert_eq!(err.pending[0].protocol, Protocol::Ssh);
+		assert!(err.window_full);
+	}
+
 	#[test]
 	#[cfg(feature = "http")]
 	fn empty_data_with_protocol_enabled_returns_insufficient_data() {
@@ -678,4 +1499,106 @@ mod tests {
 		let data = b"some data that should not crash";
 		let _ = detector.detect(data);
 	}
+
+	#[test]
+	#[cfg(all(feature = "tracing", feature = "std", feature = "tls"))]
+	fn rejection_reason_and_summary_are_traced() {
+		use std::sync::{Arc, Mutex};
+		use tracing_subscriber::util::SubscriberInitExt;
+
+		/// Collects formatted trace output in memory.
+		#[derive(Clone, Default)]
+		struct Capture(Arc<Mutex<Vec<u8>>>);
+
+		impl std::io::Write for Capture {
+			fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+				self.0.lock().unwrap().extend_from_slice(buf);
+				Ok(buf.len())
+			}
+
+			fn flush(&mut self) -> std::io::Result<()> {
+				Ok(())
+			}
+		}
+
+		let capture = Capture::default();
+		let writer = capture.clone();
+		let subscriber = tracing_subscriber::fmt()
+			.with_max_level(tracing::Level::TRACE)
+			.with_ansi(false)
+			.with_writer(move || writer.clone())
+			.finish();
+
+		let detector = ProtocolDetectorBuilder::<Unknown>::new().tls().build();
+		{
+			let _guard = subscriber.set_default();
+			assert_eq!(detector.detect(&[0x16, 0x03, 0x01, 0x00, 0x00]), Ok(None));
+		}
+
+		let output = String::from_utf8(capture.0.lock().unwrap().clone()).unwrap();
+		assert!(output.contains("probe{protocol=Tls}"));
+		assert!(output.contains("reason=\"record_length_zero\""));
+		assert_eq!(output.matches("no protocol matched").count(), 1);
+	}
+
+	#[test]
+	#[cfg(all(feature = "metrics", feature = "tls"))]
+	fn metrics_count_matches_and_time_calls() {
+		use metrics::{
+			Counter, Gauge, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit,
+		};
+		use std::sync::Mutex;
+
+		/// Records the name and labels of every registered metric.
+		#[derive(Default)]
+		struct Keys(Mutex<Vec<String>>);
+
+		impl Keys {
+			fn push(&self, key: &Key) {
+				let labels: Vec<String> = key
+					.labels()
+					.map(|label| format!("{}={}", label.key(), label.value()))
+					.collect();
+				let entry = format!("{}{{{}}}", key.name(), labels.join(","));
+				self.0.lock().unwrap().push(entry);
+			}
+		}
+
+		impl Recorder for Keys {
+			fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+			fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+			fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+			fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+				self.push(key);
+				Counter::noop()
+			}
+
+			fn register_gauge(&self, key: &Key, _: &Metadata<'_>) -> Gauge {
+				self.push(key);
+				Gauge::noop()
+			}
+
+			fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+				self.push(key);
+				Histogram::noop()
+			}
+		}
+
+		let recorder = Keys::default();
+		let detector = ProtocolDetectorBuilder::<Unknown>::new().tls().build();
+		metrics::with_local_recorder(&recorder, || {
+			let _ = detector.detect(&[0x16, 0x03, 0x01, 0x00, 0x05, 0x01]);
+			let _ = detector.detect(&[0xFF; 8]);
+		});
+
+		assert_eq!(
+			*recorder.0.lock().unwrap(),
+			[
+				"guess.detect.match{protocol=tls}",
+				"guess.detect.duration{outcome=match}",
+				"guess.detect.duration{outcome=no_match}",
+			]
+		);
+	}
 }
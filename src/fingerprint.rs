@@ -0,0 +1,828 @@
+/* src/fingerprint.rs */
+
+use core::fmt;
+#[cfg(feature = "tls")]
+use core::fmt::Write as _;
+
+/// SSH message number of `SSH_MSG_KEXINIT`.
+#[cfg(feature = "ssh")]
+const SSH_MSG_KEXINIT: u8 = 20;
+
+/// Largest SSH binary packet accepted (RFC 4253 requires support for 35000).
+#[cfg(feature = "ssh")]
+const MAX_SSH_PACKET_LEN: usize = 35_000;
+
+/// Most lines a server may send before its SSH identification string.
+#[cfg(feature = "ssh")]
+const MAX_PRE_BANNER_LINES: usize = 16;
+
+/// Per-round shift amounts of MD5.
+const MD5_SHIFTS: [u32; 16] = [7, 12, 17, 22, 5, 9, 14, 20, 4, 11, 16, 23, 6, 10, 15, 21];
+
+/// MD5 round constants: `floor(abs(sin(i + 1)) * 2^32)`.
+const MD5_CONSTANTS: [u32; 64] = [
+	0xd76a_a478,
+	0xe8c7_b756,
+	0x2420_70db,
+	0xc1bd_ceee,
+	0xf57c_0faf,
+	0x4787_c62a,
+	0xa830_4613,
+	0xfd46_9501,
+	0x6980_98d8,
+	0x8b44_f7af,
+	0xffff_5bb1,
+	0x895c_d7be,
+	0x6b90_1122,
+	0xfd98_7193,
+	0xa679_438e,
+	0x49b4_0821,
+	0xf61e_2562,
+	0xc040_b340,
+	0x265e_5a51,
+	0xe9b6_c7aa,
+	0xd62f_105d,
+	0x0244_1453,
+	0xd8a1_e681,
+	0xe7d3_fbc8,
+	0x21e1_cde6,
+	0xc337_07d6,
+	0xf4d5_0d87,
+	0x455a_14ed,
+	0xa9e3_e905,
+	0xfcef_a3f8,
+	0x676f_02d9,
+	0x8d2a_4c8a,
+	0xfffa_3942,
+	0x8771_f681,
+	0x6d9d_6122,
+	0xfde5_380c,
+	0xa4be_ea44,
+	0x4bde_cfa9,
+	0xf6bb_4b60,
+	0xbebf_bc70,
+	0x289b_7ec6,
+	0xeaa1_27fa,
+	0xd4ef_3085,
+	0x0488_1d05,
+	0xd9d4_d039,
+	0xe6db_99e5,
+	0x1fa2_7cf8,
+	0xc4ac_5665,
+	0xf429_2244,
+	0x432a_ff97,
+	0xab94_23a7,
+	0xfc93_a039,
+	0x655b_59c3,
+	0x8f0c_cc92,
+	0xffef_f47d,
+	0x8584_5dd1,
+	0x6fa8_7e4f,
+	0xfe2c_e6e0,
+	0xa301_4314,
+	0x4e08_11a1,
+	0xf753_7e82,
+	0xbd3a_f235,
+	0x2ad7_d2bb,
+	0xeb86_d391,
+];
+
+/// An MD5 fingerprint, displayed as 32 lowercase hex digits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Md5Digest(pub [u8; 16]);
+
+impl fmt::Display for Md5Digest {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for byte in self.0 {
+			write!(f, "{byte:02x}")?;
+		}
+		Ok(())
+	}
+}
+
+/// Streaming MD5, so fingerprints are hashed without building the input string.
+#[derive(Debug, Clone)]
+struct Md5 {
+	/// Chaining state.
+	state: [u32; 4],
+	/// Partial block awaiting compression.
+	block: [u8; 64],
+	/// Bytes buffered in `block`.
+	buffered: usize,
+	/// Total input length in bytes.
+	length: u64,
+}
+
+impl Md5 {
+	/// Creates a hasher with the standard initial state.
+	const fn new() -> Self {
+		Self {
+			state: [0x6745_2301, 0xefcd_ab89, 0x98ba_dcfe, 0x1032_5476],
+			block: [0; 64],
+			buffered: 0,
+			length: 0,
+		}
+	}
+
+	/// Appends `data` to the input.
+	fn update(&mut self, mut data: &[u8]) {
+		self.length = self.length.wrapping_add(data.len() as u64);
+		while !data.is_empty() {
+			let take = (64 - self.buffered).min(data.len());
+			self.block[self.buffered..self.buffered + take].copy_from_slice(&data[..take]);
+			self.buffered += take;
+			data = &data[take..];
+			if self.buffered == 64 {
+				self.compress();
+				self.buffered = 0;
+			}
+		}
+	}
+
+	/// Pads the input and returns the digest.
+	fn finalize(mut self) -> Md5Digest {
+		let bits = self.length.wrapping_mul(8);
+		self.update(&[0x80]);
+		while self.buffered != 56 {
+			self.update(&[0]);
+		}
+		self.update(&bits.to_le_bytes());
+
+		let mut digest = [0u8; 16];
+		for (out, word) in digest.chunks_exact_mut(4).zip(self.state) {
+			out.copy_from_slice(&word.to_le_bytes());
+		}
+		Md5Digest(digest)
+	}
+
+	/// Runs the compression function over the buffered block.
+	fn compress(&mut self) {
+		let mut words = [0u32; 16];
+		for (word, bytes) in words.iter_mut().zip(self.block.chunks_exact(4)) {
+			*word = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+		}
+
+		let [mut a, mut b, mut c, mut d] = self.state;
+		for i in 0..64 {
+			let (f, g) = match i / 16 {
+				0 => ((b & c) | (!b & d), i),
+				1 => ((d & b) | (!d & c), (5 * i + 1) % 16),
+				2 => (b ^ c ^ d, (3 * i + 5) % 16),
+				_ => (c ^ (b | !d), (7 * i) % 16),
+			};
+			let rotated = a
+				.wrapping_add(f)
+				.wrapping_add(MD5_CONSTANTS[i])
+				.wrapping_add(words[g])
+				.rotate_left(MD5_SHIFTS[(i / 16) * 4 + i % 4]);
+			a = d;
+			d = c;
+			c = b;
+			b = b.wrapping_add(rotated);
+		}
+
+		for (state, value) in self.state.iter_mut().zip([a, b, c, d]) {
+			*state = state.wrapping_add(value);
+		}
+	}
+}
+
+impl fmt::Write for Md5 {
+	fn write_str(&mut self, s: &str) -> fmt::Result {
+		self.update(s.as_bytes());
+		Ok(())
+	}
+}
+
+/// Computes the MD5 digest of `data`.
+#[must_use]
+pub fn md5(data: &[u8]) -> Md5Digest {
+	let mut hasher = Md5::new();
+	hasher.update(data);
+	hasher.finalize()
+}
+
+/// Algorithm name-lists of an SSH `SSH_MSG_KEXINIT` message.
+#[cfg(feature = "ssh")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct KexInit<'a> {
+	/// Key exchange algorithms.
+	pub kex_algorithms: &'a str,
+	/// Server host key algorithms.
+	pub server_host_key_algorithms: &'a str,
+	/// Ciphers, client to server.
+	pub encryption_client_to_server: &'a str,
+	/// Ciphers, server to client.
+	pub encryption_server_to_client: &'a str,
+	/// MAC algorithms, client to server.
+	pub mac_client_to_server: &'a str,
+	/// MAC algorithms, server to client.
+	pub mac_server_to_client: &'a str,
+	/// Compression algorithms, client to server.
+	pub compression_client_to_server: &'a str,
+	/// Compression algorithms, server to client.
+	pub compression_server_to_client: &'a str,
+	/// Languages, client to server.
+	pub languages_client_to_server: &'a str,
+	/// Languages, server to client.
+	pub languages_server_to_client: &'a str,
+}
+
+#[cfg(feature = "ssh")]
+impl<'a> KexInit<'a> {
+	/// Parses the `SSH_MSG_KEXINIT` at the start of an SSH stream.
+	///
+	/// `data` may begin with the identification string (and, from a server,
+	/// the lines allowed before it); the binary packet that follows must be in
+	/// view up to the last name-list. Returns `None` otherwise.
+	#[must_use]
+	pub fn parse(data: &'a [u8]) -> Option<Self> {
+		let packet = skip_identification(data)?;
+		let packet_len = usize::try_from(u32::from_be_bytes(*packet.first_chunk()?)).ok()?;
+		if !(5..=MAX_SSH_PACKET_LEN).contains(&packet_len) {
+			return None;
+		}
+		// Packet length, padding length, message number, cookie.
+		let end = (4 + packet_len).min(packet.len());
+		if packet.get(5) != Some(&SSH_MSG_KEXINIT) {
+			return None;
+		}
+		let mut pos = 4 + 1 + 1 + 16;
+		let mut lists = [""; 10];
+		for list in &mut lists {
+			let len = usize::try_from(u32::from_be_bytes(*packet.get(pos..end)?.first_chunk()?)).ok()?;
+			let bytes = packet.get(pos + 4..pos + 4 + len)?;
+			if pos + 4 + len > end || !bytes.iter().all(u8::is_ascii_graphic) {
+				return None;
+			}
+			*list = core::str::from_utf8(bytes).ok()?;
+			pos += 4 + len;
+		}
+		let [
+			kex_algorithms,
+			server_host_key_algorithms,
+			encryption_client_to_server,
+			encryption_server_to_client,
+			mac_client_to_server,
+			mac_server_to_client,
+			compression_client_to_server,
+			compression_server_to_client,
+			languages_client_to_server,
+			languages_server_to_client,
+		] = lists;
+		Some(Self {
+			kex_algorithms,
+			server_host_key_algorithms,
+			encryption_client_to_server,
+			encryption_server_to_client,
+			mac_client_to_server,
+			mac_server_to_client,
+			compression_client_to_server,
+			compression_server_to_client,
+			languages_client_to_server,
+			languages_server_to_client,
+		})
+	}
+
+	/// HASSH client fingerprint: MD5 of the key exchange, client-to-server
+	/// cipher, MAC, and compression lists joined with `;`.
+	#[must_use]
+	pub fn hassh(&self) -> Md5Digest {
+		hash_joined(&[
+			self.kex_algorithms,
+			self.encryption_client_to_server,
+			self.mac_client_to_server,
+			self.compression_client_to_server,
+		])
+	}
+
+	/// HASSHServer fingerprint: MD5 of the key exchange, server-to-client
+	/// cipher, MAC, and compression lists joined with `;`.
+	#[must_use]
+	pub fn hassh_server(&self) -> Md5Digest {
+		hash_joined(&[
+			self.kex_algorithms,
+			self.encryption_server_to_client,
+			self.mac_server_to_client,
+			self.compression_server_to_client,
+		])
+	}
+}
+
+/// Hashes `parts` joined with `;`.
+#[cfg(feature = "ssh")]
+fn hash_joined(parts: &[&str]) -> Md5Digest {
+	let mut hasher = Md5::new();
+	for (i, part) in parts.iter().enumerate() {
+		if i > 0 {
+			hasher.update(b";");
+		}
+		hasher.update(part.as_bytes());
+	}
+	hasher.finalize()
+}
+
+/// Skips the SSH identification string and any lines before it.
+///
+/// Data that does not start with text is returned as is, so a binary packet
+/// can be passed directly.
+#[cfg(feature = "ssh")]
+fn skip_identification(data: &[u8]) -> Option<&[u8]> {
+	if !data.first().is_some_and(u8::is_ascii_graphic) {
+		return Some(data);
+	}
+	let mut rest = data;
+	for _ in 0..=MAX_PRE_BANNER_LINES {
+		let end = rest.iter().position(|&b| b == b'\n')?;
+		let (line, next) = (&rest[..end], &rest[end + 1..]);
+		if line.starts_with(b"SSH-") {
+			return Some(next);
+		}
+		rest = next;
+	}
+	None
+}
+
+/// Form of the request target in an HTTP request line (RFC 9112, section 3.2).
+#[cfg(feature = "http")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TargetForm {
+	/// Path and optional query (`/index.html`).
+	Origin,
+	/// Full URI, as sent to proxies (`http://example.com/`).
+	Absolute,
+	/// Host and port, as sent with `CONNECT` (`example.com:443`).
+	Authority,
+	/// `*`, as sent with server-wide `OPTIONS`.
+	Asterisk,
+}
+
+#[cfg(feature = "http")]
+impl TargetForm {
+	/// One-letter code used in the fingerprint string.
+	const fn code(self) -> &'static [u8] {
+		match self {
+			Self::Origin => b"o",
+			Self::Absolute => b"a",
+			Self::Authority => b"c",
+			Self::Asterisk => b"s",
+		}
+	}
+}
+
+/// Shape of an HTTP/1.x request, for client fingerprinting.
+#[cfg(feature = "http")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct HttpClient<'a> {
+	/// Request method.
+	pub method: &'a str,
+	/// Protocol version (`1.0` or `1.1`).
+	pub version: &'a str,
+	/// Form of the request target.
+	pub target_form: TargetForm,
+	/// Whether the target carries a query string.
+	pub has_query: bool,
+	/// Header lines, each ending in CRLF.
+	headers: &'a [u8],
+}
+
+#[cfg(feature = "http")]
+impl<'a> HttpClient<'a> {
+	/// Parses the request line and header block of an HTTP/1.x request.
+	///
+	/// Returns `None` unless the whole header block, up to the empty line, is
+	/// in `data`.
+	#[must_use]
+	pub fn parse(data: &'a [u8]) -> Option<Self> {
+		let block_end = data.windows(4).position(|w| w == b"\r\n\r\n")?;
+		let line_end = data.windows(2).position(|w| w == b"\r\n")?;
+		let mut parts = data[..line_end].split(|&b| b == b' ');
+		let (Some(method), Some(target), Some(version), None) =
+			(parts.next(), parts.next(), parts.next(), parts.next())
+		else {
+			return None;
+		};
+
+		if method.is_empty() || !method.iter().all(|&b| b.is_ascii_uppercase() || b == b'-') {
+			return None;
+		}
+		let version = match version {
+			b"HTTP/1.0" => "1.0",
+			b"HTTP/1.1" => "1.1",
+			_ => return None,
+		};
+		let target_form = match target {
+			b"*" => TargetForm::Asterisk,
+			[b'/', ..] => TargetForm::Origin,
+			_ if target.windows(3).any(|w| w == b"://") => TargetForm::Absolute,
+			_ if method == b"CONNECT" && !target.is_empty() => TargetForm::Authority,
+			_ => return None,
+		};
+
+		let headers = data.get(line_end + 2..block_end + 2).unwrap_or_default();
+		let valid = headers
+			.split(|&b| b == b'\n')
+			.filter(|line| !line.is_empty())
+			.all(|line| {
+				// Obsolete line folding continues the previous value.
+				matches!(line.first(), Some(b' ' | b'\t'))
+					|| line
+						.iter()
+						.position(|&b| b == b':')
+						.is_some_and(|colon| colon > 0 && line[..colon].iter().all(|&b| is_token_byte(b)))
+			});
+		if !valid {
+			return None;
+		}
+
+		Some(Self {
+			method: core::str::from_utf8(method).ok()?,
+			version,
+			target_form,
+			has_query: target.contains(&b'?'),
+			headers,
+		})
+	}
+
+	/// Header names in the order the client sent them, as sent.
+	pub fn header_names(&self) -> impl Iterator<Item = &'a str> + 'a {
+		self
+			.headers
+			.split(|&b| b == b'\n')
+			.filter(|line| !line.is_empty() && !matches!(line[0], b' ' | b'\t'))
+			.filter_map(|line| {
+				let colon = line.iter().position(|&b| b == b':')?;
+				core::str::from_utf8(&line[..colon]).ok()
+			})
+	}
+
+	/// HTTP client fingerprint: MD5 of the method, version, target shape, and
+	/// lowercased header names in order.
+	///
+	/// The hashed string is `method;version;shape;names`, where the shape is
+	/// the target form code (`o`rigin, `a`bsolute, authority `c`, a`s`terisk)
+	/// followed by `q` when a query is present, and names are joined with `,`;
+	/// for example `GET;1.1;oq;host,user-agent,accept`.
+	#[must_use]
+	pub fn fingerprint(&self) -> Md5Digest {
+		let mut hasher = Md5::new();
+		hasher.update(self.method.as_bytes());
+		hasher.update(b";");
+		hasher.update(self.version.as_bytes());
+		hasher.update(b";");
+		hasher.update(self.target_form.code());
+		if self.has_query {
+			hasher.update(b"q");
+		}
+		hasher.update(b";");
+		for (i, name) in self.header_names().enumerate() {
+			if i > 0 {
+				hasher.update(b",");
+			}
+			for byte in name.bytes() {
+				hasher.update(&[byte.to_ascii_lowercase()]);
+			}
+		}
+		hasher.finalize()
+	}
+}
+
+/// Checks for an HTTP token character (RFC 9110, section 5.6.2).
+#[cfg(feature = "http")]
+fn is_token_byte(b: u8) -> bool {
+	b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b)
+}
+
+/// TLS handshake type of a `ServerHello`.
+#[cfg(feature = "tls")]
+const TLS_SERVER_HELLO: u8 = 0x02;
+
+/// Fields of a TLS `ServerHello`, for server fingerprinting.
+#[cfg(feature = "tls")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ServerHello<'a> {
+	/// Legacy version field (`0x0303` for TLS 1.2 and 1.3).
+	pub version: u16,
+	/// Cipher suite the server chose.
+	pub cipher_suite: u16,
+	/// Extension blocks, each a type, a length, and the data.
+	extensions: &'a [u8],
+}
+
+#[cfg(feature = "tls")]
+impl<'a> ServerHello<'a> {
+	/// Parses the `ServerHello` at the start of a TLS handshake record.
+	///
+	/// Returns `None` unless the record opens with a `ServerHello` whose
+	/// extensions are all in `data`.
+	#[must_use]
+	pub fn parse(data: &'a [u8]) -> Option<Self> {
+		let [0x16, 0x03, _, _, _] = *data.first_chunk::<5>()? else {
+			return None;
+		};
+		let handshake = &data[5..];
+		if *handshake.first()? != TLS_SERVER_HELLO {
+			return None;
+		}
+		let body_len = read_u24(handshake, 1)?;
+		let body = handshake.get(4..4 + body_len)?;
+
+		let version = u16::from_be_bytes(*body.first_chunk()?);
+		let session_id_len = usize::from(*body.get(2 + 32)?);
+		let pos = 2 + 32 + 1 + session_id_len;
+		let cipher_suite = u16::from_be_bytes([*body.get(pos)?, *body.get(pos + 1)?]);
+		// Compression method, then optional extensions.
+		let pos = pos + 3;
+		let extensions = match body.get(pos..pos + 2) {
+			None if body.len() == pos => &[][..],
+			None => return None,
+			Some(len) => {
+				let len = usize::from(u16::from_be_bytes([len[0], len[1]]));
+				body.get(pos + 2..pos + 2 + len)?
+			}
+		};
+
+		// Every extension must fit the block exactly.
+		let mut rest = extensions;
+		while !rest.is_empty() {
+			let len = usize::from(u16::from_be_bytes([*rest.get(2)?, *rest.get(3)?]));
+			rest = rest.get(4 + len..)?;
+		}
+		Some(Self {
+			version,
+			cipher_suite,
+			extensions,
+		})
+	}
+
+	/// Extension types in the order the server sent them.
+	pub fn extension_types(&self) -> impl Iterator<Item = u16> + 'a {
+		let mut rest = self.extensions;
+		core::iter::from_fn(move || {
+			let header = rest.first_chunk::<4>()?;
+			let extension_type = u16::from_be_bytes([header[0], header[1]]);
+			let len = usize::from(u16::from_be_bytes([header[2], header[3]]));
+			rest = &rest[4 + len..];
+			Some(extension_type)
+		})
+	}
+
+	/// JA3S fingerprint: MD5 of `version,cipher,extensions` in decimal, with
+	/// the extension types joined by `-`.
+	#[must_use]
+	pub fn ja3s(&self) -> Md5Digest {
+		let mut hasher = Md5::new();
+		let _ = write!(hasher, "{},{},", self.version, self.cipher_suite);
+		for (i, extension_type) in self.extension_types().enumerate() {
+			let separator = if i > 0 { "-" } else { "" };
+			let _ = write!(hasher, "{separator}{extension_type}");
+		}
+		hasher.finalize()
+	}
+}
+
+/// Reads a big-endian 24-bit length at `pos`.
+#[cfg(feature = "tls")]
+fn read_u24(data: &[u8], pos: usize) -> Option<usize> {
+	let bytes = data.get(pos..pos + 3)?;
+	Some(usize::from(bytes[0]) << 16 | usize::from(bytes[1]) << 8 | usize::from(bytes[2]))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Decodes a 32-digit hex digest.
+	fn digest(hex: &str) -> Md5Digest {
+		let mut bytes = [0u8; 16];
+		for (byte, pair) in bytes.iter_mut().zip(hex.as_bytes().chunks_exact(2)) {
+			*byte = u8::from_str_radix(core::str::from_utf8(pair).unwrap(), 16).unwrap();
+		}
+		Md5Digest(bytes)
+	}
+
+	#[test]
+	fn test_md5_vectors() {
+		assert_eq!(md5(b""), digest("d41d8cd98f00b204e9800998ecf8427e"));
+		assert_eq!(
+			md5(b"The quick brown fox jumps over the lazy dog"),
+			digest("9e107d9d372bb6826bd81d3542a419d6")
+		);
+		// Input spanning several blocks, fed in uneven pieces.
+		let mut hasher = Md5::new();
+		for piece in [&[b'a'; 7][..], &[b'a'; 100], &[b'a'; 93]] {
+			hasher.update(piece);
+		}
+		assert_eq!(
+			hasher.finalize(),
+			digest("887f30b43b2867f4a9accceee7d16e6c")
+		);
+	}
+
+	#[test]
+	#[cfg(feature = "std")]
+	fn test_md5_display() {
+		assert_eq!(md5(b"abc").to_string(), "900150983cd24fb0d6963f7d28e17f72");
+	}
+
+	/// Builds a KEXINIT packet from ten name-lists.
+	#[cfg(feature = "ssh")]
+	fn kexinit(lists: [&str; 10], out: &mut [u8; 512]) -> usize {
+		let mut payload_len = 1 + 16;
+		for list in lists {
+			payload_len += 4 + list.len();
+		}
+		payload_len += 5;
+		let padding = 8 - (4 + 1 + payload_len) % 8 + 4;
+		let packet_len = 1 + payload_len + padding;
+		out[..4].copy_from_slice(&(packet_len as u32).to_be_bytes());
+		out[4] = padding as u8;
+		out[5] = SSH_MSG_KEXINIT;
+		let mut pos = 6 + 16;
+		for list in lists {
+			out[pos..pos + 4].copy_from_slice(&(list.len() as u32).to_be_bytes());
+			out[pos + 4..pos + 4 + list.len()].copy_from_slice(list.as_bytes());
+			pos += 4 + list.len();
+		}
+		4 + packet_len
+	}
+
+	#[cfg(feature = "ssh")]
+	const LISTS: [&str; 10] = [
+		"curve25519-sha256,diffie-hellman-group14-sha256",
+		"ssh-ed25519,rsa-sha2-512",
+		"aes128-ctr,aes256-gcm@openssh.com",
+		"aes256-ctr",
+		"hmac-sha2-256",
+		"hmac-sha2-512",
+		"none,zlib@openssh.com",
+		"none",
+		"",
+		"",
+	];
+
+	#[test]
+	#[cfg(feature = "ssh")]
+	fn test_kexinit_after_banner() {
+		let mut packet = [0u8; 512];
+		let len = kexinit(LISTS, &mut packet);
+		let mut data = b"SSH-2.0-OpenSSH_9.6\r\n".to_vec();
+		data.extend_from_slice(&packet[..len]);
+
+		let kex = KexInit::parse(&data).unwrap();
+		assert_eq!(kex.kex_algorithms, LISTS[0]);
+		assert_eq!(kex.compression_server_to_client, "none");
+		assert_eq!(kex.languages_client_to_server, "");
+		assert_eq!(kex.hassh(), digest("7b3f76e580e44aea1e396d8af55a8228"));
+		assert_eq!(
+			kex.hassh_server(),
+			digest("9872d914bc3c6656730f743e57ac6262")
+		);
+		// The bare packet parses too.
+		assert_eq!(KexInit::parse(&packet[..len]), Some(kex));
+	}
+
+	#[test]
+	#[cfg(feature = "ssh")]
+	fn test_kexinit_rejects_truncated_or_other_messages() {
+		let mut packet = [0u8; 512];
+		let len = kexinit(LISTS, &mut packet);
+		assert_eq!(KexInit::parse(&packet[..60]), None);
+		assert_eq!(KexInit::parse(b"SSH-2.0-OpenSSH_9.6\r\n"), None);
+		packet[5] = 21; // SSH_MSG_NEWKEYS
+		assert_eq!(KexInit::parse(&packet[..len]), None);
+	}
+
+	#[test]
+	#[cfg(feature = "http")]
+	fn test_http_client_fingerprint() {
+		let data = b"GET /search?q=rust HTTP/1.1\r\nHost: example.com\r\nUser-Agent: curl/8.5.0\r\nAccept: */*\r\nAccept-Encoding: gzip\r\n\r\n";
+		let client = HttpClient::parse(data).unwrap();
+		assert_eq!(client.method, "GET");
+		assert_eq!(client.version, "1.1");
+		assert_eq!(client.target_form, TargetForm::Origin);
+		assert!(client.has_query);
+		let mut names = client.header_names();
+		assert_eq!(names.next(), Some("Host"));
+		assert_eq!(names.nth(2), Some("Accept-Encoding"));
+		assert_eq!(names.next(), None);
+		assert_eq!(
+			client.fingerprint(),
+			digest("8f63eb98eee4d4f23c77d4b2087e411c")
+		);
+
+		// Header values and the target path do not change the fingerprint.
+		let other = b"GET /?id=7 HTTP/1.1\r\nhost: example.org\r\nuser-agent: x\r\naccept: text/html\r\naccept-encoding: br\r\n\r\n";
+		assert_eq!(
+			HttpClient::parse(other).unwrap().fingerprint(),
+			client.fingerprint()
+		);
+	}
+
+	#[test]
+	#[cfg(feature = "http")]
+	fn test_http_client_connect() {
+		let data = b"CONNECT example.com:443 HTTP/1.1\r\nHost: example.com:443\r\nProxy-Connection: keep-alive\r\n\r\n";
+		let client = HttpClient::parse(data).unwrap();
+		assert_eq!(client.target_form, TargetForm::Authority);
+		assert_eq!(
+			client.fingerprint(),
+			digest("fe39a0cd289d46089c4ab1ed58c86350")
+		);
+	}
+
+	#[test]
+	#[cfg(feature = "http")]
+	fn test_http_client_needs_complete_headers() {
+		assert_eq!(
+			HttpClient::parse(b"GET / HTTP/1.1\r\nHost: example.com\r\n"),
+			None
+		);
+		assert_eq!(
+			HttpClient::parse(b"GET / HTTP/2.0\r\nHost: a\r\n\r\n"),
+			None
+		);
+		assert_eq!(
+			HttpClient::parse(b"GET / HTTP/1.1\r\nBad Header: a\r\n\r\n"),
+			None
+		);
+	}
+
+	/// Builds a TLS 1.2 `ServerHello` record with the given extensions.
+	#[cfg(feature = "tls")]
+	fn server_hello(cipher: u16, extensions: &[(u16, &[u8])], out: &mut [u8; 256]) -> usize {
+		let mut pos = 9;
+		out[pos..pos + 2].copy_from_slice(&[0x03, 0x03]);
+		pos += 2 + 32;
+		out[pos] = 0; // empty session id
+		pos += 1;
+		out[pos..pos + 2].copy_from_slice(&cipher.to_be_bytes());
+		pos += 3;
+		let extensions_start = pos + 2;
+		pos = extensions_start;
+		for (extension_type, body) in extensions {
+			out[pos..pos + 2].copy_from_slice(&extension_type.to_be_bytes());
+			out[pos + 2..pos + 4].copy_from_slice(&(body.len() as u16).to_be_bytes());
+			out[pos + 4..pos + 4 + body.len()].copy_from_slice(body);
+			pos += 4 + body.len();
+		}
+		let extensions_len = (pos - extensions_start) as u16;
+		out[extensions_start - 2..extensions_start].copy_from_slice(&extensions_len.to_be_bytes());
+		let body_len = pos - 9;
+		out[..5].copy_from_slice(&[0x16, 0x03, 0x03, 0x00, (body_len + 4) as u8]);
+		out[5..9].copy_from_slice(&[TLS_SERVER_HELLO, 0x00, 0x00, body_len as u8]);
+		pos
+	}
+
+	#[test]
+	#[cfg(feature = "tls")]
+	fn test_ja3s_tls12() {
+		let mut record = [0u8; 256];
+		let len = server_hello(
+			0xC02F,
+			&[
+				(0xFF01, &[0x00]),
+				(0x0000, &[]),
+				(0x000B, &[0x01, 0x00]),
+				(0x0010, &[0x00, 0x03, 0x02, b'h', b'2']),
+			],
+			&mut record,
+		);
+		let hello = ServerHello::parse(&record[..len]).unwrap();
+		assert_eq!(hello.version, 0x0303);
+		assert_eq!(hello.cipher_suite, 0xC02F);
+		assert_eq!(hello.ja3s(), digest("ae53107a2e47ea20c72ac44821a728bf"));
+	}
+
+	#[test]
+	#[cfg(feature = "tls")]
+	fn test_ja3s_tls13() {
+		let mut record = [0u8; 256];
+		let key_share = [0x1Du8; 36];
+		let len = server_hello(
+			0x1301,
+			&[(0x002B, &[0x03, 0x04]), (0x0033, &key_share)],
+			&mut record,
+		);
+		let hello = ServerHello::parse(&record[..len]).unwrap();
+		assert_eq!(hello.extension_types().count(), 2);
+		assert_eq!(hello.ja3s(), digest("f4febc55ea12b31ae17cfb7e614afda8"));
+		// Extensions cut off by the end of the data.
+		assert_eq!(ServerHello::parse(&record[..len - 1]), None);
+	}
+
+	#[test]
+	#[cfg(feature = "tls")]
+	fn test_server_hello_rejects_other_messages() {
+		let mut record = [0u8; 256];
+		let len = server_hello(0x1301, &[(0x002B, &[0x03, 0x04])], &mut record);
+		record[5] = 0x01; // ClientHello
+		assert_eq!(ServerHello::parse(&record[..len]), None);
+		assert_eq!(ServerHello::parse(b"\x17\x03\x03\x00\x10"), None);
+	}
+}
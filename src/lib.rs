@@ -7,19 +7,74 @@
 //!
 //! High-performance zero-copy network protocol detection with version awareness.
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+/// Records the check that made a probe reject its input.
+///
+/// Emits a `tracing` event with a `reason` field inside the active probe span;
+/// expands to nothing without the `tracing` feature.
+#[allow(unused_macros)]
+macro_rules! reject {
+	($reason:literal) => {
+		#[cfg(feature = "tracing")]
+		tracing::trace!(reason = $reason, "rejected");
+	};
+}
+
+/// Libpcap prefilter expressions built from protocol signatures.
+#[cfg(feature = "bpf")]
+pub mod bpf;
+/// Per-call detection budget module.
+mod budget;
 /// Protocol detector builder module.
 mod builder;
+/// C API for embedding the detector in non-Rust programs.
+#[cfg(feature = "capi")]
+pub mod capi;
+
 /// Custom protocol detection chain module.
-#[cfg(feature = "std")]
+#[cfg(feature = "alloc")]
 mod chain;
+/// Primitives for removing QUIC Initial packet protection.
+#[cfg(feature = "http3")]
+mod crypto;
 /// Main protocol detector implementation.
 mod detector;
+/// MD5-based client and server fingerprints (HASSH, HTTP, JA3S).
+#[cfg(feature = "fingerprint")]
+pub mod fingerprint;
+/// Probabilistic fallback classifier for traffic no detector matched.
+#[cfg(feature = "ml")]
+pub mod ml;
+/// Socket helpers for sniffing live connections.
+#[cfg(feature = "std")]
+pub mod net;
+/// Ethernet/IP/TCP/UDP header parsing for captured packets.
+#[cfg(feature = "packet")]
+pub mod packet;
+/// Tunable heuristic for length-prefixed protobuf RPC framing.
+#[cfg(feature = "protobuf")]
+pub mod protobuf;
 /// Individual protocol detection logic.
 mod protocols;
+/// Suricata and Zeek rule export from protocol signatures.
+#[cfg(feature = "rules")]
+pub mod rules;
+/// Rustls acceptor interop for TLS-or-plaintext ports.
+#[cfg(feature = "rustls")]
+pub mod rustls;
+/// Fixed payload signatures shared by the prefilter and rule exporters.
+#[cfg(any(feature = "bpf", feature = "rules"))]
+mod signature;
+/// Tower layer and router for protocol-based connection dispatch.
+#[cfg(feature = "tower")]
+pub mod tower;
 
+pub use budget::DetectionBudget;
 pub use builder::ProtocolDetectorBuilder;
-#[cfg(feature = "std")]
-pub use chain::ProtocolChainBuilder;
+#[cfg(feature = "alloc")]
+pub use chain::{ChainError, ProtocolChainBuilder};
 pub use detector::{ProtocolDetector, Tcp, Udp, Unknown};
 use thiserror::Error;
 
@@ -28,6 +83,7 @@ pub const MAX_INSPECT_BYTES: usize = 64;
 
 /// Detection status for a single protocol.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum DetectionStatus {
 	/// Confirmed match.
 	Match,
@@ -39,6 +95,7 @@ pub enum DetectionStatus {
 
 /// Protocol version information (Zero-copy).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[non_exhaustive]
 pub enum ProtocolVersion<'a> {
 	/// HTTP version (e.g., "1.1", "2.0")
@@ -49,12 +106,142 @@ pub enum ProtocolVersion<'a> {
 	Ssh(&'a str),
 	/// Redis RESP version (2 or 3)
 	Redis(u8),
+	/// SPDY version (2 or 3)
+	Spdy(u8),
+	/// AMQP dialect (e.g., "1.0", "0-9-1")
+	Amqp(&'a str),
+	/// TDS version a SQL Server client requested (e.g., "7.4")
+	Tds(&'a str),
+	/// Tarantool server version (e.g., "2.10.4")
+	Tarantool(&'a str),
 	/// Version unknown or not applicable
 	Unknown,
 }
 
+/// Protocol-specific details read from a matched payload (Zero-copy).
+///
+/// Returned by [`Protocol::metadata`] for data the protocol matched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum ProtocolMetadata<'a> {
+	/// OPC UA message type (e.g., "HEL", "OPN", "MSG")
+	OpcUa {
+		/// Three-letter message type.
+		message_type: &'a str,
+	},
+	/// BitTorrent peer handshake fields
+	BitTorrent {
+		/// Reserved extension bits, big-endian (`0x10_0000` is the extension
+		/// protocol, `0x04` the fast extension, `0x01` DHT).
+		reserved: u64,
+		/// Info-hash of the torrent the peer wants to exchange.
+		info_hash: &'a [u8; 20],
+	},
+	/// TDS client version from the PRELOGIN VERSION option
+	Tds {
+		/// Major version.
+		major: u8,
+		/// Minor version.
+		minor: u8,
+		/// Build number.
+		build: u16,
+		/// Sub-build number.
+		sub_build: u16,
+	},
+	/// Minecraft handshake fields
+	Minecraft {
+		/// Client protocol version (snapshots set bit 30).
+		protocol_version: i32,
+		/// Requested hostname, if it is in view and valid UTF-8.
+		server_address: Option<&'a str>,
+	},
+	/// Thrift message encoding and method
+	Thrift {
+		/// Compact protocol (`true`) or strict binary protocol (`false`).
+		compact: bool,
+		/// Whether the message is wrapped in the framed transport.
+		framed: bool,
+		/// Called method, if its whole name is in view.
+		method: Option<&'a str>,
+	},
+	/// VXLAN network identifier
+	Vxlan {
+		/// 24-bit VXLAN network identifier.
+		vni: u32,
+	},
+	/// GENEVE network identifier and inner protocol
+	Geneve {
+		/// 24-bit virtual network identifier.
+		vni: u32,
+		/// EtherType of the inner frame (`0x6558` for Ethernet).
+		protocol_type: u16,
+	},
+	/// gQUIC version tag
+	Gquic {
+		/// Version tag (e.g., "Q043", "Q046").
+		version: &'a str,
+	},
+	/// WebSocket upgrade request
+	WebSocket {
+		/// Offered subprotocols from `Sec-WebSocket-Protocol`, if in view.
+		subprotocols: Option<&'a str>,
+		/// Protocol carried over the WebSocket, recognized from the
+		/// subprotocols (e.g., `Protocol::Mqtt` for `mqtt`).
+		inner: Option<Protocol>,
+	},
+	/// RDP connection request fields
+	Rdp {
+		/// Routing token or cookie after `Cookie: ` (e.g.,
+		/// "mstshash=user"), if its line is in view.
+		cookie: Option<&'a str>,
+		/// `requestedProtocols` flags of the `RDP_NEG_REQ` (`0x01` TLS,
+		/// `0x02` `CredSSP`), if present.
+		requested_protocols: Option<u32>,
+	},
+	/// Semtech packet forwarder gateway
+	LoRaWan {
+		/// EUI of the LoRa gateway that sent the packet.
+		gateway_eui: u64,
+	},
+	/// RakNet offline message fields
+	RakNet {
+		/// Offline message ID (`0x01` unconnected ping, `0x05` open
+		/// connection request).
+		packet_id: u8,
+		/// Client GUID, carried by unconnected pings.
+		client_guid: Option<u64>,
+	},
+	/// PROXY protocol header fields
+	ProxyProtocol {
+		/// Header version (1 for text, 2 for binary).
+		version: u8,
+		/// Length of the whole header; the proxied payload starts right
+		/// after it.
+		header_len: usize,
+	},
+	/// ONC RPC call header fields
+	SunRpc {
+		/// Called program (e.g., 100000 portmapper, 100003 NFS, 100005
+		/// mountd).
+		program: u32,
+		/// Program version.
+		version: u32,
+		/// Procedure number within the program.
+		procedure: u32,
+	},
+	/// TLS `ClientHello` whose fingerprint resembles a Tor client's
+	TorSuspect {
+		/// Heuristic score from 50 to 80; a best-effort hint, never proof.
+		confidence: u8,
+	},
+	/// No metadata available
+	None,
+}
+
 /// Detailed protocol information including version.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct ProtocolInfo<'a> {
 	/// The detected protocol.
 	pub protocol: Protocol,
@@ -63,7 +250,12 @@ pub struct ProtocolInfo<'a> {
 }
 
 /// Errors that can occur during protocol detection.
+///
+/// New variants may be added in minor releases, so matches need a wildcard
+/// arm.
 #[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
 pub enum DetectionError {
 	/// Data is insufficient to perform the detection.
 	#[error("insufficient data: need more bytes to confirm protocol")]
@@ -71,13 +263,47 @@ pub enum DetectionError {
 	/// The requested protocol is not enabled.
 	#[error("protocol {0:?} is not enabled")]
 	ProtocolNotEnabled(Protocol),
+	/// The detector's [`DetectionBudget`] ran out before a verdict.
+	#[error("detection budget exceeded")]
+	BudgetExceeded,
 }
 
 /// Result type for protocol detection operations.
 pub type DetectionResult<T> = Result<T, DetectionError>;
 
+/// A protocol that could neither match nor be ruled out yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PendingProtocol {
+	/// The undecided protocol.
+	pub protocol: Protocol,
+	/// Lower bound on the additional bytes needed for a verdict (at least 1).
+	pub needed: usize,
+}
+
+/// Details of an inconclusive detection, returned by
+/// [`ProtocolDetector::detect_verbose`].
+#[cfg(feature = "alloc")]
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+#[error(
+	"{}: {} protocol(s) still pending",
+	if *budget_exceeded { "detection budget exceeded" } else { "insufficient data" },
+	pending.len()
+)]
+pub struct IncompleteDetection {
+	/// Undecided protocols in detection order.
+	pub pending: alloc::vec::Vec<PendingProtocol>,
+	/// `true` if the inspection window is already full, so more data cannot
+	/// change the verdict.
+	pub window_full: bool,
+	/// `true` if the detector's [`DetectionBudget`] ran out, so `pending`
+	/// only covers the protocols probed before that.
+	pub budget_exceeded: bool,
+}
+
 /// Supported protocols for detection.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[non_exhaustive]
 pub enum Protocol {
 	/// HTTP protocol.
@@ -137,9 +363,536 @@ pub enum Protocol {
 	/// NTP protocol.
 	#[cfg(feature = "ntp")]
 	Ntp,
+	/// `gRPC` protocol.
+	#[cfg(feature = "grpc")]
+	Grpc,
+	/// NATS protocol.
+	#[cfg(feature = "nats")]
+	Nats,
+	/// Git protocol.
+	#[cfg(feature = "git")]
+	Git,
+	/// IPP protocol.
+	#[cfg(feature = "ipp")]
+	Ipp,
+	/// JetDirect protocol.
+	#[cfg(feature = "jetdirect")]
+	JetDirect,
+	/// OPC UA protocol.
+	#[cfg(feature = "opcua")]
+	OpcUa,
+	/// OpenVPN protocol.
+	#[cfg(feature = "openvpn")]
+	OpenVpn,
+	/// BitTorrent protocol.
+	#[cfg(feature = "bittorrent")]
+	BitTorrent,
+	/// BitTorrent DHT protocol.
+	#[cfg(feature = "dht")]
+	Dht,
+	/// mDNS protocol.
+	#[cfg(feature = "mdns")]
+	Mdns,
+	/// NetBIOS protocol.
+	#[cfg(feature = "netbios")]
+	NetBios,
+	/// TURN protocol.
+	#[cfg(feature = "turn")]
+	Turn,
+	/// TDS protocol.
+	#[cfg(feature = "tds")]
+	Tds,
+	/// Riak protocol.
+	#[cfg(feature = "riak")]
+	Riak,
+	/// `MySQL` X protocol.
+	#[cfg(feature = "mysqlx")]
+	Mysqlx,
+	/// Minecraft protocol.
+	#[cfg(feature = "minecraft")]
+	Minecraft,
+	/// Source engine query protocol.
+	#[cfg(feature = "source_query")]
+	SourceQuery,
+	/// Graphite protocol.
+	#[cfg(feature = "graphite")]
+	Graphite,
+	/// `InfluxDB` line protocol.
+	#[cfg(feature = "influx")]
+	Influx,
+	/// Thrift protocol.
+	#[cfg(feature = "thrift")]
+	Thrift,
+	/// AJP protocol.
+	#[cfg(feature = "ajp")]
+	Ajp,
+	/// NVMe/TCP protocol.
+	#[cfg(feature = "nvme_tcp")]
+	NvmeTcp,
+	/// DICOM protocol.
+	#[cfg(feature = "dicom")]
+	Dicom,
+	/// DNSCrypt protocol.
+	#[cfg(feature = "dnscrypt")]
+	DnsCrypt,
+	/// VXLAN protocol.
+	#[cfg(feature = "vxlan")]
+	Vxlan,
+	/// GENEVE protocol.
+	#[cfg(feature = "geneve")]
+	Geneve,
+	/// Ethereum devp2p protocol.
+	#[cfg(feature = "devp2p")]
+	Devp2p,
+	/// JSON-RPC protocol.
+	#[cfg(feature = "jsonrpc")]
+	JsonRpc,
+	/// MessagePack-RPC protocol.
+	#[cfg(feature = "msgpack_rpc")]
+	MsgpackRpc,
+	/// LPD protocol.
+	#[cfg(feature = "lpd")]
+	Lpd,
+	/// HTTP/3 protocol.
+	#[cfg(feature = "http3")]
+	Http3,
+	/// gQUIC protocol.
+	#[cfg(feature = "gquic")]
+	Gquic,
+	/// SPDY protocol.
+	#[cfg(feature = "spdy")]
+	Spdy,
+	/// RakNet protocol.
+	#[cfg(feature = "raknet")]
+	RakNet,
+	/// MMS protocol.
+	#[cfg(feature = "mms")]
+	Mms,
+	/// LoRaWAN protocol.
+	#[cfg(feature = "lorawan")]
+	LoRaWan,
+	/// WebSocket protocol.
+	#[cfg(feature = "websocket")]
+	WebSocket,
+	/// Telnet protocol.
+	#[cfg(feature = "telnet")]
+	Telnet,
+	/// RDP protocol.
+	#[cfg(feature = "rdp")]
+	Rdp,
+	/// LDAP protocol.
+	#[cfg(feature = "ldap")]
+	Ldap,
+	/// Kerberos protocol.
+	#[cfg(feature = "kerberos")]
+	Kerberos,
+	/// IRC protocol.
+	#[cfg(feature = "irc")]
+	Irc,
+	/// XMPP protocol.
+	#[cfg(feature = "xmpp")]
+	Xmpp,
+	/// AMQP protocol.
+	#[cfg(feature = "amqp")]
+	Amqp,
+	/// Memcached protocol.
+	#[cfg(feature = "memcached")]
+	Memcached,
+	/// SOCKS4 protocol.
+	#[cfg(feature = "socks4")]
+	Socks4,
+	/// HAProxy PROXY protocol.
+	#[cfg(feature = "proxyproto")]
+	ProxyProtocol,
+	/// RTP protocol.
+	#[cfg(feature = "rtp")]
+	Rtp,
+	/// RTCP protocol.
+	#[cfg(feature = "rtcp")]
+	Rtcp,
+	/// RTMP protocol.
+	#[cfg(feature = "rtmp")]
+	Rtmp,
+	/// S7comm protocol.
+	#[cfg(feature = "s7")]
+	S7,
+	/// ClickHouse protocol.
+	#[cfg(feature = "clickhouse")]
+	ClickHouse,
+	/// Tarantool protocol.
+	#[cfg(feature = "tarantool")]
+	Tarantool,
+	/// Subversion (`svnserve`) protocol.
+	#[cfg(feature = "svn")]
+	Svn,
+	/// ONC RPC (Sun RPC) protocol.
+	#[cfg(feature = "sunrpc")]
+	SunRpc,
+	/// ZooKeeper protocol.
+	#[cfg(feature = "zookeeper")]
+	Zookeeper,
+	/// Mumble protocol.
+	#[cfg(feature = "mumble")]
+	Mumble,
+	/// Ident protocol.
+	#[cfg(feature = "ident")]
+	Ident,
+	/// SCGI protocol.
+	#[cfg(feature = "scgi")]
+	Scgi,
+	/// ICAP protocol.
+	#[cfg(feature = "icap")]
+	Icap,
+	/// RELP protocol.
+	#[cfg(feature = "relp")]
+	Relp,
+	/// SMPP protocol.
+	#[cfg(feature = "smpp")]
+	Smpp,
+	/// H.225 protocol.
+	#[cfg(feature = "h225")]
+	H225,
 }
 
 impl Protocol {
+	/// Every protocol compiled into this build, in declaration order.
+	#[allow(dead_code)]
+	pub(crate) const ALL: &'static [Self] = &[
+		#[cfg(feature = "http")]
+		Self::Http,
+		#[cfg(feature = "tls")]
+		Self::Tls,
+		#[cfg(feature = "ssh")]
+		Self::Ssh,
+		#[cfg(feature = "dns")]
+		Self::Dns,
+		#[cfg(feature = "quic")]
+		Self::Quic,
+		#[cfg(feature = "mysql")]
+		Self::Mysql,
+		#[cfg(feature = "postgres")]
+		Self::Postgres,
+		#[cfg(feature = "redis")]
+		Self::Redis,
+		#[cfg(feature = "mqtt")]
+		Self::Mqtt,
+		#[cfg(feature = "smtp")]
+		Self::Smtp,
+		#[cfg(feature = "pop3")]
+		Self::Pop3,
+		#[cfg(feature = "imap")]
+		Self::Imap,
+		#[cfg(feature = "ftp")]
+		Self::Ftp,
+		#[cfg(feature = "smb")]
+		Self::Smb,
+		#[cfg(feature = "stun")]
+		Self::Stun,
+		#[cfg(feature = "sip")]
+		Self::Sip,
+		#[cfg(feature = "rtsp")]
+		Self::Rtsp,
+		#[cfg(feature = "dhcp")]
+		Self::Dhcp,
+		#[cfg(feature = "ntp")]
+		Self::Ntp,
+		#[cfg(feature = "grpc")]
+		Self::Grpc,
+		#[cfg(feature = "nats")]
+		Self::Nats,
+		#[cfg(feature = "git")]
+		Self::Git,
+		#[cfg(feature = "ipp")]
+		Self::Ipp,
+		#[cfg(feature = "jetdirect")]
+		Self::JetDirect,
+		#[cfg(feature = "opcua")]
+		Self::OpcUa,
+		#[cfg(feature = "openvpn")]
+		Self::OpenVpn,
+		#[cfg(feature = "bittorrent")]
+		Self::BitTorrent,
+		#[cfg(feature = "dht")]
+		Self::Dht,
+		#[cfg(feature = "mdns")]
+		Self::Mdns,
+		#[cfg(feature = "netbios")]
+		Self::NetBios,
+		#[cfg(feature = "turn")]
+		Self::Turn,
+		#[cfg(feature = "tds")]
+		Self::Tds,
+		#[cfg(feature = "riak")]
+		Self::Riak,
+		#[cfg(feature = "mysqlx")]
+		Self::Mysqlx,
+		#[cfg(feature = "minecraft")]
+		Self::Minecraft,
+		#[cfg(feature = "source_query")]
+		Self::SourceQuery,
+		#[cfg(feature = "graphite")]
+		Self::Graphite,
+		#[cfg(feature = "influx")]
+		Self::Influx,
+		#[cfg(feature = "thrift")]
+		Self::Thrift,
+		#[cfg(feature = "ajp")]
+		Self::Ajp,
+		#[cfg(feature = "nvme_tcp")]
+		Self::NvmeTcp,
+		#[cfg(feature = "dicom")]
+		Self::Dicom,
+		#[cfg(feature = "dnscrypt")]
+		Self::DnsCrypt,
+		#[cfg(feature = "vxlan")]
+		Self::Vxlan,
+		#[cfg(feature = "geneve")]
+		Self::Geneve,
+		#[cfg(feature = "devp2p")]
+		Self::Devp2p,
+		#[cfg(feature = "jsonrpc")]
+		Self::JsonRpc,
+		#[cfg(feature = "msgpack_rpc")]
+		Self::MsgpackRpc,
+		#[cfg(feature = "lpd")]
+		Self::Lpd,
+		#[cfg(feature = "http3")]
+		Self::Http3,
+		#[cfg(feature = "gquic")]
+		Self::Gquic,
+		#[cfg(feature = "spdy")]
+		Self::Spdy,
+		#[cfg(feature = "raknet")]
+		Self::RakNet,
+		#[cfg(feature = "mms")]
+		Self::Mms,
+		#[cfg(feature = "lorawan")]
+		Self::LoRaWan,
+		#[cfg(feature = "websocket")]
+		Self::WebSocket,
+		#[cfg(feature = "telnet")]
+		Self::Telnet,
+		#[cfg(feature = "rdp")]
+		Self::Rdp,
+		#[cfg(feature = "ldap")]
+		Self::Ldap,
+		#[cfg(feature = "kerberos")]
+		Self::Kerberos,
+		#[cfg(feature = "irc")]
+		Self::Irc,
+		#[cfg(feature = "xmpp")]
+		Self::Xmpp,
+		#[cfg(feature = "amqp")]
+		Self::Amqp,
+		#[cfg(feature = "memcached")]
+		Self::Memcached,
+		#[cfg(feature = "socks4")]
+		Self::Socks4,
+		#[cfg(feature = "proxyproto")]
+		Self::ProxyProtocol,
+		#[cfg(feature = "rtp")]
+		Self::Rtp,
+		#[cfg(feature = "rtcp")]
+		Self::Rtcp,
+		#[cfg(feature = "rtmp")]
+		Self::Rtmp,
+		#[cfg(feature = "s7")]
+		Self::S7,
+		#[cfg(feature = "clickhouse")]
+		Self::ClickHouse,
+		#[cfg(feature = "tarantool")]
+		Self::Tarantool,
+		#[cfg(feature = "svn")]
+		Self::Svn,
+		#[cfg(feature = "sunrpc")]
+		Self::SunRpc,
+		#[cfg(feature = "zookeeper")]
+		Self::Zookeeper,
+		#[cfg(feature = "mumble")]
+		Self::Mumble,
+		#[cfg(feature = "ident")]
+		Self::Ident,
+		#[cfg(feature = "scgi")]
+		Self::Scgi,
+		#[cfg(feature = "icap")]
+		Self::Icap,
+		#[cfg(feature = "relp")]
+		Self::Relp,
+		#[cfg(feature = "smpp")]
+		Self::Smpp,
+		#[cfg(feature = "h225")]
+		Self::H225,
+	];
+
+	/// Every protocol compiled into this build, in the order the detector
+	/// probes them. Chain presets follow the same order.
+	pub(crate) const DETECTION_ORDER: &'static [Self] = &[
+		#[cfg(feature = "proxyproto")]
+		Self::ProxyProtocol,
+		#[cfg(feature = "ssh")]
+		Self::Ssh,
+		#[cfg(feature = "sip")]
+		Self::Sip,
+		#[cfg(feature = "rtsp")]
+		Self::Rtsp,
+		#[cfg(feature = "icap")]
+		Self::Icap,
+		#[cfg(feature = "imap")]
+		Self::Imap,
+		#[cfg(feature = "telnet")]
+		Self::Telnet,
+		#[cfg(feature = "tls")]
+		Self::Tls,
+		#[cfg(feature = "grpc")]
+		Self::Grpc,
+		#[cfg(feature = "ipp")]
+		Self::Ipp,
+		#[cfg(feature = "websocket")]
+		Self::WebSocket,
+		#[cfg(feature = "http")]
+		Self::Http,
+		#[cfg(feature = "spdy")]
+		Self::Spdy,
+		#[cfg(feature = "vxlan")]
+		Self::Vxlan,
+		#[cfg(feature = "geneve")]
+		Self::Geneve,
+		#[cfg(feature = "mdns")]
+		Self::Mdns,
+		#[cfg(feature = "netbios")]
+		Self::NetBios,
+		#[cfg(feature = "dnscrypt")]
+		Self::DnsCrypt,
+		#[cfg(feature = "dns")]
+		Self::Dns,
+		#[cfg(feature = "irc")]
+		Self::Irc,
+		#[cfg(feature = "ident")]
+		Self::Ident,
+		#[cfg(feature = "relp")]
+		Self::Relp,
+		#[cfg(feature = "xmpp")]
+		Self::Xmpp,
+		#[cfg(feature = "smtp")]
+		Self::Smtp,
+		#[cfg(feature = "ftp")]
+		Self::Ftp,
+		#[cfg(feature = "git")]
+		Self::Git,
+		#[cfg(feature = "svn")]
+		Self::Svn,
+		#[cfg(feature = "jetdirect")]
+		Self::JetDirect,
+		#[cfg(feature = "lpd")]
+		Self::Lpd,
+		#[cfg(feature = "dhcp")]
+		Self::Dhcp,
+		#[cfg(feature = "ntp")]
+		Self::Ntp,
+		#[cfg(feature = "http3")]
+		Self::Http3,
+		#[cfg(feature = "quic")]
+		Self::Quic,
+		#[cfg(feature = "gquic")]
+		Self::Gquic,
+		#[cfg(feature = "raknet")]
+		Self::RakNet,
+		#[cfg(feature = "lorawan")]
+		Self::LoRaWan,
+		#[cfg(feature = "mysql")]
+		Self::Mysql,
+		#[cfg(feature = "mysqlx")]
+		Self::Mysqlx,
+		#[cfg(feature = "postgres")]
+		Self::Postgres,
+		#[cfg(feature = "tds")]
+		Self::Tds,
+		#[cfg(feature = "clickhouse")]
+		Self::ClickHouse,
+		#[cfg(feature = "thrift")]
+		Self::Thrift,
+		#[cfg(feature = "ajp")]
+		Self::Ajp,
+		#[cfg(feature = "scgi")]
+		Self::Scgi,
+		#[cfg(feature = "nvme_tcp")]
+		Self::NvmeTcp,
+		#[cfg(feature = "dicom")]
+		Self::Dicom,
+		#[cfg(feature = "jsonrpc")]
+		Self::JsonRpc,
+		#[cfg(feature = "msgpack_rpc")]
+		Self::MsgpackRpc,
+		#[cfg(feature = "redis")]
+		Self::Redis,
+		#[cfg(feature = "memcached")]
+		Self::Memcached,
+		#[cfg(feature = "tarantool")]
+		Self::Tarantool,
+		#[cfg(feature = "zookeeper")]
+		Self::Zookeeper,
+		#[cfg(feature = "smpp")]
+		Self::Smpp,
+		#[cfg(feature = "nats")]
+		Self::Nats,
+		#[cfg(feature = "amqp")]
+		Self::Amqp,
+		#[cfg(feature = "mqtt")]
+		Self::Mqtt,
+		#[cfg(feature = "opcua")]
+		Self::OpcUa,
+		#[cfg(feature = "mms")]
+		Self::Mms,
+		#[cfg(feature = "s7")]
+		Self::S7,
+		#[cfg(feature = "rdp")]
+		Self::Rdp,
+		#[cfg(feature = "h225")]
+		Self::H225,
+		#[cfg(feature = "ldap")]
+		Self::Ldap,
+		#[cfg(feature = "kerberos")]
+		Self::Kerberos,
+		#[cfg(feature = "sunrpc")]
+		Self::SunRpc,
+		#[cfg(feature = "rtmp")]
+		Self::Rtmp,
+		#[cfg(feature = "pop3")]
+		Self::Pop3,
+		#[cfg(feature = "smb")]
+		Self::Smb,
+		#[cfg(feature = "turn")]
+		Self::Turn,
+		#[cfg(feature = "stun")]
+		Self::Stun,
+		#[cfg(feature = "riak")]
+		Self::Riak,
+		#[cfg(feature = "openvpn")]
+		Self::OpenVpn,
+		#[cfg(feature = "bittorrent")]
+		Self::BitTorrent,
+		#[cfg(feature = "minecraft")]
+		Self::Minecraft,
+		#[cfg(feature = "dht")]
+		Self::Dht,
+		#[cfg(feature = "source_query")]
+		Self::SourceQuery,
+		#[cfg(feature = "graphite")]
+		Self::Graphite,
+		#[cfg(feature = "influx")]
+		Self::Influx,
+		#[cfg(feature = "socks4")]
+		Self::Socks4,
+		#[cfg(feature = "rtcp")]
+		Self::Rtcp,
+		#[cfg(feature = "rtp")]
+		Self::Rtp,
+		#[cfg(feature = "devp2p")]
+		Self::Devp2p,
+		#[cfg(feature = "mumble")]
+		Self::Mumble,
+	];
+
 	/// Checks if the provided data matches this protocol.
 	#[inline(always)]
 	pub fn detect(&self, data: &[u8]) -> DetectionResult<bool> {
@@ -248,11 +1001,211 @@ impl Protocol {
 				bool_to_status(protocols::ntp::detect(data)),
 				ProtocolVersion::Unknown,
 			),
+			#[cfg(feature = "grpc")]
+			Self::Grpc => protocols::grpc::probe(data),
+			#[cfg(feature = "nats")]
+			Self::Nats => (
+				bool_to_status(protocols::nats::detect(data)),
+				ProtocolVersion::Unknown,
+			),
+			#[cfg(feature = "git")]
+			Self::Git => (
+				bool_to_status(protocols::git::detect(data)),
+				ProtocolVersion::Unknown,
+			),
+			#[cfg(feature = "ipp")]
+			Self::Ipp => protocols::ipp::probe(data),
+			#[cfg(feature = "jetdirect")]
+			Self::JetDirect => (
+				bool_to_status(protocols::jetdirect::detect(data)),
+				ProtocolVersion::Unknown,
+			),
+			#[cfg(feature = "opcua")]
+			Self::OpcUa => protocols::opcua::probe(data),
+			#[cfg(feature = "openvpn")]
+			Self::OpenVpn => protocols::openvpn::probe(data),
+			#[cfg(feature = "bittorrent")]
+			Self::BitTorrent => protocols::bittorrent::probe(data),
+			#[cfg(feature = "dht")]
+			Self::Dht => protocols::dht::probe(data),
+			#[cfg(feature = "mdns")]
+			Self::Mdns => (
+				bool_to_status(protocols::mdns::detect(data)),
+				ProtocolVersion::Unknown,
+			),
+			#[cfg(feature = "netbios")]
+			Self::NetBios => (
+				bool_to_status(protocols::netbios::detect(data)),
+				ProtocolVersion::Unknown,
+			),
+			#[cfg(feature = "turn")]
+			Self::Turn => protocols::turn::probe(data),
+			#[cfg(feature = "tds")]
+			Self::Tds => protocols::tds::probe(data),
+			#[cfg(feature = "riak")]
+			Self::Riak => protocols::riak::probe(data),
+			#[cfg(feature = "mysqlx")]
+			Self::Mysqlx => protocols::mysqlx::probe(data),
+			#[cfg(feature = "minecraft")]
+			Self::Minecraft => protocols::minecraft::probe(data),
+			#[cfg(feature = "source_query")]
+			Self::SourceQuery => (
+				bool_to_status(protocols::source_query::detect(data)),
+				ProtocolVersion::Unknown,
+			),
+			#[cfg(feature = "graphite")]
+			Self::Graphite => (
+				bool_to_status(protocols::graphite::detect(data)),
+				ProtocolVersion::Unknown,
+			),
+			#[cfg(feature = "influx")]
+			Self::Influx => protocols::influx::probe(data),
+			#[cfg(feature = "thrift")]
+			Self::Thrift => protocols::thrift::probe(data),
+			#[cfg(feature = "ajp")]
+			Self::Ajp => protocols::ajp::probe(data),
+			#[cfg(feature = "nvme_tcp")]
+			Self::NvmeTcp => (
+				bool_to_status(protocols::nvme_tcp::detect(data)),
+				ProtocolVersion::Unknown,
+			),
+			#[cfg(feature = "dicom")]
+			Self::Dicom => (
+				bool_to_status(protocols::dicom::detect(data)),
+				ProtocolVersion::Unknown,
+			),
+			#[cfg(feature = "dnscrypt")]
+			Self::DnsCrypt => (
+				bool_to_status(protocols::dnscrypt::detect(data)),
+				ProtocolVersion::Unknown,
+			),
+			#[cfg(feature = "vxlan")]
+			Self::Vxlan => protocols::vxlan::probe(data),
+			#[cfg(feature = "geneve")]
+			Self::Geneve => protocols::geneve::probe(data),
+			#[cfg(feature = "devp2p")]
+			Self::Devp2p => protocols::devp2p::probe(data),
+			#[cfg(feature = "jsonrpc")]
+			Self::JsonRpc => protocols::jsonrpc::probe(data),
+			#[cfg(feature = "msgpack_rpc")]
+			Self::MsgpackRpc => protocols::msgpack_rpc::probe(data),
+			#[cfg(feature = "lpd")]
+			Self::Lpd => protocols::lpd::probe(data),
+			#[cfg(feature = "http3")]
+			Self::Http3 => protocols::http3::probe(data),
+			#[cfg(feature = "gquic")]
+			Self::Gquic => protocols::gquic::probe(data),
+			#[cfg(feature = "spdy")]
+			Self::Spdy => protocols::spdy::probe(data),
+			#[cfg(feature = "raknet")]
+			Self::RakNet => protocols::raknet::probe(data),
+			#[cfg(feature = "mms")]
+			Self::Mms => protocols::mms::probe(data),
+			#[cfg(feature = "lorawan")]
+			Self::LoRaWan => protocols::lorawan::probe(data),
+			#[cfg(feature = "websocket")]
+			Self::WebSocket => protocols::websocket::probe(data),
+			#[cfg(feature = "telnet")]
+			Self::Telnet => protocols::telnet::probe(data),
+			#[cfg(feature = "rdp")]
+			Self::Rdp => protocols::rdp::probe(data),
+			#[cfg(feature = "ldap")]
+			Self::Ldap => protocols::ldap::probe(data),
+			#[cfg(feature = "kerberos")]
+			Self::Kerberos => protocols::kerberos::probe(data),
+			#[cfg(feature = "irc")]
+			Self::Irc => protocols::irc::probe(data),
+			#[cfg(feature = "xmpp")]
+			Self::Xmpp => protocols::xmpp::probe(data),
+			#[cfg(feature = "amqp")]
+			Self::Amqp => protocols::amqp::probe(data),
+			#[cfg(feature = "memcached")]
+			Self::Memcached => protocols::memcached::probe(data),
+			#[cfg(feature = "socks4")]
+			Self::Socks4 => protocols::socks4::probe(data),
+			#[cfg(feature = "proxyproto")]
+			Self::ProxyProtocol => protocols::proxyproto::probe(data),
+			#[cfg(feature = "rtp")]
+			Self::Rtp => protocols::rtp::probe(data),
+			#[cfg(feature = "rtcp")]
+			Self::Rtcp => protocols::rtcp::probe(data),
+			#[cfg(feature = "rtmp")]
+			Self::Rtmp => protocols::rtmp::probe(data),
+			#[cfg(feature = "s7")]
+			Self::S7 => protocols::s7::probe(data),
+			#[cfg(feature = "clickhouse")]
+			Self::ClickHouse => protocols::clickhouse::probe(data),
+			#[cfg(feature = "tarantool")]
+			Self::Tarantool => protocols::tarantool::probe(data),
+			#[cfg(feature = "svn")]
+			Self::Svn => protocols::svn::probe(data),
+			#[cfg(feature = "sunrpc")]
+			Self::SunRpc => protocols::sunrpc::probe(data),
+			#[cfg(feature = "zookeeper")]
+			Self::Zookeeper => protocols::zookeeper::probe(data),
+			#[cfg(feature = "mumble")]
+			Self::Mumble => protocols::mumble::probe(data),
+			#[cfg(feature = "ident")]
+			Self::Ident => protocols::ident::probe(data),
+			#[cfg(feature = "scgi")]
+			Self::Scgi => protocols::scgi::probe(data),
+			#[cfg(feature = "icap")]
+			Self::Icap => protocols::icap::probe(data),
+			#[cfg(feature = "relp")]
+			Self::Relp => protocols::relp::probe(data),
+			#[cfg(feature = "smpp")]
+			Self::Smpp => protocols::smpp::probe(data),
+			#[cfg(feature = "h225")]
+			Self::H225 => protocols::h225::probe(data),
 			#[allow(unreachable_patterns)]
 			_ => (DetectionStatus::NoMatch, ProtocolVersion::Unknown),
 		}
 	}
 
+	/// Extracts protocol-specific metadata from data this protocol matched.
+	///
+	/// Returns [`ProtocolMetadata::None`] for protocols that expose none.
+	#[must_use]
+	#[allow(unused_variables)]
+	pub fn metadata<'a>(&self, data: &'a [u8]) -> ProtocolMetadata<'a> {
+		match self {
+			#[cfg(feature = "opcua")]
+			Self::OpcUa => protocols::opcua::metadata(data),
+			#[cfg(feature = "bittorrent")]
+			Self::BitTorrent => protocols::bittorrent::metadata(data),
+			#[cfg(feature = "tds")]
+			Self::Tds => protocols::tds::metadata(data),
+			#[cfg(feature = "minecraft")]
+			Self::Minecraft => protocols::minecraft::metadata(data),
+			#[cfg(feature = "thrift")]
+			Self::Thrift => protocols::thrift::metadata(data),
+			#[cfg(feature = "vxlan")]
+			Self::Vxlan => protocols::vxlan::metadata(data),
+			#[cfg(feature = "geneve")]
+			Self::Geneve => protocols::geneve::metadata(data),
+			#[cfg(feature = "gquic")]
+			Self::Gquic => protocols::gquic::metadata(data),
+			#[cfg(feature = "http")]
+			Self::Http => protocols::websocket::metadata(data),
+			#[cfg(feature = "websocket")]
+			Self::WebSocket => protocols::websocket::metadata(data),
+			#[cfg(feature = "raknet")]
+			Self::RakNet => protocols::raknet::metadata(data),
+			#[cfg(feature = "lorawan")]
+			Self::LoRaWan => protocols::lorawan::metadata(data),
+			#[cfg(feature = "rdp")]
+			Self::Rdp => protocols::rdp::metadata(data),
+			#[cfg(feature = "proxyproto")]
+			Self::ProxyProtocol => protocols::proxyproto::metadata(data),
+			#[cfg(feature = "sunrpc")]
+			Self::SunRpc => protocols::sunrpc::metadata(data),
+			#[cfg(feature = "tor")]
+			Self::Tls => protocols::tls::metadata(data),
+			#[allow(unreachable_patterns)]
+			_ => ProtocolMetadata::None,
+		}
+	}
+
 	/// Returns the minimum number of bytes required to identify this protocol.
 	#[inline(always)]
 	#[must_use]
@@ -296,10 +1249,257 @@ impl Protocol {
 			Self::Dhcp => 44,
 			#[cfg(feature = "ntp")]
 			Self::Ntp => 48,
+			#[cfg(feature = "grpc")]
+			Self::Grpc => 24,
+			#[cfg(feature = "nats")]
+			Self::Nats => 6,
+			#[cfg(feature = "git")]
+			Self::Git => 21,
+			#[cfg(feature = "ipp")]
+			Self::Ipp => 5,
+			#[cfg(feature = "jetdirect")]
+			Self::JetDirect => 5,
+			#[cfg(feature = "opcua")]
+			Self::OpcUa => 8,
+			#[cfg(feature = "openvpn")]
+			Self::OpenVpn => 14,
+			#[cfg(feature = "bittorrent")]
+			Self::BitTorrent => 20,
+			#[cfg(feature = "dht")]
+			Self::Dht => 12,
+			#[cfg(feature = "mdns")]
+			Self::Mdns => 12,
+			#[cfg(feature = "netbios")]
+			Self::NetBios => 37,
+			#[cfg(feature = "turn")]
+			Self::Turn => 4,
+			#[cfg(feature = "tds")]
+			Self::Tds => 8,
+			#[cfg(feature = "riak")]
+			Self::Riak => 5,
+			#[cfg(feature = "mysqlx")]
+			Self::Mysqlx => 5,
+			#[cfg(feature = "minecraft")]
+			Self::Minecraft => 8,
+			#[cfg(feature = "source_query")]
+			Self::SourceQuery => 9,
+			#[cfg(feature = "graphite")]
+			Self::Graphite => 16,
+			#[cfg(feature = "influx")]
+			Self::Influx => 5,
+			#[cfg(feature = "thrift")]
+			Self::Thrift => 8,
+			#[cfg(feature = "ajp")]
+			Self::Ajp => 5,
+			#[cfg(feature = "nvme_tcp")]
+			Self::NvmeTcp => 16,
+			#[cfg(feature = "dicom")]
+			Self::Dicom => 42,
+			#[cfg(feature = "dnscrypt")]
+			Self::DnsCrypt => 12,
+			#[cfg(feature = "vxlan")]
+			Self::Vxlan => 8,
+			#[cfg(feature = "geneve")]
+			Self::Geneve => 8,
+			#[cfg(feature = "devp2p")]
+			Self::Devp2p => 3,
+			#[cfg(feature = "jsonrpc")]
+			Self::JsonRpc => 1,
+			#[cfg(feature = "msgpack_rpc")]
+			Self::MsgpackRpc => 5,
+			#[cfg(feature = "lpd")]
+			Self::Lpd => 3,
+			#[cfg(feature = "http3")]
+			Self::Http3 => 5,
+			#[cfg(feature = "gquic")]
+			Self::Gquic => 5,
+			#[cfg(feature = "spdy")]
+			Self::Spdy => 2,
+			#[cfg(feature = "raknet")]
+			Self::RakNet => 17,
+			#[cfg(feature = "mms")]
+			Self::Mms => 8,
+			#[cfg(feature = "lorawan")]
+			Self::LoRaWan => 4,
+			#[cfg(feature = "websocket")]
+			Self::WebSocket => 2,
+			#[cfg(feature = "telnet")]
+			Self::Telnet => 2,
+			#[cfg(feature = "rdp")]
+			Self::Rdp => 11,
+			#[cfg(feature = "ldap")]
+			Self::Ldap => 2,
+			#[cfg(feature = "kerberos")]
+			Self::Kerberos => 5,
+			#[cfg(feature = "irc")]
+			Self::Irc => 4,
+			#[cfg(feature = "xmpp")]
+			Self::Xmpp => 5,
+			#[cfg(feature = "amqp")]
+			Self::Amqp => 8,
+			#[cfg(feature = "memcached")]
+			Self::Memcached => 5,
+			#[cfg(feature = "socks4")]
+			Self::Socks4 => 9,
+			#[cfg(feature = "proxyproto")]
+			Self::ProxyProtocol => 15,
+			#[cfg(feature = "rtp")]
+			Self::Rtp => 12,
+			#[cfg(feature = "rtcp")]
+			Self::Rtcp => 8,
+			#[cfg(feature = "rtmp")]
+			Self::Rtmp => 9,
+			#[cfg(feature = "s7")]
+			Self::S7 => 8,
+			#[cfg(feature = "clickhouse")]
+			Self::ClickHouse => 6,
+			#[cfg(feature = "tarantool")]
+			Self::Tarantool => 12,
+			#[cfg(feature = "svn")]
+			Self::Svn => 4,
+			#[cfg(feature = "sunrpc")]
+			Self::SunRpc => 24,
+			#[cfg(feature = "zookeeper")]
+			Self::Zookeeper => 4,
+			#[cfg(feature = "mumble")]
+			Self::Mumble => 6,
+			#[cfg(feature = "ident")]
+			Self::Ident => 4,
+			#[cfg(feature = "scgi")]
+			Self::Scgi => 4,
+			#[cfg(feature = "icap")]
+			Self::Icap => 8,
+			#[cfg(feature = "relp")]
+			Self::Relp => 6,
+			#[cfg(feature = "smpp")]
+			Self::Smpp => 16,
+			#[cfg(feature = "h225")]
+			Self::H225 => 9,
 			#[allow(unreachable_patterns)]
 			_ => 1,
 		}
 	}
+
+	/// Returns `true` if this protocol is carried over TCP.
+	#[must_use]
+	pub const fn supports_tcp(&self) -> bool {
+		match self {
+			#[cfg(feature = "quic")]
+			Self::Quic => false,
+			#[cfg(feature = "dhcp")]
+			Self::Dhcp => false,
+			#[cfg(feature = "ntp")]
+			Self::Ntp => false,
+			#[cfg(feature = "dht")]
+			Self::Dht => false,
+			#[cfg(feature = "mdns")]
+			Self::Mdns => false,
+			#[cfg(feature = "source_query")]
+			Self::SourceQuery => false,
+			#[cfg(feature = "influx")]
+			Self::Influx => false,
+			#[cfg(feature = "dnscrypt")]
+			Self::DnsCrypt => false,
+			#[cfg(feature = "vxlan")]
+			Self::Vxlan => false,
+			#[cfg(feature = "geneve")]
+			Self::Geneve => false,
+			#[cfg(feature = "http3")]
+			Self::Http3 => false,
+			#[cfg(feature = "gquic")]
+			Self::Gquic => false,
+			#[cfg(feature = "raknet")]
+			Self::RakNet => false,
+			#[cfg(feature = "lorawan")]
+			Self::LoRaWan => false,
+			#[cfg(feature = "rtp")]
+			Self::Rtp => false,
+			#[cfg(feature = "rtcp")]
+			Self::Rtcp => false,
+			#[allow(unreachable_patterns)]
+			_ => true,
+		}
+	}
+
+	/// Returns `true` if this protocol is carried over UDP.
+	#[must_use]
+	pub const fn supports_udp(&self) -> bool {
+		match self {
+			#[cfg(feature = "dns")]
+			Self::Dns => true,
+			#[cfg(feature = "quic")]
+			Self::Quic => true,
+			#[cfg(feature = "stun")]
+			Self::Stun => true,
+			#[cfg(feature = "sip")]
+			Self::Sip => true,
+			#[cfg(feature = "rtsp")]
+			Self::Rtsp => true,
+			#[cfg(feature = "dhcp")]
+			Self::Dhcp => true,
+			#[cfg(feature = "ntp")]
+			Self::Ntp => true,
+			#[cfg(feature = "openvpn")]
+			Self::OpenVpn => true,
+			#[cfg(feature = "dht")]
+			Self::Dht => true,
+			#[cfg(feature = "mdns")]
+			Self::Mdns => true,
+			#[cfg(feature = "netbios")]
+			Self::NetBios => true,
+			#[cfg(feature = "turn")]
+			Self::Turn => true,
+			#[cfg(feature = "source_query")]
+			Self::SourceQuery => true,
+			#[cfg(feature = "graphite")]
+			Self::Graphite => true,
+			#[cfg(feature = "influx")]
+			Self::Influx => true,
+			#[cfg(feature = "dnscrypt")]
+			Self::DnsCrypt => true,
+			#[cfg(feature = "vxlan")]
+			Self::Vxlan => true,
+			#[cfg(feature = "geneve")]
+			Self::Geneve => true,
+			#[cfg(feature = "devp2p")]
+			Self::Devp2p => true,
+			#[cfg(feature = "http3")]
+			Self::Http3 => true,
+			#[cfg(feature = "gquic")]
+			Self::Gquic => true,
+			#[cfg(feature = "raknet")]
+			Self::RakNet => true,
+			#[cfg(feature = "lorawan")]
+			Self::LoRaWan => true,
+			#[cfg(feature = "kerberos")]
+			Self::Kerberos => true,
+			#[cfg(feature = "rtp")]
+			Self::Rtp => true,
+			#[cfg(feature = "rtcp")]
+			Self::Rtcp => true,
+			#[cfg(feature = "sunrpc")]
+			Self::SunRpc => true,
+			#[cfg(feature = "mumble")]
+			Self::Mumble => true,
+			#[allow(unreachable_patterns)]
+			_ => false,
+		}
+	}
+
+	/// Returns `true` if this protocol is recognized by heuristics (sizes and
+	/// layout around encrypted data) rather than fixed fields.
+	///
+	/// Matches for these protocols carry lower confidence; they are probed after
+	/// every other protocol.
+	#[must_use]
+	pub const fn is_heuristic(&self) -> bool {
+		match self {
+			#[cfg(feature = "devp2p")]
+			Self::Devp2p => true,
+			#[allow(unreachable_patterns)]
+			_ => false,
+		}
+	}
 }
 
 /// Helper to convert boolean to detection status.
@@ -343,6 +1543,14 @@ mod tests {
 		assert_eq!(version, ProtocolVersion::Http("1.1"));
 	}
 
+	#[test]
+	fn detection_order_lists_every_protocol_once() {
+		assert_eq!(Protocol::DETECTION_ORDER.len(), Protocol::ALL.len());
+		for protocol in Protocol::ALL {
+			assert!(Protocol::DETECTION_ORDER.contains(protocol), "{protocol:?}");
+		}
+	}
+
 	#[test]
 	#[cfg(feature = "http")]
 	fn min_bytes_http() {
@@ -367,6 +1575,13 @@ mod tests {
 		assert_eq!(Protocol::Redis.min_bytes(), 1);
 	}
 
+	#[test]
+	#[cfg(all(feature = "devp2p", feature = "http"))]
+	fn is_heuristic_marks_devp2p_only() {
+		assert!(Protocol::Devp2p.is_heuristic());
+		assert!(!Protocol::Http.is_heuristic());
+	}
+
 	// ── Error paths ──
 
 	#[test]
@@ -0,0 +1,206 @@
+/* src/ml.rs */
+use crate::ProtocolInfo;
+
+/// Number of byte classes in the model's histogram.
+const BYTE_CLASSES: usize = 8;
+
+/// Most bytes counted into the histogram.
+const MAX_BYTES: usize = 256;
+
+/// How many bytes' worth of evidence one payload contributes. The naive Bayes
+/// likelihoods treat bytes as independent, which they are not; scaling the
+/// per-byte average down to a few bytes keeps the probabilities calibrated.
+const EVIDENCE_WEIGHT: f32 = 4.0;
+
+/// Log2 likelihood of each byte class per label, trained offline on text
+/// (English prose, source code, and protocol command lines), structured
+/// binary records (big- and little-endian integers, zero padding, short
+/// length-prefixed strings), and uniformly random bytes.
+///
+/// Columns follow [`byte_class`]: NUL, other control, whitespace, digit,
+/// letter, other printable, `0x80..=0xBF`, `0xC0..=0xFF`.
+const MODEL: [(TrafficClass, [f32; BYTE_CLASSES]); 3] = [
+	(
+		TrafficClass::Text,
+		[
+			-21.371, -21.371, -2.678, -5.367, -0.561, -2.830, -10.653, -11.616,
+		],
+	),
+	(
+		TrafficClass::Binary,
+		[
+			-1.529, -2.516, -5.947, -5.991, -2.108, -4.234, -3.619, -3.639,
+		],
+	),
+	(
+		TrafficClass::Encrypted,
+		[
+			-7.995, -3.146, -6.003, -4.679, -2.300, -2.998, -2.001, -1.997,
+		],
+	),
+];
+
+/// Coarse kind of payload the classifier can tell apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum TrafficClass {
+	/// Printable text, such as a line-based protocol or JSON.
+	Text,
+	/// Structured binary records with integers and padding.
+	Binary,
+	/// Uniformly distributed bytes: encrypted or compressed data.
+	Encrypted,
+}
+
+/// A best-guess label for a payload no detector matched.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Guess {
+	/// Most likely kind of payload.
+	pub class: TrafficClass,
+	/// Posterior probability of `class`, from 0.0 to 1.0.
+	pub probability: f32,
+}
+
+/// Outcome of [`ProtocolDetector::detect_or_classify`](crate::ProtocolDetector::detect_or_classify).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Classification<'a> {
+	/// A structural detector matched.
+	Protocol(ProtocolInfo<'a>),
+	/// No detector matched; the classifier's best guess.
+	Guess(Guess),
+}
+
+/// Classifies a payload by the histogram of its byte classes.
+///
+/// A naive Bayes model over the first 256 bytes, shipped as a static table.
+/// It labels the kind of payload rather than the protocol, so use it only as
+/// a fallback once structural detection returns no match. Returns `None` for
+/// empty data.
+#[must_use]
+pub fn classify(data: &[u8]) -> Option<Guess> {
+	let data = &data[..data.len().min(MAX_BYTES)];
+	if data.is_empty() {
+		return None;
+	}
+	let mut histogram = [0u32; BYTE_CLASSES];
+	for &byte in data {
+		histogram[byte_class(byte)] += 1;
+	}
+
+	#[allow(clippy::cast_precision_loss)]
+	let scale = EVIDENCE_WEIGHT / data.len() as f32;
+	let scores = MODEL.map(|(_, likelihoods)| {
+		#[allow(clippy::cast_precision_loss)]
+		let total: f32 = histogram
+			.iter()
+			.zip(likelihoods)
+			.map(|(&count, likelihood)| count as f32 * likelihood)
+			.sum();
+		total * scale
+	});
+
+	let (best, best_score) =
+		scores
+			.iter()
+			.copied()
+			.enumerate()
+			.fold((0, f32::NEG_INFINITY), |acc, (i, score)| {
+				if score > acc.1 { (i, score) } else { acc }
+			});
+	let total: f32 = scores.iter().map(|&score| exp2(score - best_score)).sum();
+	Some(Guess {
+		class: MODEL[best].0,
+		probability: (1.0 / total).min(1.0),
+	})
+}
+
+/// Maps a byte to its histogram column.
+const fn byte_class(byte: u8) -> usize {
+	match byte {
+		0x00 => 0,
+		b'\t' | b'\n' | b'\r' | b' ' => 2,
+		0x01..=0x1F | 0x7F => 1,
+		b'0'..=b'9' => 3,
+		b'A'..=b'Z' | b'a'..=b'z' => 4,
+		0x21..=0x7E => 5,
+		0x80..=0xBF => 6,
+		0xC0..=0xFF => 7,
+	}
+}
+
+/// Computes `2^y` for `y <= 0` without `std` (relative error below 2e-4).
+fn exp2(y: f32) -> f32 {
+	if y < -126.0 {
+		return 0.0;
+	}
+	// Split into an integer power applied through the exponent bits and a
+	// fraction in (0, 1] approximated by a cubic.
+	#[allow(clippy::cast_possible_truncation)]
+	let whole = y as i32 - 1;
+	#[allow(clippy::cast_precision_loss)]
+	let fraction = y - whole as f32;
+	let power = 1.0 + fraction * (0.696_065_6 + fraction * (0.224_494_3 + fraction * 0.079_400_2));
+	#[allow(clippy::cast_sign_loss)]
+	let scale = f32::from_bits(((127 + whole) as u32) << 23);
+	power * scale
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_classify_text() {
+		let guess = classify(b"GET /index.html HTTP/1.1\r\nHost: example.com\r\n\r\n").unwrap();
+		assert_eq!(guess.class, TrafficClass::Text);
+		assert!(guess.probability > 0.9);
+	}
+
+	#[test]
+	fn test_classify_binary() {
+		let mut record = [0u8; 48];
+		record[..8].copy_from_slice(&[0x00, 0x01, 0x00, 0x2A, 0x00, 0x00, 0x10, 0x00]);
+		record[20..25].copy_from_slice(b"\x04root");
+		let guess = classify(&record).unwrap();
+		assert_eq!(guess.class, TrafficClass::Binary);
+		assert!(guess.probability > 0.9);
+	}
+
+	#[test]
+	fn test_classify_random_bytes() {
+		// A xorshift stream stands in for ciphertext.
+		let mut state = 0x2545_f491_u32;
+		let mut data = [0u8; 256];
+		for byte in &mut data {
+			state ^= state << 13;
+			state ^= state >> 17;
+			state ^= state << 5;
+			*byte = state.to_le_bytes()[0];
+		}
+		let guess = classify(&data).unwrap();
+		assert_eq!(guess.class, TrafficClass::Encrypted);
+		assert!(guess.probability > 0.5 && guess.probability <= 1.0);
+	}
+
+	#[test]
+	fn test_classify_empty() {
+		assert_eq!(classify(b""), None);
+	}
+
+	#[test]
+	fn test_exp2() {
+		for (y, expected) in [
+			(0.0f32, 1.0f32),
+			(-0.25, 0.840_896_4),
+			(-1.0, 0.5),
+			(-3.7, 0.076_946_5),
+			(-20.5, 6.743_496e-7),
+		] {
+			assert!((exp2(y) - expected).abs() <= expected * 2e-4, "{y}");
+		}
+		assert_eq!(exp2(-200.0), 0.0);
+	}
+}
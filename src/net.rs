@@ -0,0 +1,619 @@
+/* src/net.rs */
+
+use crate::{DetectionError, Protocol, ProtocolDetector, ProtocolInfo};
+#[cfg(feature = "tokio")]
+use std::{
+	future::poll_fn,
+	pin::Pin,
+	task::{Context, Poll, ready},
+};
+use std::{
+	io::{self, Read, Write},
+	net::{SocketAddr, TcpStream, UdpSocket},
+	thread,
+	time::{Duration, Instant},
+};
+#[cfg(feature = "tokio")]
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+/// Initial pause between peeks while waiting for more bytes.
+const INITIAL_BACKOFF: Duration = Duration::from_millis(1);
+/// Upper bound for the pause between peeks.
+const MAX_BACKOFF: Duration = Duration::from_millis(32);
+/// Upper bound for the bytes buffered while sniffing a stream.
+const MAX_SNIFF_BYTES: usize = 16 * 1024;
+
+/// Result of sniffing a live connection without consuming its bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Peeked<'a> {
+	/// Detected protocol, or `None` if no enabled protocol matched.
+	pub info: Option<ProtocolInfo<'a>>,
+	/// Number of bytes that were visible in the socket buffer.
+	pub peeked: usize,
+}
+
+/// Classifies a TCP connection using `MSG_PEEK`, leaving every byte in the socket.
+///
+/// The stream is peeked repeatedly until the detector reaches a verdict, the
+/// buffer is full, or `timeout` elapses. If the buffer fills up before a verdict
+/// is reached, `info` is `None`. The stream's read timeout is restored before
+/// returning.
+///
+/// # Errors
+///
+/// Returns `TimedOut` if the detector still needs more bytes when the deadline
+/// passes, `UnexpectedEof` if the peer closes the connection first, or any
+/// I/O error raised by the socket.
+pub fn classify_stream<'a, T>(
+	detector: &ProtocolDetector<T>,
+	stream: &TcpStream,
+	buf: &'a mut [u8],
+	timeout: Duration,
+) -> io::Result<Peeked<'a>> {
+	let previous_timeout = stream.read_timeout()?;
+	let result = peek_until_verdict(detector, stream, buf, timeout);
+	stream.set_read_timeout(previous_timeout)?;
+
+	let peeked = result?;
+	let info = detector.detect_info(&buf[..peeked]).ok().flatten();
+	Ok(Peeked { info, peeked })
+}
+
+/// Peeks until the detector no longer reports `InsufficientData`.
+fn peek_until_verdict<T>(
+	detector: &ProtocolDetector<T>,
+	stream: &TcpStream,
+	buf: &mut [u8],
+	timeout: Duration,
+) -> io::Result<usize> {
+	let deadline = Instant::now() + timeout;
+	let mut backoff = INITIAL_BACKOFF;
+	let mut last_len = None;
+
+	loop {
+		let remaining = deadline.saturating_duration_since(Instant::now());
+		if remaining.is_zero() {
+			return Err(io::ErrorKind::TimedOut.into());
+		}
+		stream.set_read_timeout(Some(remaining))?;
+
+		let n = match stream.peek(buf) {
+			Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+			Ok(n) => n,
+			Err(e) if is_retryable(&e) => 0,
+			Err(e) => return Err(e),
+		};
+
+		if n > 0 && last_len != Some(n) {
+			last_len = Some(n);
+			backoff = INITIAL_BACKOFF;
+			match detector.detect_info(&buf[..n]) {
+				Err(DetectionError::InsufficientData) if n < buf.len() => {}
+				_ => return Ok(n),
+			}
+		}
+
+		// `peek` returns immediately while unread bytes exist, so pause before
+		// looking again instead of spinning on the same prefix.
+		thread::sleep(backoff.min(deadline.saturating_duration_since(Instant::now())));
+		backoff = (backoff * 2).min(MAX_BACKOFF);
+	}
+}
+
+/// Result of classifying a datagram that is still queued on its socket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Datagram<'a> {
+	/// Detected protocol, or `None` if no enabled protocol matched.
+	pub info: Option<ProtocolInfo<'a>>,
+	/// Number of datagram bytes copied into the buffer.
+	pub len: usize,
+	/// Address the datagram was sent from.
+	pub peer: SocketAddr,
+}
+
+/// Classifies the next queued datagram without removing it from the socket.
+///
+/// The datagram is read with `peek_from`, so a later `recv_from` still returns
+/// it. Datagrams longer than `buf` are inspected from their first bytes only.
+/// Use a detector built with [`.udp()`](crate::ProtocolDetectorBuilder::udp)
+/// and the UDP protocols you expect (DNS, QUIC, DHCP, NTP, STUN, ...).
+///
+/// # Errors
+///
+/// Returns any I/O error raised by the socket, including `WouldBlock` on a
+/// non-blocking socket with nothing queued.
+pub fn classify_datagram<'a, T>(
+	detector: &ProtocolDetector<T>,
+	socket: &UdpSocket,
+	buf: &'a mut [u8],
+) -> io::Result<Datagram<'a>> {
+	let (len, peer) = socket.peek_from(buf)?;
+	Ok(datagram(detector, &buf[..len], peer))
+}
+
+/// Async counterpart of [`classify_datagram`] for tokio sockets.
+///
+/// # Errors
+///
+/// Returns any I/O error raised by the socket.
+#[cfg(feature = "tokio")]
+pub async fn classify_datagram_async<'a, T>(
+	detector: &ProtocolDetector<T>,
+	socket: &tokio::net::UdpSocket,
+	buf: &'a mut [u8],
+) -> io::Result<Datagram<'a>> {
+	let (len, peer) = socket.peek_from(buf).await?;
+	Ok(datagram(detector, &buf[..len], peer))
+}
+
+/// Runs detection over a complete datagram.
+fn datagram<'a, T>(
+	detector: &ProtocolDetector<T>,
+	data: &'a [u8],
+	peer: SocketAddr,
+) -> Datagram<'a> {
+	// A datagram never grows, so "insufficient data" is a final answer.
+	Datagram {
+		info: detector.detect_info(data).ok().flatten(),
+		len: data.len(),
+		peer,
+	}
+}
+
+/// Returns `true` for errors that only mean "no data yet".
+fn is_retryable(err: &io::Error) -> bool {
+	matches!(
+		err.kind(),
+		io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut | io::ErrorKind::Interrupted
+	)
+}
+
+/// A connection whose first bytes were read for detection.
+///
+/// The sniffed prefix is replayed by the first reads, so the wrapped stream can
+/// be handed to any consumer as if nothing had been read from it.
+#[derive(Debug)]
+pub struct Sniffed<IO> {
+	/// The underlying connection.
+	io: IO,
+	/// Bytes read during detection.
+	prefix: Vec<u8>,
+	/// How many prefix bytes have already been replayed.
+	pos: usize,
+	/// Detected protocol, if any.
+	protocol: Option<Protocol>,
+}
+
+impl<IO> Sniffed<IO> {
+	/// Wraps a connection together with the bytes already read from it.
+	#[must_use]
+	pub fn new(io: IO, prefix: Vec<u8>, protocol: Option<Protocol>) -> Self {
+		Self {
+			io,
+			prefix,
+			pos: 0,
+			protocol,
+		}
+	}
+
+	/// Returns the detected protocol.
+	#[must_use]
+	pub fn protocol(&self) -> Option<Protocol> {
+		self.protocol
+	}
+
+	/// Returns the detected protocol with its version, probed from the prefix.
+	#[must_use]
+	pub fn info(&self) -> Option<ProtocolInfo<'_>> {
+		self.protocol.map(|protocol| ProtocolInfo {
+			protocol,
+			version: protocol.probe_info(&self.prefix).1,
+		})
+	}
+
+	/// Returns every byte read during detection.
+	#[must_use]
+	pub fn prefix(&self) -> &[u8] {
+		&self.prefix
+	}
+
+	/// Returns a reference to the underlying connection.
+	#[must_use]
+	pub fn get_ref(&self) -> &IO {
+		&self.io
+	}
+
+	/// Returns a mutable reference to the underlying connection.
+	///
+	/// Reading from it directly skips any prefix bytes not yet replayed.
+	pub fn get_mut(&mut self) -> &mut IO {
+		&mut self.io
+	}
+
+	/// Splits into the underlying connection and the prefix bytes not yet replayed.
+	#[must_use]
+	pub fn into_parts(mut self) -> (IO, Vec<u8>) {
+		self.prefix.drain(..self.pos);
+		(self.io, self.prefix)
+	}
+
+	/// Copies pending prefix bytes into `buf`, returning how many were copied.
+	fn replay(&mut self, buf: &mut [u8]) -> usize {
+		let pending = &self.prefix[self.pos..];
+		let n = pending.len().min(buf.len());
+		buf[..n].copy_from_slice(&pending[..n]);
+		self.pos += n;
+		n
+	}
+}
+
+impl<IO: Read> Read for Sniffed<IO> {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		if self.pos < self.prefix.len() {
+			return Ok(self.replay(buf));
+		}
+		self.io.read(buf)
+	}
+}
+
+impl<IO: Write> Write for Sniffed<IO> {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.io.write(buf)
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		self.io.flush()
+	}
+}
+
+#[cfg(feature = "tokio")]
+impl<IO: AsyncRead + Unpin> AsyncRead for Sniffed<IO> {
+	fn poll_read(
+		self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+		buf: &mut ReadBuf<'_>,
+	) -> Poll<io::Result<()>> {
+		let this = self.get_mut();
+		if this.pos < this.prefix.len() {
+			let n = this.replay(buf.initialize_unfilled());
+			buf.advance(n);
+			return Poll::Ready(Ok(()));
+		}
+		Pin::new(&mut this.io).poll_read(cx, buf)
+	}
+}
+
+#[cfg(feature = "tokio")]
+impl<IO: AsyncWrite + Unpin> AsyncWrite for Sniffed<IO> {
+	fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+		Pin::new(&mut self.get_mut().io).poll_write(cx, buf)
+	}
+
+	fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+		Pin::new(&mut self.get_mut().io).poll_flush(cx)
+	}
+
+	fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+		Pin::new(&mut self.get_mut().io).poll_shutdown(cx)
+	}
+}
+
+/// Reads from a blocking connection until the detector reaches a verdict.
+///
+/// This is the counterpart of [`classify_stream`] for readers that cannot peek:
+/// the bytes read are kept in the returned [`Sniffed`] and replayed on read.
+/// Reading stops once a protocol matches, no enabled protocol can match, the
+/// peer closes the connection, or the detector's inspection window is full.
+///
+/// # Errors
+///
+/// Returns any I/O error raised by the connection.
+pub fn sniff_blocking<IO, T>(detector: &ProtocolDetector<T>, mut io: IO) -> io::Result<Sniffed<IO>>
+where
+	IO: Read,
+{
+	let capacity = detector.max_inspect_bytes.min(MAX_SNIFF_BYTES);
+	let mut prefix = vec![0u8; capacity];
+	let mut filled = 0;
+
+	let protocol = loop {
+		if filled == capacity {
+			break detector.detect(&prefix[..filled]).ok().flatten();
+		}
+		let n = match io.read(&mut prefix[filled..]) {
+			Ok(n) => n,
+			Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+			Err(e) => return Err(e),
+		};
+		if n == 0 {
+			break detector.detect(&prefix[..filled]).ok().flatten();
+		}
+		filled += n;
+		match detector.detect(&prefix[..filled]) {
+			Err(DetectionError::InsufficientData) => {}
+			result => break result.ok().flatten(),
+		}
+	};
+
+	prefix.truncate(filled);
+	Ok(Sniffed::new(io, prefix, protocol))
+}
+
+/// Reads from an async connection until the detector reaches a verdict.
+///
+/// Reading stops once a protocol matches, no enabled protocol can match, the
+/// peer closes the connection, or the detector's inspection window is full.
+/// The bytes read are kept in the returned [`Sniffed`] and replayed on read.
+///
+/// # Errors
+///
+/// Returns any I/O error raised by the connection.
+#[cfg(feature = "tokio")]
+pub async fn sniff<IO, T>(detector: &ProtocolDetector<T>, mut io: IO) -> io::Result<Sniffed<IO>>
+where
+	IO: AsyncRead + Unpin,
+{
+	let capacity = detector.max_inspect_bytes.min(MAX_SNIFF_BYTES);
+	let mut prefix = vec![0u8; capacity];
+	let mut filled = 0;
+
+	let protocol = loop {
+		if filled == capacity {
+			break detector.detect(&prefix[..filled]).ok().flatten();
+		}
+		let n = poll_fn(|cx| {
+			let mut read_buf = ReadBuf::new(&mut prefix[filled..]);
+			ready!(Pin::new(&mut io).poll_read(cx, &mut read_buf))?;
+			Poll::Ready(Ok::<_, io::Error>(read_buf.filled().len()))
+		})
+		.await?;
+		if n == 0 {
+			break detector.detect(&prefix[..filled]).ok().flatten();
+		}
+		filled += n;
+		match detector.detect(&prefix[..filled]) {
+			Err(DetectionError::InsufficientData) => {}
+			result => break result.ok().flatten(),
+		}
+	};
+
+	prefix.truncate(filled);
+	Ok(Sniffed::new(io, prefix, protocol))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	#[allow(unused_imports)]
+	use crate::Protocol;
+	use std::net::TcpListener;
+
+	/// Connects a client/server pair over loopback.
+	fn pair() -> (TcpStream, TcpStream) {
+		let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+		let client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+		let (server, _) = listener.accept().unwrap();
+		(client, server)
+	}
+
+	// ── Correct paths ──
+
+	#[test]
+	#[cfg(feature = "http")]
+	fn classify_stream_detects_http_without_consuming() {
+		let (mut client, server) = pair();
+		client.write_all(b"GET / HTTP/1.1\r\n").unwrap();
+
+		let detector = ProtocolDetector::builder().http().build();
+		let mut buf = [0u8; 64];
+		let peeked = classify_stream(&detector, &server, &mut buf, Duration::from_secs(2)).unwrap();
+		assert_eq!(peeked.info.map(|i| i.protocol), Some(Protocol::Http));
+		assert_eq!(peeked.peeked, 16);
+
+		// Bytes are still available for a regular read.
+		let mut again = [0u8; 16];
+		assert_eq!(server.peek(&mut again).unwrap(), 16);
+	}
+
+	#[test]
+	#[cfg(feature = "http")]
+	fn classify_stream_waits_for_split_writes() {
+		let (mut client, server) = pair();
+		client.write_all(b"GE").unwrap();
+		let writer = thread::spawn(move || {
+			thread::sleep(Duration::from_millis(20));
+			client.write_all(b"T / HTTP/1.1\r\n").unwrap();
+			client
+		});
+
+		let detector = ProtocolDetector::builder().http().build();
+		let mut buf = [0u8; 64];
+		let peeked = classify_stream(&detector, &server, &mut buf, Duration::from_secs(2)).unwrap();
+		assert_eq!(peeked.info.map(|i| i.protocol), Some(Protocol::Http));
+		drop(writer.join().unwrap());
+	}
+
+	#[test]
+	#[cfg(all(feature = "grpc", feature = "http"))]
+	fn classify_stream_waits_for_grpc_headers() {
+		let (mut client, server) = pair();
+		client
+			.write_all(b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n\x00\x00\x00\x04\x00\x00\x00\x00\x00")
+			.unwrap();
+		let writer = thread::spawn(move || {
+			thread::sleep(Duration::from_millis(20));
+			client
+				.write_all(b"\x00\x00\x14\x01\x04\x00\x00\x00\x01\x83\x86\x5f\x10application/grpc")
+				.unwrap();
+			client
+		});
+
+		let detector = ProtocolDetector::builder().grpc().http().build();
+		let mut buf = [0u8; 64];
+		let peeked = classify_stream(&detector, &server, &mut buf, Duration::from_secs(2)).unwrap();
+		assert_eq!(peeked.info.map(|i| i.protocol), Some(Protocol::Grpc));
+		drop(writer.join().unwrap());
+	}
+
+	#[test]
+	fn classify_stream_restores_read_timeout() {
+		let (mut client, server) = pair();
+		client.write_all(b"\x42\x42\x42\x42").unwrap();
+		server
+			.set_read_timeout(Some(Duration::from_secs(7)))
+			.unwrap();
+
+		let detector = ProtocolDetector::builder().build();
+		let mut buf = [0u8; 64];
+		let peeked = classify_stream(&detector, &server, &mut buf, Duration::from_secs(2)).unwrap();
+		assert_eq!(peeked.info, None);
+		assert_eq!(server.read_timeout().unwrap(), Some(Duration::from_secs(7)));
+	}
+
+	#[test]
+	fn sniffed_replays_prefix_before_inner_reads() {
+		let mut sniffed = Sniffed::new(&b" world"[..], b"hello".to_vec(), None);
+		let mut out = String::new();
+		sniffed.read_to_string(&mut out).unwrap();
+		assert_eq!(out, "hello world");
+	}
+
+	#[test]
+	fn sniffed_into_parts_returns_unreplayed_prefix() {
+		let mut sniffed = Sniffed::new(&b""[..], b"hello".to_vec(), None);
+		let mut head = [0u8; 2];
+		sniffed.read_exact(&mut head).unwrap();
+		let (_, rest) = sniffed.into_parts();
+		assert_eq!(rest, b"llo");
+	}
+
+	#[test]
+	#[cfg(feature = "http")]
+	fn sniff_blocking_detects_and_replays() {
+		let data: &[u8] = b"GET / HTTP/1.1\r\nHost: a\r\n\r\n";
+		let detector = ProtocolDetector::builder().http().build();
+		let mut sniffed = sniff_blocking(&detector, data).unwrap();
+		assert_eq!(sniffed.protocol(), Some(Protocol::Http));
+
+		let mut out = Vec::new();
+		sniffed.read_to_end(&mut out).unwrap();
+		assert_eq!(out, data);
+	}
+
+	#[tokio::test]
+	#[cfg(all(feature = "tokio", feature = "http"))]
+	async fn sniff_detects_and_replays_async() {
+		use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+		let (mut client, server) = tokio::io::duplex(64);
+		client.write_all(b"GET / HTTP/1.1\r\n").await.unwrap();
+		drop(client);
+
+		let detector = ProtocolDetector::builder().http().build();
+		let mut sniffed = sniff(&detector, server).await.unwrap();
+		assert_eq!(sniffed.protocol(), Some(Protocol::Http));
+		assert_eq!(
+			sniffed.info().map(|i| i.version),
+			Some(crate::ProtocolVersion::Http("1.1"))
+		);
+
+		let mut out = Vec::new();
+		sniffed.read_to_end(&mut out).await.unwrap();
+		assert_eq!(out, b"GET / HTTP/1.1\r\n");
+	}
+
+	#[test]
+	#[cfg(feature = "stun")]
+	fn classify_datagram_detects_stun_without_consuming() {
+		let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+		let client = UdpSocket::bind("127.0.0.1:0").unwrap();
+		let mut request = [0u8; 20];
+		request[1] = 0x01;
+		request[4..8].copy_from_slice(&[0x21, 0x12, 0xA4, 0x42]);
+		client
+			.send_to(&request, server.local_addr().unwrap())
+			.unwrap();
+
+		let detector = ProtocolDetector::builder().udp().stun().build();
+		let mut buf = [0u8; 64];
+		let datagram = classify_datagram(&detector, &server, &mut buf).unwrap();
+		assert_eq!(datagram.info.map(|i| i.protocol), Some(Protocol::Stun));
+		assert_eq!(datagram.len, 20);
+		assert_eq!(datagram.peer, client.local_addr().unwrap());
+
+		let mut again = [0u8; 64];
+		assert_eq!(server.recv_from(&mut again).unwrap().0, 20);
+	}
+
+	#[tokio::test]
+	#[cfg(all(feature = "tokio", feature = "stun"))]
+	async fn classify_datagram_async_detects_stun() {
+		let server = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+		let client = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+		let mut request = [0u8; 20];
+		request[1] = 0x01;
+		request[4..8].copy_from_slice(&[0x21, 0x12, 0xA4, 0x42]);
+		client
+			.send_to(&request, server.local_addr().unwrap())
+			.await
+			.unwrap();
+
+		let detector = ProtocolDetector::builder().udp().stun().build();
+		let mut buf = [0u8; 64];
+		let datagram = classify_datagram_async(&detector, &server, &mut buf)
+			.await
+			.unwrap();
+		assert_eq!(datagram.info.map(|i| i.protocol), Some(Protocol::Stun));
+		assert_eq!(datagram.peer, client.local_addr().unwrap());
+	}
+
+	// ── Error paths ──
+
+	#[test]
+	fn classify_datagram_reports_short_datagram_as_unknown() {
+		let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+		let client = UdpSocket::bind("127.0.0.1:0").unwrap();
+		client
+			.send_to(&[0x00, 0x01], server.local_addr().unwrap())
+			.unwrap();
+
+		let detector = ProtocolDetector::builder().udp().all().build();
+		let mut buf = [0u8; 64];
+		let datagram = classify_datagram(&detector, &server, &mut buf).unwrap();
+		assert_eq!(datagram.info, None);
+		assert_eq!(datagram.len, 2);
+	}
+
+	#[test]
+	fn classify_datagram_would_block_when_empty() {
+		let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+		server.set_nonblocking(true).unwrap();
+		let detector = ProtocolDetector::builder().udp().build();
+		let mut buf = [0u8; 64];
+		let err = classify_datagram(&detector, &server, &mut buf).unwrap_err();
+		assert_eq!(err.kind(), io::ErrorKind::WouldBlock);
+	}
+
+	#[test]
+	#[cfg(feature = "http")]
+	fn classify_stream_times_out_on_partial_data() {
+		let (mut client, server) = pair();
+		client.write_all(b"GE").unwrap();
+
+		let detector = ProtocolDetector::builder().http().build();
+		let mut buf = [0u8; 64];
+		let err = classify_stream(&detector, &server, &mut buf, Duration::from_millis(50)).unwrap_err();
+		assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+	}
+
+	#[test]
+	fn classify_stream_reports_eof() {
+		let (client, server) = pair();
+		drop(client);
+
+		let detector = ProtocolDetector::builder().build();
+		let mut buf = [0u8; 64];
+		let err = classify_stream(&detector, &server, &mut buf, Duration::from_secs(2)).unwrap_err();
+		assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+	}
+}
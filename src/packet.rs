@@ -0,0 +1,727 @@
+/* src/packet.rs */
+
+use crate::{DetectionResult, Protocol, ProtocolDetector, ProtocolInfo, Tcp, Udp};
+use thiserror::Error;
+
+/// EtherType for IPv4.
+const ETHERTYPE_IPV4: u16 = 0x0800;
+/// EtherType for IPv6.
+const ETHERTYPE_IPV6: u16 = 0x86DD;
+/// EtherType for transparent Ethernet bridging (an Ethernet frame in a tunnel).
+const ETHERTYPE_TEB: u16 = 0x6558;
+/// EtherType for an 802.1Q VLAN tag.
+const ETHERTYPE_VLAN: u16 = 0x8100;
+/// EtherType for an 802.1ad (QinQ) service tag.
+const ETHERTYPE_QINQ: u16 = 0x88A8;
+/// IP protocol number for TCP.
+const IPPROTO_TCP: u8 = 6;
+/// IP protocol number for UDP.
+const IPPROTO_UDP: u8 = 17;
+/// Maximum number of stacked VLAN tags to skip.
+const MAX_VLAN_TAGS: usize = 2;
+/// Maximum number of IPv6 extension headers to skip.
+const MAX_IPV6_EXTENSIONS: usize = 8;
+
+/// Transport protocol carrying a payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Transport {
+	/// Transmission Control Protocol.
+	Tcp,
+	/// User Datagram Protocol.
+	Udp,
+}
+
+/// Errors that can occur while locating the payload of a packet.
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum PacketError {
+	/// A header extends past the end of the packet.
+	#[error("packet truncated inside a header")]
+	Truncated,
+	/// The Ethernet frame does not carry IPv4 or IPv6.
+	#[error("unsupported ethertype {0:#06x}")]
+	UnsupportedEtherType(u16),
+	/// The IP version nibble is neither 4 nor 6.
+	#[error("unsupported IP version {0}")]
+	UnsupportedIpVersion(u8),
+	/// The IP payload is neither TCP nor UDP.
+	#[error("unsupported IP protocol {0}")]
+	UnsupportedTransport(u8),
+	/// The packet is a non-initial IP fragment, so it has no transport header.
+	#[error("non-initial IP fragment")]
+	Fragment,
+	/// A header field is inconsistent (for example a length below the minimum).
+	#[error("malformed header")]
+	Malformed,
+}
+
+/// Transport payload located inside a packet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Segment<'a> {
+	/// Transport protocol of the payload.
+	pub transport: Transport,
+	/// Source port.
+	pub src_port: u16,
+	/// Destination port.
+	pub dst_port: u16,
+	/// Application payload following the transport header.
+	pub payload: &'a [u8],
+}
+
+/// Detection result for a single packet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameInfo<'a> {
+	/// The located transport payload.
+	pub segment: Segment<'a>,
+	/// Detection result for the payload.
+	pub result: DetectionResult<Option<ProtocolInfo<'a>>>,
+}
+
+/// Runs a TCP or UDP detector over the payload of captured packets.
+#[derive(Debug, Clone)]
+pub struct FrameDetector {
+	/// Detector applied to TCP payloads.
+	tcp: ProtocolDetector<Tcp>,
+	/// Detector applied to UDP payloads.
+	udp: ProtocolDetector<Udp>,
+}
+
+impl FrameDetector {
+	/// Creates a frame detector from one detector per transport.
+	#[must_use]
+	pub fn new(tcp: ProtocolDetector<Tcp>, udp: ProtocolDetector<Udp>) -> Self {
+		Self { tcp, udp }
+	}
+
+	/// Detects the protocol carried by an Ethernet frame.
+	///
+	/// # Errors
+	///
+	/// Returns a [`PacketError`] if the frame does not carry a TCP or UDP payload.
+	pub fn detect_from_frame<'a>(&self, frame: &'a [u8]) -> Result<FrameInfo<'a>, PacketError> {
+		Ok(self.detect_segment(parse_frame(frame)?))
+	}
+
+	/// Detects the protocol carried by a raw IPv4 or IPv6 packet (no link header).
+	///
+	/// # Errors
+	///
+	/// Returns a [`PacketError`] if the packet does not carry a TCP or UDP payload.
+	pub fn detect_from_ip<'a>(&self, packet: &'a [u8]) -> Result<FrameInfo<'a>, PacketError> {
+		Ok(self.detect_segment(parse_ip(packet)?))
+	}
+
+	/// Detects the protocol carried inside a tunnel packet.
+	///
+	/// Returns `None` unless `info` matched a tunnel protocol whose inner frame
+	/// is in the payload (VXLAN, or GENEVE carrying Ethernet, IPv4, or IPv6).
+	/// Call it again on the inner result to unwrap nested tunnels.
+	///
+	/// The inner result is a [`PacketError`] if the inner frame does not carry a
+	/// TCP or UDP payload.
+	#[must_use]
+	pub fn detect_encapsulated<'a>(
+		&self,
+		info: &FrameInfo<'a>,
+	) -> Option<Result<FrameInfo<'a>, PacketError>> {
+		let protocol = info.result.ok()??.protocol;
+		match inner_frame(protocol, info.segment.payload)? {
+			(ETHERTYPE_TEB, frame) => Some(self.detect_from_frame(frame)),
+			(_, packet) => Some(self.detect_from_ip(packet)),
+		}
+	}
+
+	/// Runs the detector matching the segment's transport, probing the port hint first.
+	fn detect_segment<'a>(&self, segment: Segment<'a>) -> FrameInfo<'a> {
+		let hint = port_hint(segment.transport, segment.dst_port)
+			.or_else(|| port_hint(segment.transport, segment.src_port));
+		let result = match segment.transport {
+			Transport::Tcp => self.tcp.detect_info_hinted(segment.payload, hint),
+			Transport::Udp => self.udp.detect_info_hinted(segment.payload, hint),
+		};
+		FrameInfo { segment, result }
+	}
+}
+
+/// Locates the transport payload of an Ethernet II frame.
+///
+/// Up to two 802.1Q/802.1ad VLAN tags are skipped.
+///
+/// # Errors
+///
+/// Returns a [`PacketError`] if the frame does not carry a TCP or UDP payload.
+pub fn parse_frame(frame: &[u8]) -> Result<Segment<'_>, PacketError> {
+	let mut offset = 12;
+	let mut ethertype = read_u16(frame, offset)?;
+	offset += 2;
+
+	let mut tags = 0;
+	while matches!(ethertype, ETHERTYPE_VLAN | ETHERTYPE_QINQ) && tags < MAX_VLAN_TAGS {
+		ethertype = read_u16(frame, offset + 2)?;
+		offset += 4;
+		tags += 1;
+	}
+
+	match ethertype {
+		ETHERTYPE_IPV4 | ETHERTYPE_IPV6 => parse_ip(&frame[offset..]),
+		other => Err(PacketError::UnsupportedEtherType(other)),
+	}
+}
+
+/// Locates the transport payload of a raw IPv4 or IPv6 packet.
+///
+/// # Errors
+///
+/// Returns a [`PacketError`] if the packet does not carry a TCP or UDP payload.
+pub fn parse_ip(packet: &[u8]) -> Result<Segment<'_>, PacketError> {
+	let first = *packet.first().ok_or(PacketError::Truncated)?;
+	match first >> 4 {
+		4 => parse_ipv4(packet),
+		6 => parse_ipv6(packet),
+		version => Err(PacketError::UnsupportedIpVersion(version)),
+	}
+}
+
+/// Returns the protocol usually served on a well-known port.
+///
+/// The hint only changes which protocol is probed first; the payload must still
+/// match it. An HTTP hint is probed after gRPC, IPP and WebSocket, which refine
+/// HTTP and are probed ahead of it without a hint.
+#[must_use]
+#[allow(unused_variables)]
+pub fn port_hint(transport: Transport, port: u16) -> Option<Protocol> {
+	match (transport, port) {
+		#[cfg(feature = "ftp")]
+		(Transport::Tcp, 21) => Some(Protocol::Ftp),
+		#[cfg(feature = "ssh")]
+		(Transport::Tcp, 22) => Some(Protocol::Ssh),
+		#[cfg(feature = "telnet")]
+		(Transport::Tcp, 23) => Some(Protocol::Telnet),
+		#[cfg(feature = "smtp")]
+		(Transport::Tcp, 25 | 587) => Some(Protocol::Smtp),
+		#[cfg(feature = "dns")]
+		(_, 53) => Some(Protocol::Dns),
+		#[cfg(feature = "dhcp")]
+		(Transport::Udp, 67 | 68) => Some(Protocol::Dhcp),
+		#[cfg(feature = "http")]
+		(Transport::Tcp, 80 | 8080) => Some(Protocol::Http),
+		#[cfg(feature = "kerberos")]
+		(_, 88) => Some(Protocol::Kerberos),
+		#[cfg(feature = "mms")]
+		(Transport::Tcp, 102) => Some(Protocol::Mms),
+		#[cfg(feature = "dicom")]
+		(Transport::Tcp, 104 | 11112) => Some(Protocol::Dicom),
+		#[cfg(feature = "pop3")]
+		(Transport::Tcp, 110) => Some(Protocol::Pop3),
+		#[cfg(feature = "sunrpc")]
+		(_, 111 | 2049) => Some(Protocol::SunRpc),
+		#[cfg(feature = "ident")]
+		(Transport::Tcp, 113) => Some(Protocol::Ident),
+		#[cfg(feature = "ntp")]
+		(Transport::Udp, 123) => Some(Protocol::Ntp),
+		#[cfg(feature = "netbios")]
+		(Transport::Udp, 137) => Some(Protocol::NetBios),
+		#[cfg(feature = "netbios")]
+		(Transport::Tcp, 139) => Some(Protocol::NetBios),
+		#[cfg(feature = "imap")]
+		(Transport::Tcp, 143) => Some(Protocol::Imap),
+		#[cfg(feature = "ldap")]
+		(Transport::Tcp, 389) => Some(Protocol::Ldap),
+		#[cfg(feature = "tls")]
+		(Transport::Tcp, 443 | 465 | 853 | 993 | 995 | 8443) => Some(Protocol::Tls),
+		#[cfg(feature = "http3")]
+		(Transport::Udp, 443) => Some(Protocol::Http3),
+		#[cfg(all(feature = "quic", not(feature = "http3")))]
+		(Transport::Udp, 443) => Some(Protocol::Quic),
+		#[cfg(feature = "smb")]
+		(Transport::Tcp, 445) => Some(Protocol::Smb),
+		#[cfg(feature = "lpd")]
+		(Transport::Tcp, 515) => Some(Protocol::Lpd),
+		#[cfg(feature = "rtsp")]
+		(_, 554) => Some(Protocol::Rtsp),
+		#[cfg(feature = "ipp")]
+		(Transport::Tcp, 631) => Some(Protocol::Ipp),
+		#[cfg(feature = "socks4")]
+		(Transport::Tcp, 1080) => Some(Protocol::Socks4),
+		#[cfg(feature = "openvpn")]
+		(_, 1194) => Some(Protocol::OpenVpn),
+		#[cfg(feature = "icap")]
+		(Transport::Tcp, 1344) => Some(Protocol::Icap),
+		#[cfg(feature = "tds")]
+		(Transport::Tcp, 1433) => Some(Protocol::Tds),
+		#[cfg(feature = "lorawan")]
+		(Transport::Udp, 1700) => Some(Protocol::LoRaWan),
+		#[cfg(feature = "h225")]
+		(Transport::Tcp, 1720) => Some(Protocol::H225),
+		#[cfg(feature = "mqtt")]
+		(Transport::Tcp, 1883) => Some(Protocol::Mqtt),
+		#[cfg(feature = "rtmp")]
+		(Transport::Tcp, 1935) => Some(Protocol::Rtmp),
+		#[cfg(feature = "graphite")]
+		(_, 2003) => Some(Protocol::Graphite),
+		#[cfg(feature = "zookeeper")]
+		(Transport::Tcp, 2181) => Some(Protocol::Zookeeper),
+		#[cfg(feature = "smpp")]
+		(Transport::Tcp, 2775) => Some(Protocol::Smpp),
+		#[cfg(feature = "tarantool")]
+		(Transport::Tcp, 3301) => Some(Protocol::Tarantool),
+		#[cfg(feature = "mysql")]
+		(Transport::Tcp, 3306) => Some(Protocol::Mysql),
+		#[cfg(feature = "rdp")]
+		(Transport::Tcp, 3389) => Some(Protocol::Rdp),
+		#[cfg(feature = "stun")]
+		(Transport::Udp, 3478) => Some(Protocol::Stun),
+		#[cfg(feature = "svn")]
+		(Transport::Tcp, 3690) => Some(Protocol::Svn),
+		#[cfg(feature = "nats")]
+		(Transport::Tcp, 4222) => Some(Protocol::Nats),
+		#[cfg(feature = "nvme_tcp")]
+		(Transport::Tcp, 4420) => Some(Protocol::NvmeTcp),
+		#[cfg(feature = "vxlan")]
+		(Transport::Udp, 4789) => Some(Protocol::Vxlan),
+		#[cfg(feature = "opcua")]
+		(Transport::Tcp, 4840) => Some(Protocol::OpcUa),
+		#[cfg(feature = "sip")]
+		(_, 5060) => Some(Protocol::Sip),
+		#[cfg(feature = "xmpp")]
+		(Transport::Tcp, 5222) => Some(Protocol::Xmpp),
+		#[cfg(feature = "xmpp")]
+		(Transport::Tcp, 5269) => Some(Protocol::Xmpp),
+		#[cfg(feature = "mdns")]
+		(Transport::Udp, 5353) => Some(Protocol::Mdns),
+		#[cfg(feature = "postgres")]
+		(Transport::Tcp, 5432) => Some(Protocol::Postgres),
+		#[cfg(feature = "amqp")]
+		(Transport::Tcp, 5672) => Some(Protocol::Amqp),
+		#[cfg(feature = "geneve")]
+		(Transport::Udp, 6081) => Some(Protocol::Geneve),
+		#[cfg(feature = "redis")]
+		(Transport::Tcp, 6379) => Some(Protocol::Redis),
+		#[cfg(feature = "irc")]
+		(Transport::Tcp, 6667) => Some(Protocol::Irc),
+		#[cfg(feature = "bittorrent")]
+		(Transport::Tcp, 6881) => Some(Protocol::BitTorrent),
+		#[cfg(feature = "dht")]
+		(Transport::Udp, 6881) => Some(Protocol::Dht),
+		#[cfg(feature = "ajp")]
+		(Transport::Tcp, 8009) => Some(Protocol::Ajp),
+		#[cfg(feature = "riak")]
+		(Transport::Tcp, 8087) => Some(Protocol::Riak),
+		#[cfg(feature = "influx")]
+		(Transport::Udp, 8089) => Some(Protocol::Influx),
+		#[cfg(feature = "clickhouse")]
+		(Transport::Tcp, 9000) => Some(Protocol::ClickHouse),
+		#[cfg(feature = "thrift")]
+		(Transport::Tcp, 9090) => Some(Protocol::Thrift),
+		#[cfg(feature = "jetdirect")]
+		(Transport::Tcp, 9100) => Some(Protocol::JetDirect),
+		#[cfg(feature = "git")]
+		(Transport::Tcp, 9418) => Some(Protocol::Git),
+		#[cfg(feature = "memcached")]
+		(Transport::Tcp, 11211) => Some(Protocol::Memcached),
+		#[cfg(feature = "raknet")]
+		(Transport::Udp, 19132) => Some(Protocol::RakNet),
+		#[cfg(feature = "minecraft")]
+		(Transport::Tcp, 25565) => Some(Protocol::Minecraft),
+		#[cfg(feature = "source_query")]
+		(Transport::Udp, 27015) => Some(Protocol::SourceQuery),
+		#[cfg(feature = "devp2p")]
+		(_, 30303) => Some(Protocol::Devp2p),
+		#[cfg(feature = "mysqlx")]
+		(Transport::Tcp, 33060) => Some(Protocol::Mysqlx),
+		#[cfg(feature = "mumble")]
+		(_, 64738) => Some(Protocol::Mumble),
+		_ => None,
+	}
+}
+
+/// Returns the EtherType and the inner frame of a tunnel packet.
+#[allow(unused_variables)]
+fn inner_frame(protocol: Protocol, payload: &[u8]) -> Option<(u16, &[u8])> {
+	match protocol {
+		#[cfg(feature = "vxlan")]
+		Protocol::Vxlan => Some((
+			ETHERTYPE_TEB,
+			payload.get(crate::protocols::vxlan::HEADER_LEN..)?,
+		)),
+		#[cfg(feature = "geneve")]
+		Protocol::Geneve => {
+			let protocol_type = read_u16(payload, 2).ok()?;
+			let header_len = crate::protocols::geneve::header_len(payload);
+			Some((protocol_type, payload.get(header_len..)?))
+		}
+		#[allow(unreachable_patterns)]
+		_ => None,
+	}
+}
+
+/// Parses an IPv4 header and its transport header.
+fn parse_ipv4(packet: &[u8]) -> Result<Segment<'_>, PacketError> {
+	if packet.len() < 20 {
+		return Err(PacketError::Truncated);
+	}
+	let header_len = usize::from(packet[0] & 0x0F) * 4;
+	let total_len = usize::from(read_u16(packet, 2)?);
+	if header_len < 20 || total_len < header_len {
+		return Err(PacketError::Malformed);
+	}
+	if packet.len() < header_len {
+		return Err(PacketError::Truncated);
+	}
+
+	let fragment_offset = read_u16(packet, 6)? & 0x1FFF;
+	if fragment_offset != 0 {
+		return Err(PacketError::Fragment);
+	}
+
+	// Ethernet may pad short packets; captures may be truncated (snaplen).
+	let end = total_len.min(packet.len());
+	parse_transport(packet[9], &packet[header_len..end])
+}
+
+/// Parses an IPv6 header, skips extension headers, and parses the transport header.
+fn parse_ipv6(packet: &[u8]) -> Result<Segment<'_>, PacketError> {
+	if packet.len() < 40 {
+		return Err(PacketError::Truncated);
+	}
+	let payload_len = usize::from(read_u16(packet, 4)?);
+	let end = (40 + payload_len).min(packet.len());
+	let body = &packet[40..end];
+
+	let mut next = packet[6];
+	let mut offset = 0;
+	for _ in 0..MAX_IPV6_EXTENSIONS {
+		match next {
+			// Hop-by-hop, routing, destination options.
+			0 | 43 | 60 => {
+				let len = (usize::from(*body.get(offset + 1).ok_or(PacketError::Truncated)?) + 1) * 8;
+				next = body[offset];
+				offset += len;
+			}
+			// Fragment header.
+			44 => {
+				let fragment_offset = read_u16(body, offset + 2)? >> 3;
+				if fragment_offset != 0 {
+					return Err(PacketError::Fragment);
+				}
+				next = body[offset];
+				offset += 8;
+			}
+			// Authentication header.
+			51 => {
+				let len = (usize::from(*body.get(offset + 1).ok_or(PacketError::Truncated)?) + 2) * 4;
+				next = body[offset];
+				offset += len;
+			}
+			_ => break,
+		}
+	}
+
+	let transport = body.get(offset..).ok_or(PacketError::Truncated)?;
+	parse_transport(next, transport)
+}
+
+/// Parses a TCP or UDP header and returns the payload.
+fn parse_transport(protocol: u8, data: &[u8]) -> Result<Segment<'_>, PacketError> {
+	match protocol {
+		IPPROTO_TCP => {
+			if data.len() < 20 {
+				return Err(PacketError::Truncated);
+			}
+			let header_len = usize::from(data[12] >> 4) * 4;
+			if header_len < 20 {
+				return Err(PacketError::Malformed);
+			}
+			Ok(Segment {
+				transport: Transport::Tcp,
+				src_port: read_u16(data, 0)?,
+				dst_port: read_u16(data, 2)?,
+				payload: data.get(header_len..).ok_or(PacketError::Truncated)?,
+			})
+		}
+		IPPROTO_UDP => {
+			if data.len() < 8 {
+				return Err(PacketError::Truncated);
+			}
+			let len = usize::from(read_u16(data, 4)?);
+			if len < 8 {
+				return Err(PacketError::Malformed);
+			}
+			Ok(Segment {
+				transport: Transport::Udp,
+				src_port: read_u16(data, 0)?,
+				dst_port: read_u16(data, 2)?,
+				payload: &data[8..len.min(data.len())],
+			})
+		}
+		other => Err(PacketError::UnsupportedTransport(other)),
+	}
+}
+
+/// Reads a big-endian `u16` at `offset`.
+#[inline(always)]
+fn read_u16(data: &[u8], offset: usize) -> Result<u16, PacketError> {
+	match data.get(offset..offset + 2) {
+		Some(bytes) => Ok(u16::from_be_bytes([bytes[0], bytes[1]])),
+		None => Err(PacketError::Truncated),
+	}
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+	use super::*;
+
+	/// Builds an Ethernet + IPv4 frame around a transport header and payload.
+	fn ipv4_frame(protocol: u8, transport: &[u8]) -> [u8; 256] {
+		let mut frame = [0u8; 256];
+		frame[12..14].copy_from_slice(&ETHERTYPE_IPV4.to_be_bytes());
+		let ip = &mut frame[14..];
+		ip[0] = 0x45;
+		let total = (20 + transport.len()) as u16;
+		ip[2..4].copy_from_slice(&total.to_be_bytes());
+		ip[9] = protocol;
+		ip[20..20 + transport.len()].copy_from_slice(transport);
+		frame
+	}
+
+	/// Builds a TCP header (no options) followed by `payload`.
+	fn tcp(src: u16, dst: u16, payload: &[u8]) -> Vec<u8> {
+		let mut seg = vec![0u8; 20];
+		seg[0..2].copy_from_slice(&src.to_be_bytes());
+		seg[2..4].copy_from_slice(&dst.to_be_bytes());
+		seg[12] = 0x50;
+		seg.extend_from_slice(payload);
+		seg
+	}
+
+	/// Builds a UDP header followed by `payload`.
+	fn udp(src: u16, dst: u16, payload: &[u8]) -> Vec<u8> {
+		let mut seg = vec![0u8; 8];
+		seg[0..2].copy_from_slice(&src.to_be_bytes());
+		seg[2..4].copy_from_slice(&dst.to_be_bytes());
+		seg[4..6].copy_from_slice(&((8 + payload.len()) as u16).to_be_bytes());
+		seg.extend_from_slice(payload);
+		seg
+	}
+
+	// ── Correct paths ──
+
+	#[test]
+	fn parse_frame_finds_tcp_payload_and_ignores_padding() {
+		let frame = ipv4_frame(IPPROTO_TCP, &tcp(40000, 80, b"GET / HTTP/1.1\r\n"));
+		let segment = parse_frame(&frame).unwrap();
+		assert_eq!(segment.transport, Transport::Tcp);
+		assert_eq!(segment.src_port, 40000);
+		assert_eq!(segment.dst_port, 80);
+		assert_eq!(segment.payload, b"GET / HTTP/1.1\r\n");
+	}
+
+	#[test]
+	fn parse_frame_skips_vlan_tags() {
+		let inner = ipv4_frame(IPPROTO_UDP, &udp(5000, 53, b"abc"));
+		let mut frame = vec![0u8; 12];
+		frame.extend_from_slice(&ETHERTYPE_VLAN.to_be_bytes());
+		frame.extend_from_slice(&[0x00, 0x0A]);
+		frame.extend_from_slice(&inner[12..]);
+		let segment = parse_frame(&frame).unwrap();
+		assert_eq!(segment.transport, Transport::Udp);
+		assert_eq!(segment.payload, b"abc");
+	}
+
+	#[test]
+	fn parse_ip_handles_ipv6_with_extension_header() {
+		let transport = udp(1234, 443, b"quic");
+		let mut packet = vec![0u8; 40];
+		packet[0] = 0x60;
+		packet[4..6].copy_from_slice(&((8 + transport.len()) as u16).to_be_bytes());
+		packet[6] = 0; // hop-by-hop
+		packet.extend_from_slice(&[IPPROTO_UDP, 0, 0, 0, 0, 0, 0, 0]);
+		packet.extend_from_slice(&transport);
+		let segment = parse_ip(&packet).unwrap();
+		assert_eq!(segment.dst_port, 443);
+		assert_eq!(segment.payload, b"quic");
+	}
+
+	#[test]
+	#[cfg(all(feature = "http", feature = "dns"))]
+	fn detect_from_frame_uses_transport_detector() {
+		let detector = FrameDetector::new(
+			ProtocolDetector::builder().tcp().http().build(),
+			ProtocolDetector::builder().udp().dns().build(),
+		);
+		let frame = ipv4_frame(IPPROTO_TCP, &tcp(40000, 8080, b"GET / HTTP/1.1\r\n"));
+		let info = detector.detect_from_frame(&frame).unwrap();
+		assert_eq!(
+			info.result.unwrap().map(|i| i.protocol),
+			Some(Protocol::Http)
+		);
+
+		// HTTP bytes over UDP are checked against the UDP detector only.
+		let frame = ipv4_frame(IPPROTO_UDP, &udp(40000, 8080, b"GET / HTTP/1.1\r\n"));
+		let info = detector.detect_from_frame(&frame).unwrap();
+		assert_eq!(info.result, Ok(None));
+	}
+
+	#[test]
+	#[cfg(all(feature = "smtp", feature = "ftp"))]
+	fn port_hint_breaks_ties_between_overlapping_protocols() {
+		// A "220 " greeting matches both SMTP and FTP; the port decides.
+		let tcp_detector = ProtocolDetector::builder().tcp().smtp().ftp().build();
+		let detector = FrameDetector::new(tcp_detector, ProtocolDetector::builder().udp().build());
+		let greeting = b"220 mail.example.com ESMTP\r\n";
+
+		let frame = ipv4_frame(IPPROTO_TCP, &tcp(21, 50000, greeting));
+		let info = detector.detect_from_frame(&frame).unwrap();
+		assert_eq!(
+			info.result.unwrap().map(|i| i.protocol),
+			Some(Protocol::Ftp)
+		);
+
+		let frame = ipv4_frame(IPPROTO_TCP, &tcp(25, 50000, greeting));
+		let info = detector.detect_from_frame(&frame).unwrap();
+		assert_eq!(
+			info.result.unwrap().map(|i| i.protocol),
+			Some(Protocol::Smtp)
+		);
+	}
+
+	#[test]
+	#[cfg(all(feature = "http", feature = "websocket", feature = "grpc"))]
+	fn http_port_hint_keeps_websocket_and_grpc_ahead_of_http() {
+		let tcp_detector = ProtocolDetector::builder()
+			.tcp()
+			.http()
+			.websocket()
+			.grpc()
+			.build();
+		let detector = FrameDetector::new(tcp_detector, ProtocolDetector::builder().udp().build());
+		let upgrade = b"GET /ws HTTP/1.1\r\nUpgrade: websocket\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\r\n";
+		let grpc = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n\x00\x00\x00\x04\x00\x00\x00\x00\x00\
+			\x00\x00\x14\x01\x04\x00\x00\x00\x01\x83\x86\x5f\x10application/grpc";
+		for (payload, protocol) in [
+			(&upgrade[..], Protocol::WebSocket),
+			(&grpc[..], Protocol::Grpc),
+		] {
+			for port in [80, 8080, 9999] {
+				let info = detector.detect_segment(Segment {
+					transport: Transport::Tcp,
+					src_port: 50000,
+					dst_port: port,
+					payload,
+				});
+				assert_eq!(
+					info.result.unwrap().map(|i| i.protocol),
+					Some(protocol),
+					"port {port}"
+				);
+			}
+		}
+	}
+
+	// ── Error paths ──
+
+	#[test]
+	fn parse_frame_rejects_non_ip_ethertype() {
+		let mut frame = [0u8; 60];
+		frame[12..14].copy_from_slice(&0x0806u16.to_be_bytes());
+		assert_eq!(
+			parse_frame(&frame),
+			Err(PacketError::UnsupportedEtherType(0x0806))
+		);
+	}
+
+	#[test]
+	fn parse_frame_rejects_truncated_headers() {
+		assert_eq!(parse_frame(&[0u8; 10]), Err(PacketError::Truncated));
+		let frame = ipv4_frame(IPPROTO_TCP, &tcp(1, 2, b""));
+		assert_eq!(parse_frame(&frame[..40]), Err(PacketError::Truncated));
+	}
+
+	#[test]
+	fn parse_ip_rejects_fragments_and_other_transports() {
+		let mut frame = ipv4_frame(IPPROTO_UDP, &udp(1, 2, b"x"));
+		frame[14 + 7] = 0x10;
+		assert_eq!(parse_frame(&frame), Err(PacketError::Fragment));
+
+		let frame = ipv4_frame(1, &[8, 0, 0, 0, 0, 0, 0, 0]);
+		assert_eq!(
+			parse_frame(&frame),
+			Err(PacketError::UnsupportedTransport(1))
+		);
+	}
+
+	#[test]
+	fn parse_ip_rejects_unknown_version() {
+		assert_eq!(
+			parse_ip(&[0x50; 40]),
+			Err(PacketError::UnsupportedIpVersion(5))
+		);
+		assert_eq!(parse_ip(&[]), Err(PacketError::Truncated));
+	}
+
+	#[test]
+	#[cfg(all(feature = "vxlan", feature = "http"))]
+	fn detect_encapsulated_unwraps_vxlan() {
+		let detector = FrameDetector::new(
+			ProtocolDetector::builder().tcp().http().build(),
+			ProtocolDetector::builder().udp().vxlan().build(),
+		);
+		let request = b"GET / HTTP/1.1\r\n";
+		let inner = ipv4_frame(IPPROTO_TCP, &tcp(40000, 80, request));
+		let mut payload = vec![0x08, 0, 0, 0, 0x00, 0x13, 0x89, 0];
+		payload.extend_from_slice(&inner[..14 + 20 + 20 + request.len()]);
+		let frame = ipv4_frame(IPPROTO_UDP, &udp(50000, 4789, &payload));
+
+		let outer = detector.detect_from_frame(&frame).unwrap();
+		assert_eq!(
+			outer.result.unwrap().map(|i| i.protocol),
+			Some(Protocol::Vxlan)
+		);
+		let inner = detector.detect_encapsulated(&outer).unwrap().unwrap();
+		assert_eq!(inner.segment.dst_port, 80);
+		assert_eq!(
+			inner.result.unwrap().map(|i| i.protocol),
+			Some(Protocol::Http)
+		);
+		assert!(detector.detect_encapsulated(&inner).is_none());
+	}
+
+	#[test]
+	#[cfg(all(feature = "geneve", feature = "dns"))]
+	fn detect_encapsulated_unwraps_geneve_ip() {
+		let detector = FrameDetector::new(
+			ProtocolDetector::builder().tcp().build(),
+			ProtocolDetector::builder().udp().geneve().dns().build(),
+		);
+		let query =
+			b"\x12\x34\x01\x00\x00\x01\x00\x00\x00\x00\x00\x00\x07example\x03com\x00\x00\x01\x00\x01";
+		let inner = ipv4_frame(IPPROTO_UDP, &udp(40000, 53, query));
+		let mut payload = vec![0x00, 0x00, 0x08, 0x00, 0x00, 0x00, 0x07, 0x00];
+		payload.extend_from_slice(&inner[14..14 + 20 + 8 + query.len()]);
+		let frame = ipv4_frame(IPPROTO_UDP, &udp(50000, 6081, &payload));
+
+		let outer = detector.detect_from_frame(&frame).unwrap();
+		assert_eq!(
+			outer.result.unwrap().map(|i| i.protocol),
+			Some(Protocol::Geneve)
+		);
+		let inner = detector.detect_encapsulated(&outer).unwrap().unwrap();
+		assert_eq!(inner.segment.dst_port, 53);
+		assert_eq!(
+			inner.result.unwrap().map(|i| i.protocol),
+			Some(Protocol::Dns)
+		);
+	}
+
+	#[test]
+	#[cfg(feature = "http")]
+	fn empty_tcp_payload_needs_more_data() {
+		let detector = FrameDetector::new(
+			ProtocolDetector::builder().tcp().http().build(),
+			ProtocolDetector::builder().udp().build(),
+		);
+		let frame = ipv4_frame(IPPROTO_TCP, &tcp(40000, 80, b""));
+		let info = detector.detect_from_frame(&frame).unwrap();
+		assert!(info.segment.payload.is_empty());
+		assert_eq!(info.result, Err(crate::DetectionError::InsufficientData));
+	}
+}
@@ -0,0 +1,360 @@
+/* src/protobuf.rs */
+
+use crate::DetectionStatus;
+
+/// Largest protobuf field number (2^29 - 1).
+const MAX_FIELD_NUMBER: u32 = (1 << 29) - 1;
+
+/// Longest varint: ten bytes encode 64 bits.
+const MAX_VARINT_LEN: usize = 10;
+
+/// Encoding of the length that precedes each message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum LengthPrefix {
+	/// Two bytes, big-endian.
+	U16Be,
+	/// Four bytes, big-endian.
+	U32Be,
+	/// Four bytes, little-endian.
+	U32Le,
+	/// Base-128 varint, as written by `writeDelimitedTo`.
+	Varint,
+}
+
+/// Heuristic detector for length-prefixed protobuf RPC framing.
+///
+/// Many services frame each protobuf message as a length, an optional
+/// one-byte message code, and the encoded message. The detector checks the
+/// length against a bound, the code against the service's known codes, and
+/// the first field tags of the message: known wire types, field numbers in
+/// range, and nested lengths that stay inside the frame.
+///
+/// The defaults match a four-byte big-endian length with no message code;
+/// tune them for a service with the `const` setters:
+///
+/// ```
+/// use guess::DetectionStatus;
+/// use guess::protobuf::{FramedProtobuf, LengthPrefix};
+///
+/// const MY_RPC: FramedProtobuf = FramedProtobuf::new()
+///     .length_prefix(LengthPrefix::U32Le)
+///     .message_codes(&[(1, 12)])
+///     .max_length(64 * 1024);
+///
+/// let frame = [0x05, 0x00, 0x00, 0x00, 0x03, 0x08, 0x96, 0x01, 0x18];
+/// assert_eq!(MY_RPC.probe(&frame), DetectionStatus::Match);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FramedProtobuf {
+	/// Length encoding.
+	prefix: LengthPrefix,
+	/// Inclusive ranges of accepted message codes, or `None` when frames
+	/// carry no code byte.
+	message_codes: Option<&'static [(u8, u8)]>,
+	/// Largest accepted frame length.
+	max_length: u32,
+	/// Largest accepted field number.
+	max_field_number: u32,
+	/// Number of leading fields to validate.
+	fields: u8,
+}
+
+impl Default for FramedProtobuf {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl FramedProtobuf {
+	/// Creates a detector for four-byte big-endian lengths up to 16 MiB, no
+	/// message code, and two checked fields.
+	#[must_use]
+	pub const fn new() -> Self {
+		Self {
+			prefix: LengthPrefix::U32Be,
+			message_codes: None,
+			max_length: 16 * 1024 * 1024,
+			max_field_number: MAX_FIELD_NUMBER,
+			fields: 2,
+		}
+	}
+
+	/// Sets the length encoding.
+	#[must_use]
+	pub const fn length_prefix(mut self, prefix: LengthPrefix) -> Self {
+		self.prefix = prefix;
+		self
+	}
+
+	/// Expects a message code byte after the length, within one of the
+	/// inclusive `(low, high)` ranges. The length counts the code byte.
+	#[must_use]
+	pub const fn message_codes(mut self, ranges: &'static [(u8, u8)]) -> Self {
+		self.message_codes = Some(ranges);
+		self
+	}
+
+	/// Sets the largest accepted frame length.
+	#[must_use]
+	pub const fn max_length(mut self, max_length: u32) -> Self {
+		self.max_length = max_length;
+		self
+	}
+
+	/// Sets the largest accepted field number, tightening the tag check for
+	/// services whose messages only use low field numbers.
+	#[must_use]
+	pub const fn max_field_number(mut self, max_field_number: u32) -> Self {
+		self.max_field_number = max_field_number;
+		self
+	}
+
+	/// Sets how many leading fields must be valid before a frame matches.
+	#[must_use]
+	pub const fn fields(mut self, fields: u8) -> Self {
+		self.fields = fields;
+		self
+	}
+
+	/// Checks whether `data` starts with a frame of this shape.
+	///
+	/// Returns `Incomplete` while the prefix, the code, or the first field is
+	/// cut off; the first field matches only once all its bytes are in view.
+	/// Later fields past the end of `data` are not checked, and a frame that
+	/// carries only a message code matches on the code alone.
+	#[must_use]
+	pub fn probe(&self, data: &[u8]) -> DetectionStatus {
+		let (length, header) = match self.read_length(data) {
+			Some(Ok(prefix)) => prefix,
+			Some(Err(())) => {
+				reject!("invalid_length_prefix");
+				return DetectionStatus::NoMatch;
+			}
+			None => return DetectionStatus::Incomplete,
+		};
+		let mut remaining = match usize::try_from(length) {
+			Ok(remaining) if length <= u64::from(self.max_length) => remaining,
+			_ => {
+				reject!("frame_too_long");
+				return DetectionStatus::NoMatch;
+			}
+		};
+		if remaining == 0 && self.message_codes.is_none() {
+			reject!("empty_frame");
+			return DetectionStatus::NoMatch;
+		}
+
+		let mut pos = header;
+		if let Some(ranges) = self.message_codes {
+			let Some(&code) = data.get(pos) else {
+				return DetectionStatus::Incomplete;
+			};
+			if remaining == 0
+				|| !ranges
+					.iter()
+					.any(|&(low, high)| (low..=high).contains(&code))
+			{
+				reject!("unknown_message_code");
+				return DetectionStatus::NoMatch;
+			}
+			pos += 1;
+			remaining -= 1;
+		}
+
+		let mut checked = 0;
+		while remaining > 0 && checked < self.fields {
+			match self.field(data, pos, remaining) {
+				Field::Next(len) => {
+					pos += len;
+					remaining -= len;
+					checked += 1;
+				}
+				Field::Truncated if checked > 0 => return DetectionStatus::Match,
+				Field::Truncated => return DetectionStatus::Incomplete,
+				Field::Invalid => {
+					reject!("invalid_protobuf_field");
+					return DetectionStatus::NoMatch;
+				}
+			}
+		}
+		DetectionStatus::Match
+	}
+
+	/// Reads the frame length and the prefix size, `None` if cut off.
+	fn read_length(&self, data: &[u8]) -> Option<Result<(u64, usize), ()>> {
+		match self.prefix {
+			LengthPrefix::U16Be => data
+				.first_chunk()
+				.map(|b| Ok((u64::from(u16::from_be_bytes(*b)), 2))),
+			LengthPrefix::U32Be => data
+				.first_chunk()
+				.map(|b| Ok((u64::from(u32::from_be_bytes(*b)), 4))),
+			LengthPrefix::U32Le => data
+				.first_chunk()
+				.map(|b| Ok((u64::from(u32::from_le_bytes(*b)), 4))),
+			LengthPrefix::Varint => match varint(data, 0) {
+				Varint::Value(value, len) => Some(Ok((value, len))),
+				Varint::Truncated => None,
+				Varint::Invalid => Some(Err(())),
+			},
+		}
+	}
+
+	/// Walks one field at `pos` whose frame has `remaining` bytes left.
+	fn field(&self, data: &[u8], pos: usize, remaining: usize) -> Field {
+		let (tag, tag_len) = match varint(data, pos) {
+			Varint::Value(tag, len) => (tag, len),
+			Varint::Truncated => return Field::Truncated,
+			Varint::Invalid => return Field::Invalid,
+		};
+		let number = tag >> 3;
+		if number == 0 || number > u64::from(self.max_field_number) {
+			return Field::Invalid;
+		}
+		let value_len = match tag & 0x07 {
+			0 => match varint(data, pos + tag_len) {
+				Varint::Value(_, len) => len,
+				Varint::Truncated => return Field::Truncated,
+				Varint::Invalid => return Field::Invalid,
+			},
+			1 => 8,
+			2 => match varint(data, pos + tag_len) {
+				Varint::Value(len, len_len) => match usize::try_from(len) {
+					Ok(len) => len_len.saturating_add(len),
+					Err(_) => return Field::Invalid,
+				},
+				Varint::Truncated => return Field::Truncated,
+				Varint::Invalid => return Field::Invalid,
+			},
+			5 => 4,
+			// Groups (3, 4) are deprecated; 6 and 7 are unassigned.
+			_ => return Field::Invalid,
+		};
+		let len = tag_len.saturating_add(value_len);
+		if len > remaining {
+			return Field::Invalid;
+		}
+		if pos.saturating_add(len) > data.len() {
+			return Field::Truncated;
+		}
+		Field::Next(len)
+	}
+}
+
+/// Outcome of walking one field.
+enum Field {
+	/// The field is valid and this many bytes long.
+	Next(usize),
+	/// The field runs past the end of the data.
+	Truncated,
+	/// The field cannot appear in a valid message.
+	Invalid,
+}
+
+/// Outcome of reading one varint.
+enum Varint {
+	/// The value and its encoded length.
+	Value(u64, usize),
+	/// The varint runs past the end of the data.
+	Truncated,
+	/// The varint is longer than ten bytes.
+	Invalid,
+}
+
+/// Reads a base-128 varint starting at `pos`.
+fn varint(data: &[u8], pos: usize) -> Varint {
+	let mut value = 0u64;
+	for i in 0..MAX_VARINT_LEN {
+		let Some(&byte) = data.get(pos + i) else {
+			return Varint::Truncated;
+		};
+		value |= u64::from(byte & 0x7F) << (7 * i);
+		if byte & 0x80 == 0 {
+			return Varint::Value(value, i + 1);
+		}
+	}
+	Varint::Invalid
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_default_framing() {
+		// Field 1 varint 150, field 2 string "hi".
+		let frame = [
+			0x00, 0x00, 0x00, 0x07, 0x08, 0x96, 0x01, 0x12, 0x02, b'h', b'i',
+		];
+		assert_eq!(FramedProtobuf::new().probe(&frame), DetectionStatus::Match);
+		assert_eq!(
+			FramedProtobuf::new().probe(&frame[..7]),
+			DetectionStatus::Match
+		);
+		assert_eq!(
+			FramedProtobuf::new().probe(&frame[..4]),
+			DetectionStatus::Incomplete
+		);
+	}
+
+	#[test]
+	fn test_varint_prefix_and_codes() {
+		let detector = FramedProtobuf::new()
+			.length_prefix(LengthPrefix::Varint)
+			.message_codes(&[(10, 20)]);
+		assert_eq!(detector.probe(&[0x06, 0x0B]), DetectionStatus::Incomplete);
+		assert_eq!(
+			detector.probe(&[0x06, 0x0B, 0x1D, 0x01, 0x02, 0x03, 0x04]),
+			DetectionStatus::Match
+		);
+		assert_eq!(detector.probe(&[0x01, 0x0B]), DetectionStatus::Match);
+		assert_eq!(detector.probe(&[0x01, 0x09]), DetectionStatus::NoMatch);
+		// The fixed32 value of the first field is cut off.
+		assert_eq!(
+			detector.probe(&[0x06, 0x0B, 0x1D, 0x01]),
+			DetectionStatus::Incomplete
+		);
+		assert_eq!(detector.probe(&[0x00, 0x0B]), DetectionStatus::NoMatch);
+	}
+
+	#[test]
+	fn test_reject_invalid_fields() {
+		let detector = FramedProtobuf::new().length_prefix(LengthPrefix::U16Be);
+		// Field number 0.
+		assert_eq!(
+			detector.probe(&[0x00, 0x02, 0x00, 0x01]),
+			DetectionStatus::NoMatch
+		);
+		// Group wire type.
+		assert_eq!(
+			detector.probe(&[0x00, 0x02, 0x0B, 0x01]),
+			DetectionStatus::NoMatch
+		);
+		// Nested length past the frame.
+		assert_eq!(
+			detector.probe(&[0x00, 0x03, 0x0A, 0x05, b'a']),
+			DetectionStatus::NoMatch
+		);
+		// Field number above the configured bound.
+		assert_eq!(
+			detector
+				.max_field_number(15)
+				.probe(&[0x00, 0x03, 0x80, 0x01, 0x01]),
+			DetectionStatus::NoMatch
+		);
+	}
+
+	#[test]
+	fn test_reject_oversized_frame() {
+		let detector = FramedProtobuf::new().max_length(1024);
+		assert_eq!(
+			detector.probe(&[0x00, 0x01, 0x00, 0x00, 0x08, 0x01]),
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(
+			FramedProtobuf::new().probe(b"GET / HTTP/1.1\r\n"),
+			DetectionStatus::NoMatch
+		);
+	}
+}
@@ -0,0 +1,152 @@
+/* src/protocols/ajp.rs */
+use crate::{DetectionStatus, ProtocolVersion};
+
+/// Magic of packets from the web server to the container.
+const CLIENT_MAGIC: [u8; 2] = [0x12, 0x34];
+
+/// Magic of packets from the container to the web server.
+const SERVER_MAGIC: [u8; 2] = *b"AB";
+
+/// Forward Request prefix code.
+const FORWARD_REQUEST: u8 = 2;
+
+/// Largest packet the protocol allows (`max_packet_size` tops out at 64 KiB).
+const MAX_PACKET_LEN: u16 = 65_531;
+
+/// Probes for the Apache JServ Protocol (AJP/1.3).
+///
+/// Checks the `0x1234` (to container) or `AB` (from container) magic, the
+/// length, and the prefix code. Forward Requests must carry a known method
+/// code and an `HTTP/` protocol string; the fixed-size messages (CPing,
+/// Shutdown, CPong, End Response, Get Body Chunk) must have their exact
+/// lengths, and Send Headers a valid HTTP status.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	let to_container = match data.first_chunk::<2>() {
+		Some(&CLIENT_MAGIC) => true,
+		Some(&SERVER_MAGIC) => false,
+		_ => {
+			reject!("magic_mismatch");
+			return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+		}
+	};
+	if data.len() < 5 {
+		return (DetectionStatus::Incomplete, ProtocolVersion::Unknown);
+	}
+	let length = u16::from_be_bytes([data[2], data[3]]);
+	if !(1..=MAX_PACKET_LEN).contains(&length) {
+		reject!("invalid_packet_length");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	}
+
+	let status = match (to_container, data[4]) {
+		(true, FORWARD_REQUEST) => forward_request(data),
+		// Shutdown, CPing; CPong.
+		(true, 7 | 10) | (false, 9) => exact(length, 1),
+		// Send Body Chunk: chunk length, chunk, and a trailing NUL.
+		(false, 3) => match data.get(5..7) {
+			Some(chunk) => exact(
+				length,
+				u16::from_be_bytes([chunk[0], chunk[1]]).saturating_add(4),
+			),
+			None => DetectionStatus::Incomplete,
+		},
+		(false, 4) => match data.get(5..7) {
+			Some(status) if (100..=599).contains(&u16::from_be_bytes([status[0], status[1]])) => {
+				DetectionStatus::Match
+			}
+			Some(_) => DetectionStatus::NoMatch,
+			None => DetectionStatus::Incomplete,
+		},
+		// End Response: reuse flag.
+		(false, 5) => match data.get(5) {
+			Some(0 | 1) => exact(length, 2),
+			Some(_) => DetectionStatus::NoMatch,
+			None => DetectionStatus::Incomplete,
+		},
+		// Get Body Chunk: requested length.
+		(false, 6) => exact(length, 3),
+		_ => DetectionStatus::NoMatch,
+	};
+	if status == DetectionStatus::NoMatch {
+		reject!("invalid_message");
+	}
+	(status, ProtocolVersion::Unknown)
+}
+
+/// Checks the method code and the start of the protocol string.
+#[inline(always)]
+fn forward_request(data: &[u8]) -> DetectionStatus {
+	let Some(&[method, len_hi, len_lo]) = data.get(5..8) else {
+		return DetectionStatus::Incomplete;
+	};
+	// Codes 1-28 cover OPTIONS through BASELINE-CONTROL; 0xFF is the
+	// stored-method form.
+	if !matches!(method, 1..=28 | 0xFF) || !(5..=16).contains(&u16::from_be_bytes([len_hi, len_lo])) {
+		return DetectionStatus::NoMatch;
+	}
+	let protocol = &data[8..data.len().min(13)];
+	if !b"HTTP/".starts_with(protocol) {
+		return DetectionStatus::NoMatch;
+	}
+	DetectionStatus::Match
+}
+
+/// Matches when the packet length is exactly `expected`.
+#[inline(always)]
+fn exact(length: u16, expected: u16) -> DetectionStatus {
+	if length == expected {
+		DetectionStatus::Match
+	} else {
+		DetectionStatus::NoMatch
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_detect_forward_request() {
+		let data = b"\x12\x34\x00\x80\x02\x02\x00\x08HTTP/1.1\x00\x00\x01/\x00";
+		assert_eq!(
+			probe(data),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+		assert_eq!(probe(&data[..6]).0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	fn test_detect_cping_and_cpong() {
+		assert_eq!(probe(b"\x12\x34\x00\x01\x0A").0, DetectionStatus::Match);
+		assert_eq!(probe(b"AB\x00\x01\x09").0, DetectionStatus::Match);
+	}
+
+	#[test]
+	fn test_detect_server_messages() {
+		assert_eq!(
+			probe(b"AB\x00\x20\x04\x00\xC8\x00\x02OK\x00").0,
+			DetectionStatus::Match
+		);
+		assert_eq!(probe(b"AB\x00\x02\x05\x01").0, DetectionStatus::Match);
+		assert_eq!(
+			probe(b"AB\x00\x07\x03\x00\x03abc\x00").0,
+			DetectionStatus::Match
+		);
+	}
+
+	#[test]
+	fn test_reject_bad_messages() {
+		assert_eq!(
+			probe(b"\x12\x34\x00\x02\x0A\x00").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(
+			probe(b"\x12\x34\x00\x80\x02\x02\x00\x08FTP/1.1\x00").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(probe(b"AB\x00\x20\x04\x03\xE8").0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"ABCDEFGH").0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"GET / HTTP/1.1\r\n").0, DetectionStatus::NoMatch);
+	}
+}
@@ -0,0 +1,93 @@
+/* src/protocols/amqp.rs */
+use crate::{DetectionStatus, ProtocolVersion};
+
+/// Protocol name opening every AMQP protocol header.
+const MAGIC: &[u8; 4] = b"AMQP";
+
+/// Length of the protocol header.
+const HEADER_LEN: usize = 8;
+
+/// AMQP 1.0 protocol IDs: plain AMQP, TLS and SASL security layers.
+const AMQP_1_0_IDS: [u8; 3] = [0, 2, 3];
+
+/// Probes for an AMQP protocol header.
+///
+/// Expects `AMQP` followed by a known version: 1.0 (protocol ID 0, 2 or 3,
+/// then `1 0 0`), 0-10 (`1 1 0 10`), 0-9-1 (`0 0 9 1`), 0-9 (`1 1 0 9`) or
+/// 0-8 (`1 1 8 0`). The dialect is reported as the version, e.g. "1.0" or
+/// "0-9-1".
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	let len = data.len().min(MAGIC.len());
+	if data[..len] != MAGIC[..len] {
+		reject!("no_amqp_magic");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	}
+	let Some(header) = data.first_chunk::<HEADER_LEN>() else {
+		return (DetectionStatus::Incomplete, ProtocolVersion::Unknown);
+	};
+	let version = match header[4..] {
+		[id, 1, 0, 0] if AMQP_1_0_IDS.contains(&id) => "1.0",
+		[1, 1, 0, 10] => "0-10",
+		[0, 0, 9, 1] => "0-9-1",
+		[1, 1, 0, 9] => "0-9",
+		[1, 1, 8, 0] => "0-8",
+		_ => {
+			reject!("unknown_version");
+			return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+		}
+	};
+	(DetectionStatus::Match, ProtocolVersion::Amqp(version))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_detect_amqp_1_0() {
+		assert_eq!(
+			probe(b"AMQP\x00\x01\x00\x00"),
+			(DetectionStatus::Match, ProtocolVersion::Amqp("1.0"))
+		);
+		// SASL and TLS security layers.
+		assert_eq!(
+			probe(b"AMQP\x03\x01\x00\x00\x00\x00\x00\x21\x02\x01\x00\x00").1,
+			ProtocolVersion::Amqp("1.0")
+		);
+		assert_eq!(
+			probe(b"AMQP\x02\x01\x00\x00").1,
+			ProtocolVersion::Amqp("1.0")
+		);
+	}
+
+	#[test]
+	fn test_detect_amqp_0_9_1() {
+		assert_eq!(
+			probe(b"AMQP\x00\x00\x09\x01"),
+			(DetectionStatus::Match, ProtocolVersion::Amqp("0-9-1"))
+		);
+		assert_eq!(
+			probe(b"AMQP\x01\x01\x00\x09").1,
+			ProtocolVersion::Amqp("0-9")
+		);
+		assert_eq!(
+			probe(b"AMQP\x01\x01\x08\x00").1,
+			ProtocolVersion::Amqp("0-8")
+		);
+	}
+
+	#[test]
+	fn test_incomplete_header() {
+		assert_eq!(probe(b"AM").0, DetectionStatus::Incomplete);
+		assert_eq!(probe(b"AMQP\x00\x01").0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	fn test_reject_other_headers() {
+		assert_eq!(probe(b"AMQP\x01\x01\x00\x00").0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"AMQP\x05\x01\x00\x00").0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"AMQX\x00\x00\x09\x01").0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"GET / HTTP/1.1\r\n").0, DetectionStatus::NoMatch);
+	}
+}
@@ -0,0 +1,87 @@
+/* src/protocols/bittorrent.rs */
+use crate::{DetectionStatus, ProtocolMetadata, ProtocolVersion};
+
+/// Protocol string length followed by the protocol string.
+const HEADER: &[u8] = b"\x13BitTorrent protocol";
+
+/// Probes for the BitTorrent peer wire handshake.
+///
+/// Matches the length byte 19 and the literal `BitTorrent protocol`; the
+/// reserved bytes and info-hash that follow are reported by [`metadata`].
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	if data.len() < HEADER.len() {
+		if HEADER.starts_with(data) {
+			return (DetectionStatus::Incomplete, ProtocolVersion::Unknown);
+		}
+		reject!("missing_protocol_string");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	}
+	if !data.starts_with(HEADER) {
+		reject!("missing_protocol_string");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	}
+	(DetectionStatus::Match, ProtocolVersion::Unknown)
+}
+
+/// Extracts the reserved extension bits and info-hash of a full handshake.
+#[inline(always)]
+pub(crate) fn metadata(data: &[u8]) -> ProtocolMetadata<'_> {
+	if let Some((reserved, rest)) = data
+		.strip_prefix(HEADER)
+		.and_then(<[u8]>::split_first_chunk)
+		&& let Some(info_hash) = rest.first_chunk()
+	{
+		return ProtocolMetadata::BitTorrent {
+			reserved: u64::from_be_bytes(*reserved),
+			info_hash,
+		};
+	}
+	ProtocolMetadata::None
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Handshake advertising the extension protocol and DHT, without peer id.
+	const HANDSHAKE: &[u8] = b"\x13BitTorrent protocol\
+		\x00\x00\x00\x00\x00\x10\x00\x01\
+		\x01\x23\x45\x67\x89\xab\xcd\xef\x01\x23\x45\x67\x89\xab\xcd\xef\x01\x23\x45\x67";
+
+	#[test]
+	fn test_detect_bittorrent_handshake() {
+		assert_eq!(
+			probe(HANDSHAKE),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+	}
+
+	#[test]
+	fn test_metadata_reports_reserved_bits_and_info_hash() {
+		let ProtocolMetadata::BitTorrent {
+			reserved,
+			info_hash,
+		} = metadata(HANDSHAKE)
+		else {
+			panic!("expected BitTorrent metadata");
+		};
+		assert_eq!(reserved, 0x0000_0000_0010_0001);
+		assert_eq!(info_hash[..4], [0x01, 0x23, 0x45, 0x67]);
+		assert_eq!(metadata(&HANDSHAKE[..40]), ProtocolMetadata::None);
+	}
+
+	#[test]
+	fn test_incomplete_header() {
+		assert_eq!(probe(b"\x13BitTor").0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	fn test_reject_other_data() {
+		assert_eq!(
+			probe(b"\x13BitTorrent Protocol").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(probe(b"GET /announce HTTP/1.1").0, DetectionStatus::NoMatch);
+	}
+}
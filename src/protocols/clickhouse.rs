@@ -0,0 +1,133 @@
+/* src/protocols/clickhouse.rs */
+use crate::{DetectionStatus, ProtocolVersion};
+
+/// Client packet ID of Hello.
+const HELLO: u8 = 0x00;
+
+/// Longest client name accepted.
+const MAX_NAME_LEN: u64 = 128;
+
+/// Most bytes a version varint takes.
+const MAX_VARINT_LEN: usize = 5;
+
+/// Protocol revisions clients have sent, from 54000 (ClickHouse 1.1)
+/// onwards with room to grow.
+const REVISIONS: core::ops::RangeInclusive<u64> = 54_000..=99_999;
+
+/// Probes for the Hello packet opening the ClickHouse native TCP protocol.
+///
+/// Expects packet ID 0, a varint-prefixed printable client name (such as
+/// `ClickHouse client`), then the client's major and minor version and the
+/// protocol revision as varints, the revision in the range servers accept.
+/// The database, user and password strings after them are not checked.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	let status = match parse(data) {
+		Ok(()) => DetectionStatus::Match,
+		Err(status) => status,
+	};
+	(status, ProtocolVersion::Unknown)
+}
+
+/// Checks the packet ID, client name and version varints.
+#[inline(always)]
+fn parse(data: &[u8]) -> Result<(), DetectionStatus> {
+	match data.first() {
+		None => return Err(DetectionStatus::Incomplete),
+		Some(&HELLO) => {}
+		Some(_) => {
+			reject!("not_hello_packet");
+			return Err(DetectionStatus::NoMatch);
+		}
+	}
+	let (name_len, len) = var_uint(data, 1)?;
+	if !(1..=MAX_NAME_LEN).contains(&name_len) {
+		reject!("implausible_client_name_length");
+		return Err(DetectionStatus::NoMatch);
+	}
+	let name_start = 1 + len;
+	#[allow(clippy::cast_possible_truncation)]
+	let name_end = name_start + name_len as usize;
+	let name = &data[name_start.min(data.len())..name_end.min(data.len())];
+	if !name.iter().all(|&b| (0x20..0x7F).contains(&b)) {
+		reject!("client_name_not_printable");
+		return Err(DetectionStatus::NoMatch);
+	}
+	if data.len() < name_end {
+		return Err(DetectionStatus::Incomplete);
+	}
+
+	let (major, len) = var_uint(data, name_end)?;
+	let (minor, minor_len) = var_uint(data, name_end + len)?;
+	let (revision, _) = var_uint(data, name_end + len + minor_len)?;
+	if major > 100 || minor > 1000 || !REVISIONS.contains(&revision) {
+		reject!("implausible_client_version");
+		return Err(DetectionStatus::NoMatch);
+	}
+	Ok(())
+}
+
+/// Reads an unsigned LEB128 varint at `pos` and returns it with its encoded
+/// length.
+#[inline(always)]
+fn var_uint(data: &[u8], pos: usize) -> Result<(u64, usize), DetectionStatus> {
+	let mut value = 0u64;
+	for i in 0..MAX_VARINT_LEN {
+		let Some(&byte) = data.get(pos + i) else {
+			return Err(DetectionStatus::Incomplete);
+		};
+		value |= u64::from(byte & 0x7F) << (7 * i);
+		if byte & 0x80 == 0 {
+			return Ok((value, i + 1));
+		}
+	}
+	reject!("varint_too_long");
+	Err(DetectionStatus::NoMatch)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// `clickhouse-client` 24.3 at protocol revision 54467, connecting to
+	/// `default` as `default` without a password.
+	const HELLO_PACKET: &[u8] =
+		b"\x00\x11ClickHouse client\x18\x03\xC3\xA9\x03\x07default\x07default\x00";
+
+	#[test]
+	fn test_detect_client_hello() {
+		assert_eq!(
+			probe(HELLO_PACKET),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+		assert_eq!(probe(&HELLO_PACKET[..24]).0, DetectionStatus::Match);
+	}
+
+	#[test]
+	fn test_incomplete_hello() {
+		assert_eq!(probe(b"\x00").0, DetectionStatus::Incomplete);
+		assert_eq!(probe(&HELLO_PACKET[..10]).0, DetectionStatus::Incomplete);
+		assert_eq!(probe(&HELLO_PACKET[..22]).0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	fn test_reject_other_packets() {
+		// Empty or binary client name.
+		assert_eq!(
+			probe(b"\x00\x00\x18\x03\xC3\xA9\x03").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(
+			probe(b"\x00\x04\x01\x02\x03\x04\x18\x03\xC3\xA9\x03").0,
+			DetectionStatus::NoMatch
+		);
+		// Revision out of range.
+		assert_eq!(probe(b"\x00\x02ch\x18\x03\x01").0, DetectionStatus::NoMatch);
+		// Query packet, and a length-prefixed binary frame.
+		assert_eq!(probe(b"\x01\x00\x00").0, DetectionStatus::NoMatch);
+		assert_eq!(
+			probe(b"\x00\x00\x00\x10\x00\x01\x02\x03").0,
+			DetectionStatus::NoMatch
+		);
+	}
+}
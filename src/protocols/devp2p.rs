@@ -0,0 +1,134 @@
+/* src/protocols/devp2p.rs */
+use crate::{DetectionStatus, ProtocolVersion};
+
+/// Marker of the uncompressed ephemeral public key opening an ECIES message.
+const UNCOMPRESSED_POINT: u8 = 0x04;
+
+/// Smallest EIP-8 handshake: ECIES overhead (65-byte key, 16-byte IV, 32-byte
+/// MAC) around the shortest auth-ack body and the minimum 100 bytes of padding.
+const MIN_HANDSHAKE_LEN: u16 = 300;
+
+/// Largest EIP-8 handshake accepted.
+const MAX_HANDSHAKE_LEN: u16 = 2048;
+
+/// Offset of the packet type in a discv4 packet (after hash and signature).
+const DISCV4_TYPE_OFFSET: usize = 32 + 65;
+
+/// Largest discovery packet.
+const MAX_DISCV4_LEN: usize = 1280;
+
+/// Probes for Ethereum devp2p: RLPx handshakes over TCP and discv4 over UDP.
+///
+/// This is a heuristic: RLPx encrypts everything after the size prefix, so
+/// an EIP-8 auth or auth-ack is recognized by a plausible size and the
+/// uncompressed ECIES key marker. A discv4 packet is recognized by its
+/// layout: a signature recovery id of 0 or 1, a packet type from 1 to 6, and
+/// an RLP list that exactly fills the rest of the datagram. discv5 masks its
+/// header with the recipient's node ID and is not recognized.
+///
+/// The discv4 packet type sits at offset 97 and the RLP list must end with
+/// the data, so discv4 is only recognized when the inspection window covers
+/// the whole datagram.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	if is_discv4(data) {
+		return (DetectionStatus::Match, ProtocolVersion::Unknown);
+	}
+	let Some(&[high, low, marker]) = data.first_chunk::<3>() else {
+		return (DetectionStatus::Incomplete, ProtocolVersion::Unknown);
+	};
+	let size = u16::from_be_bytes([high, low]);
+	if !(MIN_HANDSHAKE_LEN..=MAX_HANDSHAKE_LEN).contains(&size) || marker != UNCOMPRESSED_POINT {
+		reject!("no_devp2p_layout");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	}
+	(DetectionStatus::Match, ProtocolVersion::Unknown)
+}
+
+/// Checks the discv4 layout: `hash || signature || type || rlp(data)`.
+#[inline(always)]
+fn is_discv4(data: &[u8]) -> bool {
+	if data.len() > MAX_DISCV4_LEN {
+		return false;
+	}
+	let (Some(&recovery_id), Some(&packet_type), Some(body)) = (
+		data.get(DISCV4_TYPE_OFFSET - 1),
+		data.get(DISCV4_TYPE_OFFSET),
+		data.get(DISCV4_TYPE_OFFSET + 1..),
+	) else {
+		return false;
+	};
+	recovery_id <= 1 && (1..=6).contains(&packet_type) && rlp_list_len(body) == Some(body.len())
+}
+
+/// Returns the encoded length of the RLP list at the start of `data`.
+#[inline(always)]
+fn rlp_list_len(data: &[u8]) -> Option<usize> {
+	let prefix = *data.first()?;
+	match prefix {
+		0xC0..=0xF7 => Some(1 + usize::from(prefix - 0xC0)),
+		0xF8..=0xF9 => {
+			let len_len = usize::from(prefix - 0xF7);
+			let len = data
+				.get(1..=len_len)?
+				.iter()
+				.fold(0, |acc, &b| (acc << 8) | usize::from(b));
+			Some(1 + len_len + len)
+		}
+		_ => None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Builds a discv4 ping with a zeroed hash and signature.
+	fn discv4_ping() -> [u8; 98 + 20] {
+		let mut packet = [0u8; 98 + 20];
+		packet[DISCV4_TYPE_OFFSET - 1] = 0x01;
+		packet[DISCV4_TYPE_OFFSET] = 0x01;
+		packet[98] = 0xC0 + 19;
+		packet[99] = 0x04; // version
+		packet
+	}
+
+	#[test]
+	fn test_detect_rlpx_auth() {
+		let mut auth = [0xA5u8; 64];
+		auth[..3].copy_from_slice(&[0x01, 0x94, 0x04]);
+		assert_eq!(
+			probe(&auth),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+		assert_eq!(probe(&auth[..2]).0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	fn test_detect_discv4() {
+		let ping = discv4_ping();
+		assert_eq!(probe(&ping).0, DetectionStatus::Match);
+		// The RLP list must fill the datagram.
+		assert_eq!(probe(&ping[..117]).0, DetectionStatus::NoMatch);
+	}
+
+	#[test]
+	fn test_detect_discv4_long_list() {
+		let mut packet = [0u8; 98 + 3 + 300];
+		packet[DISCV4_TYPE_OFFSET] = 0x04; // neighbors
+		packet[98..101].copy_from_slice(&[0xF9, 0x01, 0x2C]);
+		assert_eq!(probe(&packet).0, DetectionStatus::Match);
+	}
+
+	#[test]
+	fn test_reject_other_layouts() {
+		let mut ping = discv4_ping();
+		ping[DISCV4_TYPE_OFFSET] = 0x07;
+		assert_eq!(probe(&ping).0, DetectionStatus::NoMatch);
+
+		// Handshake size below the minimum, and a compressed key marker.
+		assert_eq!(probe(&[0x00, 0x40, 0x04, 0x00]).0, DetectionStatus::NoMatch);
+		assert_eq!(probe(&[0x01, 0x94, 0x02, 0x00]).0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"GET / HTTP/1.1\r\n").0, DetectionStatus::NoMatch);
+	}
+}
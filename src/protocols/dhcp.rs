@@ -9,12 +9,14 @@
 pub(crate) fn detect(data: &[u8]) -> bool {
 	// Minimum 44 bytes to inspect up to the hardware address padding.
 	if data.len() < 44 {
+		reject!("too_short");
 		return false;
 	}
 
 	// op: BOOTREQUEST (1) or BOOTREPLY (2)
 	let op = data[0];
 	if op != 1 && op != 2 {
+		reject!("op_invalid");
 		return false;
 	}
 
@@ -27,6 +29,7 @@ pub(crate) fn detect(data: &[u8]) -> bool {
 		1 | 6 => {
 			// Ethernet or IEEE 802
 			if hlen != 6 {
+				reject!("hlen_mismatch");
 				return false;
 			}
 			// For Ethernet, the 16-byte chaddr field (starting at offset 28)
@@ -34,6 +37,7 @@ pub(crate) fn detect(data: &[u8]) -> bool {
 			// in standard implementations.
 			for &byte in &data[34..44] {
 				if byte != 0 {
+					reject!("chaddr_padding_nonzero");
 					return false;
 				}
 			}
@@ -41,6 +45,7 @@ pub(crate) fn detect(data: &[u8]) -> bool {
 		// Other types are rare; we validate hlen is within reasonable bounds (1-16)
 		_ => {
 			if hlen == 0 || hlen > 16 {
+				reject!("hlen_invalid");
 				return false;
 			}
 		}
@@ -48,6 +53,7 @@ pub(crate) fn detect(data: &[u8]) -> bool {
 
 	// hops: usually 0, should not exceed 16 per RFC 2131
 	if data[3] > 16 {
+		reject!("too_many_hops");
 		return false;
 	}
 
@@ -56,13 +62,15 @@ pub(crate) fn detect(data: &[u8]) -> bool {
 	// flags are at offset 10 and 11.
 	// [B][MBZ...7bits] [MBZ...8bits]
 	if (data[10] & 0x7F) != 0 || data[11] != 0 {
+		reject!("flags_reserved_bits_set");
 		return false;
 	}
 
 	// If we have enough data to see the magic cookie at offset 236, check it.
 	// Magic cookie: 0x63 0x82 0x53 0x63
-	if data.len() >= 240 {
-		return data[236..240] == [0x63, 0x82, 0x53, 0x63];
+	if data.len() >= 240 && data[236..240] != [0x63, 0x82, 0x53, 0x63] {
+		reject!("magic_cookie_mismatch");
+		return false;
 	}
 
 	// Since we often only have 64 bytes, we rely on the header consistency above.
@@ -0,0 +1,195 @@
+/* src/protocols/dht.rs */
+use crate::{DetectionStatus, ProtocolVersion};
+
+/// Deepest container nesting walked inside the top-level dictionary.
+const MAX_DEPTH: u8 = 4;
+
+/// Longest byte string accepted; KRPC messages fit in one datagram.
+const MAX_STRING_LEN: usize = 1500;
+
+/// Outcome of walking one bencoded value.
+enum Step {
+	/// The value ends just before this offset.
+	Next(usize),
+	/// The value runs past the end of the data.
+	Truncated,
+	/// The bytes are not valid bencode.
+	Invalid,
+}
+
+/// Probes for a bencoded KRPC message (BitTorrent DHT).
+///
+/// Walks the top-level dictionary until both a `t` transaction id and a `y`
+/// message type of `q` (query) or `r` (response) have been seen. Values are
+/// checked structurally, with nesting and string lengths bounded.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	if data.first() != Some(&b'd') {
+		reject!("not_a_dictionary");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	}
+
+	let mut pos = 1;
+	let mut has_transaction = false;
+	let mut has_type = false;
+	loop {
+		match data.get(pos) {
+			None => return (DetectionStatus::Incomplete, ProtocolVersion::Unknown),
+			Some(b'e') => {
+				reject!("missing_krpc_keys");
+				return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+			}
+			Some(_) => {}
+		}
+
+		let key_start = pos;
+		let key_end = match string(data, pos) {
+			Step::Next(end) => end,
+			Step::Truncated => return (DetectionStatus::Incomplete, ProtocolVersion::Unknown),
+			Step::Invalid => {
+				reject!("invalid_dictionary_key");
+				return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+			}
+		};
+		let value_end = match value(data, key_end, MAX_DEPTH) {
+			Step::Next(end) => end,
+			Step::Truncated => return (DetectionStatus::Incomplete, ProtocolVersion::Unknown),
+			Step::Invalid => {
+				reject!("invalid_bencode_value");
+				return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+			}
+		};
+
+		let key = &data[key_start..key_end];
+		let val = &data[key_end..value_end];
+		if key == b"1:t" && val.first().is_some_and(u8::is_ascii_digit) {
+			has_transaction = true;
+		} else if key == b"1:y" {
+			if val != b"1:q" && val != b"1:r" {
+				reject!("unknown_message_type");
+				return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+			}
+			has_type = true;
+		}
+		if has_transaction && has_type {
+			return (DetectionStatus::Match, ProtocolVersion::Unknown);
+		}
+		pos = value_end;
+	}
+}
+
+/// Walks one bencoded value starting at `pos`.
+fn value(data: &[u8], pos: usize, depth: u8) -> Step {
+	match data.get(pos) {
+		None => Step::Truncated,
+		Some(b'0'..=b'9') => string(data, pos),
+		Some(b'i') => integer(data, pos + 1),
+		Some(b'l' | b'd') if depth == 0 => Step::Invalid,
+		Some(&open @ (b'l' | b'd')) => {
+			let mut pos = pos + 1;
+			loop {
+				match data.get(pos) {
+					None => return Step::Truncated,
+					Some(b'e') => return Step::Next(pos + 1),
+					Some(_) => {}
+				}
+				if open == b'd' {
+					pos = match string(data, pos) {
+						Step::Next(end) => end,
+						other => return other,
+					};
+				}
+				pos = match value(data, pos, depth - 1) {
+					Step::Next(end) => end,
+					other => return other,
+				};
+			}
+		}
+		Some(_) => Step::Invalid,
+	}
+}
+
+/// Walks a `<len>:<bytes>` byte string starting at `pos`.
+fn string(data: &[u8], pos: usize) -> Step {
+	let mut len = 0usize;
+	let mut cursor = pos;
+	loop {
+		match data.get(cursor) {
+			None => return Step::Truncated,
+			Some(&digit @ b'0'..=b'9') => {
+				len = len * 10 + usize::from(digit - b'0');
+				if len > MAX_STRING_LEN {
+					return Step::Invalid;
+				}
+			}
+			Some(b':') if cursor > pos => break,
+			Some(_) => return Step::Invalid,
+		}
+		cursor += 1;
+	}
+	let end = cursor + 1 + len;
+	if end > data.len() {
+		Step::Truncated
+	} else {
+		Step::Next(end)
+	}
+}
+
+/// Walks the digits and terminator of an integer whose `i` precedes `pos`.
+fn integer(data: &[u8], pos: usize) -> Step {
+	let start = if data.get(pos) == Some(&b'-') {
+		pos + 1
+	} else {
+		pos
+	};
+	let mut cursor = start;
+	loop {
+		match data.get(cursor) {
+			None => return Step::Truncated,
+			Some(b'0'..=b'9') if cursor - start < 20 => cursor += 1,
+			Some(b'e') if cursor > start => return Step::Next(cursor + 1),
+			Some(_) => return Step::Invalid,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_detect_ping_query() {
+		let data = b"d1:ad2:id20:abcdefghij0123456789e1:q4:ping1:t2:aa1:y1:qe";
+		assert_eq!(
+			probe(data),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+	}
+
+	#[test]
+	fn test_detect_response_with_nested_values() {
+		let data = b"d2:ip6:\x01\x02\x03\x04\x1a\xe11:rd2:id20:mnopqrstuvwxyz123456\
+			5:nodesl4:abcdi-7eee1:t2:aa1:v4:LT\x01\x001:y1:re";
+		assert_eq!(probe(data).0, DetectionStatus::Match);
+	}
+
+	#[test]
+	fn test_incomplete_truncated_query() {
+		let data = b"d1:ad2:id20:abcdefghij0123456789e1:q4:ping1:t2:aa1:y1:qe";
+		assert_eq!(probe(&data[..40]).0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	fn test_reject_other_message_types() {
+		assert_eq!(probe(b"d1:t2:aa1:y1:xe").0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"d1:t2:aa1:v4:LT00e").0, DetectionStatus::NoMatch);
+	}
+
+	#[test]
+	fn test_reject_invalid_bencode() {
+		assert_eq!(probe(b"d1:ti12x1:y1:qe").0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"d99999:x").0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"dx:ae").0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"GET / HTTP/1.1\r\n").0, DetectionStatus::NoMatch);
+	}
+}
@@ -0,0 +1,97 @@
+/* src/protocols/dicom.rs */
+
+/// PDU type of an A-ASSOCIATE-RQ.
+const ASSOCIATE_RQ: u8 = 0x01;
+
+/// Bytes of the fixed A-ASSOCIATE-RQ fields after the length.
+const FIXED_LEN: u32 = 68;
+
+/// Largest association request accepted.
+const MAX_PDU_LEN: u32 = 65_536;
+
+/// Length of an application entity title field.
+const AE_TITLE_LEN: usize = 16;
+
+/// Detects the DICOM upper layer protocol.
+///
+/// Matches the A-ASSOCIATE-RQ PDU: type 0x01, reserved byte 0, a big-endian
+/// length covering at least the fixed fields, protocol version 1, and called
+/// and calling AE titles of space-padded printable ASCII.
+#[inline(always)]
+pub(crate) fn detect(data: &[u8]) -> bool {
+	if data.len() < 10 + 2 * AE_TITLE_LEN {
+		reject!("too_short");
+		return false;
+	}
+	if data[0] != ASSOCIATE_RQ || data[1] != 0 {
+		reject!("not_associate_request");
+		return false;
+	}
+	let length = u32::from_be_bytes([data[2], data[3], data[4], data[5]]);
+	if !(FIXED_LEN..=MAX_PDU_LEN).contains(&length) {
+		reject!("invalid_pdu_length");
+		return false;
+	}
+	if data[6..8] != [0x00, 0x01] {
+		reject!("unsupported_protocol_version");
+		return false;
+	}
+	let (called, calling) = data[10..10 + 2 * AE_TITLE_LEN].split_at(AE_TITLE_LEN);
+	if !is_ae_title(called) || !is_ae_title(calling) {
+		reject!("invalid_ae_title");
+		return false;
+	}
+	true
+}
+
+/// Checks an AE title: printable ASCII without backslashes, not all spaces.
+#[inline(always)]
+fn is_ae_title(title: &[u8]) -> bool {
+	title
+		.iter()
+		.all(|&b| (0x20..0x7F).contains(&b) && b != b'\\')
+		&& title.iter().any(|&b| b != b' ')
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// A-ASSOCIATE-RQ header from `STORESCU` to `ANY-SCP`.
+	fn associate_rq() -> [u8; 74] {
+		let mut data = [0u8; 74];
+		data[..10].copy_from_slice(&[0x01, 0x00, 0x00, 0x00, 0x00, 0xCD, 0x00, 0x01, 0x00, 0x00]);
+		data[10..26].copy_from_slice(b"ANY-SCP         ");
+		data[26..42].copy_from_slice(b"STORESCU        ");
+		data
+	}
+
+	#[test]
+	fn test_detect_associate_request() {
+		assert!(detect(&associate_rq()));
+		assert!(detect(&associate_rq()[..42]));
+	}
+
+	#[test]
+	fn test_reject_bad_titles() {
+		let mut data = associate_rq();
+		data[10..26].copy_from_slice(&[b' '; 16]);
+		assert!(!detect(&data));
+		let mut data = associate_rq();
+		data[30] = 0x00;
+		assert!(!detect(&data));
+	}
+
+	#[test]
+	fn test_reject_bad_header() {
+		let mut data = associate_rq();
+		data[7] = 0x02;
+		assert!(!detect(&data));
+		let mut data = associate_rq();
+		data[5] = 0x10;
+		assert!(!detect(&data));
+		let mut data = associate_rq();
+		data[0] = 0x02; // A-ASSOCIATE-AC
+		assert!(!detect(&data));
+	}
+}
@@ -8,6 +8,7 @@
 pub(crate) fn detect(data: &[u8]) -> bool {
 	// Minimum UDP DNS packet is 12 bytes (header only).
 	if data.len() < 12 {
+		reject!("too_short");
 		return false;
 	}
 
@@ -20,6 +21,7 @@ pub(crate) fn detect(data: &[u8]) -> bool {
 				let first_label_len = data[12];
 				// Label length must be <= 63.
 				if first_label_len > 63 {
+					reject!("label_too_long");
 					return false;
 				}
 			}
@@ -39,6 +41,7 @@ pub(crate) fn detect(data: &[u8]) -> bool {
 				if qdcount > 0 {
 					let first_label_len = data[14];
 					if first_label_len > 63 {
+						reject!("label_too_long");
 						return false;
 					}
 				}
@@ -47,6 +50,7 @@ pub(crate) fn detect(data: &[u8]) -> bool {
 		}
 	}
 
+	reject!("invalid_header");
 	false
 }
 
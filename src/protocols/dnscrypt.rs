@@ -0,0 +1,152 @@
+/* src/protocols/dnscrypt.rs */
+
+/// Magic that starts every encrypted resolver response.
+const RESOLVER_MAGIC: &[u8; 8] = b"r6fnvWj8";
+
+/// Magic that opens a resolver certificate.
+const CERT_MAGIC: &[u8; 4] = b"DNSC";
+
+/// First labels of a certificate query name (`2.dnscrypt-cert.<provider>`).
+const CERT_LABELS: &[u8] = b"\x012\x0Ddnscrypt-cert";
+
+/// Smallest padded client query over UDP.
+const MIN_QUERY_LEN: usize = 256;
+
+/// Client queries are padded to a multiple of this block size.
+const QUERY_BLOCK: usize = 64;
+
+/// Resolver magic, nonce, and MAC of an encrypted response.
+const MIN_RESPONSE_LEN: usize = 8 + 24 + 16;
+
+/// Detects DNSCrypt.
+///
+/// Recognizes the certificate exchange (a DNS query or response for
+/// `2.dnscrypt-cert.<provider>`, or a response carrying a `DNSC` certificate
+/// with a known encryption system), encrypted responses opening with the
+/// resolver magic, and encrypted client queries by their padding: at least
+/// 256 bytes and a multiple of 64, without the fixed fields of other UDP
+/// protocols. Raise `max_inspect_bytes` to cover the whole datagram for client
+/// queries.
+#[inline(always)]
+pub(crate) fn detect(data: &[u8]) -> bool {
+	if data.len() < 12 {
+		reject!("too_short");
+		return false;
+	}
+	if data.starts_with(RESOLVER_MAGIC) {
+		if data.len() < MIN_RESPONSE_LEN {
+			reject!("short_encrypted_response");
+			return false;
+		}
+		return true;
+	}
+	if is_cert_exchange(data) {
+		return true;
+	}
+	if data.len() >= MIN_QUERY_LEN
+		&& data.len().is_multiple_of(QUERY_BLOCK)
+		&& !has_plaintext_markers(data)
+	{
+		return true;
+	}
+	reject!("no_dnscrypt_markers");
+	false
+}
+
+/// Checks a padded datagram for fixed fields of the plaintext protocols probed
+/// after this one: a single-question DNS header, the STUN magic cookie, a QUIC
+/// v1/v2 long header, the DHCP magic cookie, or a printable text start. A
+/// random client magic hits these with negligible probability.
+#[inline(always)]
+fn has_plaintext_markers(data: &[u8]) -> bool {
+	data[4..6] == [0x00, 0x01]
+		|| data[4..8] == [0x21, 0x12, 0xA4, 0x42]
+		|| (data[0] & 0x80 != 0
+			&& matches!(
+				data[1..5],
+				[0x00, 0x00, 0x00, 0x01] | [0x6B, 0x33, 0x43, 0xCF]
+			))
+		|| data[236..240] == [0x63, 0x82, 0x53, 0x63]
+		|| data[..8].iter().all(|&b| b == b' ' || b.is_ascii_graphic())
+}
+
+/// Checks for a certificate query or response in plain DNS.
+#[inline(always)]
+fn is_cert_exchange(data: &[u8]) -> bool {
+	// Standard query or response, one question.
+	if data[2] & 0x78 != 0 || data[4..6] != [0x00, 0x01] {
+		return false;
+	}
+	let name = &data[12..];
+	if name.len() > CERT_LABELS.len() && name[..CERT_LABELS.len()].eq_ignore_ascii_case(CERT_LABELS) {
+		return true;
+	}
+	// A response whose certificate is in view: magic, ES version 1 or 2,
+	// protocol minor version 0.
+	data[2] & 0x80 != 0
+		&& data.windows(8).any(|window| {
+			window.starts_with(CERT_MAGIC)
+				&& matches!(window[4..6], [0x00, 0x01 | 0x02])
+				&& window[6..8] == [0x00, 0x00]
+		})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_detect_cert_query() {
+		let mut data = [0u8; 12].to_vec();
+		data[..6].copy_from_slice(&[0x4A, 0x1C, 0x01, 0x00, 0x00, 0x01]);
+		data.extend_from_slice(b"\x012\x0Ddnscrypt-cert\x07example\x03com\x00\x00\x10\x00\x01");
+		assert!(detect(&data));
+	}
+
+	#[test]
+	fn test_detect_cert_response() {
+		let mut data = [0u8; 12].to_vec();
+		data[..8].copy_from_slice(&[0x4A, 0x1C, 0x81, 0x80, 0x00, 0x01, 0x00, 0x01]);
+		data.extend_from_slice(b"\x05other\x00\x00\x10\x00\x01\xC0\x0C\x00\x10\x00\x01");
+		data.extend_from_slice(b"\x00\x00\x0E\x10\x01\x7C\xFFDNSC\x00\x02\x00\x00");
+		assert!(detect(&data));
+	}
+
+	#[test]
+	fn test_detect_encrypted_traffic() {
+		let mut response = [0x5Au8; 64];
+		response[..8].copy_from_slice(RESOLVER_MAGIC);
+		assert!(detect(&response));
+
+		let mut query = [0xA7u8; 320];
+		query[4..6].copy_from_slice(&[0x3E, 0x91]);
+		assert!(detect(&query));
+		assert!(!detect(&query[..300]));
+	}
+
+	#[test]
+	fn test_reject_plain_dns() {
+		let mut data = [0u8; 12].to_vec();
+		data[..6].copy_from_slice(&[0x12, 0x34, 0x01, 0x00, 0x00, 0x01]);
+		data.extend_from_slice(b"\x06google\x03com\x00\x00\x01\x00\x01");
+		assert!(!detect(&data));
+
+		let mut padded = [0u8; 256];
+		padded[..6].copy_from_slice(&[0x12, 0x34, 0x81, 0x80, 0x00, 0x01]);
+		assert!(!detect(&padded));
+	}
+
+	#[test]
+	fn test_reject_padded_plaintext_protocols() {
+		// DHCP discover padded to 576 bytes.
+		let mut dhcp = [0u8; 576];
+		dhcp[..4].copy_from_slice(&[0x01, 0x01, 0x06, 0x00]);
+		dhcp[236..240].copy_from_slice(&[0x63, 0x82, 0x53, 0x63]);
+		assert!(!detect(&dhcp));
+
+		// QUIC v1 Initial padded to 1280 bytes.
+		let mut quic = [0u8; 1280];
+		quic[..5].copy_from_slice(&[0xC3, 0x00, 0x00, 0x00, 0x01]);
+		assert!(!detect(&quic));
+	}
+}
@@ -4,6 +4,7 @@
 #[inline(always)]
 pub(crate) fn detect(data: &[u8]) -> bool {
 	if data.len() < 5 {
+		reject!("too_short");
 		return false;
 	}
 
@@ -25,6 +26,7 @@ pub(crate) fn detect(data: &[u8]) -> bool {
 		return validate_line(data);
 	}
 
+	reject!("unknown_command");
 	false
 }
 
@@ -54,6 +56,7 @@ fn validate_line(data: &[u8]) -> bool {
 			break;
 		}
 		if b != b'\r' && b != b'\t' && !(32..=126).contains(&b) {
+			reject!("non_printable_byte");
 			return false;
 		}
 	}
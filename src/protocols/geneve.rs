@@ -0,0 +1,150 @@
+/* src/protocols/geneve.rs */
+use crate::{DetectionStatus, ProtocolMetadata, ProtocolVersion};
+
+/// Protocol type of an inner Ethernet frame (transparent Ethernet bridging).
+pub(crate) const PROTOCOL_ETHERNET: u16 = 0x6558;
+
+/// Protocol type of an inner IPv4 packet.
+const PROTOCOL_IPV4: u16 = 0x0800;
+
+/// Protocol type of an inner IPv6 packet.
+const PROTOCOL_IPV6: u16 = 0x86DD;
+
+/// Length of the fixed GENEVE header.
+const HEADER_LEN: usize = 8;
+
+/// Length of the inner Ethernet header.
+const ETHERNET_HEADER_LEN: usize = 14;
+
+/// Probes for a GENEVE packet.
+///
+/// Expects version 0, zero reserved bits, an Ethernet, IPv4, or IPv6 protocol
+/// type, and options that exactly fill the advertised option length. The
+/// inner frame must start where the options end: an Ethernet header, or an IP
+/// version nibble matching the protocol type.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	let Some(header) = data.first_chunk::<HEADER_LEN>() else {
+		return (DetectionStatus::Incomplete, ProtocolVersion::Unknown);
+	};
+	if header[0] >> 6 != 0 || header[1] & 0x3F != 0 || header[7] != 0 {
+		reject!("invalid_geneve_header");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	}
+	let protocol_type = u16::from_be_bytes([header[2], header[3]]);
+	if !matches!(
+		protocol_type,
+		PROTOCOL_ETHERNET | PROTOCOL_IPV4 | PROTOCOL_IPV6
+	) {
+		reject!("unknown_protocol_type");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	}
+
+	// Each option is a 4-byte header (class, type, length in words) and its data.
+	let end = header_len(data);
+	let mut pos = HEADER_LEN;
+	while pos < end {
+		let Some(&length) = data.get(pos + 3) else {
+			return (DetectionStatus::Incomplete, ProtocolVersion::Unknown);
+		};
+		pos += 4 + usize::from(length & 0x1F) * 4;
+	}
+	if pos != end {
+		reject!("option_length_mismatch");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	}
+
+	let inner = &data[end.min(data.len())..];
+	let status = match (protocol_type, inner.first()) {
+		(PROTOCOL_ETHERNET, _) if inner.len() >= ETHERNET_HEADER_LEN => DetectionStatus::Match,
+		(PROTOCOL_IPV4, Some(b)) if b >> 4 == 4 => DetectionStatus::Match,
+		(PROTOCOL_IPV6, Some(b)) if b >> 4 == 6 => DetectionStatus::Match,
+		(PROTOCOL_ETHERNET, _) | (_, None) => DetectionStatus::Incomplete,
+		_ => {
+			reject!("ip_version_mismatch");
+			DetectionStatus::NoMatch
+		}
+	};
+	(status, ProtocolVersion::Unknown)
+}
+
+/// Extracts the VNI and the protocol type of the inner frame.
+#[inline(always)]
+pub(crate) fn metadata(data: &[u8]) -> ProtocolMetadata<'_> {
+	match data.first_chunk::<HEADER_LEN>() {
+		Some(header) => ProtocolMetadata::Geneve {
+			vni: u32::from_be_bytes([0, header[4], header[5], header[6]]),
+			protocol_type: u16::from_be_bytes([header[2], header[3]]),
+		},
+		None => ProtocolMetadata::None,
+	}
+}
+
+/// Length of the header including options.
+#[inline(always)]
+pub(crate) fn header_len(data: &[u8]) -> usize {
+	HEADER_LEN + usize::from(data.first().map_or(0, |b| b & 0x3F)) * 4
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// VNI 0x1234 with one 8-byte option, carrying the start of an Ethernet frame.
+	const PACKET: &[u8] = b"\x02\x00\x65\x58\x00\x12\x34\x00\
+		\x01\x02\x80\x01\xDE\xAD\xBE\xEF\
+		\x02\x42\xAC\x11\x00\x02\x02\x42\xAC\x11\x00\x03\x08\x00";
+
+	#[test]
+	fn test_detect_ethernet_payload() {
+		assert_eq!(
+			probe(PACKET),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+		assert_eq!(
+			metadata(PACKET),
+			ProtocolMetadata::Geneve {
+				vni: 0x1234,
+				protocol_type: PROTOCOL_ETHERNET,
+			}
+		);
+		assert_eq!(header_len(PACKET), 16);
+	}
+
+	#[test]
+	fn test_detect_ip_payload() {
+		assert_eq!(
+			probe(b"\x00\x00\x08\x00\x00\x00\x07\x00\x45\x00\x00\x54").0,
+			DetectionStatus::Match
+		);
+		assert_eq!(
+			probe(b"\x00\x00\x86\xDD\x00\x00\x07\x00\x45\x00\x00\x54").0,
+			DetectionStatus::NoMatch
+		);
+	}
+
+	#[test]
+	fn test_incomplete_packet() {
+		assert_eq!(probe(&PACKET[..6]).0, DetectionStatus::Incomplete);
+		assert_eq!(probe(&PACKET[..10]).0, DetectionStatus::Incomplete);
+		assert_eq!(probe(&PACKET[..20]).0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	fn test_reject_bad_header() {
+		let mut data = PACKET.to_vec();
+		data[0] = 0x42; // version 1
+		assert_eq!(probe(&data).0, DetectionStatus::NoMatch);
+		let mut data = PACKET.to_vec();
+		data[11] = 0x02; // option runs past the option length
+		assert_eq!(probe(&data).0, DetectionStatus::NoMatch);
+		let mut data = PACKET.to_vec();
+		data[2..4].copy_from_slice(&[0x88, 0x47]); // MPLS
+		assert_eq!(probe(&data).0, DetectionStatus::NoMatch);
+		// VXLAN header.
+		assert_eq!(
+			probe(b"\x08\x00\x00\x00\x00\x13\x89\x00").0,
+			DetectionStatus::NoMatch
+		);
+	}
+}
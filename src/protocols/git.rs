@@ -0,0 +1,137 @@
+/* src/protocols/git.rs */
+
+/// Service commands a `git://` client may request.
+const SERVICES: &[&[u8]] = &[
+	b"git-upload-pack ",
+	b"git-receive-pack ",
+	b"git-upload-archive ",
+];
+
+/// Smart-HTTP service advertisements, the first pkt-line of an `info/refs` reply.
+const ADVERTISEMENTS: &[&[u8]] = &[
+	b"001e# service=git-upload-pack\n",
+	b"001f# service=git-receive-pack\n",
+];
+
+/// Detects the git wire protocol.
+///
+/// Matches a `git://` request pkt-line (four hex length digits, a service
+/// command, a path, and a `\0host=` parameter) or the smart-HTTP service
+/// advertisement that opens an `info/refs` response body.
+#[inline(always)]
+pub(crate) fn detect(data: &[u8]) -> bool {
+	if data.len() < 8 {
+		reject!("too_short");
+		return false;
+	}
+
+	if ADVERTISEMENTS
+		.iter()
+		.any(|adv| data.starts_with(adv) || adv.starts_with(data))
+	{
+		return true;
+	}
+
+	let Some(length) = pkt_length(&data[..4]) else {
+		reject!("invalid_pkt_length");
+		return false;
+	};
+	let Some(service) = SERVICES.iter().find(|s| data[4..].starts_with(s)) else {
+		reject!("unknown_service");
+		return false;
+	};
+	if length < 4 + service.len() + 2 {
+		reject!("pkt_length_too_small");
+		return false;
+	}
+
+	let end = length.min(data.len());
+	let rest = &data[4 + service.len()..end];
+	if rest.first().is_some_and(|&b| b != b'/') {
+		reject!("path_not_absolute");
+		return false;
+	}
+
+	let path_end = rest.iter().position(|&b| b == 0).unwrap_or(rest.len());
+	if rest[..path_end].iter().any(|b| !(33..=126).contains(b)) {
+		reject!("non_printable_path");
+		return false;
+	}
+	if path_end == rest.len() {
+		// The NUL separator lies beyond the window; only possible if the
+		// packet does too.
+		return end < length;
+	}
+	let params = &rest[path_end + 1..];
+	if b"host=".starts_with(params) || params.starts_with(b"host=") {
+		return true;
+	}
+
+	reject!("missing_host_parameter");
+	false
+}
+
+/// Parses a pkt-line length: four lowercase hex digits, excluding the special
+/// `0000`-`0003` values.
+#[inline(always)]
+fn pkt_length(digits: &[u8]) -> Option<usize> {
+	let mut length = 0usize;
+	for &b in digits {
+		let nibble = match b {
+			b'0'..=b'9' => b - b'0',
+			b'a'..=b'f' => b - b'a' + 10,
+			_ => return None,
+		};
+		length = length * 16 + usize::from(nibble);
+	}
+	(length >= 4).then_some(length)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_detect_git_daemon_request() {
+		assert!(detect(
+			b"0032git-upload-pack /project.git\0host=example.com\0"
+		));
+		assert!(detect(
+			b"0033git-receive-pack /project.git\0host=example.com\0"
+		));
+	}
+
+	#[test]
+	fn test_detect_git_truncated_request() {
+		assert!(detect(
+			b"0060git-upload-pack /very/long/path/to/a/repository/that/keeps/going"
+		));
+		assert!(detect(b"0032git-upload-pack /project.git\0ho"));
+	}
+
+	#[test]
+	fn test_detect_smart_http_advertisement() {
+		assert!(detect(b"001e# service=git-upload-pack\n0000"));
+		assert!(detect(b"001f# service=git-receive-pack\n"));
+		assert!(detect(b"001e# service=git-uploa"));
+	}
+
+	#[test]
+	fn test_reject_missing_host() {
+		assert!(!detect(b"0022git-upload-pack /project.git\0\0"));
+	}
+
+	#[test]
+	fn test_reject_bad_length() {
+		assert!(!detect(b"00zzgit-upload-pack /project.git\0host=x\0"));
+		assert!(!detect(b"0003git-upload-pack /project.git\0host=x\0"));
+	}
+
+	#[test]
+	fn test_reject_other_text() {
+		assert!(!detect(b"GET /project.git/info/refs HTTP/1.1\r\n"));
+		assert!(!detect(
+			b"0033git-upload-pack project.git\0host=example.com\0"
+		));
+	}
+}
@@ -0,0 +1,138 @@
+/* src/protocols/gquic.rs */
+use crate::{DetectionStatus, ProtocolMetadata, ProtocolVersion};
+
+/// Public flag: the packet carries a version (set by clients until the
+/// version is negotiated).
+const FLAG_VERSION: u8 = 0x01;
+
+/// Public flag: the packet is a public reset, which carries no version.
+const FLAG_RESET: u8 = 0x02;
+
+/// Public flag: an 8-byte connection ID follows the flags.
+const FLAG_CONNECTION_ID: u8 = 0x08;
+
+/// Long header form bit used by Q046 and later.
+const LONG_HEADER: u8 = 0x80;
+
+/// Fixed bit that Q046 and later set in long headers.
+const FIXED_BIT: u8 = 0x40;
+
+/// Probes for Google QUIC (gQUIC).
+///
+/// Recognizes the public header of Q043 and earlier (flags with the version
+/// bit set and the reset and reserved bits clear, an optional 8-byte
+/// connection ID, then a `Q0xx` version tag) and the long header Q046 and
+/// later borrowed from IETF QUIC, whose version field is the `Q0xx` tag.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	let status = match version_offset(data) {
+		Ok(offset) => match data.get(offset..offset + 4) {
+			None => DetectionStatus::Incomplete,
+			Some(tag) if is_version_tag(tag) => DetectionStatus::Match,
+			Some(_) => {
+				reject!("not_gquic_version");
+				DetectionStatus::NoMatch
+			}
+		},
+		Err(status) => status,
+	};
+	(status, ProtocolVersion::Unknown)
+}
+
+/// Extracts the gQUIC version tag (e.g. "Q043").
+#[inline(always)]
+pub(crate) fn metadata(data: &[u8]) -> ProtocolMetadata<'_> {
+	version_offset(data)
+		.ok()
+		.and_then(|offset| data.get(offset..offset + 4))
+		.filter(|tag| is_version_tag(tag))
+		.and_then(|tag| core::str::from_utf8(tag).ok())
+		.map_or(ProtocolMetadata::None, |version| ProtocolMetadata::Gquic {
+			version,
+		})
+}
+
+/// Returns the offset of the version tag from the flags byte.
+#[inline(always)]
+fn version_offset(data: &[u8]) -> Result<usize, DetectionStatus> {
+	let Some(&flags) = data.first() else {
+		return Err(DetectionStatus::Incomplete);
+	};
+	if flags & LONG_HEADER != 0 {
+		if flags & FIXED_BIT == 0 {
+			reject!("fixed_bit_clear");
+			return Err(DetectionStatus::NoMatch);
+		}
+		return Ok(1);
+	}
+	if flags & (FLAG_VERSION | FLAG_RESET) != FLAG_VERSION {
+		reject!("no_version_flag");
+		return Err(DetectionStatus::NoMatch);
+	}
+	Ok(if flags & FLAG_CONNECTION_ID == 0 {
+		1
+	} else {
+		9
+	})
+}
+
+/// Checks for a `Q0` tag followed by two digits.
+#[inline(always)]
+fn is_version_tag(tag: &[u8]) -> bool {
+	matches!(tag, [b'Q', b'0', a, b] if a.is_ascii_digit() && b.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_detect_q043_public_header() {
+		let packet = b"\x0D\x12\x34\x56\x78\x9A\xBC\xDE\xF0Q043\x01\xA0\x3F\x00";
+		assert_eq!(
+			probe(packet),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+		assert_eq!(
+			metadata(packet),
+			ProtocolMetadata::Gquic { version: "Q043" }
+		);
+		// No connection ID.
+		assert_eq!(probe(b"\x01Q039\x01").0, DetectionStatus::Match);
+	}
+
+	#[test]
+	fn test_detect_q046_long_header() {
+		let packet = b"\xC3Q046\x50\x12\x34\x56\x78\x9A\xBC\xDE\xF0\x00\x00\x00\x01";
+		assert_eq!(probe(packet).0, DetectionStatus::Match);
+		assert_eq!(
+			metadata(packet),
+			ProtocolMetadata::Gquic { version: "Q046" }
+		);
+	}
+
+	#[test]
+	fn test_incomplete_header() {
+		assert_eq!(probe(b"\x0D\x12\x34").0, DetectionStatus::Incomplete);
+		assert_eq!(probe(b"\xC3Q0").0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	fn test_reject_other_packets() {
+		// IETF QUIC v1 Initial.
+		assert_eq!(
+			probe(b"\xC3\x00\x00\x00\x01\x08\x12\x34").0,
+			DetectionStatus::NoMatch
+		);
+		// Public reset and a short header without the version flag.
+		assert_eq!(probe(b"\x0AQ043\x00").0, DetectionStatus::NoMatch);
+		assert_eq!(
+			probe(b"\x08\x12\x34\x56\x78\x9A\xBC\xDE\xF0\x01").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(
+			probe(b"\x09\x12\x34\x56\x78\x9A\xBC\xDE\xF0T050").0,
+			DetectionStatus::NoMatch
+		);
+	}
+}
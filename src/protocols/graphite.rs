@@ -0,0 +1,133 @@
+/* src/protocols/graphite.rs */
+
+/// Detects the Graphite plaintext protocol.
+///
+/// Expects a complete first line of `metric.path value timestamp`: a dotted
+/// printable metric name (tags after `;` included), a decimal value, and a
+/// 9- or 10-digit epoch timestamp (or `-1` for "now"). A second complete line
+/// is validated the same way; a partial second line must be printable.
+#[inline(always)]
+pub(crate) fn detect(data: &[u8]) -> bool {
+	let Some(end) = data.iter().position(|&b| b == b'\n') else {
+		reject!("no_complete_line");
+		return false;
+	};
+	if !is_metric_line(&data[..end]) {
+		reject!("invalid_metric_line");
+		return false;
+	}
+
+	let rest = &data[end + 1..];
+	match rest.iter().position(|&b| b == b'\n') {
+		Some(end) if !is_metric_line(&rest[..end]) => {
+			reject!("invalid_second_line");
+			false
+		}
+		None if !rest.iter().all(|&b| b == b' ' || b.is_ascii_graphic()) => {
+			reject!("non_printable_second_line");
+			false
+		}
+		_ => true,
+	}
+}
+
+/// Checks one line without its `\n`.
+#[inline(always)]
+fn is_metric_line(line: &[u8]) -> bool {
+	let line = line.strip_suffix(b"\r").unwrap_or(line);
+	let mut fields = line.split(|&b| b == b' ');
+	let (Some(path), Some(value), Some(timestamp), None) =
+		(fields.next(), fields.next(), fields.next(), fields.next())
+	else {
+		return false;
+	};
+	is_metric_path(path) && is_number(value) && is_timestamp(timestamp)
+}
+
+/// Checks for a printable, dotted metric name.
+#[inline(always)]
+fn is_metric_path(path: &[u8]) -> bool {
+	path.contains(&b'.') && path.first() != Some(&b'.') && path.iter().all(u8::is_ascii_graphic)
+}
+
+/// Checks for a decimal number with optional sign, fraction, and exponent.
+#[inline(always)]
+fn is_number(value: &[u8]) -> bool {
+	let value = value.strip_prefix(b"-").unwrap_or(value);
+	let (mantissa, exponent) = match value.iter().position(|&b| b == b'e' || b == b'E') {
+		Some(i) => (&value[..i], Some(&value[i + 1..])),
+		None => (value, None),
+	};
+	let (whole, fraction) = split_at_dot(mantissa);
+	let exponent_valid = exponent.is_none_or(|exponent| {
+		let digits = exponent
+			.strip_prefix(b"-")
+			.or_else(|| exponent.strip_prefix(b"+"))
+			.unwrap_or(exponent);
+		!digits.is_empty() && is_digits(digits)
+	});
+	whole.len() + fraction.len() > 0 && is_digits(whole) && is_digits(fraction) && exponent_valid
+}
+
+/// Checks for an epoch timestamp in seconds, possibly fractional.
+#[inline(always)]
+fn is_timestamp(timestamp: &[u8]) -> bool {
+	if timestamp == b"-1" {
+		return true;
+	}
+	let (seconds, fraction) = split_at_dot(timestamp);
+	(9..=10).contains(&seconds.len()) && is_digits(seconds) && is_digits(fraction)
+}
+
+/// Splits at the first `.`, dropping it.
+#[inline(always)]
+fn split_at_dot(value: &[u8]) -> (&[u8], &[u8]) {
+	match value.iter().position(|&b| b == b'.') {
+		Some(i) => (&value[..i], &value[i + 1..]),
+		None => (value, &[]),
+	}
+}
+
+/// Returns whether every byte is an ASCII digit.
+#[inline(always)]
+fn is_digits(value: &[u8]) -> bool {
+	value.iter().all(u8::is_ascii_digit)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_detect_single_line() {
+		assert!(detect(b"servers.web01.cpu.load 0.42 1700000000\n"));
+		assert!(detect(b"app.requests;env=prod -12 1700000000.5\r\n"));
+		assert!(detect(b"app.latency 1.5e-3 -1\n"));
+	}
+
+	#[test]
+	fn test_detect_two_lines() {
+		assert!(detect(
+			b"servers.web01.cpu.load 0.42 1700000000\nservers.web01.mem.used 812 1700000000\n"
+		));
+		assert!(detect(
+			b"servers.web01.cpu.load 0.42 1700000000\nservers.web01.me"
+		));
+	}
+
+	#[test]
+	fn test_reject_malformed_lines() {
+		assert!(!detect(b"servers.web01.cpu.load 0.42 1700000000"));
+		assert!(!detect(b"servers.web01.cpu.load high 1700000000\n"));
+		assert!(!detect(b"servers.web01.cpu.load 0.42 17000\n"));
+		assert!(!detect(b"load 0.42 1700000000\n"));
+		assert!(!detect(b"servers.cpu 1 1700000000\nservers.mem 1\n"));
+	}
+
+	#[test]
+	fn test_reject_other_protocols() {
+		assert!(!detect(b"cpu.load,host=a value=0.42 1700000000\n"));
+		assert!(!detect(b"GET / HTTP/1.1\r\n"));
+		assert!(!detect(b"+OK POP3 ready\r\n"));
+	}
+}
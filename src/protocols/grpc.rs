@@ -0,0 +1,136 @@
+/* src/protocols/grpc.rs */
+use crate::{DetectionStatus, ProtocolVersion};
+
+/// HTTP/2 client connection preface.
+const PREFACE: &[u8] = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n";
+
+/// `application/grpc` as a literal header value.
+const CONTENT_TYPE: &[u8] = b"application/grpc";
+
+/// `application/grpc` after HPACK Huffman coding.
+///
+/// The code is exactly 88 bits long, so it also prefixes the Huffman coding of
+/// `application/grpc+proto` and similar subtypes.
+const CONTENT_TYPE_HUFFMAN: &[u8] = &[
+	0x1d, 0x75, 0xd0, 0x62, 0x0d, 0x26, 0x3d, 0x4c, 0x4d, 0x65, 0x64,
+];
+
+/// HEADERS frame type.
+const FRAME_HEADERS: u8 = 0x01;
+
+/// Probes for gRPC: the HTTP/2 preface followed by a HEADERS frame that
+/// carries `content-type: application/grpc`.
+///
+/// Frames before the first HEADERS frame (SETTINGS, WINDOW_UPDATE) are skipped.
+/// The header block is searched for the content type as a literal or
+/// Huffman-coded value; a complete first HEADERS frame without it is not gRPC.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	if data.len() < PREFACE.len() {
+		if PREFACE.starts_with(data) {
+			return (DetectionStatus::Incomplete, ProtocolVersion::Unknown);
+		}
+		reject!("missing_h2_preface");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	}
+	if !data.starts_with(PREFACE) {
+		reject!("missing_h2_preface");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	}
+
+	let mut pos = PREFACE.len();
+	while data.len() >= pos + 9 {
+		let length = u32::from_be_bytes([0, data[pos], data[pos + 1], data[pos + 2]]) as usize;
+		let start = pos + 9;
+		let end = start + length;
+		if data[pos + 3] == FRAME_HEADERS {
+			let block = &data[start..end.min(data.len())];
+			if contains(block, CONTENT_TYPE) || contains(block, CONTENT_TYPE_HUFFMAN) {
+				return (DetectionStatus::Match, ProtocolVersion::Unknown);
+			}
+			if end <= data.len() {
+				reject!("headers_without_grpc_content_type");
+				return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+			}
+			return (DetectionStatus::Incomplete, ProtocolVersion::Unknown);
+		}
+		pos = end;
+	}
+
+	(DetectionStatus::Incomplete, ProtocolVersion::Unknown)
+}
+
+/// Returns whether `needle` occurs anywhere in `haystack`.
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+	haystack
+		.windows(needle.len())
+		.any(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Writes the preface, an empty SETTINGS frame, then a HEADERS frame
+	/// holding `block` into `out`, returning the length written.
+	fn request(block: &[u8], out: &mut [u8; 96]) -> usize {
+		let settings = [0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00];
+		let len = u8::try_from(block.len()).unwrap();
+		let headers = [0x00, 0x00, len, 0x01, 0x04, 0x00, 0x00, 0x00, 0x01];
+		let mut pos = 0;
+		for part in [PREFACE, &settings, &headers, block] {
+			out[pos..pos + part.len()].copy_from_slice(part);
+			pos += part.len();
+		}
+		pos
+	}
+
+	#[test]
+	fn test_detect_grpc_literal_content_type() {
+		// :method POST, :scheme http, content-type literal (indexed name 31)
+		let mut buf = [0; 96];
+		let len = request(b"\x83\x86\x5f\x10application/grpc", &mut buf);
+		assert_eq!(
+			probe(&buf[..len]),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+	}
+
+	#[test]
+	fn test_detect_grpc_huffman_content_type() {
+		let mut block = [0x83, 0x86, 0x5f, 0x8b, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+		block[4..].copy_from_slice(CONTENT_TYPE_HUFFMAN);
+		let mut buf = [0; 96];
+		let len = request(&block, &mut buf);
+		assert_eq!(probe(&buf[..len]).0, DetectionStatus::Match);
+	}
+
+	#[test]
+	fn test_incomplete_preface() {
+		assert_eq!(probe(b"PRI * HTTP/2").0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	fn test_incomplete_before_headers() {
+		assert_eq!(probe(PREFACE).0, DetectionStatus::Incomplete);
+		let mut buf = [0; 96];
+		request(b"\x83\x86\x5f\x10application/grpc", &mut buf);
+		assert_eq!(probe(&buf[..45]).0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	fn test_reject_plain_h2_request() {
+		// :method GET, :scheme https, :path /
+		let mut buf = [0; 96];
+		let len = request(&[0x82, 0x87, 0x84], &mut buf);
+		assert_eq!(probe(&buf[..len]).0, DetectionStatus::NoMatch);
+	}
+
+	#[test]
+	fn test_reject_http1() {
+		assert_eq!(
+			probe(b"POST /helloworld.Greeter/SayHello HTTP/1.1\r\n").0,
+			DetectionStatus::NoMatch
+		);
+	}
+}
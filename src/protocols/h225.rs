@@ -0,0 +1,138 @@
+/* src/protocols/h225.rs */
+use crate::{DetectionStatus, ProtocolVersion};
+
+/// TPKT version (RFC 1006).
+const TPKT_VERSION: u8 = 0x03;
+
+/// Q.931 protocol discriminator.
+const Q931_DISCRIMINATOR: u8 = 0x08;
+
+/// Call reference length; H.225.0 always uses two octets.
+const CALL_REFERENCE_LEN: u8 = 0x02;
+
+/// Q.931 SETUP message type.
+const SETUP: u8 = 0x05;
+
+/// Length of the TPKT header and the Q.931 header up to the message type.
+const HEADER_LEN: usize = 9;
+
+/// User-user information element, which carries the H.225.0 PDU.
+const USER_USER_IE: u8 = 0x7E;
+
+/// User-user protocol discriminator for X.208/X.209 coded user information.
+const USER_USER_DISCRIMINATOR: u8 = 0x05;
+
+/// Shortest TPKT packet: the headers and a user-user element with one byte.
+const MIN_TPKT_LEN: usize = HEADER_LEN + 4;
+
+/// Probes for an H.225.0 call signaling SETUP (H.323).
+///
+/// Expects a TPKT header, a Q.931 header with a two-octet call reference
+/// and the SETUP message type, then walks the information elements up to
+/// the user-user element, which must carry X.208/X.209 coded information.
+/// The user-user element uses the two-octet length of H.225.0.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	(probe_status(data), ProtocolVersion::Unknown)
+}
+
+/// Checks the TPKT and Q.931 headers, then the information elements.
+#[inline(always)]
+fn probe_status(data: &[u8]) -> DetectionStatus {
+	let Some(header) = data.first_chunk::<HEADER_LEN>() else {
+		return DetectionStatus::Incomplete;
+	};
+	if header[0] != TPKT_VERSION || header[1] != 0x00 {
+		reject!("not_tpkt");
+		return DetectionStatus::NoMatch;
+	}
+	let tpkt_len = usize::from(u16::from_be_bytes([header[2], header[3]]));
+	if tpkt_len < MIN_TPKT_LEN {
+		reject!("tpkt_too_short");
+		return DetectionStatus::NoMatch;
+	}
+	if header[4] != Q931_DISCRIMINATOR || header[5] != CALL_REFERENCE_LEN || header[8] != SETUP {
+		reject!("not_q931_setup");
+		return DetectionStatus::NoMatch;
+	}
+
+	let mut pos = HEADER_LEN;
+	while pos < tpkt_len {
+		let Some(&id) = data.get(pos) else {
+			return DetectionStatus::Incomplete;
+		};
+		// Single-octet elements have the high bit set.
+		if id & 0x80 != 0 {
+			pos += 1;
+			continue;
+		}
+		if id == USER_USER_IE {
+			let Some(&[len_hi, len_lo, discriminator]) =
+				data.get(pos + 1..).and_then(<[u8]>::first_chunk::<3>)
+			else {
+				return DetectionStatus::Incomplete;
+			};
+			let len = usize::from(u16::from_be_bytes([len_hi, len_lo]));
+			if len == 0 || pos + 3 + len > tpkt_len || discriminator != USER_USER_DISCRIMINATOR {
+				reject!("invalid_user_user");
+				return DetectionStatus::NoMatch;
+			}
+			return DetectionStatus::Match;
+		}
+		let Some(&len) = data.get(pos + 1) else {
+			return DetectionStatus::Incomplete;
+		};
+		pos += 2 + usize::from(len);
+	}
+	reject!("no_user_user");
+	DetectionStatus::NoMatch
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// SETUP with bearer capability, display, called party number and a
+	/// user-user element.
+	const SETUP_PDU: &[u8] = b"\x03\x00\x00\x28\x08\x02\x00\x01\x05\x04\x03\x88\x93\xA5\
+		\x28\x05alice\x70\x04\x81123\x7E\x00\x0A\x05\x20\xB8\x06\x00\x08\x91\x4A\x00\x04";
+
+	#[test]
+	fn test_detect_setup() {
+		assert_eq!(
+			probe(SETUP_PDU),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+		// Sending complete (single octet) before the user-user element.
+		let setup = b"\x03\x00\x00\x12\x08\x02\x80\x07\x05\xA1\x7E\x00\x05\x05\x20\xB8\x06\x00";
+		assert_eq!(probe(setup).0, DetectionStatus::Match);
+	}
+
+	#[test]
+	fn test_incomplete_setup() {
+		assert_eq!(probe(&SETUP_PDU[..6]).0, DetectionStatus::Incomplete);
+		assert_eq!(probe(&SETUP_PDU[..20]).0, DetectionStatus::Incomplete);
+		assert_eq!(probe(&SETUP_PDU[..29]).0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	fn test_reject_other_messages() {
+		let mut pdu = [0u8; 40];
+		// CONNECT, then another discriminator, then a non-ASN.1 user-user.
+		pdu.copy_from_slice(SETUP_PDU);
+		pdu[8] = 0x07;
+		assert_eq!(probe(&pdu).0, DetectionStatus::NoMatch);
+		pdu.copy_from_slice(SETUP_PDU);
+		pdu[4] = 0x09;
+		assert_eq!(probe(&pdu).0, DetectionStatus::NoMatch);
+		pdu.copy_from_slice(SETUP_PDU);
+		pdu[30] = 0x04;
+		assert_eq!(probe(&pdu).0, DetectionStatus::NoMatch);
+		// Packet ending without a user-user element.
+		let setup = b"\x03\x00\x00\x0E\x08\x02\x00\x01\x05\x04\x03\x88\x93\xA5";
+		assert_eq!(probe(setup).0, DetectionStatus::NoMatch);
+		// RDP connection request over TPKT.
+		let rdp = b"\x03\x00\x00\x13\x0E\xE0\x00\x00\x00\x00\x00\x01\x00\x08\x00\x03\x00\x00\x00";
+		assert_eq!(probe(rdp).0, DetectionStatus::NoMatch);
+	}
+}
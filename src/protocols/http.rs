@@ -18,6 +18,11 @@ pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
 		}
 	}
 
+	// HTTP/2 server preface (SETTINGS frame)
+	if data[0] == 0x00 {
+		return probe_h2_settings(data);
+	}
+
 	// HTTP/1.x Methods
 	let is_method = matches!(data[0], b'G' | b'P' | b'D' | b'H' | b'O' | b'C' | b'T');
 	if is_method {
@@ -48,9 +53,42 @@ pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
 		}
 	}
 
+	reject!("no_request_line");
 	(DetectionStatus::NoMatch, ProtocolVersion::Unknown)
 }
 
+/// Probes for the SETTINGS frame that opens the server side of an HTTP/2 connection.
+///
+/// Expects a frame header with a length of at most 16384 in whole 6-byte
+/// settings, type 0x04, no flags (or ACK with an empty payload), and stream 0.
+#[inline(always)]
+fn probe_h2_settings(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	let length = u32::from_be_bytes([0, data[0], data[1], data[2]]);
+	if length > 16384 || !length.is_multiple_of(6) {
+		reject!("h2_settings_length_invalid");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	}
+	if data[3] != 0x04 {
+		reject!("h2_not_settings_frame");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	}
+	if let Some(&flags) = data.get(4)
+		&& flags != 0x00
+		&& !(flags == 0x01 && length == 0)
+	{
+		reject!("h2_settings_flags_invalid");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	}
+	if data.iter().take(9).skip(5).any(|&b| b != 0) {
+		reject!("h2_settings_stream_nonzero");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	}
+	if data.len() < 9 {
+		return (DetectionStatus::Incomplete, ProtocolVersion::Unknown);
+	}
+	(DetectionStatus::Match, ProtocolVersion::Http("2.0"))
+}
+
 /// Helper to check for common HTTP methods.
 #[inline(always)]
 fn is_likely_http_method(data: &[u8]) -> bool {
@@ -78,3 +116,58 @@ fn find_sub(data: &[u8], sub: &[u8]) -> Option<usize> {
 	}
 	None
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_detect_h2_server_settings() {
+		let data = [
+			0x00, 0x00, 0x12, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, // header, 3 settings
+			0x00, 0x03, 0x00, 0x00, 0x00, 0x64, // MAX_CONCURRENT_STREAMS
+		];
+		assert_eq!(
+			probe(&data),
+			(DetectionStatus::Match, ProtocolVersion::Http("2.0"))
+		);
+	}
+
+	#[test]
+	fn test_detect_h2_settings_ack() {
+		let data = [0x00, 0x00, 0x00, 0x04, 0x01, 0x00, 0x00, 0x00, 0x00];
+		assert_eq!(probe(&data).0, DetectionStatus::Match);
+	}
+
+	#[test]
+	fn test_incomplete_h2_settings_header() {
+		assert_eq!(
+			probe(&[0x00, 0x00, 0x06, 0x04, 0x00]).0,
+			DetectionStatus::Incomplete
+		);
+	}
+
+	#[test]
+	fn test_reject_h2_settings_on_stream() {
+		let data = [0x00, 0x00, 0x06, 0x04, 0x00, 0x00, 0x00, 0x00, 0x01];
+		assert_eq!(probe(&data).0, DetectionStatus::NoMatch);
+	}
+
+	#[test]
+	fn test_reject_h2_settings_bad_length() {
+		let data = [0x00, 0x00, 0x07, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00];
+		assert_eq!(probe(&data).0, DetectionStatus::NoMatch);
+	}
+
+	#[test]
+	fn test_reject_h2_non_settings_frame() {
+		let data = [0x00, 0x00, 0x08, 0x06, 0x00, 0x00, 0x00, 0x00, 0x00];
+		assert_eq!(probe(&data).0, DetectionStatus::NoMatch);
+	}
+
+	#[test]
+	fn test_reject_h2_ack_with_payload() {
+		let data = [0x00, 0x00, 0x06, 0x04, 0x01, 0x00, 0x00, 0x00, 0x00];
+		assert_eq!(probe(&data).0, DetectionStatus::NoMatch);
+	}
+}
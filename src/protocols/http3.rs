@@ -0,0 +1,420 @@
+/* src/protocols/http3.rs */
+use crate::{
+	DetectionStatus, ProtocolVersion,
+	crypto::{Aes128, hkdf_expand_label, hkdf_extract},
+};
+
+/// QUIC version 1 (RFC 9000).
+const QUIC_V1: u32 = 0x0000_0001;
+
+/// QUIC version 2 (RFC 9369).
+const QUIC_V2: u32 = 0x6b33_43cf;
+
+/// Initial salt of QUIC version 1 (RFC 9001, section 5.2).
+const V1_INITIAL_SALT: [u8; 20] = [
+	0x38, 0x76, 0x2c, 0xf7, 0xf5, 0x59, 0x34, 0xb3, 0x4d, 0x17, 0x9a, 0xe6, 0xa4, 0xc8, 0x0c, 0xad,
+	0xcc, 0xbb, 0x7f, 0x0a,
+];
+
+/// Initial salt of QUIC version 2 (RFC 9369, section 3.3.1).
+const V2_INITIAL_SALT: [u8; 20] = [
+	0x0d, 0xed, 0xe3, 0xde, 0xf7, 0x00, 0xa6, 0xdb, 0x81, 0x93, 0x81, 0xbe, 0x6e, 0x26, 0x9d, 0xcb,
+	0xf9, 0xbd, 0x2e, 0xd9,
+];
+
+/// Longest connection ID allowed by QUIC version 1 and 2.
+const MAX_CID_LEN: usize = 20;
+
+/// Length of the AES-GCM authentication tag.
+const TAG_LEN: usize = 16;
+
+/// Largest Initial payload decrypted; client Initials fill one datagram.
+const MAX_PAYLOAD_LEN: usize = 1500;
+
+/// Most CRYPTO frames tracked while reassembling the `ClientHello`.
+const MAX_CRYPTO_FRAMES: usize = 32;
+
+/// TLS extension type of ALPN.
+const EXTENSION_ALPN: u16 = 16;
+
+/// Key derivation parameters of a QUIC version.
+struct InitialKeys {
+	/// Salt for HKDF-Extract over the Destination Connection ID.
+	salt: &'static [u8; 20],
+	/// Label of the packet protection key.
+	key_label: &'static [u8],
+	/// Label of the packet protection IV.
+	iv_label: &'static [u8],
+	/// Label of the header protection key.
+	hp_label: &'static [u8],
+	/// Long header packet type of Initial packets.
+	initial_type: u8,
+}
+
+/// Key derivation of QUIC version 1.
+const V1_KEYS: InitialKeys = InitialKeys {
+	salt: &V1_INITIAL_SALT,
+	key_label: b"quic key",
+	iv_label: b"quic iv",
+	hp_label: b"quic hp",
+	initial_type: 0,
+};
+
+/// Key derivation of QUIC version 2, which also renumbers the packet types.
+const V2_KEYS: InitialKeys = InitialKeys {
+	salt: &V2_INITIAL_SALT,
+	key_label: b"quicv2 key",
+	iv_label: b"quicv2 iv",
+	hp_label: b"quicv2 hp",
+	initial_type: 1,
+};
+
+/// Probes for HTTP/3 in a QUIC client Initial packet.
+///
+/// Derives the Initial keys from the Destination Connection ID (QUIC version
+/// 1 or 2), removes header protection, decrypts the payload in counter mode
+/// without verifying the tag, reassembles the CRYPTO frames, and matches when
+/// the `ClientHello` offers `h3` or a draft `h3-*` token in ALPN, reporting
+/// HTTP version "3". Other QUIC
+/// traffic, and Initials whose ALPN extension falls outside the first packet,
+/// do not match. Client Initials are at least 1200 bytes, so raise
+/// `max_inspect_bytes` to cover the whole datagram.
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	match client_hello_alpn(data) {
+		Ok(true) => (DetectionStatus::Match, ProtocolVersion::Http("3")),
+		Ok(false) => {
+			reject!("no_h3_alpn");
+			(DetectionStatus::NoMatch, ProtocolVersion::Unknown)
+		}
+		Err(status) => (status, ProtocolVersion::Unknown),
+	}
+}
+
+/// Decrypts a client Initial and checks its `ClientHello` for an HTTP/3 ALPN.
+fn client_hello_alpn(data: &[u8]) -> Result<bool, DetectionStatus> {
+	let Some(&[first, v0, v1, v2, v3]) = data.first_chunk::<5>() else {
+		return Err(DetectionStatus::Incomplete);
+	};
+	if first & 0xC0 != 0xC0 {
+		reject!("not_long_header");
+		return Err(DetectionStatus::NoMatch);
+	}
+	let keys = match u32::from_be_bytes([v0, v1, v2, v3]) {
+		QUIC_V1 => &V1_KEYS,
+		QUIC_V2 => &V2_KEYS,
+		_ => {
+			reject!("unsupported_version");
+			return Err(DetectionStatus::NoMatch);
+		}
+	};
+	if (first >> 4) & 0x03 != keys.initial_type {
+		reject!("not_initial");
+		return Err(DetectionStatus::NoMatch);
+	}
+
+	let (dcid, pos) = connection_id(data, 5)?;
+	let (_, pos) = connection_id(data, pos)?;
+	let (token_len, pos) = varint(data, pos).ok_or(DetectionStatus::Incomplete)?;
+	let pos = usize::try_from(token_len)
+		.ok()
+		.and_then(|len| pos.checked_add(len))
+		.ok_or(DetectionStatus::NoMatch)?;
+	let (length, pn_offset) = varint(data, pos).ok_or(DetectionStatus::Incomplete)?;
+	let Some(end) = usize::try_from(length)
+		.ok()
+		.filter(|&len| (4 + TAG_LEN..=MAX_PAYLOAD_LEN).contains(&len))
+		.map(|len| pn_offset + len)
+	else {
+		reject!("invalid_initial_length");
+		return Err(DetectionStatus::NoMatch);
+	};
+	let Some(packet) = data.get(..end) else {
+		return Err(DetectionStatus::Incomplete);
+	};
+
+	let mut secret = [0u8; 32];
+	hkdf_expand_label(&hkdf_extract(keys.salt, dcid), b"client in", &mut secret);
+	let mut key = [0u8; 16];
+	let mut iv = [0u8; 12];
+	let mut hp = [0u8; 16];
+	hkdf_expand_label(&secret, keys.key_label, &mut key);
+	hkdf_expand_label(&secret, keys.iv_label, &mut iv);
+	hkdf_expand_label(&secret, keys.hp_label, &mut hp);
+
+	// Header protection samples 16 bytes starting 4 bytes past the packet
+	// number offset, whatever the packet number length.
+	let mut mask = [0u8; 16];
+	mask.copy_from_slice(&packet[pn_offset + 4..pn_offset + 4 + 16]);
+	Aes128::new(&hp).encrypt_block(&mut mask);
+	let first = first ^ (mask[0] & 0x0F);
+	if first & 0x0C != 0 {
+		reject!("reserved_bits_set");
+		return Err(DetectionStatus::NoMatch);
+	}
+	let pn_len = usize::from(first & 0x03) + 1;
+	let packet_number = packet[pn_offset..pn_offset + pn_len]
+		.iter()
+		.zip(&mask[1..])
+		.fold(0u64, |acc, (&b, &m)| (acc << 8) | u64::from(b ^ m));
+
+	let ciphertext = &packet[pn_offset + pn_len..packet.len() - TAG_LEN];
+	let mut payload = [0u8; MAX_PAYLOAD_LEN];
+	let payload = &mut payload[..ciphertext.len()];
+	payload.copy_from_slice(ciphertext);
+	for (byte, pn) in iv[4..].iter_mut().zip(packet_number.to_be_bytes()) {
+		*byte ^= pn;
+	}
+	decrypt_ctr(&Aes128::new(&key), &iv, payload);
+
+	let mut hello = [0u8; MAX_PAYLOAD_LEN];
+	let hello_len = reassemble_crypto(payload, &mut hello)?;
+	Ok(offers_h3(&hello[..hello_len]))
+}
+
+/// Reads a length-prefixed connection ID at `pos`.
+fn connection_id(data: &[u8], pos: usize) -> Result<(&[u8], usize), DetectionStatus> {
+	let len = usize::from(*data.get(pos).ok_or(DetectionStatus::Incomplete)?);
+	if len > MAX_CID_LEN {
+		reject!("connection_id_too_long");
+		return Err(DetectionStatus::NoMatch);
+	}
+	let cid = data
+		.get(pos + 1..pos + 1 + len)
+		.ok_or(DetectionStatus::Incomplete)?;
+	Ok((cid, pos + 1 + len))
+}
+
+/// Decrypts AES-GCM ciphertext in place: counter mode from block 2 of `iv`.
+fn decrypt_ctr(cipher: &Aes128, iv: &[u8; 12], data: &mut [u8]) {
+	let mut counter_block = [0u8; 16];
+	counter_block[..12].copy_from_slice(iv);
+	for (counter, chunk) in (2u32..).zip(data.chunks_mut(16)) {
+		counter_block[12..].copy_from_slice(&counter.to_be_bytes());
+		let mut keystream = counter_block;
+		cipher.encrypt_block(&mut keystream);
+		for (byte, key) in chunk.iter_mut().zip(keystream) {
+			*byte ^= key;
+		}
+	}
+}
+
+/// Copies the contiguous prefix of CRYPTO frame data into `out`, returning its
+/// length. Frames may arrive in any order.
+fn reassemble_crypto(payload: &[u8], out: &mut [u8]) -> Result<usize, DetectionStatus> {
+	let mut frames = [(0usize, &[][..]); MAX_CRYPTO_FRAMES];
+	let mut count = 0;
+	let mut pos = 0;
+	while pos < payload.len() {
+		let (frame_type, next) = varint(payload, pos).ok_or(DetectionStatus::NoMatch)?;
+		pos = match frame_type {
+			// PADDING and PING.
+			0x00 | 0x01 => next,
+			// ACK, with ECN counts for 0x03.
+			0x02 | 0x03 => skip_ack(payload, next, frame_type == 0x03).ok_or(DetectionStatus::NoMatch)?,
+			0x06 => {
+				let (offset, data, end) = crypto_frame(payload, next).ok_or(DetectionStatus::NoMatch)?;
+				if count < MAX_CRYPTO_FRAMES {
+					frames[count] = (offset, data);
+					count += 1;
+				}
+				end
+			}
+			// CONNECTION_CLOSE carries no handshake.
+			0x1C => break,
+			_ => {
+				reject!("invalid_initial_frame");
+				return Err(DetectionStatus::NoMatch);
+			}
+		};
+	}
+
+	let mut filled = 0;
+	let mut progress = true;
+	while progress {
+		progress = false;
+		for &(offset, data) in &frames[..count] {
+			if offset <= filled && filled < offset + data.len() {
+				let data = &data[filled - offset..];
+				let take = data.len().min(out.len() - filled);
+				out[filled..filled + take].copy_from_slice(&data[..take]);
+				filled += take;
+				progress |= take > 0;
+			}
+		}
+	}
+	if filled == 0 {
+		reject!("no_crypto_data");
+		return Err(DetectionStatus::NoMatch);
+	}
+	Ok(filled)
+}
+
+/// Reads a QUIC variable-length integer at `pos`, returning it and the offset
+/// after it.
+fn varint(data: &[u8], pos: usize) -> Option<(u64, usize)> {
+	let first = *data.get(pos)?;
+	let len = 1 << (first >> 6);
+	let bytes = data.get(pos..pos + len)?;
+	let value = bytes[1..]
+		.iter()
+		.fold(u64::from(first & 0x3F), |acc, &b| (acc << 8) | u64::from(b));
+	Some((value, pos + len))
+}
+
+/// Reads a QUIC variable-length integer that must fit in `usize`.
+fn varint_usize(data: &[u8], pos: usize) -> Option<(usize, usize)> {
+	let (value, next) = varint(data, pos)?;
+	Some((usize::try_from(value).ok()?, next))
+}
+
+/// Skips the body of an ACK frame at `pos`, returning the offset after it.
+fn skip_ack(data: &[u8], pos: usize, ecn: bool) -> Option<usize> {
+	// Largest acknowledged, ACK delay, range count, first range.
+	let (_, pos) = varint(data, pos)?;
+	let (_, pos) = varint(data, pos)?;
+	let (ranges, pos) = varint_usize(data, pos)?;
+	let (_, mut pos) = varint(data, pos)?;
+	// Each further range is a gap and a length; a range takes at least two
+	// bytes, so the count is bounded by the payload.
+	if ranges > data.len() {
+		return None;
+	}
+	for _ in 0..ranges * 2 + if ecn { 3 } else { 0 } {
+		pos = varint(data, pos)?.1;
+	}
+	Some(pos)
+}
+
+/// Reads the body of a CRYPTO frame at `pos`: the stream offset, the data,
+/// and the offset after the frame.
+fn crypto_frame(data: &[u8], pos: usize) -> Option<(usize, &[u8], usize)> {
+	let (offset, pos) = varint_usize(data, pos)?;
+	let (len, pos) = varint_usize(data, pos)?;
+	let end = pos.checked_add(len)?;
+	offset.checked_add(len)?;
+	Some((offset, data.get(pos..end)?, end))
+}
+
+/// Walks a (possibly truncated) `ClientHello` and checks its ALPN extension
+/// for `h3` or a draft `h3-*` token.
+fn offers_h3(hello: &[u8]) -> bool {
+	if hello.first() != Some(&0x01) {
+		reject!("not_client_hello");
+		return false;
+	}
+	// Handshake header, legacy version and random.
+	let mut pos = 4 + 2 + 32;
+	// Session ID, cipher suites, compression methods.
+	for len_len in [1, 2, 1] {
+		let Some(len) = read_len(hello, pos, len_len) else {
+			return false;
+		};
+		pos += len_len + len;
+	}
+	pos += 2;
+
+	while let (Some(ext_type), Some(ext_len)) = (read_len(hello, pos, 2), read_len(hello, pos + 2, 2))
+	{
+		let Some(body) = hello.get(pos + 4..pos + 4 + ext_len) else {
+			return false;
+		};
+		if ext_type == usize::from(EXTENSION_ALPN) {
+			return body.get(2..).is_some_and(|mut list| {
+				while let Some((&len, rest)) = list.split_first() {
+					let Some(protocol) = rest.get(..usize::from(len)) else {
+						return false;
+					};
+					if protocol == b"h3" || protocol.starts_with(b"h3-") {
+						return true;
+					}
+					list = &rest[usize::from(len)..];
+				}
+				false
+			});
+		}
+		pos += 4 + ext_len;
+	}
+	false
+}
+
+/// Reads a big-endian length of `len_len` bytes at `pos`.
+fn read_len(data: &[u8], pos: usize, len_len: usize) -> Option<usize> {
+	Some(
+		data
+			.get(pos..pos + len_len)?
+			.iter()
+			.fold(0, |acc, &b| (acc << 8) | usize::from(b)),
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// QUIC v1 client Initial (DCID `8394c8f03e515708`) offering ALPN `h3`.
+	const V1_H3: &str = "ca00000001088394c8f03e5157080000405fb1c97f7ad1b1c943d7689f6eec1139bb497629b7e704e97586e0d9cfff68a3d9a0356b10352466c1894137eda5ca1c2608c4c61f15b20f84c21578b149168cb643a6b75d4fa8243c6983db885a738fb1408a140ce2f192d3866096266b4b0f";
+
+	/// QUIC v2 client Initial offering `h2` and `h3-29`, with the `ClientHello`
+	/// split over two CRYPTO frames in reverse order around PING and PADDING.
+	const V2_H3_29: &str = "d06b3343cf088394c8f03e51570800004068994c4c2bf8e025db9ac35b6fac9a2be23bd6915a95bb85a4872125e299642c654008037a7321f8bc9b60a098d665ada339c74549a6cf982c3fb08dcc1fda56872907170f3b9500a56c1e4c9501b3361c395a181f02bf0783e2011633e42c3c4ee6794c7e955db818";
+
+	/// QUIC v1 client Initial offering only `h2` and `http/1.1`.
+	const V1_H2: &str = "cb00000001088394c8f03e5157080000406435204a5dd1b1c934d7689f11ec1139bb497629b7e704e97586e0d9cfff68a3d9a0356b10352466c1894137eda5ca1c2608c4c61f15b20684c21578b149168cb643adb7524fa8253401f7aff87542a186529ec4de01fbde064ae2c2a1c2e576f9498db2f3";
+
+	/// Decodes a hex packet into a buffer, returning it and its length.
+	fn packet(hex: &str) -> ([u8; 256], usize) {
+		let mut out = [0u8; 256];
+		for (byte, pair) in out.iter_mut().zip(hex.as_bytes().chunks_exact(2)) {
+			*byte = u8::from_str_radix(core::str::from_utf8(pair).unwrap(), 16).unwrap();
+		}
+		(out, hex.len() / 2)
+	}
+
+	#[test]
+	fn test_detect_h3_initial() {
+		let (data, len) = packet(V1_H3);
+		assert_eq!(
+			probe(&data[..len]),
+			(DetectionStatus::Match, ProtocolVersion::Http("3"))
+		);
+		// Trailing coalesced packets or padding are ignored.
+		assert_eq!(probe(&data).0, DetectionStatus::Match);
+	}
+
+	#[test]
+	fn test_detect_v2_reordered_crypto_frames() {
+		let (data, len) = packet(V2_H3_29);
+		assert_eq!(
+			probe(&data[..len]),
+			(DetectionStatus::Match, ProtocolVersion::Http("3"))
+		);
+	}
+
+	#[test]
+	fn test_incomplete_initial() {
+		let (data, len) = packet(V1_H3);
+		assert_eq!(probe(&data[..4]).0, DetectionStatus::Incomplete);
+		assert_eq!(probe(&data[..64]).0, DetectionStatus::Incomplete);
+		assert_eq!(probe(&data[..len - 1]).0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	fn test_reject_other_quic() {
+		let (data, len) = packet(V1_H2);
+		assert_eq!(probe(&data[..len]).0, DetectionStatus::NoMatch);
+
+		// A different DCID derives different keys.
+		let (mut data, len) = packet(V1_H3);
+		data[6] ^= 0x01;
+		assert_eq!(probe(&data[..len]).0, DetectionStatus::NoMatch);
+
+		// Handshake packet type, unknown version, short header.
+		let (mut data, len) = packet(V1_H3);
+		data[0] = 0xE0;
+		assert_eq!(probe(&data[..len]).0, DetectionStatus::NoMatch);
+		let (mut data, len) = packet(V1_H3);
+		data[4] = 0x02;
+		assert_eq!(probe(&data[..len]).0, DetectionStatus::NoMatch);
+		assert_eq!(probe(&[0x40; 32]).0, DetectionStatus::NoMatch);
+	}
+}
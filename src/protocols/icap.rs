@@ -0,0 +1,149 @@
+/* src/protocols/icap.rs */
+use crate::{DetectionStatus, ProtocolVersion};
+
+/// ICAP request methods (RFC 3507).
+const METHODS: [&[u8]; 3] = [b"REQMOD ", b"RESPMOD ", b"OPTIONS "];
+
+/// Scheme every ICAP request URI starts with.
+const SCHEME: &[u8] = b"icap://";
+
+/// Version that starts a status line.
+const VERSION: &[u8] = b"ICAP/1.0";
+
+/// Version that ends a request line.
+const REQUEST_SUFFIX: &[u8] = b" ICAP/1.0";
+
+/// Longest request line searched for the version.
+const MAX_LINE_LEN: usize = 1024;
+
+/// Probes for ICAP (RFC 3507).
+///
+/// Recognizes `REQMOD`, `RESPMOD` and `OPTIONS` request lines with an
+/// `icap://` URI and ending in ` ICAP/1.0`, and `ICAP/1.0` status lines with
+/// a three-digit status code.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	let status = if data.first() == Some(&b'I') {
+		probe_status_line(data)
+	} else {
+		probe_request_line(data)
+	};
+	(status, ProtocolVersion::Unknown)
+}
+
+/// Checks `ICAP/1.0 ` and the status code.
+#[inline(always)]
+fn probe_status_line(data: &[u8]) -> DetectionStatus {
+	let len = data.len().min(VERSION.len());
+	if data[..len] != VERSION[..len] {
+		reject!("not_icap_status_line");
+		return DetectionStatus::NoMatch;
+	}
+	let Some(status) = data.get(VERSION.len()..VERSION.len() + 5) else {
+		return DetectionStatus::Incomplete;
+	};
+	match status {
+		[b' ', a, b, c, b' ' | b'\r' | b'\n'] if [a, b, c].iter().all(|d| d.is_ascii_digit()) => {
+			DetectionStatus::Match
+		}
+		_ => {
+			reject!("invalid_status_code");
+			DetectionStatus::NoMatch
+		}
+	}
+}
+
+/// Checks the method, the URI scheme, and the version ending the line.
+#[inline(always)]
+fn probe_request_line(data: &[u8]) -> DetectionStatus {
+	let Some(method) = METHODS.iter().find(|method| {
+		let len = data.len().min(method.len());
+		data[..len] == method[..len]
+	}) else {
+		reject!("unknown_method");
+		return DetectionStatus::NoMatch;
+	};
+	let Some(uri) = data.get(method.len()..) else {
+		return DetectionStatus::Incomplete;
+	};
+	let len = uri.len().min(SCHEME.len());
+	if !uri[..len].eq_ignore_ascii_case(&SCHEME[..len]) {
+		reject!("not_icap_uri");
+		return DetectionStatus::NoMatch;
+	}
+
+	let line_end = data.iter().position(|&b| b == b'\r' || b == b'\n');
+	let line = &data[..line_end.unwrap_or(data.len())];
+	if !line.iter().all(|b| (0x21..=0x7E).contains(b) || *b == b' ') {
+		reject!("non_printable_byte");
+		return DetectionStatus::NoMatch;
+	}
+	match line_end {
+		Some(_) if line.ends_with(REQUEST_SUFFIX) => DetectionStatus::Match,
+		None if data.len() < MAX_LINE_LEN => DetectionStatus::Incomplete,
+		_ => {
+			reject!("no_icap_version");
+			DetectionStatus::NoMatch
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_detect_requests() {
+		assert_eq!(
+			probe(b"REQMOD icap://icap-server.net/server?arg=87 ICAP/1.0\r\nHost: icap-server.net\r\n"),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+		assert_eq!(
+			probe(b"RESPMOD icap://icap.example.org/satisf ICAP/1.0\r\n").0,
+			DetectionStatus::Match
+		);
+		assert_eq!(
+			probe(b"OPTIONS icap://icap.server.net/sample-service ICAP/1.0\n").0,
+			DetectionStatus::Match
+		);
+	}
+
+	#[test]
+	fn test_detect_status_line() {
+		assert_eq!(probe(b"ICAP/1.0 200 OK\r\n").0, DetectionStatus::Match);
+		assert_eq!(
+			probe(b"ICAP/1.0 204 No Content\r\n").0,
+			DetectionStatus::Match
+		);
+	}
+
+	#[test]
+	fn test_incomplete_line() {
+		assert_eq!(probe(b"REQ").0, DetectionStatus::Incomplete);
+		assert_eq!(
+			probe(b"REQMOD icap://server/filter").0,
+			DetectionStatus::Incomplete
+		);
+		assert_eq!(probe(b"ICAP/1.0 2").0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	fn test_reject_other_protocols() {
+		// HTTP and RTSP requests sharing the OPTIONS method.
+		assert_eq!(probe(b"OPTIONS * HTTP/1.1\r\n").0, DetectionStatus::NoMatch);
+		assert_eq!(
+			probe(b"OPTIONS rtsp://example.com/media RTSP/1.0\r\n").0,
+			DetectionStatus::NoMatch
+		);
+		// Missing version, wrong version, bad status code.
+		assert_eq!(
+			probe(b"REQMOD icap://server/filter\r\n").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(
+			probe(b"REQMOD icap://server/filter ICAP/2.0\r\n").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(probe(b"ICAP/1.0 OK\r\n").0, DetectionStatus::NoMatch);
+	}
+}
@@ -0,0 +1,154 @@
+/* src/protocols/ident.rs */
+use crate::{DetectionStatus, ProtocolVersion};
+
+/// Response types after the port pair.
+const RESPONSE_TYPES: [&[u8]; 2] = [b"USERID", b"ERROR"];
+
+/// Most digits in a port number.
+const MAX_PORT_DIGITS: usize = 5;
+
+/// Probes for the Ident protocol (RFC 1413).
+///
+/// Recognizes a query, `<port> , <port>` ending the line, and a response,
+/// the same port pair followed by `: USERID :` or `: ERROR :`. Ports must be
+/// 1 to 65535; spaces around the separators are optional.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	let status = match parse(data) {
+		Ok(()) => DetectionStatus::Match,
+		Err(status) => status,
+	};
+	(status, ProtocolVersion::Unknown)
+}
+
+/// Checks the port pair, then the line ending or the response type.
+#[inline(always)]
+fn parse(data: &[u8]) -> Result<(), DetectionStatus> {
+	let rest = port(data)?;
+	let rest = separator(rest, b',')?;
+	let rest = skip_spaces(port(rest)?);
+	match rest.first() {
+		None => Err(DetectionStatus::Incomplete),
+		Some(b'\n') => Ok(()),
+		Some(b'\r') => match rest.get(1) {
+			None => Err(DetectionStatus::Incomplete),
+			Some(b'\n') => Ok(()),
+			Some(_) => {
+				reject!("bare_carriage_return");
+				Err(DetectionStatus::NoMatch)
+			}
+		},
+		Some(b':') => {
+			let rest = skip_spaces(&rest[1..]);
+			let Some(kind) = RESPONSE_TYPES.iter().find(|kind| {
+				let len = rest.len().min(kind.len());
+				rest[..len] == kind[..len]
+			}) else {
+				reject!("unknown_response_type");
+				return Err(DetectionStatus::NoMatch);
+			};
+			let Some(rest) = rest.get(kind.len()..) else {
+				return Err(DetectionStatus::Incomplete);
+			};
+			separator(rest, b':').map(|_| ())
+		}
+		Some(_) => {
+			reject!("unexpected_after_ports");
+			Err(DetectionStatus::NoMatch)
+		}
+	}
+}
+
+/// Reads a port number from 1 to 65535.
+#[inline(always)]
+fn port(data: &[u8]) -> Result<&[u8], DetectionStatus> {
+	let digits = data.iter().take_while(|b| b.is_ascii_digit()).count();
+	if digits > MAX_PORT_DIGITS {
+		reject!("port_too_long");
+		return Err(DetectionStatus::NoMatch);
+	}
+	if digits == data.len() {
+		return Err(DetectionStatus::Incomplete);
+	}
+	let value = data[..digits]
+		.iter()
+		.fold(0u32, |acc, &b| acc * 10 + u32::from(b - b'0'));
+	if !(1..=u32::from(u16::MAX)).contains(&value) {
+		reject!("invalid_port");
+		return Err(DetectionStatus::NoMatch);
+	}
+	Ok(&data[digits..])
+}
+
+/// Skips optional spaces, then expects `separator` and skips spaces after it.
+#[inline(always)]
+fn separator(data: &[u8], separator: u8) -> Result<&[u8], DetectionStatus> {
+	let rest = skip_spaces(data);
+	match rest.first() {
+		None => Err(DetectionStatus::Incomplete),
+		Some(&b) if b == separator => Ok(skip_spaces(&rest[1..])),
+		Some(_) => {
+			reject!("missing_separator");
+			Err(DetectionStatus::NoMatch)
+		}
+	}
+}
+
+/// Skips spaces and tabs.
+#[inline(always)]
+fn skip_spaces(data: &[u8]) -> &[u8] {
+	let len = data
+		.iter()
+		.take_while(|&&b| b == b' ' || b == b'\t')
+		.count();
+	&data[len..]
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_detect_query() {
+		assert_eq!(
+			probe(b"6193, 23\r\n"),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+		assert_eq!(probe(b"6195 , 25\n").0, DetectionStatus::Match);
+	}
+
+	#[test]
+	fn test_detect_response() {
+		assert_eq!(
+			probe(b"6193, 23 : USERID : UNIX : stjohns\r\n").0,
+			DetectionStatus::Match
+		);
+		assert_eq!(
+			probe(b"6195, 26 : ERROR : NO-USER\r\n").0,
+			DetectionStatus::Match
+		);
+	}
+
+	#[test]
+	fn test_incomplete_line() {
+		assert_eq!(probe(b"6193").0, DetectionStatus::Incomplete);
+		assert_eq!(probe(b"6193, 23").0, DetectionStatus::Incomplete);
+		assert_eq!(probe(b"6193, 23\r").0, DetectionStatus::Incomplete);
+		assert_eq!(probe(b"6193, 23 : USER").0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	fn test_reject_other_payloads() {
+		// Port out of range, zero port, missing comma.
+		assert_eq!(probe(b"70000, 23\r\n").0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"0, 23\r\n").0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"6193 23\r\n").0, DetectionStatus::NoMatch);
+		// Unknown response type and plain text.
+		assert_eq!(
+			probe(b"6193, 23 : OTHER : x\r\n").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(probe(b"1, 2, 3\r\n").0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"USER guest\r\n").0, DetectionStatus::NoMatch);
+	}
+}
@@ -0,0 +1,265 @@
+/* src/protocols/influx.rs */
+use crate::{DetectionStatus, ProtocolVersion};
+
+/// Longest timestamp: nanoseconds since the epoch fit in 19 digits.
+const MAX_TIMESTAMP_DIGITS: usize = 19;
+
+/// Boolean field values the line protocol accepts.
+const BOOLEANS: [&[u8]; 10] = [
+	b"t", b"T", b"true", b"True", b"TRUE", b"f", b"F", b"false", b"False", b"FALSE",
+];
+
+/// Outcome of walking one field value.
+enum Step {
+	/// The value ends just before this offset.
+	Next(usize),
+	/// The value runs past the end of the data.
+	Truncated,
+	/// The value is not a line protocol value.
+	Invalid,
+}
+
+/// Probes for an `InfluxDB` line protocol datagram.
+///
+/// Tokenizes the first line as `measurement[,tag=value...] field=value[,...]
+/// [timestamp]`, honoring backslash escapes in names and quoted string
+/// values. Field values must be floats, `i`/`u`-suffixed integers, booleans,
+/// or strings. The end of the data counts as the end of the line once the
+/// first field value is in view.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	match walk(data) {
+		Ok(()) => (DetectionStatus::Match, ProtocolVersion::Unknown),
+		Err(status) => (status, ProtocolVersion::Unknown),
+	}
+}
+
+/// Walks the first line, rejecting or asking for more data on failure.
+#[inline(always)]
+fn walk(data: &[u8]) -> Result<(), DetectionStatus> {
+	if data.first() == Some(&b'#') {
+		reject!("comment_line");
+		return Err(DetectionStatus::NoMatch);
+	}
+	let mut pos = name(data, 0, b", ")?;
+
+	while data.get(pos) == Some(&b',') {
+		pos = name(data, pos + 1, b",= ")?;
+		expect(data, pos, b'=')?;
+		pos = name(data, pos + 1, b",= ")?;
+	}
+	expect(data, pos, b' ')?;
+	pos += 1;
+
+	let mut fields = 0;
+	loop {
+		pos = name(data, pos, b",= ")?;
+		expect(data, pos, b'=')?;
+		pos = match value(data, pos + 1) {
+			Step::Next(end) => end,
+			Step::Truncated if fields > 0 => return Ok(()),
+			Step::Truncated => return Err(DetectionStatus::Incomplete),
+			Step::Invalid => {
+				reject!("invalid_field_value");
+				return Err(DetectionStatus::NoMatch);
+			}
+		};
+		fields += 1;
+		match data.get(pos) {
+			Some(b',') => pos += 1,
+			Some(b' ') => break,
+			None | Some(b'\n') => return Ok(()),
+			Some(_) => {
+				reject!("unexpected_field_delimiter");
+				return Err(DetectionStatus::NoMatch);
+			}
+		}
+	}
+
+	let timestamp = &data[pos + 1..];
+	let end = timestamp
+		.iter()
+		.position(|&b| b == b'\n')
+		.unwrap_or(timestamp.len());
+	let digits = timestamp[..end]
+		.strip_prefix(b"-")
+		.unwrap_or(&timestamp[..end]);
+	if digits.len() > MAX_TIMESTAMP_DIGITS || !digits.iter().all(u8::is_ascii_digit) {
+		reject!("invalid_timestamp");
+		return Err(DetectionStatus::NoMatch);
+	}
+	Ok(())
+}
+
+/// Walks a non-empty name starting at `pos` up to an unescaped delimiter from
+/// `stops`, returning the delimiter's offset.
+#[inline(always)]
+fn name(data: &[u8], pos: usize, stops: &[u8]) -> Result<usize, DetectionStatus> {
+	let mut i = pos;
+	while let Some(&b) = data.get(i) {
+		if b == b'\\' {
+			i += 2;
+			continue;
+		}
+		if stops.contains(&b) || b == b'\n' {
+			if i == pos {
+				reject!("empty_name");
+				return Err(DetectionStatus::NoMatch);
+			}
+			return Ok(i);
+		}
+		// Control characters, and bytes that never appear in UTF-8.
+		if b < 0x20 || b == 0x7F || matches!(b, 0xC0 | 0xC1 | 0xF5..=0xFF) {
+			reject!("invalid_name_byte");
+			return Err(DetectionStatus::NoMatch);
+		}
+		i += 1;
+	}
+	Err(DetectionStatus::Incomplete)
+}
+
+/// Requires `delimiter` at `pos`.
+#[inline(always)]
+fn expect(data: &[u8], pos: usize, delimiter: u8) -> Result<(), DetectionStatus> {
+	match data.get(pos) {
+		Some(&b) if b == delimiter => Ok(()),
+		Some(_) => {
+			reject!("unexpected_delimiter");
+			Err(DetectionStatus::NoMatch)
+		}
+		None => Err(DetectionStatus::Incomplete),
+	}
+}
+
+/// Walks one field value starting at `pos`.
+///
+/// A string cut off by the end of the data still counts as a value.
+#[inline(always)]
+fn value(data: &[u8], pos: usize) -> Step {
+	match data.get(pos) {
+		None => Step::Truncated,
+		Some(b'"') => {
+			let mut i = pos + 1;
+			while let Some(&b) = data.get(i) {
+				match b {
+					b'\\' => i += 2,
+					b'"' => return Step::Next(i + 1),
+					_ => i += 1,
+				}
+			}
+			Step::Next(data.len())
+		}
+		Some(_) => {
+			let end = data[pos..]
+				.iter()
+				.position(|b| matches!(b, b',' | b' ' | b'\n'))
+				.map_or(data.len(), |i| pos + i);
+			let token = &data[pos..end];
+			if BOOLEANS.contains(&token) || is_number(token) {
+				Step::Next(end)
+			} else if end == data.len() {
+				Step::Truncated
+			} else {
+				Step::Invalid
+			}
+		}
+	}
+}
+
+/// Checks for a float, an `i`-suffixed integer, or a `u`-suffixed unsigned
+/// integer.
+#[inline(always)]
+fn is_number(token: &[u8]) -> bool {
+	let is_digits = |s: &[u8]| !s.is_empty() && s.iter().all(u8::is_ascii_digit);
+	if let Some(int) = token.strip_suffix(b"i") {
+		return is_digits(int.strip_prefix(b"-").unwrap_or(int));
+	}
+	if let Some(uint) = token.strip_suffix(b"u") {
+		return is_digits(uint);
+	}
+
+	let float = token.strip_prefix(b"-").unwrap_or(token);
+	let (mantissa, exponent) = match float.iter().position(|&b| b == b'e' || b == b'E') {
+		Some(i) => (&float[..i], Some(&float[i + 1..])),
+		None => (float, None),
+	};
+	let (whole, fraction) = match mantissa.iter().position(|&b| b == b'.') {
+		Some(i) => (&mantissa[..i], &mantissa[i + 1..]),
+		None => (mantissa, &[][..]),
+	};
+	let digits_only = |s: &[u8]| s.iter().all(u8::is_ascii_digit);
+	whole.len() + fraction.len() > 0
+		&& digits_only(whole)
+		&& digits_only(fraction)
+		&& exponent.is_none_or(|exponent| {
+			is_digits(
+				exponent
+					.strip_prefix(b"-")
+					.or_else(|| exponent.strip_prefix(b"+"))
+					.unwrap_or(exponent),
+			)
+		})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_detect_full_line() {
+		assert_eq!(
+			probe(b"cpu,host=server01,region=us-west usage_idle=98.2,cores=8i 1700000000000000000\n"),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+		assert_eq!(probe(b"mem used=812u").0, DetectionStatus::Match);
+	}
+
+	#[test]
+	fn test_detect_escapes_and_strings() {
+		let data = b"disk\\ io,path=C:\\\\data,label=a\\,b msg=\"hello, \\\"world\\\"\",ok=true";
+		assert_eq!(probe(data).0, DetectionStatus::Match);
+		// A string cut off by the inspect window.
+		assert_eq!(
+			probe(b"log,app=web msg=\"request from 10.0.0.1 to").0,
+			DetectionStatus::Match
+		);
+	}
+
+	#[test]
+	fn test_incomplete_before_first_field() {
+		assert_eq!(
+			probe(b"cpu,host=server01,regi").0,
+			DetectionStatus::Incomplete
+		);
+		assert_eq!(probe(b"cpu,host=a usage").0, DetectionStatus::Incomplete);
+		assert_eq!(probe(b"cpu,host=a usage=").0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	fn test_reject_malformed_lines() {
+		assert_eq!(
+			probe(b"cpu usage=high 1700000000\n").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(probe(b"cpu,host usage=1\n").0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"cpu usage=1 17000x\n").0, DetectionStatus::NoMatch);
+		assert_eq!(
+			probe(b"# comment\ncpu usage=1\n").0,
+			DetectionStatus::NoMatch
+		);
+	}
+
+	#[test]
+	fn test_reject_other_protocols() {
+		assert_eq!(
+			probe(b"servers.cpu.load 0.42 1700000000\n").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(probe(b"GET / HTTP/1.1\r\n").0, DetectionStatus::NoMatch);
+		assert_eq!(
+			probe(b"\x12\x34\x01\x00\x00\x01").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(probe(&[0xFF; 16]).0, DetectionStatus::NoMatch);
+	}
+}
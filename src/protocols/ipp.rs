@@ -0,0 +1,94 @@
+/* src/protocols/ipp.rs */
+use crate::{DetectionStatus, ProtocolVersion};
+
+/// IPP media type, compared case-insensitively.
+const CONTENT_TYPE: &[u8] = b"application/ipp";
+
+/// Probes for IPP: an HTTP/1.x `POST` whose headers carry
+/// `Content-Type: application/ipp`.
+///
+/// The content type usually sits past the default 64-byte window, so a `POST`
+/// whose headers are cut off is reported as incomplete rather than rejected.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	if data.len() < 5 {
+		if b"POST ".starts_with(data) {
+			return (DetectionStatus::Incomplete, ProtocolVersion::Unknown);
+		}
+		reject!("not_post");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	}
+	if !data.starts_with(b"POST ") {
+		reject!("not_post");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	}
+
+	let Some(eol) = data.iter().position(|&b| b == b'\n') else {
+		return (DetectionStatus::Incomplete, ProtocolVersion::Unknown);
+	};
+	if !data[..eol].windows(8).any(|w| w == b" HTTP/1.") {
+		reject!("not_http1_request");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	}
+
+	let headers = &data[eol + 1..];
+	let end = headers
+		.windows(4)
+		.position(|w| w == b"\r\n\r\n")
+		.unwrap_or(headers.len());
+	if headers[..end]
+		.windows(CONTENT_TYPE.len())
+		.any(|w| w.eq_ignore_ascii_case(CONTENT_TYPE))
+	{
+		return (DetectionStatus::Match, ProtocolVersion::Unknown);
+	}
+	if end < headers.len() {
+		reject!("not_ipp_content_type");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	}
+	(DetectionStatus::Incomplete, ProtocolVersion::Unknown)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_detect_ipp_request() {
+		let data = b"POST /ipp/print HTTP/1.1\r\nContent-Type: application/ipp\r\n\r\n\x02\x00";
+		assert_eq!(
+			probe(data),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+		assert_eq!(
+			probe(b"POST /printers/lab HTTP/1.1\r\ncontent-type: Application/IPP\r\n").0,
+			DetectionStatus::Match
+		);
+	}
+
+	#[test]
+	fn test_incomplete_headers() {
+		assert_eq!(probe(b"POS").0, DetectionStatus::Incomplete);
+		assert_eq!(
+			probe(b"POST /ipp/print HTTP/1.1\r\nHost: printer.local:631\r\n").0,
+			DetectionStatus::Incomplete
+		);
+	}
+
+	#[test]
+	fn test_reject_other_content_type() {
+		assert_eq!(
+			probe(b"POST /api HTTP/1.1\r\nContent-Type: application/json\r\n\r\n{}").0,
+			DetectionStatus::NoMatch
+		);
+	}
+
+	#[test]
+	fn test_reject_other_methods() {
+		assert_eq!(
+			probe(b"GET /ipp/print HTTP/1.1\r\n").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(probe(b"POST /x SIP/2.0\r\n").0, DetectionStatus::NoMatch);
+	}
+}
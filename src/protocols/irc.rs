@@ -0,0 +1,225 @@
+/* src/protocols/irc.rs */
+use crate::{DetectionStatus, ProtocolVersion};
+
+/// Commands an IRC session can open with.
+const COMMANDS: [&[u8]; 5] = [b"NICK", b"USER", b"PASS", b"CAP", b"NOTICE"];
+
+/// Server notices sent to clients that have not registered yet.
+const NOTICES: [&[u8]; 2] = [b"NOTICE AUTH ", b"NOTICE * "];
+
+/// Longest nickname accepted.
+const MAX_NICK_LEN: usize = 32;
+
+/// Longest message prefix (server name) accepted.
+const MAX_PREFIX_LEN: usize = 64;
+
+/// Probes for the opening messages of an IRC session.
+///
+/// Recognizes client registration (`NICK`, `USER` with its four parameters,
+/// `CAP LS`, or `PASS` followed by one of those) and server greetings
+/// (`NOTICE AUTH`, `NOTICE *`, or a numeric reply after a server prefix).
+/// Bare `USER` and `PASS` lines are left to FTP and POP3.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	let status = match data.strip_prefix(b":") {
+		Some(message) => server_message(message),
+		None => client_message(data, true),
+	};
+	(status, ProtocolVersion::Unknown)
+}
+
+/// Checks a client registration line, or a server notice without prefix.
+fn client_message(data: &[u8], allow_pass: bool) -> DetectionStatus {
+	let (line, rest) = split_line(data);
+	let Some(space) = line.iter().position(|&b| b == b' ') else {
+		return if rest.is_none() && COMMANDS.iter().any(|command| command.starts_with(line)) {
+			DetectionStatus::Incomplete
+		} else {
+			reject!("unknown_command");
+			DetectionStatus::NoMatch
+		};
+	};
+	let params = &line[space + 1..];
+	match &line[..space] {
+		b"NICK" => nick(params),
+		b"USER" => user(params, rest.is_some()),
+		b"CAP" => cap(params, rest.is_some()),
+		b"PASS" if allow_pass && !params.is_empty() => match rest {
+			None | Some([]) => DetectionStatus::Incomplete,
+			Some(next) => client_message(next, false),
+		},
+		b"NOTICE" => server_command(line),
+		_ => {
+			reject!("unknown_command");
+			DetectionStatus::NoMatch
+		}
+	}
+}
+
+/// Checks the nickname of a `NICK` line.
+fn nick(params: &[u8]) -> DetectionStatus {
+	let nick = params.split(|&b| b == b' ').next().unwrap_or_default();
+	if nick.is_empty() {
+		return DetectionStatus::Incomplete;
+	}
+	let valid = nick.len() <= MAX_NICK_LEN
+		&& !nick[0].is_ascii_digit()
+		&& nick
+			.iter()
+			.all(|&b| b.is_ascii_alphanumeric() || b"[]\\`_^{|}-".contains(&b));
+	if valid {
+		DetectionStatus::Match
+	} else {
+		reject!("invalid_nickname");
+		DetectionStatus::NoMatch
+	}
+}
+
+/// Checks for `USER <user> <mode> <unused> :<realname>`.
+fn user(params: &[u8], complete: bool) -> DetectionStatus {
+	let mut parts = params.splitn(4, |&b| b == b' ');
+	let fields = [parts.next(), parts.next(), parts.next()];
+	let realname = parts.next();
+	let words = fields
+		.iter()
+		.flatten()
+		.all(|word| !word.is_empty() && word.iter().all(u8::is_ascii_graphic));
+	match realname {
+		_ if !words => {
+			reject!("invalid_user_parameters");
+			DetectionStatus::NoMatch
+		}
+		Some([b':', ..]) => DetectionStatus::Match,
+		None | Some([]) if !complete => DetectionStatus::Incomplete,
+		_ => {
+			reject!("no_realname");
+			DetectionStatus::NoMatch
+		}
+	}
+}
+
+/// Checks for `CAP LS`, optionally with a version.
+fn cap(params: &[u8], complete: bool) -> DetectionStatus {
+	match params {
+		[b'L', b'S'] | [b'L', b'S', b' ', ..] => DetectionStatus::Match,
+		[] | [b'L'] if !complete => DetectionStatus::Incomplete,
+		_ => {
+			reject!("not_cap_ls");
+			DetectionStatus::NoMatch
+		}
+	}
+}
+
+/// Checks a server message after its leading `:`.
+fn server_message(data: &[u8]) -> DetectionStatus {
+	let prefix_len = data
+		.iter()
+		.take(MAX_PREFIX_LEN + 1)
+		.position(|&b| !b.is_ascii_graphic());
+	match prefix_len {
+		None if data.len() <= MAX_PREFIX_LEN => DetectionStatus::Incomplete,
+		Some(len) if len > 0 && data[len] == b' ' => server_command(&data[len + 1..]),
+		_ => {
+			reject!("invalid_prefix");
+			DetectionStatus::NoMatch
+		}
+	}
+}
+
+/// Checks for a pre-registration notice or a three-digit numeric reply.
+fn server_command(data: &[u8]) -> DetectionStatus {
+	if NOTICES.iter().any(|notice| data.starts_with(notice)) {
+		return DetectionStatus::Match;
+	}
+	match data {
+		[a, b, c, b' ', ..] if [a, b, c].iter().all(|d| d.is_ascii_digit()) => DetectionStatus::Match,
+		_ if data.len() < 4 && data.iter().all(u8::is_ascii_digit) => DetectionStatus::Incomplete,
+		_ if NOTICES.iter().any(|notice| notice.starts_with(data)) => DetectionStatus::Incomplete,
+		_ => {
+			reject!("unknown_server_command");
+			DetectionStatus::NoMatch
+		}
+	}
+}
+
+/// Splits off the first line, returning it without its terminator and the
+/// data after it once the terminator is in view.
+fn split_line(data: &[u8]) -> (&[u8], Option<&[u8]>) {
+	match data.iter().position(|&b| b == b'\n') {
+		Some(end) => {
+			let line = &data[..end];
+			(
+				line.strip_suffix(b"\r").unwrap_or(line),
+				Some(&data[end + 1..]),
+			)
+		}
+		None => (data, None),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_detect_client_registration() {
+		assert_eq!(
+			probe(b"NICK guest\r\nUSER guest 0 * :Guest User\r\n"),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+		assert_eq!(
+			probe(b"CAP LS 302\r\nNICK guest\r\n").0,
+			DetectionStatus::Match
+		);
+		assert_eq!(probe(b"USER bot 8 * :bot\r\n").0, DetectionStatus::Match);
+		assert_eq!(
+			probe(b"PASS hunter2\r\nNICK [bot]\r\n").0,
+			DetectionStatus::Match
+		);
+	}
+
+	#[test]
+	fn test_detect_server_greeting() {
+		assert_eq!(
+			probe(b"NOTICE AUTH :*** Looking up your hostname...\r\n").0,
+			DetectionStatus::Match
+		);
+		assert_eq!(
+			probe(b":irc.example.net NOTICE * :*** Checking Ident\r\n").0,
+			DetectionStatus::Match
+		);
+		assert_eq!(
+			probe(b":irc.example.net 001 guest :Welcome to the network\r\n").0,
+			DetectionStatus::Match
+		);
+	}
+
+	#[test]
+	fn test_incomplete_messages() {
+		assert_eq!(probe(b"NI").0, DetectionStatus::Incomplete);
+		assert_eq!(probe(b"USER guest 0").0, DetectionStatus::Incomplete);
+		assert_eq!(probe(b"PASS secret\r\n").0, DetectionStatus::Incomplete);
+		assert_eq!(probe(b":irc.example").0, DetectionStatus::Incomplete);
+		assert_eq!(probe(b":irc.example.net 00").0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	fn test_reject_other_protocols() {
+		// FTP and POP3 logins, an SMTP banner, and an HTTP request.
+		assert_eq!(probe(b"USER anonymous\r\n").0, DetectionStatus::NoMatch);
+		assert_eq!(
+			probe(b"PASS secret\r\nSTAT\r\n").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(
+			probe(b"220 mail.example.com ESMTP\r\n").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(probe(b"GET / HTTP/1.1\r\n").0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"NICK 9lives\r\n").0, DetectionStatus::NoMatch);
+		assert_eq!(
+			probe(b":irc.example.net PRIVMSG #c :hi\r\n").0,
+			DetectionStatus::NoMatch
+		);
+	}
+}
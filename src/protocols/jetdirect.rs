@@ -0,0 +1,71 @@
+/* src/protocols/jetdirect.rs */
+
+/// PJL Universal Exit Language sequence that opens a raw print job.
+const UEL: &[u8] = b"\x1b%-12345X";
+
+/// Job languages that may follow the exit sequence: PJL, PCL reset, and
+/// PostScript.
+const LANGUAGES: &[&[u8]] = &[b"@PJL", b"\x1bE", b"%!PS"];
+
+/// Detects a raw (JetDirect, port 9100) print job.
+///
+/// Matches the Universal Exit Language sequence followed by a PJL command, a
+/// PCL reset, or a PostScript header, as well as a job that opens directly
+/// with a PJL command.
+#[inline(always)]
+pub(crate) fn detect(data: &[u8]) -> bool {
+	if data.len() < 5 {
+		reject!("too_short");
+		return false;
+	}
+
+	if let Some(rest) = data.strip_prefix(UEL) {
+		let rest = rest.strip_prefix(b"\r\n").unwrap_or(rest);
+		if rest.is_empty()
+			|| LANGUAGES
+				.iter()
+				.any(|lang| rest.starts_with(lang) || lang.starts_with(rest))
+		{
+			return true;
+		}
+		reject!("unknown_job_language");
+		return false;
+	}
+
+	if data.starts_with(b"@PJL") && matches!(data[4], b' ' | b'\r' | b'\n') {
+		return true;
+	}
+
+	reject!("missing_uel");
+	false
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_detect_pjl_job() {
+		assert!(detect(b"\x1b%-12345X@PJL JOB NAME=\"report\"\r\n"));
+		assert!(detect(b"\x1b%-12345X\r\n@PJL ENTER LANGUAGE=PCL\r\n"));
+		assert!(detect(b"@PJL INFO STATUS\r\n"));
+	}
+
+	#[test]
+	fn test_detect_other_languages() {
+		assert!(detect(b"\x1b%-12345X\x1bE\x1b&l1X"));
+		assert!(detect(b"\x1b%-12345X%!PS-Adobe-3.0\n"));
+	}
+
+	#[test]
+	fn test_reject_unknown_payload() {
+		assert!(!detect(b"\x1b%-12345XHELLO"));
+		assert!(!detect(b"@PJLX"));
+		assert!(!detect(b"%!PS-Adobe-3.0\n"));
+	}
+
+	#[test]
+	fn test_short_data() {
+		assert!(!detect(b"\x1b%-1"));
+	}
+}
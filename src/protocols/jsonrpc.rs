@@ -0,0 +1,211 @@
+/* src/protocols/jsonrpc.rs */
+use crate::{DetectionStatus, ProtocolVersion};
+
+/// Version member every JSON-RPC 2.0 message carries.
+const VERSION_KEY: &[u8] = b"\"jsonrpc\"";
+
+/// Value of the version member.
+const VERSION_VALUE: &[u8] = b"\"2.0\"";
+
+/// Bytes of the first line searched for the version member: the default
+/// inspection window, so a message without it is rejected once the window
+/// is full instead of waiting for data the detector never sees.
+const MAX_SEARCH_LEN: usize = crate::MAX_INSPECT_BYTES;
+
+/// Longest header line of the LSP base protocol accepted.
+const MAX_HEADER_LINE: usize = 128;
+
+/// Probes for JSON-RPC 2.0 over a raw stream.
+///
+/// Accepts line-delimited messages (a JSON object or batch array whose first
+/// line carries `"jsonrpc": "2.0"`) and the LSP base protocol, where a header
+/// block of `Content-Length` and optional `Content-Type` fields precedes each
+/// message. The body after a complete header block is checked the same way
+/// when it is in view. The version member must appear within the first 64
+/// bytes of a message, and a header block that fills those bytes matches
+/// once it has carried `Content-Length`.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	let status = match data.first() {
+		None => DetectionStatus::Incomplete,
+		Some(b'{' | b'[') => message(data),
+		Some(b'C' | b'c') => headers(data),
+		Some(_) => {
+			reject!("no_jsonrpc_start");
+			DetectionStatus::NoMatch
+		}
+	};
+	(status, ProtocolVersion::Unknown)
+}
+
+/// Checks the LSP header block and the message that follows it.
+#[inline(always)]
+fn headers(data: &[u8]) -> DetectionStatus {
+	let mut pos = 0;
+	let mut has_length = false;
+	loop {
+		let rest = &data[pos..];
+		let Some(end) = rest.windows(2).position(|w| w == b"\r\n") else {
+			if rest.len() > MAX_HEADER_LINE || !is_header_prefix(rest) {
+				reject!("invalid_lsp_header");
+				return DetectionStatus::NoMatch;
+			}
+			if has_length && data.len() >= MAX_SEARCH_LEN {
+				return DetectionStatus::Match;
+			}
+			return DetectionStatus::Incomplete;
+		};
+		let line = &rest[..end];
+		pos += end + 2;
+		if line.is_empty() {
+			break;
+		}
+		if let Some(value) = strip_field(line, b"content-length:") {
+			let value = value.trim_ascii();
+			if value.is_empty() || !value.iter().all(u8::is_ascii_digit) {
+				reject!("invalid_content_length");
+				return DetectionStatus::NoMatch;
+			}
+			has_length = true;
+		} else if strip_field(line, b"content-type:").is_none() {
+			reject!("unknown_lsp_header");
+			return DetectionStatus::NoMatch;
+		}
+	}
+	if !has_length {
+		reject!("missing_content_length");
+		return DetectionStatus::NoMatch;
+	}
+	match data.get(pos) {
+		None => DetectionStatus::Match,
+		Some(b'{' | b'[') => match message(&data[pos..]) {
+			DetectionStatus::Incomplete => DetectionStatus::Match,
+			status => status,
+		},
+		Some(_) => {
+			reject!("body_not_json");
+			DetectionStatus::NoMatch
+		}
+	}
+}
+
+/// Checks the first line of a message for the version member.
+#[inline(always)]
+fn message(data: &[u8]) -> DetectionStatus {
+	let line_end = data.iter().position(|&b| b == b'\n');
+	let line = &data[..line_end.unwrap_or(data.len())];
+	if has_version(line) {
+		return DetectionStatus::Match;
+	}
+	if line_end.is_none() && data.len() < MAX_SEARCH_LEN {
+		return DetectionStatus::Incomplete;
+	}
+	reject!("missing_jsonrpc_version");
+	DetectionStatus::NoMatch
+}
+
+/// Finds `"jsonrpc"`, optional whitespace, `:`, optional whitespace, `"2.0"`.
+#[inline(always)]
+fn has_version(line: &[u8]) -> bool {
+	line
+		.windows(VERSION_KEY.len())
+		.enumerate()
+		.filter(|(_, window)| *window == VERSION_KEY)
+		.any(|(i, _)| {
+			let rest = line[i + VERSION_KEY.len()..].trim_ascii_start();
+			rest
+				.strip_prefix(b":")
+				.is_some_and(|value| value.trim_ascii_start().starts_with(VERSION_VALUE))
+		})
+}
+
+/// Strips a case-insensitive field name from a header line.
+#[inline(always)]
+fn strip_field<'a>(line: &'a [u8], name: &[u8]) -> Option<&'a [u8]> {
+	line
+		.get(..name.len())
+		.filter(|prefix| prefix.eq_ignore_ascii_case(name))
+		.map(|_| &line[name.len()..])
+}
+
+/// Checks whether a partial header line can still become a known field.
+#[inline(always)]
+fn is_header_prefix(partial: &[u8]) -> bool {
+	[&b"content-length:"[..], b"content-type:"]
+		.iter()
+		.any(|name| {
+			let n = partial.len().min(name.len());
+			partial[..n].eq_ignore_ascii_case(&name[..n])
+		})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_detect_line_delimited() {
+		assert_eq!(
+			probe(b"{\"jsonrpc\":\"2.0\",\"method\":\"eth_blockNumber\",\"params\":[],\"id\":1}\n"),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+		assert_eq!(
+			probe(b"{\"id\": 7, \"jsonrpc\" : \"2.0\", \"result\": true}").0,
+			DetectionStatus::Match
+		);
+		assert_eq!(
+			probe(b"[{\"jsonrpc\":\"2.0\",\"method\":\"ping\"}]\n").0,
+			DetectionStatus::Match
+		);
+	}
+
+	#[test]
+	fn test_detect_lsp_framing() {
+		let data =
+			b"Content-Length: 52\r\n\r\n{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"initialize\"}";
+		assert_eq!(probe(data).0, DetectionStatus::Match);
+		let data =
+			b"content-length: 52\r\nContent-Type: application/vscode-jsonrpc; charset=utf-8\r\n\r\n";
+		assert_eq!(probe(data).0, DetectionStatus::Match);
+	}
+
+	#[test]
+	fn test_incomplete_message() {
+		assert_eq!(probe(b"{\"jsonr").0, DetectionStatus::Incomplete);
+		assert_eq!(probe(b"Content-Len").0, DetectionStatus::Incomplete);
+		assert_eq!(
+			probe(b"Content-Length: 52\r\n").0,
+			DetectionStatus::Incomplete
+		);
+	}
+
+	#[test]
+	fn test_decides_within_window() {
+		// A JSON line without the version member in the first 64 bytes.
+		let data = b"{\"method\":\"textDocument/didOpen\",\"params\":{\"textDocument\":{\"uri\"";
+		assert!(data.len() >= MAX_SEARCH_LEN);
+		assert_eq!(probe(&data[..MAX_SEARCH_LEN]).0, DetectionStatus::NoMatch);
+		// An LSP header block cut inside its Content-Type line.
+		let data =
+			b"Content-Length: 52\r\nContent-Type: application/vscode-jsonrpc; charset=utf-8\r\n\r\n";
+		assert_eq!(probe(&data[..MAX_SEARCH_LEN]).0, DetectionStatus::Match);
+	}
+
+	#[test]
+	fn test_reject_other_json() {
+		assert_eq!(
+			probe(b"{\"jsonrpc\":\"1.0\",\"method\":\"ping\"}\n").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(probe(b"{\"name\":\"value\"}\n").0, DetectionStatus::NoMatch);
+		assert_eq!(
+			probe(b"Content-Length: 5\r\n\r\nhello").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(
+			probe(b"Connection: close\r\n\r\n").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(probe(b"GET / HTTP/1.1\r\n").0, DetectionStatus::NoMatch);
+	}
+}
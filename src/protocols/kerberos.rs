@@ -0,0 +1,162 @@
+/* src/protocols/kerberos.rs */
+use crate::{DetectionStatus, ProtocolVersion};
+
+/// BER tag of `AS-REQ` (`[APPLICATION 10]`).
+const AS_REQ: u8 = 0x6A;
+
+/// BER tag of `TGS-REQ` (`[APPLICATION 12]`).
+const TGS_REQ: u8 = 0x6C;
+
+/// BER tag of a universal constructed SEQUENCE.
+const SEQUENCE: u8 = 0x30;
+
+/// Length of the record mark that precedes messages over TCP.
+const RECORD_MARK_LEN: usize = 4;
+
+/// Largest message accepted over TCP.
+const MAX_TCP_LEN: usize = 1 << 20;
+
+/// Probes for a Kerberos 5 `AS-REQ` or `TGS-REQ`.
+///
+/// Accepts the bare message sent over UDP and the message behind the
+/// 4-byte record mark used over TCP. The `KDC-REQ` must start with
+/// `pvno` 5 and a `msg-type` matching the application tag.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	(probe_status(data), ProtocolVersion::Unknown)
+}
+
+/// Finds the message and checks its leading fields.
+#[inline(always)]
+fn probe_status(data: &[u8]) -> DetectionStatus {
+	let Some(&first) = data.first() else {
+		return DetectionStatus::Incomplete;
+	};
+	if matches!(first, AS_REQ | TGS_REQ) {
+		return probe_message(data);
+	}
+	let Some(mark) = data.first_chunk::<RECORD_MARK_LEN>() else {
+		return if first & 0x80 == 0 {
+			DetectionStatus::Incomplete
+		} else {
+			reject!("not_kdc_request");
+			DetectionStatus::NoMatch
+		};
+	};
+	let len = usize::try_from(u32::from_be_bytes(*mark)).unwrap_or(usize::MAX);
+	if len > MAX_TCP_LEN {
+		reject!("record_mark_invalid");
+		return DetectionStatus::NoMatch;
+	}
+	let message = &data[RECORD_MARK_LEN..];
+	match message.first().copied() {
+		None => DetectionStatus::Incomplete,
+		Some(AS_REQ | TGS_REQ) => match probe_message(message) {
+			DetectionStatus::Match if message.len() > len => {
+				reject!("record_mark_too_short");
+				DetectionStatus::NoMatch
+			}
+			status => status,
+		},
+		Some(_) => {
+			reject!("not_kdc_request");
+			DetectionStatus::NoMatch
+		}
+	}
+}
+
+/// Checks the application tag, the `KDC-REQ` SEQUENCE, `pvno` and
+/// `msg-type`.
+#[inline(always)]
+fn probe_message(data: &[u8]) -> DetectionStatus {
+	let msg_type = if data[0] == AS_REQ { 10 } else { 12 };
+	let mut pos = 0;
+	for tag in [data[0], SEQUENCE] {
+		match data.get(pos) {
+			None => return DetectionStatus::Incomplete,
+			Some(&actual) if actual == tag => {}
+			Some(_) => {
+				reject!("unexpected_tag");
+				return DetectionStatus::NoMatch;
+			}
+		}
+		match data.get(pos + 1) {
+			None => return DetectionStatus::Incomplete,
+			Some(&len) if len < 0x80 => pos += 2,
+			Some(&len) if (0x81..=0x84).contains(&len) => pos += 2 + usize::from(len & 0x7F),
+			Some(_) => {
+				reject!("length_form_invalid");
+				return DetectionStatus::NoMatch;
+			}
+		}
+	}
+	// pvno [1] INTEGER 5, msg-type [2] INTEGER.
+	let expected = [
+		0xA1, 0x03, 0x02, 0x01, 0x05, 0xA2, 0x03, 0x02, 0x01, msg_type,
+	];
+	let fields = &data[data.len().min(pos)..];
+	let seen = fields.len().min(expected.len());
+	if fields[..seen] != expected[..seen] {
+		reject!("pvno_or_msg_type_invalid");
+		DetectionStatus::NoMatch
+	} else if seen < expected.len() {
+		DetectionStatus::Incomplete
+	} else {
+		DetectionStatus::Match
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Start of an `AS-REQ` for `krbtgt/EXAMPLE.COM`, cut after `msg-type`.
+	const AS_REQ_UDP: &[u8] =
+		b"\x6A\x81\xB4\x30\x81\xB1\xA1\x03\x02\x01\x05\xA2\x03\x02\x01\x0A\xA3\x15\x30\x13";
+
+	#[test]
+	fn test_detect_udp_as_req() {
+		assert_eq!(
+			probe(AS_REQ_UDP),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+	}
+
+	#[test]
+	fn test_detect_tcp_tgs_req() {
+		let data = b"\x00\x00\x05\x9A\x6C\x82\x05\x96\x30\x82\x05\x92\xA1\x03\x02\x01\x05\xA2\x03\x02\x01\x0C\xA3\x82";
+		assert_eq!(probe(data).0, DetectionStatus::Match);
+	}
+
+	#[test]
+	fn test_incomplete_request() {
+		assert_eq!(probe(&AS_REQ_UDP[..8]).0, DetectionStatus::Incomplete);
+		assert_eq!(probe(b"\x00\x00\x01").0, DetectionStatus::Incomplete);
+		assert_eq!(
+			probe(b"\x00\x00\x00\xB7\x6A\x81\xB4").0,
+			DetectionStatus::Incomplete
+		);
+	}
+
+	#[test]
+	fn test_reject_other_messages() {
+		// AS-REP, wrong pvno, and an AS-REQ tag with a TGS msg-type.
+		assert_eq!(
+			probe(b"\x6B\x81\xB4\x30\x81\xB1\xA0\x03\x02\x01\x05").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(
+			probe(b"\x6A\x81\xB4\x30\x81\xB1\xA1\x03\x02\x01\x04").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(
+			probe(b"\x6A\x81\xB4\x30\x81\xB1\xA1\x03\x02\x01\x05\xA2\x03\x02\x01\x0C").0,
+			DetectionStatus::NoMatch
+		);
+		// A record mark shorter than the message it frames.
+		assert_eq!(
+			probe(b"\x00\x00\x00\x04\x6A\x81\xB4\x30\x81\xB1\xA1\x03\x02\x01\x05\xA2\x03\x02\x01\x0A").0,
+			DetectionStatus::NoMatch
+		);
+	}
+}
@@ -0,0 +1,187 @@
+/* src/protocols/ldap.rs */
+use crate::{DetectionStatus, ProtocolVersion};
+
+/// BER tag of a universal constructed SEQUENCE.
+const SEQUENCE: u8 = 0x30;
+
+/// BER tag of a universal INTEGER.
+const INTEGER: u8 = 0x02;
+
+/// BER tag of a universal OCTET STRING.
+const OCTET_STRING: u8 = 0x04;
+
+/// BER tag of the `BindRequest` protocol operation (`[APPLICATION 0]`).
+const BIND_REQUEST: u8 = 0x60;
+
+/// Highest LDAP protocol version.
+const MAX_VERSION: u8 = 3;
+
+/// Probes for an LDAP `BindRequest`, the first PDU of most LDAP sessions.
+///
+/// Expects an `LDAPMessage` SEQUENCE holding a positive message ID of at
+/// most four bytes and a `BindRequest` with version 1 to 3 and an OCTET
+/// STRING name, each fitting inside its parent.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	(probe_status(data), ProtocolVersion::Unknown)
+}
+
+/// Walks the BER headers down to the bind name.
+#[inline(always)]
+fn probe_status(data: &[u8]) -> DetectionStatus {
+	let mut reader = Reader { data, pos: 0 };
+	let message = match reader.header(SEQUENCE) {
+		Ok(len) => reader.pos + len,
+		Err(status) => return status,
+	};
+
+	let id_len = match reader.header(INTEGER) {
+		Ok(len) => len,
+		Err(status) => return status,
+	};
+	if !(1..=4).contains(&id_len) {
+		reject!("message_id_length_invalid");
+		return DetectionStatus::NoMatch;
+	}
+	match data.get(reader.pos) {
+		None => return DetectionStatus::Incomplete,
+		Some(&b) if b & 0x80 != 0 => {
+			reject!("message_id_negative");
+			return DetectionStatus::NoMatch;
+		}
+		Some(_) => reader.pos += id_len,
+	}
+
+	let bind = match reader.header(BIND_REQUEST) {
+		Ok(len) => reader.pos + len,
+		Err(status) => return status,
+	};
+	if bind > message {
+		reject!("bind_request_overflows_message");
+		return DetectionStatus::NoMatch;
+	}
+	match reader.header(INTEGER) {
+		Ok(1) => {}
+		Ok(_) => {
+			reject!("version_length_invalid");
+			return DetectionStatus::NoMatch;
+		}
+		Err(status) => return status,
+	}
+	match data.get(reader.pos) {
+		None => return DetectionStatus::Incomplete,
+		Some(version) if (1..=MAX_VERSION).contains(version) => reader.pos += 1,
+		Some(_) => {
+			reject!("version_invalid");
+			return DetectionStatus::NoMatch;
+		}
+	}
+	match reader.header(OCTET_STRING) {
+		Ok(len) if reader.pos + len <= bind => DetectionStatus::Match,
+		Ok(_) => {
+			reject!("name_overflows_bind_request");
+			DetectionStatus::NoMatch
+		}
+		Err(status) => status,
+	}
+}
+
+/// Cursor over BER-encoded data.
+struct Reader<'a> {
+	/// Encoded data.
+	data: &'a [u8],
+	/// Offset of the next header.
+	pos: usize,
+}
+
+impl Reader<'_> {
+	/// Reads a tag, which must be `tag`, and a definite length of at most
+	/// four bytes, leaving the cursor at the contents.
+	fn header(&mut self, tag: u8) -> Result<usize, DetectionStatus> {
+		let Some(&actual) = self.data.get(self.pos) else {
+			return Err(DetectionStatus::Incomplete);
+		};
+		if actual != tag {
+			reject!("unexpected_tag");
+			return Err(DetectionStatus::NoMatch);
+		}
+		let Some(&first) = self.data.get(self.pos + 1) else {
+			return Err(DetectionStatus::Incomplete);
+		};
+		if first < 0x80 {
+			self.pos += 2;
+			return Ok(usize::from(first));
+		}
+		let count = usize::from(first & 0x7F);
+		if !(1..=4).contains(&count) {
+			reject!("length_form_invalid");
+			return Err(DetectionStatus::NoMatch);
+		}
+		let Some(bytes) = self.data.get(self.pos + 2..self.pos + 2 + count) else {
+			return Err(DetectionStatus::Incomplete);
+		};
+		let len = bytes
+			.iter()
+			.fold(0usize, |len, &b| (len << 8) | usize::from(b));
+		self.pos += 2 + count;
+		Ok(len)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_detect_simple_bind() {
+		// Message 1: bind as cn=admin,dc=example,dc=org with password "secret".
+		let data =
+			b"\x30\x2C\x02\x01\x01\x60\x27\x02\x01\x03\x04\x1Acn=admin,dc=example,dc=org\x80\x06secret";
+		assert_eq!(
+			probe(data),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+		// Anonymous bind with a long-form message length.
+		assert_eq!(
+			probe(b"\x30\x84\x00\x00\x00\x0C\x02\x01\x01\x60\x07\x02\x01\x03\x04\x00\x80\x00").0,
+			DetectionStatus::Match
+		);
+	}
+
+	#[test]
+	fn test_incomplete_bind() {
+		assert_eq!(probe(b"\x30").0, DetectionStatus::Incomplete);
+		assert_eq!(probe(b"\x30\x0C\x02\x01").0, DetectionStatus::Incomplete);
+		assert_eq!(
+			probe(b"\x30\x0C\x02\x01\x01\x60\x07\x02\x01").0,
+			DetectionStatus::Incomplete
+		);
+	}
+
+	#[test]
+	fn test_reject_other_messages() {
+		// Search request, LDAP version 4, negative message ID, and a bind
+		// request longer than its message.
+		assert_eq!(
+			probe(b"\x30\x25\x02\x01\x02\x63\x20\x04\x00").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(
+			probe(b"\x30\x0C\x02\x01\x01\x60\x07\x02\x01\x04\x04\x00\x80\x00").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(
+			probe(b"\x30\x0C\x02\x01\xFF\x60\x07\x02\x01\x03\x04\x00").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(
+			probe(b"\x30\x05\x02\x01\x01\x60\x07\x02\x01\x03\x04\x00").0,
+			DetectionStatus::NoMatch
+		);
+		// SNMP shares the SEQUENCE and INTEGER prefix.
+		assert_eq!(
+			probe(b"\x30\x26\x02\x01\x01\x04\x06public\xA0\x19").0,
+			DetectionStatus::NoMatch
+		);
+	}
+}
@@ -0,0 +1,126 @@
+/* src/protocols/lorawan.rs */
+use crate::{DetectionStatus, ProtocolMetadata, ProtocolVersion};
+
+/// Identifier of a `PUSH_DATA` packet (uplinks and gateway status).
+const PUSH_DATA: u8 = 0x00;
+
+/// Identifier of a `PULL_DATA` packet (downlink keepalive).
+const PULL_DATA: u8 = 0x02;
+
+/// Length of the header: version, random token, identifier, gateway EUI.
+const HEADER_LEN: usize = 12;
+
+/// Probes for the Semtech UDP packet forwarder protocol spoken between LoRa
+/// gateways and network servers.
+///
+/// Expects protocol version 1 or 2 and a `PUSH_DATA` or `PULL_DATA`
+/// identifier after the random token, then the 8-byte gateway EUI. A
+/// `PULL_DATA` ends there, while a `PUSH_DATA` carries a JSON object.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	(probe_status(data), ProtocolVersion::Unknown)
+}
+
+/// Checks the header and what follows the gateway EUI.
+#[inline(always)]
+fn probe_status(data: &[u8]) -> DetectionStatus {
+	let [version, _, _, identifier, ..] = *data else {
+		return DetectionStatus::Incomplete;
+	};
+	if !matches!(version, 1 | 2) {
+		reject!("unknown_version");
+		return DetectionStatus::NoMatch;
+	}
+	if data.len() < HEADER_LEN {
+		return if matches!(identifier, PUSH_DATA | PULL_DATA) {
+			DetectionStatus::Incomplete
+		} else {
+			reject!("unexpected_identifier");
+			DetectionStatus::NoMatch
+		};
+	}
+	match (identifier, data.get(HEADER_LEN)) {
+		(PULL_DATA, None) | (PUSH_DATA, Some(b'{')) => DetectionStatus::Match,
+		(PUSH_DATA, None) => DetectionStatus::Incomplete,
+		_ => {
+			reject!("unexpected_body");
+			DetectionStatus::NoMatch
+		}
+	}
+}
+
+/// Extracts the gateway EUI.
+#[inline(always)]
+pub(crate) fn metadata(data: &[u8]) -> ProtocolMetadata<'_> {
+	if probe_status(data) != DetectionStatus::Match {
+		return ProtocolMetadata::None;
+	}
+	data
+		.get(4..HEADER_LEN)
+		.and_then(|eui| eui.try_into().ok())
+		.map_or(ProtocolMetadata::None, |eui| ProtocolMetadata::LoRaWan {
+			gateway_eui: u64::from_be_bytes(eui),
+		})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_detect_push_data() {
+		let packet = b"\x02\x7A\x3C\x00\xAA\x55\x5A\x00\x00\x00\x01\x01{\"stat\":{\"time\":\"2024-01-01 00:00:00 GMT\"}}";
+		assert_eq!(
+			probe(packet),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+		assert_eq!(
+			metadata(packet),
+			ProtocolMetadata::LoRaWan {
+				gateway_eui: 0xAA55_5A00_0000_0101,
+			}
+		);
+	}
+
+	#[test]
+	fn test_detect_pull_data() {
+		let packet = b"\x01\x12\x34\x02\xAA\x55\x5A\x00\x00\x00\x01\x01";
+		assert_eq!(probe(packet).0, DetectionStatus::Match);
+		assert_eq!(
+			metadata(packet),
+			ProtocolMetadata::LoRaWan {
+				gateway_eui: 0xAA55_5A00_0000_0101,
+			}
+		);
+	}
+
+	#[test]
+	fn test_incomplete_header() {
+		assert_eq!(probe(b"\x02\x7A").0, DetectionStatus::Incomplete);
+		assert_eq!(
+			probe(b"\x02\x7A\x3C\x00\xAA\x55").0,
+			DetectionStatus::Incomplete
+		);
+		assert_eq!(
+			probe(b"\x02\x7A\x3C\x00\xAA\x55\x5A\x00\x00\x00\x01\x01").0,
+			DetectionStatus::Incomplete
+		);
+	}
+
+	#[test]
+	fn test_reject_other_packets() {
+		// Unknown version, PUSH_ACK, PULL_DATA with trailing bytes, and a
+		// PUSH_DATA without JSON.
+		assert_eq!(probe(b"\x03\x7A\x3C\x00").0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"\x02\x7A\x3C\x01").0, DetectionStatus::NoMatch);
+		assert_eq!(
+			probe(b"\x02\x12\x34\x02\xAA\x55\x5A\x00\x00\x00\x01\x01\x00").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(
+			probe(b"\x02\x12\x34\x00\xAA\x55\x5A\x00\x00\x00\x01\x01\x80").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(metadata(b"\x02\x7A\x3C\x01"), ProtocolMetadata::None);
+	}
+}
@@ -0,0 +1,110 @@
+/* src/protocols/lpd.rs */
+use crate::{DetectionStatus, ProtocolVersion};
+
+/// Longest queue name accepted.
+const MAX_QUEUE_LEN: usize = 64;
+
+/// Longest command line accepted, including the command byte and LF: the
+/// default inspection window, so a line without LF in view is rejected
+/// instead of waiting for data the detector never sees.
+const MAX_LINE_LEN: usize = crate::MAX_INSPECT_BYTES;
+
+/// Probes for an LPD daemon command (RFC 1179).
+///
+/// Expects a command byte from 0x01 (print waiting jobs) to 0x05 (remove
+/// jobs), a printable queue name without spaces, and for commands 0x03 to
+/// 0x05 printable operands, all terminated by LF within the first 64 bytes.
+/// The remove command must name the requesting agent.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	let Some(&command) = data.first() else {
+		return (DetectionStatus::Incomplete, ProtocolVersion::Unknown);
+	};
+	if !(0x01..=0x05).contains(&command) {
+		reject!("invalid_command");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	}
+
+	let window = &data[1..data.len().min(MAX_LINE_LEN)];
+	let line_end = window.iter().position(|&b| b == b'\n');
+	let line = &window[..line_end.unwrap_or(window.len())];
+	let queue_len = line.iter().position(|&b| b == b' ').unwrap_or(line.len());
+	let (queue, operands) = line.split_at(queue_len);
+
+	if queue.len() > MAX_QUEUE_LEN || !queue.iter().all(u8::is_ascii_graphic) {
+		reject!("invalid_queue_name");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	}
+	// Only the list and remove commands take operands.
+	if (!operands.is_empty() && command < 0x03)
+		|| !operands.iter().all(|&b| b == b' ' || b.is_ascii_graphic())
+	{
+		reject!("invalid_operands");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	}
+
+	match line_end {
+		Some(_) if queue.is_empty() => {
+			reject!("empty_queue_name");
+			(DetectionStatus::NoMatch, ProtocolVersion::Unknown)
+		}
+		Some(_) if command == 0x05 && operands.trim_ascii().is_empty() => {
+			reject!("missing_agent");
+			(DetectionStatus::NoMatch, ProtocolVersion::Unknown)
+		}
+		Some(_) => (DetectionStatus::Match, ProtocolVersion::Unknown),
+		None if data.len() < MAX_LINE_LEN => (DetectionStatus::Incomplete, ProtocolVersion::Unknown),
+		None => {
+			reject!("line_too_long");
+			(DetectionStatus::NoMatch, ProtocolVersion::Unknown)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_detect_commands() {
+		assert_eq!(
+			probe(b"\x02lp\n"),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+		assert_eq!(probe(b"\x03office-laser\n").0, DetectionStatus::Match);
+		assert_eq!(probe(b"\x04raw alice 42\n").0, DetectionStatus::Match);
+		assert_eq!(probe(b"\x05lp root 17 18\n").0, DetectionStatus::Match);
+	}
+
+	#[test]
+	fn test_incomplete_command() {
+		assert_eq!(probe(b"\x02").0, DetectionStatus::Incomplete);
+		assert_eq!(probe(b"\x02lp").0, DetectionStatus::Incomplete);
+		assert_eq!(probe(b"\x05lp root").0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	fn test_decides_within_window() {
+		// A list command whose operands run past the window.
+		let mut line = [b'j'; MAX_LINE_LEN];
+		line[..5].copy_from_slice(b"\x04raw ");
+		assert_eq!(
+			probe(&line[..MAX_LINE_LEN - 1]).0,
+			DetectionStatus::Incomplete
+		);
+		assert_eq!(probe(&line).0, DetectionStatus::NoMatch);
+	}
+
+	#[test]
+	fn test_reject_invalid_commands() {
+		assert_eq!(probe(b"\x06lp\n").0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"\x02\n").0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"\x02lp extra\n").0, DetectionStatus::NoMatch);
+		// Remove jobs without the agent.
+		assert_eq!(probe(b"\x05lp\n").0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"\x01\x00\x00\x00").0, DetectionStatus::NoMatch);
+		let mut long = [b'a'; 80];
+		long[0] = 0x02;
+		assert_eq!(probe(&long).0, DetectionStatus::NoMatch);
+	}
+}
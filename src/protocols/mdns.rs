@@ -0,0 +1,142 @@
+/* src/protocols/mdns.rs */
+
+/// Detects multicast DNS (RFC 6762).
+///
+/// Expects a DNS header with transaction id 0, opcode 0, the Z bit clear
+/// and rcode 0, whose first record name ends in `.local` or whose class has
+/// the top bit set (unicast-response bit on questions, cache-flush bit on
+/// answers). A name cut off by the inspection window still counts when a
+/// label already seen is a DNS-SD service or protocol label (`_ipp`,
+/// `_tcp`), so long instance names are not left to unicast DNS.
+#[inline(always)]
+pub(crate) fn detect(data: &[u8]) -> bool {
+	if data.len() < 12 {
+		reject!("too_short");
+		return false;
+	}
+	if data[0] != 0 || data[1] != 0 {
+		reject!("nonzero_transaction_id");
+		return false;
+	}
+	if data[2] & 0x78 != 0 || data[3] & 0x4F != 0 {
+		reject!("nonzero_opcode_z_or_rcode");
+		return false;
+	}
+	let records = [4, 6, 8, 10]
+		.iter()
+		.map(|&i| u32::from(u16::from_be_bytes([data[i], data[i + 1]])))
+		.sum::<u32>();
+	if records == 0 || records > 400 {
+		reject!("implausible_record_counts");
+		return false;
+	}
+
+	// Walk the first name, remembering its last label and whether any
+	// label so far names a DNS-SD service.
+	let mut pos = 12;
+	let mut last_label: &[u8] = &[];
+	let mut service_label = false;
+	loop {
+		let Some(&len) = data.get(pos) else {
+			if service_label {
+				return true;
+			}
+			reject!("truncated_name");
+			return false;
+		};
+		if len == 0 {
+			pos += 1;
+			break;
+		}
+		if len > 63 {
+			reject!("compressed_or_invalid_label");
+			return false;
+		}
+		let Some(label) = data.get(pos + 1..pos + 1 + usize::from(len)) else {
+			if service_label || data.get(pos + 1) == Some(&b'_') {
+				return true;
+			}
+			reject!("truncated_name");
+			return false;
+		};
+		last_label = label;
+		service_label |= label.len() > 1 && label[0] == b'_';
+		pos += 1 + usize::from(len);
+	}
+
+	if last_label.eq_ignore_ascii_case(b"local") {
+		return true;
+	}
+	if data.get(pos + 2).is_some_and(|&class| class & 0x80 != 0) {
+		return true;
+	}
+
+	reject!("no_multicast_markers");
+	false
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_detect_mdns_service_query() {
+		let mut data = [0u8; 12].to_vec();
+		data[5] = 0x01; // QDCOUNT
+		data.extend_from_slice(b"\x0b_googlecast\x04_tcp\x05local\x00\x00\x0c\x00\x01");
+		assert!(detect(&data));
+	}
+
+	#[test]
+	fn test_detect_mdns_unicast_response_bit() {
+		let mut data = [0u8; 12].to_vec();
+		data[5] = 0x01;
+		data.extend_from_slice(b"\x014\x011\x03168\x03192\x07in-addr\x04arpa\x00\x00\x0c\x80\x01");
+		assert!(detect(&data));
+	}
+
+	#[test]
+	fn test_detect_mdns_announcement() {
+		let mut data = [0u8; 12].to_vec();
+		data[2] = 0x84; // QR, AA
+		data[7] = 0x01; // ANCOUNT
+		data.extend_from_slice(b"\x07printer\x05LOCAL\x00\x00\x01\x80\x01");
+		assert!(detect(&data));
+	}
+
+	#[test]
+	fn test_reject_unicast_dns() {
+		let data = [
+			0x12, 0x34, 0x01, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x06, b'g', b'o',
+			b'o', b'g', b'l', b'e', 0x03, b'c', b'o', b'm', 0x00, 0x00, 0x01, 0x00, 0x01,
+		];
+		assert!(!detect(&data));
+		let mut data = data;
+		data[0] = 0;
+		data[1] = 0;
+		assert!(!detect(&data));
+	}
+
+	#[test]
+	fn test_detect_service_name_past_window() {
+		// A Chromecast TXT record whose `.local` lies past the default
+		// 64-byte window.
+		let mut data = [0u8; 12].to_vec();
+		data[2] = 0x84; // QR, AA
+		data[7] = 0x01; // ANCOUNT
+		data.push(49);
+		data.extend_from_slice(b"Chromecast-Ultra-7c7b3bcd8c9c0d5c0ff0f6f0e2f5e3d1");
+		data.extend_from_slice(b"\x0b_googlecast\x04_tcp\x05local\x00\x00\x10\x80\x01");
+		assert!(detect(&data));
+		assert!(detect(&data[..64]));
+		assert!(detect(&data[..70]));
+	}
+
+	#[test]
+	fn test_reject_truncated_name() {
+		let mut data = [0u8; 12].to_vec();
+		data[5] = 0x01;
+		data.extend_from_slice(b"\x07printer\x05lo");
+		assert!(!detect(&data));
+	}
+}
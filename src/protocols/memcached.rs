@@ -0,0 +1,171 @@
+/* src/protocols/memcached.rs */
+use crate::{DetectionStatus, ProtocolVersion};
+
+/// Commands of the ASCII protocol recognized as a session opening.
+const COMMANDS: [&[u8]; 14] = [
+	b"get", b"gets", b"set", b"add", b"replace", b"append", b"prepend", b"cas", b"touch", b"delete",
+	b"incr", b"decr", b"stats", b"version",
+];
+
+/// Longest key the server accepts.
+const MAX_KEY_LEN: usize = 250;
+
+/// Longest command line accepted.
+const MAX_LINE_LEN: usize = 2048;
+
+/// Probes for a memcached ASCII command line.
+///
+/// Expects a lowercase command followed by the arguments it takes: keys for
+/// `get` and `gets`, a key with numeric flags, expiry and length for storage
+/// commands, a key and expiry for `touch`, and nothing but words for `stats`
+/// and `version`. The line must end in CRLF. Memcached commands are
+/// case-sensitive, so uppercase Redis inline commands (and HTTP's `GET`)
+/// never match, and lowercase Redis `set` fails the numeric arguments.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	let Some(end) = data.windows(2).position(|pair| pair == b"\r\n") else {
+		return (partial_line(data), ProtocolVersion::Unknown);
+	};
+	let mut args = data[..end].split(|&b| b == b' ');
+	let command = args.next().unwrap_or_default();
+	let valid = match command {
+		b"get" | b"gets" => args.next().is_some_and(is_key) && args.all(is_key),
+		b"set" | b"add" | b"replace" | b"append" | b"prepend" => storage(&mut args, false),
+		b"cas" => storage(&mut args, true),
+		b"touch" => {
+			args.next().is_some_and(is_key) && args.next().is_some_and(is_expiry) && no_reply(&mut args)
+		}
+		b"delete" => args.next().is_some_and(is_key) && no_reply(&mut args),
+		b"incr" | b"decr" => {
+			args.next().is_some_and(is_key) && args.next().is_some_and(is_number) && no_reply(&mut args)
+		}
+		b"stats" => args.all(|arg| !arg.is_empty() && arg.iter().all(u8::is_ascii_graphic)),
+		b"version" => args.next().is_none(),
+		_ => {
+			reject!("unknown_command");
+			return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+		}
+	};
+	if valid {
+		(DetectionStatus::Match, ProtocolVersion::Unknown)
+	} else {
+		reject!("invalid_arguments");
+		(DetectionStatus::NoMatch, ProtocolVersion::Unknown)
+	}
+}
+
+/// Checks a line whose CRLF is not in view yet.
+#[inline(always)]
+fn partial_line(data: &[u8]) -> DetectionStatus {
+	if data.len() > MAX_LINE_LEN {
+		reject!("line_too_long");
+		return DetectionStatus::NoMatch;
+	}
+	let known = if let Some(space) = data.iter().position(|&b| b == b' ') {
+		COMMANDS.contains(&&data[..space])
+	} else {
+		let word = data.strip_suffix(b"\r").unwrap_or(data);
+		COMMANDS.iter().any(|command| command.starts_with(word))
+	};
+	if known {
+		DetectionStatus::Incomplete
+	} else {
+		reject!("unknown_command");
+		DetectionStatus::NoMatch
+	}
+}
+
+/// Checks `<key> <flags> <exptime> <bytes> [<cas unique>] [noreply]`.
+#[inline(always)]
+fn storage<'a>(args: &mut impl Iterator<Item = &'a [u8]>, cas: bool) -> bool {
+	args.next().is_some_and(is_key)
+		&& args.next().is_some_and(is_number)
+		&& args.next().is_some_and(is_expiry)
+		&& args.next().is_some_and(is_number)
+		&& (!cas || args.next().is_some_and(is_number))
+		&& no_reply(args)
+}
+
+/// Checks for an optional trailing `noreply` and nothing after it.
+#[inline(always)]
+fn no_reply<'a>(args: &mut impl Iterator<Item = &'a [u8]>) -> bool {
+	matches!(args.next(), None | Some(b"noreply")) && args.next().is_none()
+}
+
+/// Checks for a key: printable, without spaces or control characters.
+#[inline(always)]
+fn is_key(key: &[u8]) -> bool {
+	(1..=MAX_KEY_LEN).contains(&key.len()) && key.iter().all(u8::is_ascii_graphic)
+}
+
+/// Checks for an unsigned decimal number.
+#[inline(always)]
+fn is_number(arg: &[u8]) -> bool {
+	!arg.is_empty() && arg.iter().all(u8::is_ascii_digit)
+}
+
+/// Checks for an expiry time, which may be negative to expire at once.
+#[inline(always)]
+fn is_expiry(arg: &[u8]) -> bool {
+	is_number(arg.strip_prefix(b"-").unwrap_or(arg))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_detect_retrieval_commands() {
+		assert_eq!(
+			probe(b"get user:42\r\n"),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+		assert_eq!(probe(b"gets a b c\r\n").0, DetectionStatus::Match);
+		assert_eq!(probe(b"stats\r\n").0, DetectionStatus::Match);
+		assert_eq!(probe(b"stats slabs\r\n").0, DetectionStatus::Match);
+		assert_eq!(probe(b"version\r\n").0, DetectionStatus::Match);
+	}
+
+	#[test]
+	fn test_detect_storage_commands() {
+		assert_eq!(
+			probe(b"set session 0 3600 5\r\nhello\r\n").0,
+			DetectionStatus::Match
+		);
+		assert_eq!(
+			probe(b"cas session 0 0 5 12345 noreply\r\n").0,
+			DetectionStatus::Match
+		);
+		assert_eq!(probe(b"touch session -1\r\n").0, DetectionStatus::Match);
+		assert_eq!(probe(b"incr hits 1\r\n").0, DetectionStatus::Match);
+		assert_eq!(
+			probe(b"delete session noreply\r\n").0,
+			DetectionStatus::Match
+		);
+	}
+
+	#[test]
+	fn test_incomplete_line() {
+		assert_eq!(probe(b"ge").0, DetectionStatus::Incomplete);
+		assert_eq!(probe(b"stats").0, DetectionStatus::Incomplete);
+		assert_eq!(probe(b"set session 0 36").0, DetectionStatus::Incomplete);
+		assert_eq!(probe(b"version\r").0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	fn test_reject_other_commands() {
+		// Redis inline commands and HTTP.
+		assert_eq!(probe(b"GET user:42\r\n").0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"set user:42 alice\r\n").0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"GET / HTTP/1.1\r\n").0, DetectionStatus::NoMatch);
+		// Missing key, stray arguments.
+		assert_eq!(probe(b"get\r\n").0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"get  key\r\n").0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"version 1\r\n").0, DetectionStatus::NoMatch);
+		assert_eq!(
+			probe(b"touch session 10 noreply extra\r\n").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(probe(b"quit\r\n").0, DetectionStatus::NoMatch);
+	}
+}
@@ -0,0 +1,197 @@
+/* src/protocols/minecraft.rs */
+use crate::{DetectionStatus, ProtocolMetadata, ProtocolVersion};
+
+/// Packet id of the handshake.
+const HANDSHAKE_ID: u8 = 0x00;
+
+/// Largest packet length a three-byte VarInt can carry.
+const MAX_PACKET_LEN: i32 = (1 << 21) - 1;
+
+/// Longest server address string the protocol allows.
+const MAX_ADDRESS_LEN: i32 = 32767;
+
+/// Longest VarInt encoding.
+const MAX_VARINT_LEN: usize = 5;
+
+/// Handshake fields parsed up to the server address.
+struct Handshake<'a> {
+	/// Client protocol version.
+	protocol_version: i32,
+	/// Server address bytes in view.
+	address: &'a [u8],
+	/// Whether the whole address is in view.
+	address_complete: bool,
+}
+
+/// Probes for the Minecraft Java Edition handshake.
+///
+/// Expects a VarInt packet length, packet id 0, a VarInt protocol version, a
+/// length-prefixed server address, a port, and next state 1 (status), 2
+/// (login), or 3 (transfer). The packet length must equal the sum of the
+/// fields, so the address need not be fully in view.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	match parse(data) {
+		Ok(_) => (DetectionStatus::Match, ProtocolVersion::Unknown),
+		Err(status) => (status, ProtocolVersion::Unknown),
+	}
+}
+
+/// Extracts the protocol version and the requested server address.
+///
+/// Modded clients and proxies append `\0`-separated data to the address; only
+/// the hostname before the first `\0` is reported.
+#[inline(always)]
+pub(crate) fn metadata(data: &[u8]) -> ProtocolMetadata<'_> {
+	let Ok(handshake) = parse(data) else {
+		return ProtocolMetadata::None;
+	};
+	let server_address = match handshake.address.iter().position(|&b| b == 0) {
+		Some(end) => Some(&handshake.address[..end]),
+		None if handshake.address_complete => Some(handshake.address),
+		None => None,
+	}
+	.and_then(|host| core::str::from_utf8(host).ok());
+	ProtocolMetadata::Minecraft {
+		protocol_version: handshake.protocol_version,
+		server_address,
+	}
+}
+
+/// Parses the handshake, rejecting or asking for more data on failure.
+#[inline(always)]
+fn parse(data: &[u8]) -> Result<Handshake<'_>, DetectionStatus> {
+	let (length, mut pos) = var_int(data, 0)?;
+	if !(1..=MAX_PACKET_LEN).contains(&length) {
+		reject!("invalid_packet_length");
+		return Err(DetectionStatus::NoMatch);
+	}
+	match data.get(pos) {
+		None => return Err(DetectionStatus::Incomplete),
+		Some(&HANDSHAKE_ID) => pos += 1,
+		Some(_) => {
+			reject!("not_handshake_packet");
+			return Err(DetectionStatus::NoMatch);
+		}
+	}
+	let (protocol_version, version_len) = var_int(data, pos)?;
+	pos += version_len;
+	let (address_len, address_len_len) = var_int(data, pos)?;
+	if !(1..=MAX_ADDRESS_LEN).contains(&address_len) {
+		reject!("invalid_address_length");
+		return Err(DetectionStatus::NoMatch);
+	}
+	pos += address_len_len;
+
+	// Both lengths are positive and bounded above.
+	let address_len = address_len.unsigned_abs() as usize;
+	let expected = 1 + version_len + address_len_len + address_len + 3;
+	if length.unsigned_abs() as usize != expected {
+		reject!("packet_length_mismatch");
+		return Err(DetectionStatus::NoMatch);
+	}
+
+	let end = pos + address_len;
+	let address = &data[pos.min(data.len())..end.min(data.len())];
+	if address.iter().any(|&b| b != 0 && (b < 0x20 || b == 0x7F)) {
+		reject!("invalid_server_address");
+		return Err(DetectionStatus::NoMatch);
+	}
+	if data
+		.get(end + 2)
+		.is_some_and(|state| !(1..=3).contains(state))
+	{
+		reject!("invalid_next_state");
+		return Err(DetectionStatus::NoMatch);
+	}
+	Ok(Handshake {
+		protocol_version,
+		address,
+		address_complete: data.len() >= end,
+	})
+}
+
+/// Reads a VarInt at `pos` and returns it with its encoded length.
+#[inline(always)]
+fn var_int(data: &[u8], pos: usize) -> Result<(i32, usize), DetectionStatus> {
+	let mut value = 0u32;
+	for i in 0..MAX_VARINT_LEN {
+		let Some(&byte) = data.get(pos + i) else {
+			return Err(DetectionStatus::Incomplete);
+		};
+		value |= u32::from(byte & 0x7F) << (7 * i);
+		if byte & 0x80 == 0 {
+			return Ok((value.cast_signed(), i + 1));
+		}
+	}
+	reject!("varint_too_long");
+	Err(DetectionStatus::NoMatch)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// 1.20.4 client (protocol 765) asking `mc.example.com:25565` for status,
+	/// followed by the status request.
+	const STATUS_HANDSHAKE: &[u8] = b"\x15\x00\xFD\x05\x0Emc.example.com\x63\xDD\x01\x01\x00";
+
+	#[test]
+	fn test_detect_status_handshake() {
+		assert_eq!(
+			probe(STATUS_HANDSHAKE),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+		assert_eq!(
+			metadata(STATUS_HANDSHAKE),
+			ProtocolMetadata::Minecraft {
+				protocol_version: 765,
+				server_address: Some("mc.example.com"),
+			}
+		);
+	}
+
+	#[test]
+	fn test_detect_forge_login_handshake() {
+		let data = b"\x1B\x00\xFD\x05\x14mc.example.com\x00FML3\x00\x63\xDD\x02";
+		assert_eq!(probe(data).0, DetectionStatus::Match);
+		assert_eq!(
+			metadata(&data[..12]),
+			ProtocolMetadata::Minecraft {
+				protocol_version: 765,
+				server_address: None,
+			}
+		);
+		assert_eq!(
+			metadata(&data[..22]),
+			ProtocolMetadata::Minecraft {
+				protocol_version: 765,
+				server_address: Some("mc.example.com"),
+			}
+		);
+	}
+
+	#[test]
+	fn test_incomplete_handshake() {
+		assert_eq!(probe(&STATUS_HANDSHAKE[..3]).0, DetectionStatus::Incomplete);
+		assert_eq!(probe(&STATUS_HANDSHAKE[..10]).0, DetectionStatus::Match);
+	}
+
+	#[test]
+	fn test_reject_length_mismatch() {
+		let mut data = STATUS_HANDSHAKE.to_vec();
+		data[0] = 0x16;
+		assert_eq!(probe(&data).0, DetectionStatus::NoMatch);
+	}
+
+	#[test]
+	fn test_reject_bad_fields() {
+		let mut data = STATUS_HANDSHAKE.to_vec();
+		data[21] = 0x04; // next state
+		assert_eq!(probe(&data).0, DetectionStatus::NoMatch);
+		let mut data = STATUS_HANDSHAKE.to_vec();
+		data[1] = 0x01; // packet id
+		assert_eq!(probe(&data).0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"GET / HTTP/1.1\r\n").0, DetectionStatus::NoMatch);
+	}
+}
@@ -0,0 +1,141 @@
+/* src/protocols/mms.rs */
+use crate::{DetectionStatus, ProtocolVersion};
+
+/// TPKT version (RFC 1006).
+const TPKT_VERSION: u8 = 0x03;
+
+/// Length of the TPKT header.
+const TPKT_HEADER_LEN: usize = 4;
+
+/// Length indicator of a class 0 COTP data TPDU header.
+const COTP_DT_LEN: u8 = 0x02;
+
+/// COTP data TPDU code.
+const COTP_DT: u8 = 0xF0;
+
+/// Offset of the ISO session SPDU, after TPKT and COTP.
+const SPDU_OFFSET: usize = TPKT_HEADER_LEN + 3;
+
+/// ISO 8327 session CONNECT SPDU type.
+const SPDU_CONNECT: u8 = 0x0D;
+
+/// BER encoding of the MMS object identifier arc `1.0.9506.2`, shared by
+/// the MMS abstract syntax and application context.
+const MMS_OID_PREFIX: [u8; 4] = [0x28, 0xCA, 0x22, 0x02];
+
+/// Context tag of the MMS `initiate-RequestPDU`.
+const INITIATE_REQUEST: u8 = 0xA8;
+
+/// Probes for an MMS (ISO 9506, IEC 61850) association request.
+///
+/// Expects a TPKT header, a COTP data TPDU, and a session CONNECT whose
+/// presentation and ACSE layers name the MMS object identifier, followed by
+/// an MMS `initiate-RequestPDU`. S7comm shares the TPKT and COTP prefix but
+/// carries its own header in place of the session layer.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	(probe_status(data), ProtocolVersion::Unknown)
+}
+
+/// Checks the TPKT, COTP and session headers, then searches the TPKT
+/// payload for the MMS identifiers.
+#[inline(always)]
+fn probe_status(data: &[u8]) -> DetectionStatus {
+	let Some(header) = data.first_chunk::<SPDU_OFFSET>() else {
+		return DetectionStatus::Incomplete;
+	};
+	if header[0] != TPKT_VERSION || header[1] != 0x00 {
+		reject!("not_tpkt");
+		return DetectionStatus::NoMatch;
+	}
+	let tpkt_len = usize::from(u16::from_be_bytes([header[2], header[3]]));
+	if tpkt_len <= SPDU_OFFSET + 2 {
+		reject!("tpkt_too_short");
+		return DetectionStatus::NoMatch;
+	}
+	if header[4] != COTP_DT_LEN || header[5] != COTP_DT {
+		reject!("not_cotp_data");
+		return DetectionStatus::NoMatch;
+	}
+	let Some(&spdu) = data.get(SPDU_OFFSET) else {
+		return DetectionStatus::Incomplete;
+	};
+	if spdu != SPDU_CONNECT {
+		reject!("not_session_connect");
+		return DetectionStatus::NoMatch;
+	}
+
+	let payload = &data[SPDU_OFFSET..data.len().min(tpkt_len)];
+	let initiate =
+		find(payload, &MMS_OID_PREFIX).is_some_and(|pos| payload[pos..].contains(&INITIATE_REQUEST));
+	if initiate {
+		DetectionStatus::Match
+	} else if data.len() < tpkt_len {
+		DetectionStatus::Incomplete
+	} else {
+		reject!("no_mms_initiate");
+		DetectionStatus::NoMatch
+	}
+}
+
+/// Returns the position of `needle` in `haystack`.
+#[inline(always)]
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+	haystack
+		.windows(needle.len())
+		.position(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// IEC 61850 client association: session CONNECT, presentation CP-type,
+	/// ACSE AARQ with the MMS application context, and `initiate-RequestPDU`.
+	const INITIATE: &str = "0300008d02f0800d840506130100160102140200023302000134020001c16e316ca003800101a265810400000001820400000001a4123010020101060528ca22020130040602510161433041020101a03c603aa107060528ca220203be2f282d020103a028a826800300fde8810105820105830105a416800101810305f100820c03ee1c00000408000079ef18";
+
+	/// Decodes a hex packet into a buffer, returning it and its length.
+	fn packet(hex: &str) -> ([u8; 256], usize) {
+		let mut out = [0u8; 256];
+		for (byte, pair) in out.iter_mut().zip(hex.as_bytes().chunks_exact(2)) {
+			*byte = u8::from_str_radix(core::str::from_utf8(pair).unwrap(), 16).unwrap();
+		}
+		(out, hex.len() / 2)
+	}
+
+	#[test]
+	fn test_detect_initiate_request() {
+		let (data, len) = packet(INITIATE);
+		assert_eq!(
+			probe(&data[..len]),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+	}
+
+	#[test]
+	fn test_incomplete_association() {
+		let (data, _) = packet(INITIATE);
+		assert_eq!(probe(&data[..5]).0, DetectionStatus::Incomplete);
+		assert_eq!(probe(&data[..60]).0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	fn test_reject_s7comm_and_cotp_connect() {
+		// S7comm setup communication over the same TPKT and COTP prefix.
+		let s7 = b"\x03\x00\x00\x19\x02\xF0\x80\x32\x01\x00\x00\x00\x00\x00\x08\x00\x00\xF0\x00\x00\x01\x00\x01\x01\xE0";
+		assert_eq!(probe(s7).0, DetectionStatus::NoMatch);
+		// COTP connection request.
+		let cr =
+			b"\x03\x00\x00\x16\x11\xE0\x00\x00\x00\x01\x00\xC1\x02\x01\x00\xC2\x02\x01\x02\xC0\x01\x0A";
+		assert_eq!(probe(cr).0, DetectionStatus::NoMatch);
+	}
+
+	#[test]
+	fn test_reject_connect_without_mms() {
+		let (mut data, len) = packet(INITIATE);
+		// Replace both MMS object identifiers.
+		data[61] = 0x29;
+		data[87] = 0x29;
+		assert_eq!(probe(&data[..len]).0, DetectionStatus::NoMatch);
+	}
+}
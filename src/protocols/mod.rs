@@ -1,59 +1,248 @@
 /* src/protocols/mod.rs */
 
+/// AJP protocol detection.
+#[cfg(feature = "ajp")]
+pub(crate) mod ajp;
+/// AMQP protocol detection.
+#[cfg(feature = "amqp")]
+pub(crate) mod amqp;
+/// BitTorrent protocol detection.
+#[cfg(feature = "bittorrent")]
+pub(crate) mod bittorrent;
+/// ClickHouse protocol detection.
+#[cfg(feature = "clickhouse")]
+pub(crate) mod clickhouse;
+/// Ethereum devp2p protocol detection.
+#[cfg(feature = "devp2p")]
+pub(crate) mod devp2p;
 /// DHCP protocol detection.
 #[cfg(feature = "dhcp")]
 pub(crate) mod dhcp;
+/// BitTorrent DHT protocol detection.
+#[cfg(feature = "dht")]
+pub(crate) mod dht;
+/// DICOM protocol detection.
+#[cfg(feature = "dicom")]
+pub(crate) mod dicom;
 /// DNS protocol detection.
 #[cfg(feature = "dns")]
 pub(crate) mod dns;
+/// DNSCrypt protocol detection.
+#[cfg(feature = "dnscrypt")]
+pub(crate) mod dnscrypt;
 /// FTP protocol detection.
 #[cfg(feature = "ftp")]
 pub(crate) mod ftp;
+/// GENEVE protocol detection.
+#[cfg(feature = "geneve")]
+pub(crate) mod geneve;
+/// Git protocol detection.
+#[cfg(feature = "git")]
+pub(crate) mod git;
+/// gQUIC protocol detection.
+#[cfg(feature = "gquic")]
+pub(crate) mod gquic;
+/// Graphite protocol detection.
+#[cfg(feature = "graphite")]
+pub(crate) mod graphite;
+/// `gRPC` protocol detection.
+#[cfg(feature = "grpc")]
+pub(crate) mod grpc;
+/// H.225 protocol detection.
+#[cfg(feature = "h225")]
+pub(crate) mod h225;
 /// HTTP protocol detection.
 #[cfg(feature = "http")]
 pub(crate) mod http;
+/// HTTP/3 protocol detection.
+#[cfg(feature = "http3")]
+pub(crate) mod http3;
+/// ICAP protocol detection.
+#[cfg(feature = "icap")]
+pub(crate) mod icap;
+/// Ident protocol detection.
+#[cfg(feature = "ident")]
+pub(crate) mod ident;
 /// IMAP protocol detection.
 #[cfg(feature = "imap")]
 pub(crate) mod imap;
+/// `InfluxDB` line protocol detection.
+#[cfg(feature = "influx")]
+pub(crate) mod influx;
+/// IPP protocol detection.
+#[cfg(feature = "ipp")]
+pub(crate) mod ipp;
+/// IRC protocol detection.
+#[cfg(feature = "irc")]
+pub(crate) mod irc;
+/// JetDirect protocol detection.
+#[cfg(feature = "jetdirect")]
+pub(crate) mod jetdirect;
+/// JSON-RPC protocol detection.
+#[cfg(feature = "jsonrpc")]
+pub(crate) mod jsonrpc;
+/// Kerberos protocol detection.
+#[cfg(feature = "kerberos")]
+pub(crate) mod kerberos;
+/// LDAP protocol detection.
+#[cfg(feature = "ldap")]
+pub(crate) mod ldap;
+/// LoRaWAN protocol detection.
+#[cfg(feature = "lorawan")]
+pub(crate) mod lorawan;
+/// LPD protocol detection.
+#[cfg(feature = "lpd")]
+pub(crate) mod lpd;
+/// mDNS protocol detection.
+#[cfg(feature = "mdns")]
+pub(crate) mod mdns;
+/// Memcached protocol detection.
+#[cfg(feature = "memcached")]
+pub(crate) mod memcached;
+/// Minecraft protocol detection.
+#[cfg(feature = "minecraft")]
+pub(crate) mod minecraft;
+/// MMS protocol detection.
+#[cfg(feature = "mms")]
+pub(crate) mod mms;
 /// MQTT protocol detection.
 #[cfg(feature = "mqtt")]
 pub(crate) mod mqtt;
+/// MessagePack-RPC protocol detection.
+#[cfg(feature = "msgpack_rpc")]
+pub(crate) mod msgpack_rpc;
+/// Mumble protocol detection.
+#[cfg(feature = "mumble")]
+pub(crate) mod mumble;
 /// `MySQL` protocol detection.
 #[cfg(feature = "mysql")]
 pub(crate) mod mysql;
+/// `MySQL` X protocol detection.
+#[cfg(feature = "mysqlx")]
+pub(crate) mod mysqlx;
+/// NATS protocol detection.
+#[cfg(feature = "nats")]
+pub(crate) mod nats;
+/// NetBIOS protocol detection.
+#[cfg(feature = "netbios")]
+pub(crate) mod netbios;
 /// NTP protocol detection.
 #[cfg(feature = "ntp")]
 pub(crate) mod ntp;
+/// NVMe/TCP protocol detection.
+#[cfg(feature = "nvme_tcp")]
+pub(crate) mod nvme_tcp;
+/// OPC UA protocol detection.
+#[cfg(feature = "opcua")]
+pub(crate) mod opcua;
+/// OpenVPN protocol detection.
+#[cfg(feature = "openvpn")]
+pub(crate) mod openvpn;
 /// POP3 protocol detection.
 #[cfg(feature = "pop3")]
 pub(crate) mod pop3;
 /// `PostgreSQL` protocol detection.
 #[cfg(feature = "postgres")]
 pub(crate) mod postgres;
+/// HAProxy PROXY protocol detection.
+#[cfg(feature = "proxyproto")]
+pub(crate) mod proxyproto;
 /// QUIC protocol detection.
 #[cfg(feature = "quic")]
 pub(crate) mod quic;
+/// RakNet protocol detection.
+#[cfg(feature = "raknet")]
+pub(crate) mod raknet;
+/// RDP protocol detection.
+#[cfg(feature = "rdp")]
+pub(crate) mod rdp;
 /// Redis protocol detection.
 #[cfg(feature = "redis")]
 pub(crate) mod redis;
+/// RELP protocol detection.
+#[cfg(feature = "relp")]
+pub(crate) mod relp;
+/// Riak protocol detection.
+#[cfg(feature = "riak")]
+pub(crate) mod riak;
+/// RTCP protocol detection.
+#[cfg(feature = "rtcp")]
+pub(crate) mod rtcp;
+/// RTMP protocol detection.
+#[cfg(feature = "rtmp")]
+pub(crate) mod rtmp;
+/// RTP protocol detection.
+#[cfg(feature = "rtp")]
+pub(crate) mod rtp;
 /// RTSP protocol detection.
 #[cfg(feature = "rtsp")]
 pub(crate) mod rtsp;
+/// S7comm protocol detection.
+#[cfg(feature = "s7")]
+pub(crate) mod s7;
+/// SCGI protocol detection.
+#[cfg(feature = "scgi")]
+pub(crate) mod scgi;
 /// SIP protocol detection.
 #[cfg(feature = "sip")]
 pub(crate) mod sip;
 /// SMB protocol detection.
 #[cfg(feature = "smb")]
 pub(crate) mod smb;
+/// SMPP protocol detection.
+#[cfg(feature = "smpp")]
+pub(crate) mod smpp;
 /// SMTP protocol detection.
 #[cfg(feature = "smtp")]
 pub(crate) mod smtp;
+/// SOCKS4 protocol detection.
+#[cfg(feature = "socks4")]
+pub(crate) mod socks4;
+/// Source engine query protocol detection.
+#[cfg(feature = "source_query")]
+pub(crate) mod source_query;
+/// SPDY protocol detection.
+#[cfg(feature = "spdy")]
+pub(crate) mod spdy;
 /// SSH protocol detection.
 #[cfg(feature = "ssh")]
 pub(crate) mod ssh;
 /// STUN protocol detection.
 #[cfg(feature = "stun")]
 pub(crate) mod stun;
+/// ONC RPC (Sun RPC) protocol detection.
+#[cfg(feature = "sunrpc")]
+pub(crate) mod sunrpc;
+/// Subversion (`svnserve`) protocol detection.
+#[cfg(feature = "svn")]
+pub(crate) mod svn;
+/// Tarantool protocol detection.
+#[cfg(feature = "tarantool")]
+pub(crate) mod tarantool;
+/// TDS protocol detection.
+#[cfg(feature = "tds")]
+pub(crate) mod tds;
+/// Telnet protocol detection.
+#[cfg(feature = "telnet")]
+pub(crate) mod telnet;
+/// Thrift protocol detection.
+#[cfg(feature = "thrift")]
+pub(crate) mod thrift;
 /// TLS protocol detection.
 #[cfg(feature = "tls")]
 pub(crate) mod tls;
+/// TURN protocol detection.
+#[cfg(feature = "turn")]
+pub(crate) mod turn;
+/// VXLAN protocol detection.
+#[cfg(feature = "vxlan")]
+pub(crate) mod vxlan;
+/// WebSocket protocol detection.
+#[cfg(any(feature = "http", feature = "websocket"))]
+pub(crate) mod websocket;
+/// XMPP protocol detection.
+#[cfg(feature = "xmpp")]
+pub(crate) mod xmpp;
+/// ZooKeeper protocol detection.
+#[cfg(feature = "zookeeper")]
+pub(crate) mod zookeeper;
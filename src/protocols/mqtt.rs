@@ -6,10 +6,12 @@
 #[inline(always)]
 pub(crate) fn detect(data: &[u8]) -> bool {
 	if data.len() < 12 {
+		reject!("too_short");
 		return false;
 	}
 
 	if data[0] != 0x10 {
+		reject!("not_connect_packet");
 		return false;
 	}
 
@@ -30,22 +32,26 @@ pub(crate) fn detect(data: &[u8]) -> bool {
 	}
 
 	if !found_len || data.len() < offset + 2 {
+		reject!("remaining_length_invalid");
 		return false;
 	}
 
 	let name_len = u16::from_be_bytes([data[offset], data[offset + 1]]) as usize;
 
 	if (remaining_length as usize) < name_len + 6 {
+		reject!("remaining_length_too_small");
 		return false;
 	}
 
 	offset += 2;
 
 	if name_len != 4 && name_len != 6 {
+		reject!("protocol_name_length_invalid");
 		return false;
 	}
 
 	if data.len() < offset + name_len + 1 {
+		reject!("protocol_name_truncated");
 		return false;
 	}
 
@@ -55,7 +61,10 @@ pub(crate) fn detect(data: &[u8]) -> bool {
 	match name {
 		b"MQTT" => level == 4 || level == 5,
 		b"MQIsdp" => level == 3,
-		_ => false,
+		_ => {
+			reject!("protocol_name_unknown");
+			false
+		}
 	}
 }
 
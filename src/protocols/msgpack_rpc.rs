@@ -0,0 +1,282 @@
+/* src/protocols/msgpack_rpc.rs */
+use crate::{DetectionStatus, ProtocolVersion};
+
+/// Fixarray header of a four-element request.
+const REQUEST_ARRAY: u8 = 0x94;
+
+/// Fixarray header of a three-element notification.
+const NOTIFICATION_ARRAY: u8 = 0x93;
+
+/// Longest method name accepted.
+const MAX_METHOD_LEN: usize = 256;
+
+/// Deepest nesting the walker follows in the parameters.
+const MAX_DEPTH: usize = 8;
+
+/// Most values the walker visits before accepting what it has seen.
+const MAX_VALUES: usize = 64;
+
+/// Outcome of walking the parameters.
+enum Walk {
+	/// The value ends just before this offset.
+	End(usize),
+	/// The value runs past the end of the data or the walk budget.
+	Truncated,
+	/// The bytes are not valid MessagePack.
+	Invalid,
+}
+
+/// Probes for a MessagePack-RPC request or notification.
+///
+/// A request is `[0, msgid, method, params]` and a notification `[2, method,
+/// params]`: a fixarray header, the message type, a non-negative integer
+/// msgid (requests only), a printable method name, and a parameter array.
+/// The parameters are walked with a depth- and size-bounded MessagePack
+/// reader.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	match parse(data) {
+		Ok(()) => (DetectionStatus::Match, ProtocolVersion::Unknown),
+		Err(status) => (status, ProtocolVersion::Unknown),
+	}
+}
+
+/// Parses the message envelope and walks the parameters.
+#[inline(always)]
+fn parse(data: &[u8]) -> Result<(), DetectionStatus> {
+	let Some(&[header, kind]) = data.first_chunk::<2>() else {
+		return Err(DetectionStatus::Incomplete);
+	};
+	let mut pos = match (header, kind) {
+		(REQUEST_ARRAY, 0x00) => msgid(data, 2)?,
+		(NOTIFICATION_ARRAY, 0x02) => 2,
+		_ => {
+			reject!("not_rpc_envelope");
+			return Err(DetectionStatus::NoMatch);
+		}
+	};
+	pos = method(data, pos)?;
+
+	match data.get(pos) {
+		None => Err(DetectionStatus::Incomplete),
+		Some(0x90..=0x9F | 0xDC | 0xDD) => {
+			let mut budget = MAX_VALUES;
+			match walk(data, pos, 0, &mut budget) {
+				Walk::End(_) | Walk::Truncated => Ok(()),
+				Walk::Invalid => {
+					reject!("invalid_msgpack_params");
+					Err(DetectionStatus::NoMatch)
+				}
+			}
+		}
+		Some(_) => {
+			reject!("params_not_array");
+			Err(DetectionStatus::NoMatch)
+		}
+	}
+}
+
+/// Checks a non-negative integer msgid at `pos`, returning the offset after it.
+#[inline(always)]
+fn msgid(data: &[u8], pos: usize) -> Result<usize, DetectionStatus> {
+	let len = match data.get(pos) {
+		None => return Err(DetectionStatus::Incomplete),
+		Some(0x00..=0x7F) => 1,
+		Some(0xCC) => 2,
+		Some(0xCD) => 3,
+		Some(0xCE) => 5,
+		Some(_) => {
+			reject!("invalid_msgid");
+			return Err(DetectionStatus::NoMatch);
+		}
+	};
+	Ok(pos + len)
+}
+
+/// Checks the method name string at `pos`, returning the offset after it.
+#[inline(always)]
+fn method(data: &[u8], pos: usize) -> Result<usize, DetectionStatus> {
+	let (header_len, len) = match data.get(pos) {
+		None => return Err(DetectionStatus::Incomplete),
+		Some(&b @ 0xA1..=0xBF) => (1, usize::from(b & 0x1F)),
+		Some(0xD9) => match data.get(pos + 1) {
+			None => return Err(DetectionStatus::Incomplete),
+			Some(&len) => (2, usize::from(len)),
+		},
+		Some(_) => {
+			reject!("method_not_string");
+			return Err(DetectionStatus::NoMatch);
+		}
+	};
+	if len == 0 || len > MAX_METHOD_LEN {
+		reject!("invalid_method_length");
+		return Err(DetectionStatus::NoMatch);
+	}
+	let start = pos + header_len;
+	let end = start + len;
+	let name = &data[start.min(data.len())..end.min(data.len())];
+	if !name.iter().all(u8::is_ascii_graphic) {
+		reject!("non_printable_method");
+		return Err(DetectionStatus::NoMatch);
+	}
+	if data.len() < end {
+		return Err(DetectionStatus::Incomplete);
+	}
+	Ok(end)
+}
+
+/// Walks one MessagePack value at `pos`.
+fn walk(data: &[u8], pos: usize, depth: usize, budget: &mut usize) -> Walk {
+	if *budget == 0 {
+		return Walk::Truncated;
+	}
+	*budget -= 1;
+	let Some(&b) = data.get(pos) else {
+		return Walk::Truncated;
+	};
+	let (header, body, items) = match b {
+		0x00..=0x7F | 0xC0 | 0xC2 | 0xC3 | 0xE0..=0xFF => (1, 0, 0),
+		0x80..=0x8F => (1, 0, usize::from(b & 0x0F) * 2),
+		0x90..=0x9F => (1, 0, usize::from(b & 0x0F)),
+		0xA0..=0xBF => (1, usize::from(b & 0x1F), 0),
+		// Fixed-size numbers and fixext (a type byte and 1..=16 data bytes).
+		0xCC | 0xD0 => (2, 0, 0),
+		0xCD | 0xD1 | 0xD4 => (3, 0, 0),
+		0xD5 => (4, 0, 0),
+		0xCA | 0xCE | 0xD2 => (5, 0, 0),
+		0xD6 => (6, 0, 0),
+		0xCB | 0xCF | 0xD3 => (9, 0, 0),
+		0xD7 => (10, 0, 0),
+		0xD8 => (18, 0, 0),
+		// bin 8/16/32, str 8/16/32, ext 8/16/32: a length, then the data
+		// (ext adds a type byte).
+		0xC4 | 0xD9 | 0xC5 | 0xDA | 0xC6 | 0xDB | 0xC7 | 0xC8 | 0xC9 => {
+			let (len_len, ext) = match b {
+				0xC4 | 0xD9 => (1, 0),
+				0xC5 | 0xDA => (2, 0),
+				0xC6 | 0xDB => (4, 0),
+				0xC7 => (1, 1),
+				0xC8 => (2, 1),
+				_ => (4, 1),
+			};
+			let Some(len) = read_len(data, pos + 1, len_len) else {
+				return Walk::Truncated;
+			};
+			(1 + len_len + ext, len, 0)
+		}
+		0xDC | 0xDE => {
+			let Some(len) = read_len(data, pos + 1, 2) else {
+				return Walk::Truncated;
+			};
+			(
+				3,
+				0,
+				if b == 0xDE {
+					len.saturating_mul(2)
+				} else {
+					len
+				},
+			)
+		}
+		0xDD | 0xDF => {
+			let Some(len) = read_len(data, pos + 1, 4) else {
+				return Walk::Truncated;
+			};
+			(
+				5,
+				0,
+				if b == 0xDF {
+					len.saturating_mul(2)
+				} else {
+					len
+				},
+			)
+		}
+		// 0xC1 is never used.
+		_ => return Walk::Invalid,
+	};
+
+	let mut end = (pos + header).saturating_add(body);
+	if items > 0 && depth >= MAX_DEPTH {
+		return Walk::Truncated;
+	}
+	for _ in 0..items {
+		match walk(data, end, depth + 1, budget) {
+			Walk::End(next) => end = next,
+			other => return other,
+		}
+	}
+	if end > data.len() {
+		return Walk::Truncated;
+	}
+	Walk::End(end)
+}
+
+/// Reads a big-endian length of `len_len` bytes at `pos`.
+#[inline(always)]
+fn read_len(data: &[u8], pos: usize, len_len: usize) -> Option<usize> {
+	Some(
+		data
+			.get(pos..pos + len_len)?
+			.iter()
+			.fold(0, |acc, &b| (acc << 8) | usize::from(b)),
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// `[0, 1, "add", [1, 2]]`
+	const REQUEST: &[u8] = b"\x94\x00\x01\xA3add\x92\x01\x02";
+
+	#[test]
+	fn test_detect_request() {
+		assert_eq!(
+			probe(REQUEST),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+		// `[0, 70000, "nvim_get_api_info", []]`
+		assert_eq!(
+			probe(b"\x94\x00\xCE\x00\x01\x11\x70\xB1nvim_get_api_info\x90").0,
+			DetectionStatus::Match
+		);
+	}
+
+	#[test]
+	fn test_detect_notification_with_nested_params() {
+		// `[2, "log", [{"level": "info", "tags": ["a"]}, 1.5]]`
+		let data = b"\x93\x02\xA3log\x92\x82\xA5level\xA4info\xA4tags\x91\xA1a\xCB\x3F\xF8\x00\x00\x00\x00\x00\x00";
+		assert_eq!(probe(data).0, DetectionStatus::Match);
+		// Parameters cut off by the inspect window still match.
+		assert_eq!(probe(&data[..12]).0, DetectionStatus::Match);
+	}
+
+	#[test]
+	fn test_incomplete_envelope() {
+		assert_eq!(probe(&REQUEST[..1]).0, DetectionStatus::Incomplete);
+		assert_eq!(probe(&REQUEST[..5]).0, DetectionStatus::Incomplete);
+		assert_eq!(probe(&REQUEST[..6]).0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	fn test_reject_invalid_messages() {
+		// Response type.
+		assert_eq!(probe(b"\x94\x01\x01\xC0\x03").0, DetectionStatus::NoMatch);
+		// Negative msgid, integer method, non-array params.
+		assert_eq!(
+			probe(b"\x94\x00\xFF\xA3add\x90").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(probe(b"\x94\x00\x01\x05\x90").0, DetectionStatus::NoMatch);
+		assert_eq!(
+			probe(b"\x94\x00\x01\xA3add\x01").0,
+			DetectionStatus::NoMatch
+		);
+		// The never-used 0xC1 inside the parameters.
+		assert_eq!(
+			probe(b"\x94\x00\x01\xA3add\x91\xC1").0,
+			DetectionStatus::NoMatch
+		);
+	}
+}
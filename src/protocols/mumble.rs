@@ -0,0 +1,134 @@
+/* src/protocols/mumble.rs */
+use crate::{DetectionStatus, ProtocolVersion};
+
+/// Length of the control message header: type and payload length.
+const HEADER_LEN: usize = 6;
+
+/// Length of a UDP ping: a zero type word and an 8-byte timestamp.
+const PING_LEN: usize = 12;
+
+/// Largest `Version` payload accepted.
+const MAX_VERSION_LEN: u32 = 1024;
+
+/// Legacy `version_v1` values accepted: `major << 16 | minor << 8 | patch`
+/// with major version 1 or 2.
+const VERSION_V1: core::ops::RangeInclusive<u64> = 0x1_0000..=0x2_FFFF;
+
+/// Probes for Mumble.
+///
+/// The control channel runs inside TLS, so its first message is only
+/// visible to callers that hand over decrypted data, such as a
+/// TLS-terminating proxy: a `Version` message (type 0, a short payload
+/// length, then a protobuf body starting with a known field and a plausible
+/// legacy version). On UDP, matches the 12-byte ping a client sends to query
+/// a server: a zero type word followed by a timestamp.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	(probe_status(data), ProtocolVersion::Unknown)
+}
+
+/// Checks for a UDP ping, then for a control channel `Version` message.
+#[inline(always)]
+fn probe_status(data: &[u8]) -> DetectionStatus {
+	if data.len() == PING_LEN && data[..4] == [0; 4] {
+		return DetectionStatus::Match;
+	}
+	let Some(header) = data.first_chunk::<HEADER_LEN>() else {
+		return if data.iter().take(2).all(|&b| b == 0) {
+			DetectionStatus::Incomplete
+		} else {
+			reject!("not_version_message");
+			DetectionStatus::NoMatch
+		};
+	};
+	let len = u32::from_be_bytes([header[2], header[3], header[4], header[5]]);
+	if header[..2] != [0, 0] || !(2..=MAX_VERSION_LEN).contains(&len) {
+		reject!("not_version_message");
+		return DetectionStatus::NoMatch;
+	}
+	let body = &data[HEADER_LEN..];
+	match body.first() {
+		None => DetectionStatus::Incomplete,
+		// version_v1.
+		Some(0x08) => match var_int(&body[1..]) {
+			Some(version) if VERSION_V1.contains(&version) => DetectionStatus::Match,
+			None if body.len() < 11 => DetectionStatus::Incomplete,
+			_ => {
+				reject!("implausible_version");
+				DetectionStatus::NoMatch
+			}
+		},
+		// release, os, os_version (strings) and version_v2.
+		Some(0x12 | 0x1A | 0x22 | 0x28) => DetectionStatus::Match,
+		Some(_) => {
+			reject!("unexpected_field");
+			DetectionStatus::NoMatch
+		}
+	}
+}
+
+/// Reads a protobuf varint, or returns `None` if it is cut off or too long.
+#[inline(always)]
+fn var_int(data: &[u8]) -> Option<u64> {
+	let mut value = 0u64;
+	for (i, &byte) in data.iter().take(10).enumerate() {
+		value |= u64::from(byte & 0x7F) << (7 * i);
+		if byte & 0x80 == 0 {
+			return Some(value);
+		}
+	}
+	None
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// `Version` from a Mumble 1.4.287 client on Linux.
+	const VERSION_MESSAGE: &[u8] =
+		b"\x00\x00\x00\x00\x00\x1D\x08\xFF\x89\x04\x12\x071.4.287\x1A\x05Linux\x22\x07Fedora ";
+
+	#[test]
+	fn test_detect_version_message() {
+		assert_eq!(
+			probe(VERSION_MESSAGE),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+		// version_v2 only.
+		assert_eq!(
+			probe(b"\x00\x00\x00\x00\x00\x06\x28\x80\x80\x80\x80\x80").0,
+			DetectionStatus::Match
+		);
+	}
+
+	#[test]
+	fn test_detect_udp_ping() {
+		let ping = b"\x00\x00\x00\x00\x00\x00\x01\x8F\x3A\x2B\x1C\x0D";
+		assert_eq!(probe(ping).0, DetectionStatus::Match);
+	}
+
+	#[test]
+	fn test_incomplete_message() {
+		assert_eq!(probe(b"\x00\x00\x00").0, DetectionStatus::Incomplete);
+		assert_eq!(probe(&VERSION_MESSAGE[..6]).0, DetectionStatus::Incomplete);
+		assert_eq!(probe(&VERSION_MESSAGE[..8]).0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	fn test_reject_other_messages() {
+		// UDPTunnel (type 1), an oversized payload, a version 9.x client.
+		assert_eq!(
+			probe(b"\x00\x01\x00\x00\x00\x10\x08\x87\x88\x04").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(
+			probe(b"\x00\x00\x00\x01\x00\x00\x08\x87\x88\x04").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(
+			probe(b"\x00\x00\x00\x00\x00\x10\x08\x80\x80\x24").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(probe(b"GET / HTTP/1.1").0, DetectionStatus::NoMatch);
+	}
+}
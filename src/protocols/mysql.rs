@@ -6,19 +6,23 @@
 #[inline(always)]
 pub(crate) fn detect(data: &[u8]) -> bool {
 	if data.len() < 10 {
+		reject!("too_short");
 		return false;
 	}
 
 	let payload_len = u32::from_le_bytes([data[0], data[1], data[2], 0]);
 	if !(30..=1024).contains(&payload_len) {
+		reject!("payload_length_out_of_range");
 		return false;
 	}
 
 	if data[3] != 0 {
+		reject!("sequence_id_nonzero");
 		return false;
 	}
 
 	if data[4] != 0x0A {
+		reject!("protocol_version_not_10");
 		return false;
 	}
 
@@ -30,6 +34,7 @@ pub(crate) fn detect(data: &[u8]) -> bool {
 			break;
 		}
 		if !(32..=126).contains(&b) {
+			reject!("non_printable_server_version");
 			return false;
 		}
 	}
@@ -39,6 +44,7 @@ pub(crate) fn detect(data: &[u8]) -> bool {
 	};
 
 	if data.len() > idx + 13 && data[idx + 13] != 0 {
+		reject!("filler_nonzero");
 		return false;
 	}
 
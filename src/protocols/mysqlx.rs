@@ -0,0 +1,94 @@
+/* src/protocols/mysqlx.rs */
+use crate::{
+	DetectionStatus, ProtocolVersion,
+	protobuf::{FramedProtobuf, LengthPrefix},
+};
+
+/// Message types from `Mysqlx.ClientMessages`.
+///
+/// The client speaks first, so server types are left out. Type 0 is
+/// unassigned, which keeps DICOM PDUs (a type byte and a zero reserved byte
+/// read as a length of 1) out, and type 10 is unassigned, which keeps the
+/// classic protocol greeting (protocol version 10 after a three-byte length
+/// and sequence 0) out.
+const MESSAGE_TYPES: &[(u8, u8)] = &[(1, 7), (12, 12), (17, 20), (24, 25), (30, 32), (40, 46)];
+
+/// Largest frame length accepted. A client opens with capability,
+/// authentication or statement messages far below 1 MiB, and the cap keeps
+/// MessagePack arrays and other binary headers from reading as lengths.
+const MAX_FRAME_LEN: u32 = 1024 * 1024;
+
+/// X Protocol framing: a four-byte little-endian length counting the type
+/// byte, then the protobuf message.
+const MYSQLX: FramedProtobuf = FramedProtobuf::new()
+	.length_prefix(LengthPrefix::U32Le)
+	.message_codes(MESSAGE_TYPES)
+	.max_length(MAX_FRAME_LEN)
+	.max_field_number(16);
+
+/// Probes for the `MySQL` X Protocol.
+///
+/// Expects a little-endian length up to 1 MiB, a client message type, and
+/// well-formed leading protobuf fields, the first one fully in view, as in `CapabilitiesGet` (an empty
+/// message) or `CapabilitiesSet` and `AuthenticateStart`.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	(MYSQLX.probe(data), ProtocolVersion::Unknown)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_detect_capabilities_get() {
+		assert_eq!(
+			probe(&[0x01, 0x00, 0x00, 0x00, 0x01]),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+	}
+
+	#[test]
+	fn test_detect_authenticate_start() {
+		// AuthenticateStart { mech_name: "PLAIN", auth_data: "\0root\0" }
+		let data = b"\x10\x00\x00\x00\x04\x0A\x05PLAIN\x12\x06\x00root\x00";
+		assert_eq!(probe(data).0, DetectionStatus::Match);
+		assert_eq!(probe(&data[..5]).0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	fn test_reject_classic_mysql_greeting() {
+		let data = b"\x4A\x00\x00\x00\x0A8.0.36\x00\x08\x00\x00\x00";
+		assert_eq!(probe(data).0, DetectionStatus::NoMatch);
+	}
+
+	#[test]
+	fn test_reject_bad_fields() {
+		// Field number 0 after CapabilitiesSet.
+		assert_eq!(
+			probe(&[0x03, 0x00, 0x00, 0x00, 0x02, 0x00, 0x01]).0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(probe(b"GET / HTTP/1.1\r\n").0, DetectionStatus::NoMatch);
+	}
+
+	#[test]
+	fn test_reject_other_protocols() {
+		// DICOM A-ASSOCIATE-RQ: a length of 1 and type 0.
+		let dicom = [0x01, 0x00, 0x00, 0x00, 0x00, 0xCD, 0x00, 0x01, 0x00, 0x00];
+		assert_eq!(probe(&dicom).0, DetectionStatus::NoMatch);
+		// MessagePack-RPC requests read as lengths above the cap.
+		for msgpack in [
+			&b"\x94\x00\x01\xA3add\x92\x01\x02"[..],
+			b"\x94\x00\xCE\x00\x01\x11\x70\xB1nvim_get_api_info\x90",
+			b"\x94\x00\x01\x05\x90",
+		] {
+			assert_eq!(probe(msgpack).0, DetectionStatus::NoMatch);
+		}
+		// A server message type.
+		assert_eq!(
+			probe(&[0x01, 0x00, 0x00, 0x00, 0x0B]).0,
+			DetectionStatus::NoMatch
+		);
+	}
+}
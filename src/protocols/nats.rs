@@ -0,0 +1,139 @@
+/* src/protocols/nats.rs */
+
+/// Detects the NATS client protocol.
+///
+/// Recognizes the server's `INFO {...}` banner, the client's `CONNECT {...}`
+/// handshake, and the `PING` and `SUB` commands.
+#[inline(always)]
+pub(crate) fn detect(data: &[u8]) -> bool {
+	if data.len() < 6 {
+		reject!("too_short");
+		return false;
+	}
+
+	if data.starts_with(b"INFO ") {
+		return validate_json_line(&data[5..]);
+	}
+	if data.starts_with(b"CONNECT ") {
+		return validate_json_line(&data[8..]);
+	}
+	if data.starts_with(b"PING\r\n") {
+		return true;
+	}
+	if data.starts_with(b"SUB ") {
+		return validate_sub(&data[4..]);
+	}
+
+	reject!("unknown_command");
+	false
+}
+
+/// Checks that `rest` opens a JSON object and, if the line ends in the window,
+/// that it closes one.
+#[inline(always)]
+fn validate_json_line(rest: &[u8]) -> bool {
+	let Some(line) = printable_line(rest) else {
+		return false;
+	};
+	if !line.starts_with(b"{\"") && !line.starts_with(b"{}") {
+		reject!("payload_not_json_object");
+		return false;
+	}
+	if line.len() < rest.len() && !line.ends_with(b"}") {
+		reject!("payload_not_json_object");
+		return false;
+	}
+	true
+}
+
+/// Checks `SUB <subject> [queue group] <sid>`, counting arguments only when
+/// the line ends in the window.
+#[inline(always)]
+fn validate_sub(rest: &[u8]) -> bool {
+	let Some(line) = printable_line(rest) else {
+		return false;
+	};
+	if line.first().is_none_or(|&b| b == b' ') {
+		reject!("missing_subject");
+		return false;
+	}
+	if line.len() == rest.len() {
+		return true;
+	}
+	let args = line
+		.split(|&b| b == b' ')
+		.filter(|arg| !arg.is_empty())
+		.count();
+	if !(2..=3).contains(&args) {
+		reject!("wrong_sub_arguments");
+		return false;
+	}
+	true
+}
+
+/// Returns the line before `\r\n`, or everything up to the 64-byte window if
+/// it does not end there. Rejects non-printable bytes.
+#[inline(always)]
+fn printable_line(data: &[u8]) -> Option<&[u8]> {
+	let limit = data.len().min(64);
+	for (i, &b) in data[..limit].iter().enumerate() {
+		if b == b'\r' {
+			return Some(&data[..i]);
+		}
+		if !(32..=126).contains(&b) {
+			reject!("non_printable_byte");
+			return None;
+		}
+	}
+	Some(&data[..limit])
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_detect_nats_server_info() {
+		assert!(detect(
+			b"INFO {\"server_id\":\"NCXYZ\",\"version\":\"2.10.7\",\"proto\":1}\r\n"
+		));
+		assert!(detect(
+			b"INFO {\"server_id\":\"NCXYZ\",\"server_name\":\"nats-0\",\"version\""
+		));
+	}
+
+	#[test]
+	fn test_detect_nats_client_commands() {
+		assert!(detect(
+			b"CONNECT {\"verbose\":false,\"pedantic\":false}\r\n"
+		));
+		assert!(detect(b"CONNECT {}\r\n"));
+		assert!(detect(b"PING\r\n"));
+		assert!(detect(b"SUB orders.* 1\r\n"));
+		assert!(detect(b"SUB orders.* workers 42\r\n"));
+	}
+
+	#[test]
+	fn test_reject_sip_info() {
+		assert!(!detect(b"INFO sip:alice@example.com SIP/2.0\r\n"));
+	}
+
+	#[test]
+	fn test_reject_malformed_json() {
+		assert!(!detect(b"INFO {\"server_id\":\"x\"\r\n"));
+		assert!(!detect(b"CONNECT [1,2]\r\n"));
+	}
+
+	#[test]
+	fn test_reject_malformed_sub() {
+		assert!(!detect(b"SUB orders\r\n"));
+		assert!(!detect(b"SUB a b c d\r\n"));
+		assert!(!detect(b"SUB  1\r\n"));
+	}
+
+	#[test]
+	fn test_short_data() {
+		assert!(!detect(b"PING"));
+		assert!(!detect(b"INFO"));
+	}
+}
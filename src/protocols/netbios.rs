@@ -0,0 +1,168 @@
+/* src/protocols/netbios.rs */
+
+/// Session Service message type of a session request.
+const SESSION_REQUEST: u8 = 0x81;
+
+/// Length byte of a first-level encoded NetBIOS name.
+const ENCODED_NAME_LEN: u8 = 0x20;
+
+/// Name Service record types: general name (`NB`) and node status
+/// (`NBSTAT`).
+const RECORD_TYPES: [[u8; 2]; 2] = [[0x00, 0x20], [0x00, 0x21]];
+
+/// Internet record class.
+const CLASS_IN: [u8; 2] = [0x00, 0x01];
+
+/// Detects NetBIOS over TCP/IP (RFC 1002).
+///
+/// Recognizes Name Service packets (a DNS-like header whose first name is a
+/// 32-byte first-level encoding using only `A`-`P`, followed by an `NB` or
+/// `NBSTAT` record of class `IN` when no scope is appended) and Session Service
+/// session requests (type 0x81 carrying encoded called and calling names).
+#[inline(always)]
+pub(crate) fn detect(data: &[u8]) -> bool {
+	if data.len() < 37 {
+		reject!("too_short");
+		return false;
+	}
+
+	if data[0] == SESSION_REQUEST && data[1] & 0xFE == 0 && data[4] == ENCODED_NAME_LEN {
+		return detect_session_request(data);
+	}
+	detect_name_service(data)
+}
+
+/// Checks a session request: both names encoded and the length consistent.
+#[inline(always)]
+fn detect_session_request(data: &[u8]) -> bool {
+	let length = u16::from_be_bytes([data[2], data[3]]);
+	if !(68..=512).contains(&length) {
+		reject!("implausible_session_length");
+		return false;
+	}
+	// Called name, its scope, then the calling name.
+	if !is_encoded_name(&data[5..data.len().min(37)])
+		|| data.get(37).is_some_and(|&b| b != 0)
+		|| data.get(38).is_some_and(|&b| b != ENCODED_NAME_LEN)
+		|| !is_encoded_name(&data[data.len().min(39)..data.len().min(71)])
+	{
+		reject!("invalid_encoded_name");
+		return false;
+	}
+	true
+}
+
+/// Checks a name service packet: opcode, counts, the encoded first name,
+/// and its record type and class.
+#[inline(always)]
+fn detect_name_service(data: &[u8]) -> bool {
+	let opcode = (data[2] >> 3) & 0x0F;
+	if !matches!(opcode, 0 | 5 | 6 | 7 | 8) {
+		reject!("invalid_opcode");
+		return false;
+	}
+	let qdcount = u16::from_be_bytes([data[4], data[5]]);
+	let ancount = u16::from_be_bytes([data[6], data[7]]);
+	if u32::from(qdcount) + u32::from(ancount) != 1 {
+		reject!("unexpected_record_counts");
+		return false;
+	}
+	if data[12] != ENCODED_NAME_LEN || !is_encoded_name(&data[13..data.len().min(45)]) {
+		reject!("invalid_encoded_name");
+		return false;
+	}
+	// A NetBIOS scope, rarely used, would push the type and class further.
+	if data.get(45) == Some(&0) {
+		let record_type = data.get(46..48);
+		let class = data.get(48..50);
+		if record_type.is_some_and(|t| !RECORD_TYPES.iter().any(|known| known == t))
+			|| class.is_some_and(|c| c != CLASS_IN)
+		{
+			reject!("unexpected_record_type");
+			return false;
+		}
+	}
+	true
+}
+
+/// Returns whether every byte is a first-level encoding nibble (`A`-`P`).
+#[inline(always)]
+fn is_encoded_name(name: &[u8]) -> bool {
+	name.iter().all(|b| (b'A'..=b'P').contains(b))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// `*` padded with NULs: the wildcard name used by node status queries.
+	const WILDCARD: &[u8; 32] = b"CKAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA";
+
+	/// `WORKSTATION` padded with spaces, suffix 0x00.
+	const WORKSTATION: &[u8; 32] = b"FHEPFCELEHFCEPFFFACACACACACACAAA";
+
+	#[test]
+	fn test_detect_name_query() {
+		let mut data = [0u8; 50];
+		data[..12].copy_from_slice(&[0x80, 0x94, 0x00, 0x00, 0x00, 0x01, 0, 0, 0, 0, 0, 0]);
+		data[12] = 0x20;
+		data[13..45].copy_from_slice(WILDCARD);
+		data[46..50].copy_from_slice(&[0x00, 0x21, 0x00, 0x01]);
+		assert!(detect(&data));
+	}
+
+	#[test]
+	fn test_detect_name_registration() {
+		let mut data = [0u8; 50];
+		data[..12].copy_from_slice(&[0x12, 0x34, 0x29, 0x10, 0x00, 0x01, 0, 0, 0, 0, 0, 1]);
+		data[12] = 0x20;
+		data[13..45].copy_from_slice(WORKSTATION);
+		data[46..50].copy_from_slice(&[0x00, 0x20, 0x00, 0x01]);
+		assert!(detect(&data));
+	}
+
+	#[test]
+	fn test_detect_session_request() {
+		let mut data = [0u8; 72];
+		data[..4].copy_from_slice(&[0x81, 0x00, 0x00, 0x44]);
+		data[4] = 0x20;
+		data[5..37].copy_from_slice(WORKSTATION);
+		data[38] = 0x20;
+		data[39..71].copy_from_slice(WILDCARD);
+		assert!(detect(&data));
+		assert!(detect(&data[..64]));
+	}
+
+	#[test]
+	fn test_reject_dns_query() {
+		let mut data = [0u8; 40];
+		data[..12].copy_from_slice(&[0x12, 0x34, 0x01, 0x00, 0x00, 0x01, 0, 0, 0, 0, 0, 0]);
+		data[12] = 0x06;
+		data[13..19].copy_from_slice(b"google");
+		assert!(!detect(&data));
+	}
+
+	#[test]
+	fn test_reject_bad_names() {
+		let mut data = [0u8; 50];
+		data[5] = 0x01;
+		data[12] = 0x20;
+		data[13..45].copy_from_slice(WILDCARD);
+		data[20] = b'Z';
+		assert!(!detect(&data));
+
+		// An encoded name followed by an A record of class IN.
+		let mut data = [0u8; 50];
+		data[5] = 0x01;
+		data[12] = 0x20;
+		data[13..45].copy_from_slice(WORKSTATION);
+		data[46..50].copy_from_slice(&[0x00, 0x01, 0x00, 0x01]);
+		assert!(!detect(&data));
+
+		let mut data = [0u8; 72];
+		data[..5].copy_from_slice(&[0x81, 0x00, 0x00, 0x44, 0x20]);
+		data[5..37].copy_from_slice(WORKSTATION);
+		data[38] = 0x20;
+		assert!(!detect(&data));
+	}
+}
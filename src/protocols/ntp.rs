@@ -4,6 +4,7 @@
 #[inline(always)]
 pub(crate) fn detect(data: &[u8]) -> bool {
 	if data.len() < 48 {
+		reject!("too_short");
 		return false;
 	}
 
@@ -12,25 +13,30 @@ pub(crate) fn detect(data: &[u8]) -> bool {
 	let mode = first_byte & 0x07;
 
 	if !(1..=4).contains(&vn) {
+		reject!("version_invalid");
 		return false;
 	}
 
 	if !(1..=7).contains(&mode) {
+		reject!("mode_invalid");
 		return false;
 	}
 
 	let stratum = data[1];
 	if stratum > 16 {
+		reject!("stratum_invalid");
 		return false;
 	}
 
 	let poll = data[2];
 	if poll > 20 {
+		reject!("poll_invalid");
 		return false;
 	}
 
 	let precision = data[3] as i8;
 	if !(-32..=16).contains(&precision) {
+		reject!("precision_invalid");
 		return false;
 	}
 
@@ -42,6 +48,7 @@ pub(crate) fn detect(data: &[u8]) -> bool {
 		}
 	}
 	if all_zero_transmit {
+		reject!("transmit_timestamp_zero");
 		return false;
 	}
 
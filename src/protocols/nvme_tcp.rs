@@ -0,0 +1,90 @@
+/* src/protocols/nvme_tcp.rs */
+
+/// PDU type of an Initialize Connection Request.
+const IC_REQ: u8 = 0x00;
+
+/// Header and total length of an ICReq PDU.
+const IC_REQ_LEN: u8 = 128;
+
+/// Largest host PDU data alignment (HPDA is a 5-bit field).
+const MAX_HPDA: u8 = 31;
+
+/// Detects NVMe over TCP connection setup.
+///
+/// Matches the ICReq PDU: type 0x00, no flags, a 128-byte header with no
+/// data offset and a PDU length of 128, PDU format version 0, a valid data
+/// alignment and digest selection, and zeroed reserved bytes.
+#[inline(always)]
+pub(crate) fn detect(data: &[u8]) -> bool {
+	if data.len() < 16 {
+		reject!("too_short");
+		return false;
+	}
+	if data[..4] != [IC_REQ, 0x00, IC_REQ_LEN, 0x00] {
+		reject!("not_icreq_header");
+		return false;
+	}
+	if data[4..8] != u32::from(IC_REQ_LEN).to_le_bytes() {
+		reject!("pdu_length_mismatch");
+		return false;
+	}
+	if data[8..10] != [0, 0] {
+		reject!("unsupported_pdu_format_version");
+		return false;
+	}
+	if data[10] > MAX_HPDA || data[11] & !0x03 != 0 {
+		reject!("invalid_alignment_or_digest");
+		return false;
+	}
+	if data[16..data.len().min(usize::from(IC_REQ_LEN))]
+		.iter()
+		.any(|&b| b != 0)
+	{
+		reject!("reserved_bytes_nonzero");
+		return false;
+	}
+	true
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// ICReq with header and data digests enabled and MAXR2T 0.
+	fn icreq() -> [u8; 128] {
+		let mut data = [0u8; 128];
+		data[..8].copy_from_slice(&[0x00, 0x00, 0x80, 0x00, 0x80, 0x00, 0x00, 0x00]);
+		data[11] = 0x03;
+		data
+	}
+
+	#[test]
+	fn test_detect_icreq() {
+		assert!(detect(&icreq()));
+		assert!(detect(&icreq()[..64]));
+	}
+
+	#[test]
+	fn test_reject_bad_fields() {
+		let mut data = icreq();
+		data[4] = 0x48;
+		assert!(!detect(&data));
+		let mut data = icreq();
+		data[8] = 0x01;
+		assert!(!detect(&data));
+		let mut data = icreq();
+		data[11] = 0x04;
+		assert!(!detect(&data));
+		let mut data = icreq();
+		data[40] = 0x01;
+		assert!(!detect(&data));
+	}
+
+	#[test]
+	fn test_reject_other_pdus() {
+		let mut data = icreq();
+		data[0] = 0x01; // ICResp
+		assert!(!detect(&data));
+		assert!(!detect(&[0u8; 32]));
+	}
+}
@@ -0,0 +1,199 @@
+/* src/protocols/opcua.rs */
+use crate::{DetectionStatus, ProtocolMetadata, ProtocolVersion};
+
+/// Largest message chunk accepted, in bytes.
+const MAX_MESSAGE_SIZE: u32 = 16 * 1024 * 1024;
+
+/// Smallest receive and send buffer a conforming peer may announce.
+const MIN_BUFFER_SIZE: u32 = 8192;
+
+/// Longest endpoint URL allowed in a Hello message.
+const MAX_URL_LEN: u32 = 4096;
+
+/// Scheme of the endpoint URL a Hello message names.
+const URL_SCHEME: &[u8] = b"opc.tcp://";
+
+/// Probes for the OPC UA binary (UA TCP) protocol.
+///
+/// Checks the 8-byte message header: a `HEL`, `ACK`, `OPN`, or `MSG` type,
+/// the `F` final-chunk marker (`MSG` may also be `C` or `A`), and a
+/// little-endian size within limits. `HEL` and `ACK` must also announce
+/// protocol version 0 and buffer sizes of at least 8192 bytes, and the
+/// endpoint URL of a `HEL` must start with `opc.tcp://` as far as it is in
+/// view.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	let Some(kind) = message_type(data) else {
+		reject!("unknown_message_type");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	};
+	if data.len() < 4 {
+		return (DetectionStatus::Incomplete, ProtocolVersion::Unknown);
+	}
+	let final_only = kind != b"MSG";
+	if !(data[3] == b'F' || (!final_only && matches!(data[3], b'C' | b'A'))) {
+		reject!("invalid_chunk_type");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	}
+	if data.len() < 8 {
+		return (DetectionStatus::Incomplete, ProtocolVersion::Unknown);
+	}
+
+	let size = read_u32(data, 4);
+	let min_size = match kind {
+		b"HEL" => 32,
+		b"ACK" => 28,
+		_ => 12,
+	};
+	if !(min_size..=MAX_MESSAGE_SIZE).contains(&size) {
+		reject!("message_size_out_of_range");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	}
+	if kind == b"OPN" || kind == b"MSG" {
+		return (DetectionStatus::Match, ProtocolVersion::Unknown);
+	}
+
+	// HEL and ACK: version, receive buffer, send buffer.
+	if data.len() < 20 {
+		return (DetectionStatus::Incomplete, ProtocolVersion::Unknown);
+	}
+	if read_u32(data, 8) != 0 {
+		reject!("unsupported_protocol_version");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	}
+	if read_u32(data, 12) < MIN_BUFFER_SIZE || read_u32(data, 16) < MIN_BUFFER_SIZE {
+		reject!("buffer_size_too_small");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	}
+	if kind == b"HEL" && data.len() >= 32 {
+		let url_len = read_u32(data, 28);
+		if url_len != u32::MAX && (url_len > MAX_URL_LEN || size != 32 + url_len) {
+			reject!("endpoint_url_length_mismatch");
+			return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+		}
+		let url = &data[32..];
+		let len = url.len().min(URL_SCHEME.len());
+		if url_len != u32::MAX && !url[..len].eq_ignore_ascii_case(&URL_SCHEME[..len]) {
+			reject!("not_opc_tcp_url");
+			return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+		}
+	}
+	(DetectionStatus::Match, ProtocolVersion::Unknown)
+}
+
+/// Extracts the message type of a matched message.
+#[inline(always)]
+pub(crate) fn metadata(data: &[u8]) -> ProtocolMetadata<'_> {
+	match data.get(..3) {
+		Some(kind @ (b"HEL" | b"ACK" | b"OPN" | b"MSG")) => ProtocolMetadata::OpcUa {
+			message_type: core::str::from_utf8(kind).unwrap_or_default(),
+		},
+		_ => ProtocolMetadata::None,
+	}
+}
+
+/// Returns the message type if the data starts with (a prefix of) a known one.
+#[inline(always)]
+fn message_type(data: &[u8]) -> Option<&'static [u8; 3]> {
+	[b"HEL", b"ACK", b"OPN", b"MSG"]
+		.into_iter()
+		.find(|kind| data.starts_with(*kind) || (!data.is_empty() && kind.starts_with(data)))
+}
+
+/// Reads a little-endian `u32` at `offset`.
+#[inline(always)]
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+	u32::from_le_bytes([
+		data[offset],
+		data[offset + 1],
+		data[offset + 2],
+		data[offset + 3],
+	])
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// A Hello message for `opc.tcp://plc:4840`.
+	const HELLO: &[u8] = b"HELF\x33\x00\x00\x00\
+		\x00\x00\x00\x00\
+		\x00\x00\x01\x00\
+		\x00\x00\x01\x00\
+		\x00\x00\x00\x00\
+		\x00\x00\x00\x00\
+		\x13\x00\x00\x00opc.tcp://plc:4840/";
+
+	#[test]
+	fn test_detect_opcua_hello() {
+		assert_eq!(
+			probe(HELLO),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+		assert_eq!(
+			metadata(HELLO),
+			ProtocolMetadata::OpcUa {
+				message_type: "HEL"
+			}
+		);
+	}
+
+	#[test]
+	fn test_detect_opcua_ack_and_secure_channel() {
+		let ack = b"ACKF\x1c\x00\x00\x00\x00\x00\x00\x00\x00\x00\x01\x00\x00\x00\x01\x00";
+		assert_eq!(probe(ack).0, DetectionStatus::Match);
+		assert_eq!(
+			probe(b"OPNF\x84\x00\x00\x00\x00\x00\x00\x00").0,
+			DetectionStatus::Match
+		);
+		assert_eq!(
+			probe(b"MSGC\x00\x20\x00\x00\x01\x00\x00\x00").0,
+			DetectionStatus::Match
+		);
+		assert_eq!(
+			metadata(b"MSGC\x00\x20\x00\x00"),
+			ProtocolMetadata::OpcUa {
+				message_type: "MSG"
+			}
+		);
+	}
+
+	#[test]
+	fn test_incomplete_header() {
+		assert_eq!(probe(b"HE").0, DetectionStatus::Incomplete);
+		assert_eq!(probe(b"HELF\x33\x00").0, DetectionStatus::Incomplete);
+		assert_eq!(probe(&HELLO[..16]).0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	fn test_reject_bad_header() {
+		assert_eq!(probe(b"HELC\x33\x00\x00\x00").0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"OPNF\x04\x00\x00\x00").0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"MSGF\x00\x00\x00\x7f").0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"HELLO world").0, DetectionStatus::NoMatch);
+	}
+
+	#[test]
+	fn test_reject_bad_hello_fields() {
+		let mut hello = HELLO.to_vec();
+		hello[8] = 1;
+		assert_eq!(probe(&hello).0, DetectionStatus::NoMatch);
+		let mut hello = HELLO.to_vec();
+		hello[14] = 0;
+		assert_eq!(probe(&hello).0, DetectionStatus::NoMatch);
+		let mut hello = HELLO.to_vec();
+		hello[28] = 0x20;
+		assert_eq!(probe(&hello).0, DetectionStatus::NoMatch);
+		let mut hello = HELLO.to_vec();
+		hello[32..39].copy_from_slice(b"http://");
+		assert_eq!(probe(&hello).0, DetectionStatus::NoMatch);
+	}
+
+	#[test]
+	fn test_endpoint_url_cut_by_window() {
+		assert_eq!(probe(&HELLO[..36]).0, DetectionStatus::Match);
+		let mut hello = HELLO.to_vec();
+		hello[33] = b'x';
+		assert_eq!(probe(&hello[..36]).0, DetectionStatus::NoMatch);
+	}
+}
@@ -0,0 +1,150 @@
+/* src/protocols/openvpn.rs */
+use crate::{DetectionStatus, ProtocolVersion};
+
+/// `P_CONTROL_HARD_RESET_CLIENT_V1` with key id 0, sent by key method 1.
+const HARD_RESET_CLIENT_V1: u8 = 0x01 << 3;
+
+/// `P_CONTROL_HARD_RESET_CLIENT_V2` with key id 0.
+const HARD_RESET_CLIENT_V2: u8 = 0x07 << 3;
+
+/// `P_CONTROL_HARD_RESET_CLIENT_V3` with key id 0.
+const HARD_RESET_CLIENT_V3: u8 = 0x0a << 3;
+
+/// Offsets of the replay packet id when the control channel is wrapped:
+/// `tls-crypt` (no HMAC before it), `tls-auth` with SHA-1, and with SHA-256.
+const WRAPPED_PACKET_ID_OFFSETS: [usize; 3] = [9, 29, 41];
+
+/// Largest TCP frame accepted for a client hard reset. A `tls-crypt-v2`
+/// reset carries the wrapped client key, itself up to 1024 bytes.
+const MAX_FRAME_LEN: u16 = 2048;
+
+/// Probes for an OpenVPN client's opening hard-reset packet.
+///
+/// Over UDP the packet starts the datagram; over TCP it follows a 2-byte
+/// big-endian length. The opcode must be `P_CONTROL_HARD_RESET_CLIENT_V1`,
+/// `_V2` or `_V3` with key id 0, the session id non-zero, and either the plain ACK
+/// array length and message packet id both zero, or a wrapped replay packet id
+/// of 1.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	let packet = if is_hard_reset(data[0]) {
+		data
+	} else if let Some(&opcode) = data.get(2) {
+		let len = u16::from_be_bytes([data[0], data[1]]);
+		if !is_hard_reset(opcode) || !(14..=MAX_FRAME_LEN).contains(&len) {
+			reject!("not_hard_reset_client");
+			return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+		}
+		&data[2..]
+	} else if data[0] == 0 {
+		return (DetectionStatus::Incomplete, ProtocolVersion::Unknown);
+	} else {
+		reject!("not_hard_reset_client");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	};
+
+	if packet.len() < 14 {
+		return (DetectionStatus::Incomplete, ProtocolVersion::Unknown);
+	}
+	if packet[1..9].iter().all(|&b| b == 0) {
+		reject!("zero_session_id");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	}
+	if packet[9] == 0 && packet[10..14] == [0, 0, 0, 0] {
+		return (DetectionStatus::Match, ProtocolVersion::Unknown);
+	}
+	for offset in WRAPPED_PACKET_ID_OFFSETS {
+		if packet.get(offset..offset + 4) == Some(&[0, 0, 0, 1]) {
+			return (DetectionStatus::Match, ProtocolVersion::Unknown);
+		}
+	}
+
+	let last = WRAPPED_PACKET_ID_OFFSETS[WRAPPED_PACKET_ID_OFFSETS.len() - 1];
+	if packet.len() < last + 4 {
+		return (DetectionStatus::Incomplete, ProtocolVersion::Unknown);
+	}
+	reject!("implausible_packet_id");
+	(DetectionStatus::NoMatch, ProtocolVersion::Unknown)
+}
+
+/// Returns whether `byte` is a client hard-reset opcode with key id 0.
+#[inline(always)]
+fn is_hard_reset(byte: u8) -> bool {
+	matches!(
+		byte,
+		HARD_RESET_CLIENT_V1 | HARD_RESET_CLIENT_V2 | HARD_RESET_CLIENT_V3
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Plain client hard reset: opcode, session id, empty ACK array, packet id 0.
+	const PLAIN: [u8; 14] = [
+		0x38, 0x5a, 0x1f, 0x03, 0x9c, 0x44, 0x10, 0x7e, 0xd2, 0x00, 0x00, 0x00, 0x00, 0x00,
+	];
+
+	#[test]
+	fn test_detect_udp_hard_reset() {
+		assert_eq!(
+			probe(&PLAIN),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+	}
+
+	#[test]
+	fn test_detect_v1_hard_reset() {
+		let mut data = PLAIN;
+		data[0] = HARD_RESET_CLIENT_V1;
+		assert_eq!(probe(&data).0, DetectionStatus::Match);
+	}
+
+	#[test]
+	fn test_detect_tcp_hard_reset() {
+		let mut data = [0u8; 16];
+		data[1] = 14;
+		data[2..].copy_from_slice(&PLAIN);
+		data[2] = HARD_RESET_CLIENT_V3;
+		assert_eq!(probe(&data).0, DetectionStatus::Match);
+		// A tls-crypt-v2 reset longer than 1024 bytes.
+		data[..2].copy_from_slice(&1400u16.to_be_bytes());
+		assert_eq!(probe(&data).0, DetectionStatus::Match);
+		data[..2].copy_from_slice(&4096u16.to_be_bytes());
+		assert_eq!(probe(&data).0, DetectionStatus::NoMatch);
+	}
+
+	#[test]
+	fn test_detect_tls_crypt_reset() {
+		let mut data = [0u8; 60];
+		data[..9].copy_from_slice(&PLAIN[..9]);
+		data[9..13].copy_from_slice(&[0, 0, 0, 1]);
+		data[13..17].copy_from_slice(&[0x66, 0x2a, 0x0b, 0x10]);
+		data[17] = 0xab;
+		assert_eq!(probe(&data).0, DetectionStatus::Match);
+	}
+
+	#[test]
+	fn test_incomplete_packet() {
+		assert_eq!(probe(&PLAIN[..8]).0, DetectionStatus::Incomplete);
+		assert_eq!(probe(&[0x00, 0x0e]).0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	fn test_reject_other_opcodes() {
+		let mut data = PLAIN;
+		data[0] = 0x39;
+		assert_eq!(probe(&data).0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"GET / HTTP/1.1\r\n").0, DetectionStatus::NoMatch);
+	}
+
+	#[test]
+	fn test_reject_zero_session_or_bad_packet_id() {
+		let mut data = PLAIN;
+		data[1..9].fill(0);
+		assert_eq!(probe(&data).0, DetectionStatus::NoMatch);
+		let mut data = [0xEEu8; 48];
+		data[0] = HARD_RESET_CLIENT_V2;
+		assert_eq!(probe(&data).0, DetectionStatus::NoMatch);
+	}
+}
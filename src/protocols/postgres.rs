@@ -6,6 +6,7 @@
 #[inline(always)]
 pub(crate) fn detect(data: &[u8]) -> bool {
 	if data.len() < 8 {
+		reject!("too_short");
 		return false;
 	}
 
@@ -26,6 +27,7 @@ pub(crate) fn detect(data: &[u8]) -> bool {
 					break;
 				}
 				if !(32..=126).contains(&b) {
+					reject!("non_printable_parameter");
 					return false;
 				}
 			}
@@ -34,6 +36,7 @@ pub(crate) fn detect(data: &[u8]) -> bool {
 		return true;
 	}
 
+	reject!("unknown_request_code");
 	false
 }
 
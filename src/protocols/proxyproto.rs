@@ -0,0 +1,289 @@
+/* src/protocols/proxyproto.rs */
+use crate::{DetectionStatus, ProtocolMetadata, ProtocolVersion};
+
+/// Opening of a version 1 (text) header.
+const V1_PREFIX: &[u8] = b"PROXY ";
+
+/// Longest version 1 header, including its CRLF.
+const V1_MAX_LEN: usize = 107;
+
+/// Signature opening a version 2 (binary) header.
+const V2_SIGNATURE: &[u8; 12] = b"\r\n\r\n\x00\r\nQUIT\n";
+
+/// Length of the fixed part of a version 2 header.
+const V2_FIXED_LEN: usize = 16;
+
+/// Probes for a PROXY protocol header in front of a proxied connection.
+///
+/// Recognizes the version 1 text header: `PROXY`, then `TCP4` or `TCP6`
+/// with source and destination addresses of that family and two ports, or
+/// `UNKNOWN` with anything up to the CRLF. The whole header must be in view;
+/// `TCP6` headers can be longer than the default inspection window, so raise
+/// `max_inspect_bytes` to 107 to see every one of them.
+///
+/// Also recognizes the version 2 binary header: its 12-byte signature, then
+/// version 2 with the `LOCAL` or `PROXY` command, a known address family and
+/// transport, and a length that fits the addresses of that family. Only the
+/// fixed 16 bytes need to be in view.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	let status = match header(data) {
+		Ok(_) => DetectionStatus::Match,
+		Err(status) => status,
+	};
+	(status, ProtocolVersion::Unknown)
+}
+
+/// Extracts the header version and its total length, so callers can skip
+/// the header and detect the proxied payload.
+#[inline(always)]
+pub(crate) fn metadata(data: &[u8]) -> ProtocolMetadata<'_> {
+	header(data).map_or(ProtocolMetadata::None, |(version, header_len)| {
+		ProtocolMetadata::ProxyProtocol {
+			version,
+			header_len,
+		}
+	})
+}
+
+/// Validates a header of either version, returning the version and the
+/// header length.
+#[inline(always)]
+fn header(data: &[u8]) -> Result<(u8, usize), DetectionStatus> {
+	match data.first() {
+		None => Err(DetectionStatus::Incomplete),
+		Some(b'P') => v1_header_len(data).map(|len| (1, len)),
+		Some(b'\r') => v2_header_len(data).map(|len| (2, len)),
+		Some(_) => {
+			reject!("no_proxy_signature");
+			Err(DetectionStatus::NoMatch)
+		}
+	}
+}
+
+/// Validates a version 1 header and returns its length, CRLF included.
+#[inline(always)]
+fn v1_header_len(data: &[u8]) -> Result<usize, DetectionStatus> {
+	let len = data.len().min(V1_PREFIX.len());
+	if data[..len] != V1_PREFIX[..len] {
+		reject!("no_proxy_prefix");
+		return Err(DetectionStatus::NoMatch);
+	}
+	let window = &data[..data.len().min(V1_MAX_LEN)];
+	let Some(end) = window.windows(2).position(|pair| pair == b"\r\n") else {
+		return if data.len() < V1_MAX_LEN {
+			Err(DetectionStatus::Incomplete)
+		} else {
+			reject!("header_too_long");
+			Err(DetectionStatus::NoMatch)
+		};
+	};
+
+	let mut fields = data[V1_PREFIX.len()..end].split(|&b| b == b' ');
+	let is_address: fn(&[u8]) -> bool = match fields.next() {
+		Some(b"UNKNOWN") => return Ok(end + 2),
+		Some(b"TCP4") => is_ipv4,
+		Some(b"TCP6") => is_ipv6,
+		_ => {
+			reject!("unknown_transport");
+			return Err(DetectionStatus::NoMatch);
+		}
+	};
+	let valid = fields.next().is_some_and(is_address)
+		&& fields.next().is_some_and(is_address)
+		&& fields.next().is_some_and(is_port)
+		&& fields.next().is_some_and(is_port)
+		&& fields.next().is_none();
+	if valid {
+		Ok(end + 2)
+	} else {
+		reject!("invalid_addresses");
+		Err(DetectionStatus::NoMatch)
+	}
+}
+
+/// Validates the fixed part of a version 2 header and returns the length
+/// of the whole header.
+#[inline(always)]
+fn v2_header_len(data: &[u8]) -> Result<usize, DetectionStatus> {
+	let len = data.len().min(V2_SIGNATURE.len());
+	if data[..len] != V2_SIGNATURE[..len] {
+		reject!("no_v2_signature");
+		return Err(DetectionStatus::NoMatch);
+	}
+	let Some(fixed) = data.first_chunk::<V2_FIXED_LEN>() else {
+		return Err(DetectionStatus::Incomplete);
+	};
+	// Version 2 in the high nibble, then `LOCAL` (0) or `PROXY` (1).
+	if !matches!(fixed[12], 0x20 | 0x21) {
+		reject!("invalid_version_command");
+		return Err(DetectionStatus::NoMatch);
+	}
+	// Address family (unspecified, IPv4, IPv6, Unix) and transport (stream
+	// or datagram); the family sets the size of the address block.
+	let address_len = match fixed[13] {
+		0x00 => 0,
+		0x11 | 0x12 => 12,
+		0x21 | 0x22 => 36,
+		0x31 | 0x32 => 216,
+		_ => {
+			reject!("invalid_address_family");
+			return Err(DetectionStatus::NoMatch);
+		}
+	};
+	let len = usize::from(u16::from_be_bytes([fixed[14], fixed[15]]));
+	if len < address_len {
+		reject!("addresses_truncated");
+		return Err(DetectionStatus::NoMatch);
+	}
+	Ok(V2_FIXED_LEN + len)
+}
+
+/// Checks for a dotted-quad IPv4 address.
+#[inline(always)]
+fn is_ipv4(field: &[u8]) -> bool {
+	let mut octets = 0;
+	field.split(|&b| b == b'.').all(|octet| {
+		octets += 1;
+		(1..=3).contains(&octet.len())
+			&& octet.iter().all(u8::is_ascii_digit)
+			&& octet
+				.iter()
+				.fold(0u16, |n, &d| n * 10 + u16::from(d - b'0'))
+				<= 255
+	}) && octets == 4
+}
+
+/// Checks for the characters and shape of a textual IPv6 address.
+#[inline(always)]
+fn is_ipv6(field: &[u8]) -> bool {
+	(2..=45).contains(&field.len())
+		&& field.contains(&b':')
+		&& field
+			.iter()
+			.all(|&b| b.is_ascii_hexdigit() || b == b':' || b == b'.')
+}
+
+/// Checks for a decimal port number.
+#[inline(always)]
+fn is_port(field: &[u8]) -> bool {
+	(1..=5).contains(&field.len())
+		&& field.iter().all(u8::is_ascii_digit)
+		&& field
+			.iter()
+			.fold(0u32, |n, &d| n * 10 + u32::from(d - b'0'))
+			<= 65535
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_detect_v1_tcp4() {
+		let data = b"PROXY TCP4 192.168.0.1 192.168.0.11 56324 443\r\nGET / HTTP/1.1\r\n";
+		assert_eq!(
+			probe(data),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+		assert_eq!(
+			metadata(data),
+			ProtocolMetadata::ProxyProtocol {
+				version: 1,
+				header_len: 47,
+			}
+		);
+		assert_eq!(&data[47..51], b"GET ");
+	}
+
+	#[test]
+	fn test_detect_v1_tcp6_and_unknown() {
+		assert_eq!(
+			probe(b"PROXY TCP6 2001:db8::1 ::ffff:10.0.0.1 4124 8443\r\n").0,
+			DetectionStatus::Match
+		);
+		assert_eq!(v1_header_len(b"PROXY UNKNOWN\r\n"), Ok(15));
+		assert_eq!(
+			probe(b"PROXY UNKNOWN ffff::1 ffff::2 1 2\r\n").0,
+			DetectionStatus::Match
+		);
+	}
+
+	#[test]
+	fn test_detect_v2_header() {
+		// PROXY command, TCP over IPv4, 12 address bytes plus a 3-byte TLV.
+		let data = b"\r\n\r\n\x00\r\nQUIT\n\x21\x11\x00\x0F\xC0\xA8\x00\x01\xC0\xA8\x00\x0B\xDC\x04\x01\xBB\x04\x00\x00\x16\x03\x01";
+		assert_eq!(
+			probe(data),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+		assert_eq!(
+			metadata(data),
+			ProtocolMetadata::ProxyProtocol {
+				version: 2,
+				header_len: 31,
+			}
+		);
+		// LOCAL command (health check) without addresses.
+		assert_eq!(
+			probe(b"\r\n\r\n\x00\r\nQUIT\n\x20\x00\x00\x00").0,
+			DetectionStatus::Match
+		);
+	}
+
+	#[test]
+	fn test_incomplete_header() {
+		assert_eq!(probe(b"\r\n\r\n\x00").0, DetectionStatus::Incomplete);
+		assert_eq!(
+			probe(b"\r\n\r\n\x00\r\nQUIT\n\x21").0,
+			DetectionStatus::Incomplete
+		);
+		assert_eq!(probe(b"PRO").0, DetectionStatus::Incomplete);
+		assert_eq!(
+			probe(b"PROXY TCP4 192.168.0.1 ").0,
+			DetectionStatus::Incomplete
+		);
+	}
+
+	#[test]
+	fn test_reject_malformed_headers() {
+		assert_eq!(
+			probe(b"PROXY TCP4 192.168.0.256 10.0.0.1 1 2\r\n").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(
+			probe(b"PROXY TCP4 2001:db8::1 10.0.0.1 1 2\r\n").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(
+			probe(b"PROXY TCP6 2001:db8::1 2001:db8::2 70000 2\r\n").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(
+			probe(b"PROXY UDP4 10.0.0.1 10.0.0.2 1 2\r\n").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(
+			probe(b"PROXY TCP4 10.0.0.1 10.0.0.2 1\r\n").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(probe(b"GET / HTTP/1.1\r\n").0, DetectionStatus::NoMatch);
+		// Version 1 in a binary header, unknown family, truncated addresses.
+		assert_eq!(
+			probe(b"\r\n\r\n\x00\r\nQUIT\n\x11\x11\x00\x0C").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(
+			probe(b"\r\n\r\n\x00\r\nQUIT\n\x21\x41\x00\x0C").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(
+			probe(b"\r\n\r\n\x00\r\nQUIT\n\x21\x21\x00\x0C").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(metadata(b"\r\n\r\n\x01"), ProtocolMetadata::None);
+		let mut long = [b'A'; 128];
+		long[..V1_PREFIX.len()].copy_from_slice(V1_PREFIX);
+		assert_eq!(probe(&long).0, DetectionStatus::NoMatch);
+	}
+}
@@ -4,12 +4,14 @@
 #[inline(always)]
 pub(crate) fn detect(data: &[u8]) -> bool {
 	if data.len() < 7 {
+		reject!("too_short");
 		return false;
 	}
 
 	let first_byte = data[0];
 
 	if (first_byte & 0xC0) != 0xC0 {
+		reject!("not_long_header");
 		return false;
 	}
 
@@ -21,11 +23,13 @@ pub(crate) fn detect(data: &[u8]) -> bool {
 		|| (0xff000000..=0xff0000ff).contains(&version);
 
 	if !is_valid_version {
+		reject!("unknown_version");
 		return false;
 	}
 
 	let dcid_len = data[5] as usize;
 	if dcid_len > 20 {
+		reject!("dcid_too_long");
 		return false;
 	}
 
@@ -34,7 +38,12 @@ pub(crate) fn detect(data: &[u8]) -> bool {
 	}
 
 	let scid_len = data[6 + dcid_len] as usize;
-	scid_len <= 20
+	if scid_len > 20 {
+		reject!("scid_too_long");
+		return false;
+	}
+
+	true
 }
 
 #[cfg(test)]
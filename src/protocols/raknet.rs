@@ -0,0 +1,133 @@
+/* src/protocols/raknet.rs */
+use crate::{DetectionStatus, ProtocolMetadata, ProtocolVersion};
+
+/// Packet ID of an unconnected ping.
+const UNCONNECTED_PING: u8 = 0x01;
+
+/// Packet ID of an open connection request (first step).
+const OPEN_CONNECTION_REQUEST_1: u8 = 0x05;
+
+/// Magic that marks every RakNet offline message.
+const OFFLINE_MAGIC: [u8; 16] = [
+	0x00, 0xFF, 0xFF, 0x00, 0xFE, 0xFE, 0xFE, 0xFE, 0xFD, 0xFD, 0xFD, 0xFD, 0x12, 0x34, 0x56, 0x78,
+];
+
+/// Offset of the magic in an unconnected ping, after the 8-byte timestamp.
+const PING_MAGIC_OFFSET: usize = 9;
+
+/// Offset of the magic in an open connection request.
+const REQUEST_MAGIC_OFFSET: usize = 1;
+
+/// Offset of the client GUID in an unconnected ping.
+const PING_GUID_OFFSET: usize = PING_MAGIC_OFFSET + OFFLINE_MAGIC.len();
+
+/// Probes for a RakNet offline message (Minecraft Bedrock Edition).
+///
+/// Expects an unconnected ping or an open connection request followed by
+/// the 16-byte offline-message magic.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	let status = match magic_offset(data) {
+		Ok(offset) => match data.get(offset..offset + OFFLINE_MAGIC.len()) {
+			None => DetectionStatus::Incomplete,
+			Some(magic) if magic == OFFLINE_MAGIC => DetectionStatus::Match,
+			Some(_) => {
+				reject!("offline_magic_mismatch");
+				DetectionStatus::NoMatch
+			}
+		},
+		Err(status) => status,
+	};
+	(status, ProtocolVersion::Unknown)
+}
+
+/// Extracts the packet ID and, for an unconnected ping, the client GUID.
+#[inline(always)]
+pub(crate) fn metadata(data: &[u8]) -> ProtocolMetadata<'_> {
+	if probe(data).0 != DetectionStatus::Match {
+		return ProtocolMetadata::None;
+	}
+	let packet_id = data[0];
+	let client_guid = match packet_id {
+		UNCONNECTED_PING => data
+			.get(PING_GUID_OFFSET..PING_GUID_OFFSET + 8)
+			.and_then(|guid| guid.try_into().ok())
+			.map(u64::from_be_bytes),
+		_ => None,
+	};
+	ProtocolMetadata::RakNet {
+		packet_id,
+		client_guid,
+	}
+}
+
+/// Returns the offset of the magic for the packet ID.
+#[inline(always)]
+fn magic_offset(data: &[u8]) -> Result<usize, DetectionStatus> {
+	match data.first() {
+		None => Err(DetectionStatus::Incomplete),
+		Some(&UNCONNECTED_PING) => Ok(PING_MAGIC_OFFSET),
+		Some(&OPEN_CONNECTION_REQUEST_1) => Ok(REQUEST_MAGIC_OFFSET),
+		Some(_) => {
+			reject!("not_offline_message");
+			Err(DetectionStatus::NoMatch)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Unconnected ping with timestamp 0x2A and client GUID `0x0123456789ABCDEF`.
+	const PING: &[u8] = b"\x01\x00\x00\x00\x00\x00\x00\x00\x2A\x00\xFF\xFF\x00\xFE\xFE\xFE\xFE\xFD\xFD\xFD\xFD\x12\x34\x56\x78\x01\x23\x45\x67\x89\xAB\xCD\xEF";
+
+	#[test]
+	fn test_detect_unconnected_ping() {
+		assert_eq!(
+			probe(PING),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+		assert_eq!(
+			metadata(PING),
+			ProtocolMetadata::RakNet {
+				packet_id: 0x01,
+				client_guid: Some(0x0123_4567_89AB_CDEF),
+			}
+		);
+	}
+
+	#[test]
+	fn test_detect_open_connection_request() {
+		let request =
+			b"\x05\x00\xFF\xFF\x00\xFE\xFE\xFE\xFE\xFD\xFD\xFD\xFD\x12\x34\x56\x78\x0B\x00\x00\x00";
+		assert_eq!(probe(request).0, DetectionStatus::Match);
+		assert_eq!(
+			metadata(request),
+			ProtocolMetadata::RakNet {
+				packet_id: 0x05,
+				client_guid: None,
+			}
+		);
+	}
+
+	#[test]
+	fn test_incomplete_magic() {
+		assert_eq!(probe(&PING[..20]).0, DetectionStatus::Incomplete);
+		assert_eq!(probe(b"\x05\x00\xFF").0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	fn test_reject_other_packets() {
+		// Connected frame set and a ping with a corrupted magic.
+		assert_eq!(
+			probe(b"\x84\x00\x00\x00\x40\x00\x90\x00\x00\x00\x09").0,
+			DetectionStatus::NoMatch
+		);
+		let mut ping = [0u8; 33];
+		ping.copy_from_slice(PING);
+		ping[13] = 0x00;
+		assert_eq!(probe(&ping).0, DetectionStatus::NoMatch);
+		assert_eq!(metadata(&ping), ProtocolMetadata::None);
+	}
+}
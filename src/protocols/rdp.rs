@@ -0,0 +1,167 @@
+/* src/protocols/rdp.rs */
+use crate::{DetectionStatus, ProtocolMetadata, ProtocolVersion};
+
+/// TPKT version (RFC 1006).
+const TPKT_VERSION: u8 = 0x03;
+
+/// X.224 Connection Request TPDU code (class 0, no credit).
+const X224_CONNECTION_REQUEST: u8 = 0xE0;
+
+/// Length of the TPKT header and the fixed X.224 Connection Request fields.
+const HEADER_LEN: usize = 11;
+
+/// Prefix of the routing token or cookie that may follow the header.
+const COOKIE_PREFIX: &[u8] = b"Cookie: ";
+
+/// `RDP_NEG_REQ` structure type.
+const NEG_REQ: u8 = 0x01;
+
+/// Length of an `RDP_NEG_REQ`.
+const NEG_REQ_LEN: usize = 8;
+
+/// Probes for the X.224 Connection Request that opens an RDP connection.
+///
+/// Expects a TPKT header whose length agrees with the X.224 length
+/// indicator, a Connection Request with a zero destination reference and
+/// class 0, then nothing, a `Cookie:` line, or an `RDP_NEG_REQ`. ISO
+/// transport connections for MMS or S7comm carry TSAP parameters instead.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	(probe_status(data), ProtocolVersion::Unknown)
+}
+
+/// Checks the TPKT and X.224 headers and the start of the variable part.
+#[inline(always)]
+fn probe_status(data: &[u8]) -> DetectionStatus {
+	if data.first().is_some_and(|&b| b != TPKT_VERSION) || data.get(1).is_some_and(|&b| b != 0) {
+		reject!("not_tpkt");
+		return DetectionStatus::NoMatch;
+	}
+	let Some(header) = data.first_chunk::<HEADER_LEN>() else {
+		return DetectionStatus::Incomplete;
+	};
+	let tpkt_len = usize::from(u16::from_be_bytes([header[2], header[3]]));
+	if tpkt_len < HEADER_LEN || usize::from(header[4]) + 5 != tpkt_len {
+		reject!("length_mismatch");
+		return DetectionStatus::NoMatch;
+	}
+	if header[5] != X224_CONNECTION_REQUEST || header[6..8] != [0, 0] || header[10] != 0 {
+		reject!("not_connection_request");
+		return DetectionStatus::NoMatch;
+	}
+
+	let variable = &data[HEADER_LEN..data.len().min(tpkt_len)];
+	let Some(&first) = variable.first() else {
+		return if data.len() < tpkt_len {
+			DetectionStatus::Incomplete
+		} else {
+			DetectionStatus::Match
+		};
+	};
+	let prefix = &COOKIE_PREFIX[..variable.len().min(COOKIE_PREFIX.len())];
+	if first == NEG_REQ || variable.starts_with(prefix) {
+		DetectionStatus::Match
+	} else {
+		reject!("unexpected_variable_part");
+		DetectionStatus::NoMatch
+	}
+}
+
+/// Extracts the cookie and the security protocols the client requested.
+#[inline(always)]
+pub(crate) fn metadata(data: &[u8]) -> ProtocolMetadata<'_> {
+	if probe_status(data) != DetectionStatus::Match {
+		return ProtocolMetadata::None;
+	}
+	let tpkt_len = usize::from(u16::from_be_bytes([data[2], data[3]]));
+	let mut rest = &data[HEADER_LEN..data.len().min(tpkt_len)];
+	let mut cookie = None;
+	if let Some(line) = rest.strip_prefix(COOKIE_PREFIX) {
+		let Some(end) = line.windows(2).position(|pair| pair == b"\r\n") else {
+			return ProtocolMetadata::Rdp {
+				cookie: None,
+				requested_protocols: None,
+			};
+		};
+		cookie = core::str::from_utf8(&line[..end]).ok();
+		rest = &line[end + 2..];
+	}
+	let requested_protocols = rest
+		.get(..NEG_REQ_LEN)
+		.filter(|neg| {
+			neg[0] == NEG_REQ && usize::from(u16::from_le_bytes([neg[2], neg[3]])) == NEG_REQ_LEN
+		})
+		.map(|neg| u32::from_le_bytes([neg[4], neg[5], neg[6], neg[7]]));
+	ProtocolMetadata::Rdp {
+		cookie,
+		requested_protocols,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// mstsc Connection Request with a `mstshash` cookie, requesting TLS,
+	/// CredSSP and early user authorization.
+	const REQUEST: &[u8] = b"\x03\x00\x00\x2F\x2A\xE0\x00\x00\x00\x00\x00Cookie: mstshash=administr\r\n\x01\x00\x08\x00\x0B\x00\x00\x00";
+
+	#[test]
+	fn test_detect_connection_request() {
+		assert_eq!(
+			probe(REQUEST),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+		assert_eq!(
+			metadata(REQUEST),
+			ProtocolMetadata::Rdp {
+				cookie: Some("mstshash=administr"),
+				requested_protocols: Some(0x0B),
+			}
+		);
+	}
+
+	#[test]
+	fn test_detect_without_cookie() {
+		let request = b"\x03\x00\x00\x13\x0E\xE0\x00\x00\x00\x00\x00\x01\x00\x08\x00\x03\x00\x00\x00";
+		assert_eq!(probe(request).0, DetectionStatus::Match);
+		assert_eq!(
+			metadata(request),
+			ProtocolMetadata::Rdp {
+				cookie: None,
+				requested_protocols: Some(0x03),
+			}
+		);
+		// Legacy clients send the bare header.
+		assert_eq!(
+			probe(b"\x03\x00\x00\x0B\x06\xE0\x00\x00\x12\x34\x00").0,
+			DetectionStatus::Match
+		);
+	}
+
+	#[test]
+	fn test_incomplete_request() {
+		assert_eq!(probe(b"\x03\x00\x00").0, DetectionStatus::Incomplete);
+		assert_eq!(probe(&REQUEST[..10]).0, DetectionStatus::Incomplete);
+		assert_eq!(probe(&REQUEST[..11]).0, DetectionStatus::Incomplete);
+		assert_eq!(probe(&REQUEST[..14]).0, DetectionStatus::Match);
+	}
+
+	#[test]
+	fn test_reject_other_tpkt() {
+		// ISO transport Connection Request with TSAP parameters (S7comm).
+		let cotp =
+			b"\x03\x00\x00\x16\x11\xE0\x00\x00\x00\x01\x00\xC1\x02\x01\x00\xC2\x02\x01\x02\xC0\x01\x0A";
+		assert_eq!(probe(cotp).0, DetectionStatus::NoMatch);
+		// COTP data TPDU, mismatched length indicator, other TPKT version.
+		assert_eq!(
+			probe(b"\x03\x00\x00\x0B\x02\xF0\x80\x32\x01\x00\x00").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(
+			probe(b"\x03\x00\x00\x0B\x07\xE0\x00\x00\x00\x00\x00").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(probe(b"\x02\x00\x00\x0B").0, DetectionStatus::NoMatch);
+	}
+}
@@ -14,7 +14,10 @@ pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
 		return match data[0] {
 			b'+' | b'-' | b':' | b'$' | b'*' | b'_' | b',' | b'#' | b'!' | b'=' | b'(' | b'%' | b'~'
 			| b'>' => (DetectionStatus::Incomplete, ProtocolVersion::Unknown),
-			_ => (DetectionStatus::NoMatch, ProtocolVersion::Unknown),
+			_ => {
+				reject!("type_marker_invalid");
+				(DetectionStatus::NoMatch, ProtocolVersion::Unknown)
+			}
 		};
 	}
 
@@ -25,6 +28,7 @@ pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
 		b'+' | b'-' => {
 			// Simple String / Error: printable ASCII or \r
 			if !(32..=126).contains(&second) && second != b'\r' {
+				reject!("second_byte_invalid");
 				return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
 			}
 			2
@@ -32,6 +36,7 @@ pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
 		b':' | b'$' | b'*' => {
 			// Integer / Bulk String / Array: digit or '-'
 			if !second.is_ascii_digit() && second != b'-' {
+				reject!("second_byte_invalid");
 				return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
 			}
 			2
@@ -39,6 +44,7 @@ pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
 		b'#' => {
 			// RESP3 Boolean: must be 't' or 'f'
 			if second != b't' && second != b'f' {
+				reject!("second_byte_invalid");
 				return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
 			}
 			3
@@ -46,6 +52,7 @@ pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
 		b'_' => {
 			// RESP3 Null: must be '\r'
 			if second != b'\r' {
+				reject!("second_byte_invalid");
 				return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
 			}
 			3
@@ -53,6 +60,7 @@ pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
 		b',' => {
 			// RESP3 Double: digit, sign, or inf/nan prefix
 			if !second.is_ascii_digit() && !matches!(second, b'-' | b'+' | b'i' | b'n') {
+				reject!("second_byte_invalid");
 				return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
 			}
 			3
@@ -60,6 +68,7 @@ pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
 		b'(' => {
 			// RESP3 Big Number: digit or '-'
 			if !second.is_ascii_digit() && second != b'-' {
+				reject!("second_byte_invalid");
 				return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
 			}
 			3
@@ -67,11 +76,15 @@ pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
 		b'!' | b'=' | b'%' | b'~' | b'>' => {
 			// RESP3 Blob Error / Verbatim / Map / Set / Push: digit (length)
 			if !second.is_ascii_digit() {
+				reject!("second_byte_invalid");
 				return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
 			}
 			3
 		}
-		_ => return (DetectionStatus::NoMatch, ProtocolVersion::Unknown),
+		_ => {
+			reject!("type_marker_invalid");
+			return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+		}
 	};
 
 	(DetectionStatus::Match, ProtocolVersion::Redis(resp_ver))
@@ -0,0 +1,152 @@
+/* src/protocols/relp.rs */
+use crate::{DetectionStatus, ProtocolVersion};
+
+/// RELP commands, including the `rsp` responses a server sends.
+const COMMANDS: [&[u8]; 5] = [b"open", b"syslog", b"close", b"rsp", b"serverclose"];
+
+/// Offer every `open` command starts with.
+const RELP_VERSION: &[u8] = b"relp_version=";
+
+/// Most digits in the transaction number and the data length.
+const MAX_DIGITS: usize = 9;
+
+/// Probes for RELP (Reliable Event Logging Protocol).
+///
+/// Expects a frame header: transaction number, command, and data length
+/// separated by spaces, then a space before the data or the closing newline
+/// for empty frames. An `open` command must start its data with the
+/// `relp_version=` offer.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	let status = match parse(data) {
+		Ok(()) => DetectionStatus::Match,
+		Err(status) => status,
+	};
+	(status, ProtocolVersion::Unknown)
+}
+
+/// Checks the header fields and the start of the data.
+#[inline(always)]
+fn parse(data: &[u8]) -> Result<(), DetectionStatus> {
+	let (txnr, rest) = number(data)?;
+	if txnr == 0 {
+		reject!("zero_transaction_number");
+		return Err(DetectionStatus::NoMatch);
+	}
+	let rest = space(rest)?;
+	let Some(command) = COMMANDS.iter().find(|command| {
+		let len = rest.len().min(command.len());
+		rest[..len] == command[..len]
+	}) else {
+		reject!("unknown_command");
+		return Err(DetectionStatus::NoMatch);
+	};
+	let rest = space(
+		rest
+			.get(command.len()..)
+			.ok_or(DetectionStatus::Incomplete)?,
+	)?;
+	let (data_len, rest) = number(rest)?;
+	let Some(&separator) = rest.first() else {
+		return Err(DetectionStatus::Incomplete);
+	};
+	match (data_len, separator) {
+		(0, b'\n') => return Ok(()),
+		(1.., b' ') => {}
+		_ => {
+			reject!("invalid_frame_separator");
+			return Err(DetectionStatus::NoMatch);
+		}
+	}
+	if *command == b"open" {
+		let offer = &rest[1..];
+		let len = offer.len().min(RELP_VERSION.len());
+		if offer[..len] != RELP_VERSION[..len] {
+			reject!("no_relp_version_offer");
+			return Err(DetectionStatus::NoMatch);
+		}
+	}
+	Ok(())
+}
+
+/// Reads a decimal number of up to nine digits.
+#[inline(always)]
+fn number(data: &[u8]) -> Result<(u32, &[u8]), DetectionStatus> {
+	let digits = data.iter().take_while(|b| b.is_ascii_digit()).count();
+	if digits > MAX_DIGITS {
+		reject!("number_too_long");
+		return Err(DetectionStatus::NoMatch);
+	}
+	if digits == data.len() {
+		return Err(DetectionStatus::Incomplete);
+	}
+	if digits == 0 {
+		reject!("missing_number");
+		return Err(DetectionStatus::NoMatch);
+	}
+	let value = data[..digits]
+		.iter()
+		.fold(0u32, |acc, &b| acc * 10 + u32::from(b - b'0'));
+	Ok((value, &data[digits..]))
+}
+
+/// Expects a single space.
+#[inline(always)]
+fn space(data: &[u8]) -> Result<&[u8], DetectionStatus> {
+	match data.first() {
+		None => Err(DetectionStatus::Incomplete),
+		Some(b' ') => Ok(&data[1..]),
+		Some(_) => {
+			reject!("missing_space");
+			Err(DetectionStatus::NoMatch)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_detect_open_offer() {
+		let frame = b"1 open 85 relp_version=0\nrelp_software=librelp,1.2.16,http://librelp.adiscon.com\ncommands=syslog\n";
+		assert_eq!(
+			probe(frame),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+	}
+
+	#[test]
+	fn test_detect_other_frames() {
+		assert_eq!(
+			probe(b"1 rsp 92 200 OK\nrelp_version=0\n").0,
+			DetectionStatus::Match
+		);
+		assert_eq!(
+			probe(b"2 syslog 36 <13>Oct 16 12:00:00 host app: hi\n").0,
+			DetectionStatus::Match
+		);
+		assert_eq!(probe(b"3 close 0\n").0, DetectionStatus::Match);
+	}
+
+	#[test]
+	fn test_incomplete_header() {
+		assert_eq!(probe(b"1").0, DetectionStatus::Incomplete);
+		assert_eq!(probe(b"1 op").0, DetectionStatus::Incomplete);
+		assert_eq!(probe(b"1 open 85").0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	fn test_reject_other_payloads() {
+		// Open without an offer, unknown command, empty frame with data.
+		assert_eq!(probe(b"1 open 5 hello").0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"1 fetch 5 hello").0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"1 close 0 x").0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"0 open 85 relp").0, DetectionStatus::NoMatch);
+		// Plain syslog over TCP.
+		assert_eq!(
+			probe(b"<13>Oct 16 12:00:00 host app: hi\n").0,
+			DetectionStatus::NoMatch
+		);
+	}
+}
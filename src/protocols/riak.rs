@@ -0,0 +1,106 @@
+/* src/protocols/riak.rs */
+use crate::{
+	DetectionStatus, ProtocolVersion,
+	protobuf::{FramedProtobuf, LengthPrefix},
+};
+
+/// Documented Riak PB message codes: the KV and bucket requests, MapReduce
+/// and secondary indexes, counters, Yokozuna search admin, coverage, data
+/// types, time series, and the auth/STARTTLS codes at the top of the range.
+const MESSAGE_CODES: &[(u8, u8)] = &[
+	(0, 34),
+	(40, 42),
+	(50, 60),
+	(70, 71),
+	(80, 83),
+	(90, 104),
+	(253, 255),
+];
+
+/// Largest frame length accepted. Riak advises keeping objects below 1 MB,
+/// and the cap keeps STUN, TPKT and RTMP headers from reading as lengths.
+const MAX_FRAME_LEN: u32 = 1024 * 1024;
+
+/// Riak PB framing: a four-byte big-endian length counting the message code
+/// byte. Riak messages use low field numbers.
+const RIAK: FramedProtobuf = FramedProtobuf::new()
+	.length_prefix(LengthPrefix::U32Be)
+	.message_codes(MESSAGE_CODES)
+	.max_length(MAX_FRAME_LEN)
+	.max_field_number(32);
+
+/// Probes for the Riak protocol buffers interface.
+///
+/// Expects a four-byte length up to 1 MiB, a message code from the documented
+/// ranges, and well-formed leading protobuf fields, the first one fully in
+/// view.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	(RIAK.probe(data), ProtocolVersion::Unknown)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_detect_ping() {
+		assert_eq!(
+			probe(&[0x00, 0x00, 0x00, 0x01, 0x01]),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+	}
+
+	#[test]
+	fn test_detect_get_request() {
+		// RpbGetReq { bucket: "users", key: "alice" }
+		let data = b"\x00\x00\x00\x0F\x09\x0A\x05users\x12\x05alice";
+		assert_eq!(probe(data).0, DetectionStatus::Match);
+		assert_eq!(probe(&data[..5]).0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	fn test_reject_unknown_code() {
+		assert_eq!(
+			probe(&[0x00, 0x00, 0x00, 0x01, 0x24]).0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(
+			probe(&[0x00, 0x00, 0x00, 0x00, 0x01]).0,
+			DetectionStatus::NoMatch
+		);
+	}
+
+	#[test]
+	fn test_reject_other_protocols() {
+		assert_eq!(probe(b"GET / HTTP/1.1\r\n").0, DetectionStatus::NoMatch);
+		// PostgreSQL SSLRequest: a valid code, then an out-of-range field number.
+		assert_eq!(
+			probe(&[0x00, 0x00, 0x00, 0x08, 0x04, 0xD2, 0x16, 0x2F]).0,
+			DetectionStatus::NoMatch
+		);
+	}
+
+	#[test]
+	fn test_no_match_on_baseline_protocols() {
+		// STUN binding, allocate, refresh, send and data indications, binding
+		// success and TURN create-permission headers: the magic cookie reads
+		// as a first field far longer than the window.
+		for message_type in [
+			0x0001u16, 0x0003, 0x0104, 0x0016, 0x0017, 0x0006, 0x0013, 0x0107,
+		] {
+			let mut stun = [0u8; 20];
+			stun[..2].copy_from_slice(&message_type.to_be_bytes());
+			stun[4..8].copy_from_slice(&[0x21, 0x12, 0xA4, 0x42]);
+			stun[8..].fill(0x5A);
+			assert_ne!(probe(&stun).0, DetectionStatus::Match);
+		}
+		// RDP X.224 Connection Request and RTMP C0+C1 handshakes.
+		let rdp = b"\x03\x00\x00\x0B\x06\xE0\x00\x00\x00\x00\x00";
+		assert_eq!(probe(rdp).0, DetectionStatus::NoMatch);
+		let rtmp = b"\x03\x00\x00\x00\x00\x09\x00\x7C\x02\xA1";
+		assert_eq!(probe(rtmp).0, DetectionStatus::NoMatch);
+		let rtmp = b"\x03\x00\x00\x12\x34\x00\x00\x00\x00\x5A\x5A";
+		assert_eq!(probe(rtmp).0, DetectionStatus::NoMatch);
+	}
+}
@@ -0,0 +1,163 @@
+/* src/protocols/rtcp.rs */
+use crate::{DetectionStatus, ProtocolVersion};
+
+/// Length of the common packet header.
+const HEADER_LEN: usize = 4;
+
+/// RTCP version 2 in the top two bits of the first byte.
+const VERSION_2: u8 = 0x80;
+
+/// Padding bit.
+const PADDING: u8 = 0x20;
+
+/// Sender report.
+const SR: u8 = 200;
+
+/// Receiver report.
+const RR: u8 = 201;
+
+/// Source description.
+const SDES: u8 = 202;
+
+/// Goodbye.
+const BYE: u8 = 203;
+
+/// Application-defined.
+const APP: u8 = 204;
+
+/// Extended report, the last packet type a compound packet may carry.
+const XR: u8 = 207;
+
+/// Probes for an RTCP compound packet.
+///
+/// The first packet must be version 2 with type SR, RR, SDES, BYE or APP
+/// (200–204), a length covering its fixed fields and report count, and no
+/// padding, and it must be wholly in view. Packets after it must chain on
+/// from its length with version 2 and a type from 200 to 207 (feedback and
+/// extended reports included); one running past the inspected data is
+/// accepted, since compound packets often outgrow the inspection window.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	(probe_status(data), ProtocolVersion::Unknown)
+}
+
+/// Checks the first packet, then walks the rest of the compound packet.
+#[inline(always)]
+fn probe_status(data: &[u8]) -> DetectionStatus {
+	let Some(&first) = data.first() else {
+		return DetectionStatus::Incomplete;
+	};
+	if first & 0xC0 != VERSION_2 || first & PADDING != 0 {
+		reject!("not_version_2");
+		return DetectionStatus::NoMatch;
+	}
+	let Some(header) = data.first_chunk::<HEADER_LEN>() else {
+		return DetectionStatus::Incomplete;
+	};
+	let count = usize::from(first & 0x1F);
+	let words = usize::from(u16::from_be_bytes([header[2], header[3]]));
+	// Length in 32-bit words after the header: SSRC and sender info, report
+	// blocks of six words, SSRC lists, or SSRC and name for APP.
+	let min_words = match header[1] {
+		SR => 6 + 6 * count,
+		RR => 1 + 6 * count,
+		SDES | BYE => count,
+		APP => 2,
+		_ => {
+			reject!("not_rtcp_packet_type");
+			return DetectionStatus::NoMatch;
+		}
+	};
+	if words < min_words {
+		reject!("length_too_short");
+		return DetectionStatus::NoMatch;
+	}
+
+	let mut offset = HEADER_LEN + words * 4;
+	if offset > data.len() {
+		return DetectionStatus::Incomplete;
+	}
+	while let Some(header) = data.get(offset..offset + HEADER_LEN) {
+		if header[0] & 0xC0 != VERSION_2 || !(SR..=XR).contains(&header[1]) {
+			reject!("invalid_compound_packet");
+			return DetectionStatus::NoMatch;
+		}
+		offset += HEADER_LEN + usize::from(u16::from_be_bytes([header[2], header[3]])) * 4;
+	}
+	if offset >= data.len() {
+		DetectionStatus::Match
+	} else {
+		reject!("trailing_bytes");
+		DetectionStatus::NoMatch
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Receiver report with one report block, then an SDES chunk with a
+	/// CNAME item.
+	const RR_SDES: [u8; 48] = [
+		0x81, 0xC9, 0x00, 0x07, 0x12, 0x34, 0x56, 0x78, 0xDE, 0xAD, 0xBE, 0xEF, 0x00, 0x00, 0x00, 0x00,
+		0x00, 0x00, 0x12, 0x34, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+		0x81, 0xCA, 0x00, 0x03, 0x12, 0x34, 0x56, 0x78, 0x01, 0x04, b'h', b'o', b's', b't', 0x00, 0x00,
+	];
+
+	#[test]
+	fn test_detect_compound_packet() {
+		assert_eq!(
+			probe(&RR_SDES),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+		// Cut inside the SDES packet, as a small inspection window would.
+		assert_eq!(probe(&RR_SDES[..40]).0, DetectionStatus::Match);
+	}
+
+	#[test]
+	fn test_detect_single_packets() {
+		// Sender report without report blocks.
+		let mut sr = [0u8; 28];
+		sr[..4].copy_from_slice(&[0x80, SR, 0x00, 0x06]);
+		sr[4..8].copy_from_slice(&[0x12, 0x34, 0x56, 0x78]);
+		assert_eq!(probe(&sr).0, DetectionStatus::Match);
+		// BYE for one source.
+		assert_eq!(
+			probe(&[0x81, BYE, 0x00, 0x01, 0x12, 0x34, 0x56, 0x78]).0,
+			DetectionStatus::Match
+		);
+	}
+
+	#[test]
+	fn test_incomplete_packet() {
+		assert_eq!(probe(&RR_SDES[..2]).0, DetectionStatus::Incomplete);
+		assert_eq!(probe(&RR_SDES[..20]).0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	fn test_reject_other_packets() {
+		// RTP with payload type 0, version 1, padding on the first packet.
+		let mut data = RR_SDES;
+		data[1] = 0x00;
+		assert_eq!(probe(&data).0, DetectionStatus::NoMatch);
+		let mut data = RR_SDES;
+		data[0] = 0x41;
+		assert_eq!(probe(&data).0, DetectionStatus::NoMatch);
+		let mut data = RR_SDES;
+		data[0] = 0xA1;
+		assert_eq!(probe(&data).0, DetectionStatus::NoMatch);
+		// Length too short for the report count.
+		let mut data = RR_SDES;
+		data[0] = 0x82;
+		assert_eq!(probe(&data).0, DetectionStatus::NoMatch);
+		// Second packet with a type outside RTCP.
+		let mut data = RR_SDES;
+		data[33] = 0x60;
+		assert_eq!(probe(&data).0, DetectionStatus::NoMatch);
+		// Stray bytes after the last packet.
+		assert_eq!(
+			probe(&[0x81, BYE, 0x00, 0x01, 0x12, 0x34, 0x56, 0x78, 0x00]).0,
+			DetectionStatus::NoMatch
+		);
+	}
+}
@@ -0,0 +1,100 @@
+/* src/protocols/rtmp.rs */
+use crate::{DetectionStatus, ProtocolVersion};
+
+/// C0 version of plain RTMP.
+const RTMP: u8 = 0x03;
+
+/// C0 version of encrypted RTMP (RTMPE).
+const RTMPE: u8 = 0x06;
+
+/// Length of C0 plus the C1 time and zero (or version) fields.
+const HEADER_LEN: usize = 9;
+
+/// Probes for the C0 and C1 chunks opening an RTMP handshake.
+///
+/// Expects version 3 (RTMP) or 6 (RTMPE), then the C1 timestamp and a field
+/// that is either zero, as the plain handshake sends it, or a player
+/// version announcing the digest handshake: Flash Player 9 to 32, or
+/// librtmp's `128.0.x.x`, always with minor version 0. Only
+/// these first 9 bytes of the 1537-byte chunks are checked; the rest of C1
+/// is random.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	(probe_status(data), ProtocolVersion::Unknown)
+}
+
+/// Checks the C0 version and the C1 zero or version field.
+#[inline(always)]
+fn probe_status(data: &[u8]) -> DetectionStatus {
+	match data.first() {
+		None => return DetectionStatus::Incomplete,
+		Some(&RTMP | &RTMPE) => {}
+		Some(_) => {
+			reject!("unknown_version");
+			return DetectionStatus::NoMatch;
+		}
+	}
+	let Some(header) = data.first_chunk::<HEADER_LEN>() else {
+		return DetectionStatus::Incomplete;
+	};
+	let digest_version = matches!(header[5], 9..=32 | 0x80) && header[6] == 0;
+	if header[5..] == [0; 4] || digest_version {
+		DetectionStatus::Match
+	} else {
+		reject!("invalid_c1_zero_field");
+		DetectionStatus::NoMatch
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_detect_plain_handshake() {
+		let mut data = [0u8; 64];
+		data[0] = RTMP;
+		data[1..5].copy_from_slice(&[0x00, 0x00, 0x12, 0x34]);
+		data[9..].fill(0x5A);
+		assert_eq!(
+			probe(&data),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+	}
+
+	#[test]
+	fn test_detect_digest_handshake() {
+		// Flash Player 9.0.124.2, and librtmp's 128.0.7.2.
+		assert_eq!(
+			probe(b"\x03\x00\x00\x00\x00\x09\x00\x7C\x02\xA1").0,
+			DetectionStatus::Match
+		);
+		assert_eq!(
+			probe(b"\x06\x00\x00\x00\x00\x80\x00\x07\x02\xA1").0,
+			DetectionStatus::Match
+		);
+	}
+
+	#[test]
+	fn test_incomplete_handshake() {
+		assert_eq!(probe(b"\x03").0, DetectionStatus::Incomplete);
+		assert_eq!(
+			probe(b"\x03\x00\x00\x12\x34\x00").0,
+			DetectionStatus::Incomplete
+		);
+	}
+
+	#[test]
+	fn test_reject_other_payloads() {
+		// TPKT carrying an X.224 Connection Request.
+		assert_eq!(
+			probe(b"\x03\x00\x00\x13\x0E\xE0\x00\x00\x00\x00\x00\x01\x00\x08\x00").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(
+			probe(b"\x05\x00\x00\x00\x00\x00\x00\x00\x00").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(probe(b"GET / HTTP/1.1\r\n").0, DetectionStatus::NoMatch);
+	}
+}
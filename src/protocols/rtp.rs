@@ -0,0 +1,145 @@
+/* src/protocols/rtp.rs */
+use crate::{DetectionStatus, ProtocolVersion};
+
+/// Length of the fixed RTP header.
+const HEADER_LEN: usize = 12;
+
+/// RTP version 2 in the top two bits of the first byte.
+const VERSION_2: u8 = 0x80;
+
+/// Padding bit.
+const PADDING: u8 = 0x20;
+
+/// Header extension bit.
+const EXTENSION: u8 = 0x10;
+
+/// Probes for an RTP media packet.
+///
+/// Expects version 2, a static (0–34) or dynamic (96–127) payload type,
+/// which leaves out the range RTCP packet types fall in with the marker bit
+/// set, a non-zero SSRC, and a sequence number and timestamp that are not
+/// both zero, since senders start them at random values. The CSRC list,
+/// header extension and padding must all fit within the datagram, leaving
+/// a payload.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	(probe_status(data), ProtocolVersion::Unknown)
+}
+
+/// Checks the fixed header, then the lengths of its optional parts.
+#[inline(always)]
+fn probe_status(data: &[u8]) -> DetectionStatus {
+	let Some(&first) = data.first() else {
+		return DetectionStatus::Incomplete;
+	};
+	if first & 0xC0 != VERSION_2 {
+		reject!("not_version_2");
+		return DetectionStatus::NoMatch;
+	}
+	let Some(header) = data.first_chunk::<HEADER_LEN>() else {
+		return DetectionStatus::Incomplete;
+	};
+	let payload_type = header[1] & 0x7F;
+	if !matches!(payload_type, 0..=34 | 96..=127) {
+		reject!("unassigned_payload_type");
+		return DetectionStatus::NoMatch;
+	}
+	if header[2..8] == [0; 6] {
+		reject!("zero_sequence_and_timestamp");
+		return DetectionStatus::NoMatch;
+	}
+	if header[8..12] == [0; 4] {
+		reject!("zero_ssrc");
+		return DetectionStatus::NoMatch;
+	}
+
+	let mut len = HEADER_LEN + usize::from(first & 0x0F) * 4;
+	if first & EXTENSION != 0 {
+		let Some(extension) = data.get(len..len + 4) else {
+			reject!("extension_truncated");
+			return DetectionStatus::NoMatch;
+		};
+		len += 4 + usize::from(u16::from_be_bytes([extension[2], extension[3]])) * 4;
+	}
+	let padding = if first & PADDING == 0 {
+		0
+	} else {
+		usize::from(data[data.len() - 1])
+	};
+	if first & PADDING != 0 && padding == 0 {
+		reject!("zero_padding");
+		return DetectionStatus::NoMatch;
+	}
+	if len + padding >= data.len() {
+		reject!("no_payload");
+		return DetectionStatus::NoMatch;
+	}
+	DetectionStatus::Match
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// PCMU packet: sequence 0x1234, timestamp 0xA0, SSRC 0xDEADBEEF, then
+	/// four payload bytes.
+	const PCMU: [u8; 16] = [
+		0x80, 0x00, 0x12, 0x34, 0x00, 0x00, 0x00, 0xA0, 0xDE, 0xAD, 0xBE, 0xEF, 0xFF, 0xFE, 0x7F, 0x7E,
+	];
+
+	#[test]
+	fn test_detect_static_payload() {
+		assert_eq!(
+			probe(&PCMU),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+	}
+
+	#[test]
+	fn test_detect_dynamic_payload_with_extension() {
+		// Marker, payload type 96, one CSRC, a one-word extension and
+		// two bytes of padding.
+		let mut data = [0u8; 32];
+		data[..12].copy_from_slice(&[
+			0xB1, 0xE0, 0x00, 0x01, 0x00, 0x01, 0x5F, 0x90, 0x01, 0x02, 0x03, 0x04,
+		]);
+		data[12..16].copy_from_slice(&[0x0A, 0x0B, 0x0C, 0x0D]);
+		data[16..20].copy_from_slice(&[0xBE, 0xDE, 0x00, 0x01]);
+		data[31] = 2;
+		assert_eq!(probe(&data).0, DetectionStatus::Match);
+	}
+
+	#[test]
+	fn test_incomplete_header() {
+		assert_eq!(probe(&PCMU[..1]).0, DetectionStatus::Incomplete);
+		assert_eq!(probe(&PCMU[..8]).0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	fn test_reject_other_packets() {
+		// RTCP sender report (packet type 200).
+		let mut data = PCMU;
+		data[1] = 0xC8;
+		assert_eq!(probe(&data).0, DetectionStatus::NoMatch);
+		// Version 1, zero SSRC, zero sequence and timestamp.
+		let mut data = PCMU;
+		data[0] = 0x40;
+		assert_eq!(probe(&data).0, DetectionStatus::NoMatch);
+		let mut data = PCMU;
+		data[8..12].fill(0);
+		assert_eq!(probe(&data).0, DetectionStatus::NoMatch);
+		let mut data = PCMU;
+		data[2..8].fill(0);
+		assert_eq!(probe(&data).0, DetectionStatus::NoMatch);
+		// CSRC list, extension or padding running past the datagram.
+		let mut data = PCMU;
+		data[0] = 0x81;
+		assert_eq!(probe(&data).0, DetectionStatus::NoMatch);
+		let mut data = PCMU;
+		data[0] = 0x90;
+		assert_eq!(probe(&data).0, DetectionStatus::NoMatch);
+		let mut data = PCMU;
+		data[0] = 0xA0;
+		assert_eq!(probe(&data).0, DetectionStatus::NoMatch);
+	}
+}
@@ -4,6 +4,7 @@
 #[inline(always)]
 pub(crate) fn detect(data: &[u8]) -> bool {
 	if data.len() < 14 {
+		reject!("too_short");
 		return false;
 	}
 
@@ -15,6 +16,7 @@ pub(crate) fn detect(data: &[u8]) -> bool {
 		return validate_rtsp_line(data, false);
 	}
 
+	reject!("unknown_method");
 	false
 }
 
@@ -47,6 +49,7 @@ fn validate_rtsp_line(data: &[u8], is_response: bool) -> bool {
 			break;
 		}
 		if !(32..=126).contains(&b) {
+			reject!("non_printable_byte");
 			return false;
 		}
 	}
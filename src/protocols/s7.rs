@@ -0,0 +1,185 @@
+/* src/protocols/s7.rs */
+use crate::{DetectionStatus, ProtocolVersion};
+
+/// TPKT version (RFC 1006).
+const TPKT_VERSION: u8 = 0x03;
+
+/// Length of the TPKT header.
+const TPKT_LEN: usize = 4;
+
+/// COTP Connection Request TPDU code (class 0, no credit).
+const COTP_CONNECTION_REQUEST: u8 = 0xE0;
+
+/// COTP Data TPDU code.
+const COTP_DATA: u8 = 0xF0;
+
+/// Length of the TPKT header and the fixed Connection Request fields.
+const CR_HEADER_LEN: usize = 11;
+
+/// Connection Request parameter carrying the called (destination) TSAP.
+const PARAM_DST_TSAP: u8 = 0xC2;
+
+/// S7comm protocol ID opening every S7 PDU.
+const S7_PROTOCOL_ID: u8 = 0x32;
+
+/// Probes for S7comm over ISO-on-TCP.
+///
+/// Recognizes the COTP Connection Request opening the connection, whose
+/// called TSAP names an S7 connection type (1 PG, 2 OP or 3 basic) followed
+/// by the rack and slot; MMS uses TSAPs starting with zero and RDP carries
+/// no TSAP parameters at all. Also recognizes a COTP Data TPDU carrying an
+/// S7 PDU: protocol ID `0x32`, a known message type and a zero reserved
+/// field.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	(probe_status(data), ProtocolVersion::Unknown)
+}
+
+/// Checks the TPKT header, then the COTP TPDU it carries.
+#[inline(always)]
+fn probe_status(data: &[u8]) -> DetectionStatus {
+	if data.first().is_some_and(|&b| b != TPKT_VERSION) || data.get(1).is_some_and(|&b| b != 0) {
+		reject!("not_tpkt");
+		return DetectionStatus::NoMatch;
+	}
+	let Some(header) = data.first_chunk::<{ TPKT_LEN + 2 }>() else {
+		return DetectionStatus::Incomplete;
+	};
+	let tpkt_len = usize::from(u16::from_be_bytes([header[2], header[3]]));
+	let cotp_len = usize::from(header[4]) + 1;
+	if TPKT_LEN + cotp_len > tpkt_len {
+		reject!("length_mismatch");
+		return DetectionStatus::NoMatch;
+	}
+	match header[5] {
+		COTP_CONNECTION_REQUEST if TPKT_LEN + cotp_len == tpkt_len => {
+			connection_request(&data[..data.len().min(tpkt_len)], data.len() >= tpkt_len)
+		}
+		COTP_DATA => data
+			.get(TPKT_LEN + cotp_len..)
+			.map_or(DetectionStatus::Incomplete, s7_pdu),
+		_ => {
+			reject!("unexpected_tpdu");
+			DetectionStatus::NoMatch
+		}
+	}
+}
+
+/// Walks the Connection Request parameters looking for an S7 called TSAP;
+/// `complete` tells whether the whole request is in view.
+#[inline(always)]
+fn connection_request(data: &[u8], complete: bool) -> DetectionStatus {
+	let Some(fixed) = data.get(..CR_HEADER_LEN) else {
+		return DetectionStatus::Incomplete;
+	};
+	if fixed[6..8] != [0, 0] || fixed[10] != 0 {
+		reject!("not_class_0_request");
+		return DetectionStatus::NoMatch;
+	}
+	let mut params = &data[CR_HEADER_LEN..];
+	loop {
+		let [code, len, rest @ ..] = params else {
+			if complete {
+				reject!("no_called_tsap");
+				return DetectionStatus::NoMatch;
+			}
+			return DetectionStatus::Incomplete;
+		};
+		let len = usize::from(*len);
+		if *code == PARAM_DST_TSAP {
+			return match rest {
+				[1..=3, _, ..] if len == 2 => DetectionStatus::Match,
+				[] | [1..=3] if len == 2 => DetectionStatus::Incomplete,
+				_ => {
+					reject!("not_s7_tsap");
+					DetectionStatus::NoMatch
+				}
+			};
+		}
+		let Some(next) = rest.get(len..) else {
+			if complete {
+				reject!("parameter_truncated");
+				return DetectionStatus::NoMatch;
+			}
+			return DetectionStatus::Incomplete;
+		};
+		params = next;
+	}
+}
+
+/// Checks the header of an S7 PDU.
+#[inline(always)]
+fn s7_pdu(pdu: &[u8]) -> DetectionStatus {
+	// Job, ack, ack with data, and userdata.
+	match pdu {
+		[] => DetectionStatus::Incomplete,
+		[S7_PROTOCOL_ID] | [S7_PROTOCOL_ID, 1..=3 | 7] | [S7_PROTOCOL_ID, 1..=3 | 7, 0] => {
+			DetectionStatus::Incomplete
+		}
+		[S7_PROTOCOL_ID, 1..=3 | 7, 0, 0, ..] => DetectionStatus::Match,
+		_ => {
+			reject!("not_s7_pdu");
+			DetectionStatus::NoMatch
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// COTP Connection Request from an S7 client: TPDU size 1024, calling
+	/// TSAP 01 00, called TSAP 01 02 (PG, rack 0, slot 2).
+	const CONNECT: [u8; 22] = [
+		0x03, 0x00, 0x00, 0x16, 0x11, 0xE0, 0x00, 0x00, 0x00, 0x01, 0x00, 0xC0, 0x01, 0x0A, 0xC1, 0x02,
+		0x01, 0x00, 0xC2, 0x02, 0x01, 0x02,
+	];
+
+	/// S7 Setup Communication job in a COTP Data TPDU.
+	const SETUP: [u8; 25] = [
+		0x03, 0x00, 0x00, 0x19, 0x02, 0xF0, 0x80, 0x32, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x08, 0x00,
+		0x00, 0xF0, 0x00, 0x00, 0x01, 0x00, 0x01, 0x01, 0xE0,
+	];
+
+	#[test]
+	fn test_detect_connection_request() {
+		assert_eq!(
+			probe(&CONNECT),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+	}
+
+	#[test]
+	fn test_detect_setup_communication() {
+		assert_eq!(probe(&SETUP).0, DetectionStatus::Match);
+	}
+
+	#[test]
+	fn test_incomplete_tpdu() {
+		assert_eq!(probe(&CONNECT[..3]).0, DetectionStatus::Incomplete);
+		assert_eq!(probe(&CONNECT[..16]).0, DetectionStatus::Incomplete);
+		assert_eq!(probe(&SETUP[..8]).0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	fn test_reject_other_iso_transports() {
+		// MMS: called TSAP 00 01.
+		let mut data = CONNECT;
+		data[20..22].copy_from_slice(&[0x00, 0x01]);
+		assert_eq!(probe(&data).0, DetectionStatus::NoMatch);
+		// RDP: Connection Request without parameters.
+		assert_eq!(
+			probe(b"\x03\x00\x00\x0B\x06\xE0\x00\x00\x00\x00\x00").0,
+			DetectionStatus::NoMatch
+		);
+		// MMS session CONNECT in a Data TPDU.
+		let mut data = SETUP;
+		data[7] = 0x0D;
+		assert_eq!(probe(&data).0, DetectionStatus::NoMatch);
+		// Unknown S7 message type.
+		let mut data = SETUP;
+		data[8] = 0x05;
+		assert_eq!(probe(&data).0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"GET / HTTP/1.1\r\n").0, DetectionStatus::NoMatch);
+	}
+}
@@ -0,0 +1,104 @@
+/* src/protocols/scgi.rs */
+use crate::{DetectionStatus, ProtocolVersion};
+
+/// First header name, which the SCGI specification requires.
+const CONTENT_LENGTH: &[u8] = b"CONTENT_LENGTH\0";
+
+/// Most digits in the netstring length.
+const MAX_LENGTH_DIGITS: usize = 7;
+
+/// Most digits in the `CONTENT_LENGTH` value.
+const MAX_VALUE_DIGITS: usize = 19;
+
+/// Probes for an SCGI request.
+///
+/// Expects the header netstring's decimal length and `:`, then
+/// `CONTENT_LENGTH` as the first header name with a decimal value. The
+/// length must at least cover that first header.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	(probe_status(data), ProtocolVersion::Unknown)
+}
+
+/// Checks the netstring length and the first header.
+#[inline(always)]
+fn probe_status(data: &[u8]) -> DetectionStatus {
+	let digits = data.iter().take_while(|b| b.is_ascii_digit()).count();
+	if digits > MAX_LENGTH_DIGITS || data.first() == Some(&b'0') {
+		reject!("invalid_netstring_length");
+		return DetectionStatus::NoMatch;
+	}
+	let Some(&colon) = data.get(digits) else {
+		return DetectionStatus::Incomplete;
+	};
+	if digits == 0 || colon != b':' {
+		reject!("not_netstring");
+		return DetectionStatus::NoMatch;
+	}
+	let length = data[..digits]
+		.iter()
+		.fold(0usize, |acc, &b| acc * 10 + usize::from(b - b'0'));
+
+	let headers = &data[digits + 1..];
+	let len = headers.len().min(CONTENT_LENGTH.len());
+	if headers[..len] != CONTENT_LENGTH[..len] {
+		reject!("no_content_length_header");
+		return DetectionStatus::NoMatch;
+	}
+	let value = &headers[len..];
+	let value_digits = value.iter().take_while(|b| b.is_ascii_digit()).count();
+	if value_digits > MAX_VALUE_DIGITS {
+		reject!("content_length_too_long");
+		return DetectionStatus::NoMatch;
+	}
+	let Some(&nul) = value.get(value_digits) else {
+		return DetectionStatus::Incomplete;
+	};
+	if value_digits == 0 || nul != 0 || length < CONTENT_LENGTH.len() + value_digits + 1 {
+		reject!("invalid_content_length");
+		return DetectionStatus::NoMatch;
+	}
+	DetectionStatus::Match
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_detect_request() {
+		let request = b"70:CONTENT_LENGTH\x0027\x00SCGI\x001\x00REQUEST_METHOD\x00POST\x00REQUEST_URI\x00/deepthought\x00,";
+		assert_eq!(
+			probe(request),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+		assert_eq!(probe(&request[..21]).0, DetectionStatus::Match);
+	}
+
+	#[test]
+	fn test_incomplete_request() {
+		assert_eq!(probe(b"70").0, DetectionStatus::Incomplete);
+		assert_eq!(probe(b"70:CONTENT_").0, DetectionStatus::Incomplete);
+		assert_eq!(
+			probe(b"70:CONTENT_LENGTH\x002").0,
+			DetectionStatus::Incomplete
+		);
+	}
+
+	#[test]
+	fn test_reject_other_payloads() {
+		// Other first header, empty value, length too short for the header.
+		assert_eq!(probe(b"70:SCGI\x001\x00").0, DetectionStatus::NoMatch);
+		assert_eq!(
+			probe(b"70:CONTENT_LENGTH\x00\x00SCGI").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(
+			probe(b"10:CONTENT_LENGTH\x000\x00").0,
+			DetectionStatus::NoMatch
+		);
+		// Leading zero and a bencoded string.
+		assert_eq!(probe(b"070:CONTENT").0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"4:spam").0, DetectionStatus::NoMatch);
+	}
+}
@@ -4,6 +4,7 @@
 #[inline(always)]
 pub(crate) fn detect(data: &[u8]) -> bool {
 	if data.len() < 12 {
+		reject!("too_short");
 		return false;
 	}
 
@@ -15,6 +16,7 @@ pub(crate) fn detect(data: &[u8]) -> bool {
 		return validate_sip_line(data, false);
 	}
 
+	reject!("unknown_method");
 	false
 }
 
@@ -50,6 +52,7 @@ fn validate_sip_line(data: &[u8], is_response: bool) -> bool {
 			break;
 		}
 		if !(32..=126).contains(&b) {
+			reject!("non_printable_byte");
 			return false;
 		}
 	}
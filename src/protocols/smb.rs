@@ -6,6 +6,7 @@
 #[inline(always)]
 pub(crate) fn detect(data: &[u8]) -> bool {
 	if data.len() < 4 {
+		reject!("too_short");
 		return false;
 	}
 
@@ -25,6 +26,7 @@ pub(crate) fn detect(data: &[u8]) -> bool {
 		}
 	}
 
+	reject!("missing_smb_magic");
 	false
 }
 
@@ -0,0 +1,145 @@
+/* src/protocols/smpp.rs */
+use crate::{DetectionStatus, ProtocolVersion};
+
+/// `bind_receiver`, `bind_transmitter` and `bind_transceiver`.
+const BIND_COMMANDS: [u32; 3] = [0x0000_0001, 0x0000_0002, 0x0000_0009];
+
+/// Length of the PDU header.
+const HEADER_LEN: usize = 16;
+
+/// Longest `system_id`, `password` and `system_type`, each with its NUL.
+const STRING_LIMITS: [usize; 3] = [16, 9, 13];
+
+/// Longest `address_range`, with its NUL.
+const MAX_ADDRESS_RANGE_LEN: usize = 41;
+
+/// Bind body length without any string contents: four NULs, the interface
+/// version, `addr_ton` and `addr_npi`.
+const MIN_BODY_LEN: usize = 7;
+
+/// Longest bind PDU: every string at its limit.
+const MAX_PDU_LEN: usize = HEADER_LEN + 16 + 9 + 13 + 3 + MAX_ADDRESS_RANGE_LEN;
+
+/// Interface versions: 3.3, 3.4 and 5.0.
+const INTERFACE_VERSIONS: [u8; 3] = [0x33, 0x34, 0x50];
+
+/// Probes for an SMPP bind request.
+///
+/// Expects a header with a `command_length` that fits a bind body, a
+/// `bind_transmitter`, `bind_receiver` or `bind_transceiver` command ID, a
+/// zero `command_status` and a nonzero sequence number. Then `system_id`,
+/// `password` and `system_type` must be short printable C strings, followed
+/// by interface version 3.3, 3.4 or 5.0, a known `addr_ton` and `addr_npi`,
+/// and an `address_range` as far as it is in view.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	let status = match parse(data) {
+		Ok(()) => DetectionStatus::Match,
+		Err(status) => status,
+	};
+	(status, ProtocolVersion::Unknown)
+}
+
+/// Checks the header and the bind body.
+#[inline(always)]
+fn parse(data: &[u8]) -> Result<(), DetectionStatus> {
+	let Some(header) = data.first_chunk::<HEADER_LEN>() else {
+		return Err(DetectionStatus::Incomplete);
+	};
+	let word =
+		|i: usize| u32::from_be_bytes([header[i], header[i + 1], header[i + 2], header[i + 3]]);
+	let length = usize::try_from(word(0)).unwrap_or(usize::MAX);
+	if !(HEADER_LEN + MIN_BODY_LEN..=MAX_PDU_LEN).contains(&length)
+		|| !BIND_COMMANDS.contains(&word(4))
+		|| word(8) != 0
+		|| !(1..=0x7FFF_FFFF).contains(&word(12))
+	{
+		reject!("not_bind_request");
+		return Err(DetectionStatus::NoMatch);
+	}
+
+	let mut rest = &data[HEADER_LEN..];
+	for limit in STRING_LIMITS {
+		rest = c_string(rest, limit)?;
+	}
+	let Some(&[version, ton, npi]) = rest.first_chunk::<3>() else {
+		return Err(DetectionStatus::Incomplete);
+	};
+	// Type of number 0-6, numbering plan indicators 0-18.
+	if !INTERFACE_VERSIONS.contains(&version) || ton > 6 || npi > 18 {
+		reject!("invalid_bind_fields");
+		return Err(DetectionStatus::NoMatch);
+	}
+	match c_string(&rest[3..], MAX_ADDRESS_RANGE_LEN) {
+		Ok(_) | Err(DetectionStatus::Incomplete) => Ok(()),
+		Err(status) => Err(status),
+	}
+}
+
+/// Checks a printable NUL-terminated string of at most `limit` bytes with
+/// the NUL, returning what follows it.
+#[inline(always)]
+fn c_string(data: &[u8], limit: usize) -> Result<&[u8], DetectionStatus> {
+	let window = &data[..data.len().min(limit)];
+	let len = window.iter().take_while(|b| b.is_ascii_graphic()).count();
+	match window.get(len) {
+		Some(0) => Ok(&data[len + 1..]),
+		None if data.len() < limit => Err(DetectionStatus::Incomplete),
+		_ => {
+			reject!("invalid_c_string");
+			Err(DetectionStatus::NoMatch)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// `bind_transceiver` for `smppclient1`, interface version 3.4.
+	const BIND_TRANSCEIVER: &[u8] =
+		b"\x00\x00\x00\x2A\x00\x00\x00\x09\x00\x00\x00\x00\x00\x00\x00\x01\
+		smppclient1\x00password\x00\x00\x34\x00\x00\x00";
+
+	#[test]
+	fn test_detect_bind() {
+		assert_eq!(
+			probe(BIND_TRANSCEIVER),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+		// bind_transmitter with a system type and an address range.
+		let bind = b"\x00\x00\x00\x27\x00\x00\x00\x02\x00\x00\x00\x00\x00\x00\x00\x07esme\x00secret\x00VMA\x00\x50\x01\x01^44\x00";
+		assert_eq!(probe(bind).0, DetectionStatus::Match);
+	}
+
+	#[test]
+	fn test_incomplete_bind() {
+		assert_eq!(
+			probe(&BIND_TRANSCEIVER[..12]).0,
+			DetectionStatus::Incomplete
+		);
+		assert_eq!(
+			probe(&BIND_TRANSCEIVER[..30]).0,
+			DetectionStatus::Incomplete
+		);
+	}
+
+	#[test]
+	fn test_reject_other_pdus() {
+		let mut pdu = [0u8; 42];
+		// submit_sm, then a nonzero status, then interface version 2.0.
+		pdu.copy_from_slice(BIND_TRANSCEIVER);
+		pdu[7] = 0x04;
+		assert_eq!(probe(&pdu).0, DetectionStatus::NoMatch);
+		pdu.copy_from_slice(BIND_TRANSCEIVER);
+		pdu[11] = 0x0D;
+		assert_eq!(probe(&pdu).0, DetectionStatus::NoMatch);
+		pdu.copy_from_slice(BIND_TRANSCEIVER);
+		pdu[38] = 0x20;
+		assert_eq!(probe(&pdu).0, DetectionStatus::NoMatch);
+		// System ID longer than 15 characters.
+		let long =
+			b"\x00\x00\x00\x30\x00\x00\x00\x01\x00\x00\x00\x00\x00\x00\x00\x01averyveryverylongid\x00";
+		assert_eq!(probe(long).0, DetectionStatus::NoMatch);
+	}
+}
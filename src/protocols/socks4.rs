@@ -0,0 +1,179 @@
+/* src/protocols/socks4.rs */
+use crate::{DetectionStatus, ProtocolVersion};
+
+/// SOCKS protocol version 4.
+const VERSION: u8 = 0x04;
+
+/// `CONNECT` command.
+const CONNECT: u8 = 0x01;
+
+/// `BIND` command.
+const BIND: u8 = 0x02;
+
+/// Length of the fixed part: version, command, port and IPv4 address.
+const FIXED_LEN: usize = 8;
+
+/// Longest user ID accepted.
+const MAX_USER_ID_LEN: usize = 255;
+
+/// Longest SOCKS4a domain name accepted.
+const MAX_DOMAIN_LEN: usize = 255;
+
+/// Probes for a SOCKS4 or SOCKS4a client request.
+///
+/// Expects version 4, a `CONNECT` or `BIND` command, a non-zero port and an
+/// IPv4 address, then a NUL-terminated printable user ID. An address of
+/// `0.0.0.x` with `x` non-zero marks SOCKS4a, where a NUL-terminated domain
+/// name follows the user ID. The request must end at its last terminator,
+/// since clients wait for the reply before sending anything else.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	(probe_status(data), ProtocolVersion::Unknown)
+}
+
+/// Checks the fixed header, then the variable-length strings.
+#[inline(always)]
+fn probe_status(data: &[u8]) -> DetectionStatus {
+	match data {
+		[] => return DetectionStatus::Incomplete,
+		[VERSION] | [VERSION, CONNECT | BIND, ..] => {}
+		_ => {
+			reject!("not_socks4_request");
+			return DetectionStatus::NoMatch;
+		}
+	}
+	let Some(header) = data.first_chunk::<FIXED_LEN>() else {
+		return DetectionStatus::Incomplete;
+	};
+	if header[2..4] == [0, 0] {
+		reject!("zero_port");
+		return DetectionStatus::NoMatch;
+	}
+	let socks4a = match header[4..] {
+		[0, 0, 0, 0] => {
+			reject!("zero_address");
+			return DetectionStatus::NoMatch;
+		}
+		[0, 0, 0, _] => true,
+		_ => false,
+	};
+
+	let (_, rest) = match terminated(&data[FIXED_LEN..], MAX_USER_ID_LEN, u8::is_ascii_graphic) {
+		Ok(parts) => parts,
+		Err(status) => return status,
+	};
+	let rest = if socks4a {
+		let domain_char = |b: &u8| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.');
+		match terminated(rest, MAX_DOMAIN_LEN, domain_char) {
+			Ok(([], _)) => {
+				reject!("empty_domain");
+				return DetectionStatus::NoMatch;
+			}
+			Ok((_, rest)) => rest,
+			Err(status) => return status,
+		}
+	} else {
+		rest
+	};
+	if rest.is_empty() {
+		DetectionStatus::Match
+	} else {
+		reject!("trailing_data");
+		DetectionStatus::NoMatch
+	}
+}
+
+/// Splits off a NUL-terminated string of `allowed` bytes, returning the
+/// string and the data after its terminator.
+#[inline(always)]
+fn terminated(
+	data: &[u8],
+	max_len: usize,
+	allowed: impl Fn(&u8) -> bool,
+) -> Result<(&[u8], &[u8]), DetectionStatus> {
+	let len = data.iter().take_while(|b| allowed(b)).count();
+	match data.get(len) {
+		_ if len > max_len => {
+			reject!("string_too_long");
+			Err(DetectionStatus::NoMatch)
+		}
+		None => Err(DetectionStatus::Incomplete),
+		Some(0) => Ok((&data[..len], &data[len + 1..])),
+		Some(_) => {
+			reject!("invalid_string");
+			Err(DetectionStatus::NoMatch)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_detect_connect_request() {
+		// CONNECT 93.184.216.34:80 as "alice".
+		assert_eq!(
+			probe(b"\x04\x01\x00\x50\x5D\xB8\xD8\x22alice\x00"),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+		// BIND with an empty user ID.
+		assert_eq!(
+			probe(b"\x04\x02\x1F\x90\x0A\x00\x00\x01\x00").0,
+			DetectionStatus::Match
+		);
+	}
+
+	#[test]
+	fn test_detect_socks4a_request() {
+		assert_eq!(
+			probe(b"\x04\x01\x01\xBB\x00\x00\x00\x01\x00example.com\x00").0,
+			DetectionStatus::Match
+		);
+	}
+
+	#[test]
+	fn test_incomplete_request() {
+		assert_eq!(probe(b"\x04").0, DetectionStatus::Incomplete);
+		assert_eq!(probe(b"\x04\x01\x00\x50").0, DetectionStatus::Incomplete);
+		assert_eq!(
+			probe(b"\x04\x01\x00\x50\x5D\xB8\xD8\x22ali").0,
+			DetectionStatus::Incomplete
+		);
+		assert_eq!(
+			probe(b"\x04\x01\x01\xBB\x00\x00\x00\x01\x00example").0,
+			DetectionStatus::Incomplete
+		);
+	}
+
+	#[test]
+	fn test_reject_other_requests() {
+		// SOCKS5 greeting, unknown command, zero port, zero address.
+		assert_eq!(probe(b"\x05\x01\x00").0, DetectionStatus::NoMatch);
+		assert_eq!(
+			probe(b"\x04\x03\x00\x50\x5D\xB8\xD8\x22\x00").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(
+			probe(b"\x04\x01\x00\x00\x5D\xB8\xD8\x22\x00").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(
+			probe(b"\x04\x01\x00\x50\x00\x00\x00\x00\x00").0,
+			DetectionStatus::NoMatch
+		);
+		// Binary user ID, empty SOCKS4a domain, trailing data.
+		assert_eq!(
+			probe(b"\x04\x01\x00\x50\x5D\xB8\xD8\x22\x01\x00").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(
+			probe(b"\x04\x01\x00\x50\x00\x00\x00\x01\x00\x00").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(
+			probe(b"\x04\x01\x00\x50\x5D\xB8\xD8\x22\x00GET").0,
+			DetectionStatus::NoMatch
+		);
+	}
+}
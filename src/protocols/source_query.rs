@@ -0,0 +1,108 @@
+/* src/protocols/source_query.rs */
+
+/// Header of a packet that fits in one datagram (-1, little-endian).
+const SINGLE: [u8; 4] = [0xFF, 0xFF, 0xFF, 0xFF];
+
+/// Header of one fragment of a split response (-2, little-endian).
+const SPLIT: [u8; 4] = [0xFE, 0xFF, 0xFF, 0xFF];
+
+/// Payload of an `A2S_INFO` request after its `T` type byte.
+const INFO_QUERY: &[u8] = b"Source Engine Query\0";
+
+/// Largest fragment size a Source server announces.
+const MAX_SPLIT_SIZE: u16 = 1400;
+
+/// Detects Source engine (Steam) server queries.
+///
+/// Recognizes `A2S_INFO` requests (`T` followed by `Source Engine Query`),
+/// `A2S_PLAYER` and `A2S_RULES` requests (`U`/`V` with a 4-byte challenge),
+/// the server's `A` challenge reply, and fragments of split responses in
+/// either the Source or the GoldSrc header layout.
+#[inline(always)]
+pub(crate) fn detect(data: &[u8]) -> bool {
+	if data.len() < 9 {
+		reject!("too_short");
+		return false;
+	}
+	match data.first_chunk() {
+		Some(&SINGLE) => detect_single(data),
+		Some(&SPLIT) => detect_split(data),
+		_ => {
+			reject!("unknown_header");
+			false
+		}
+	}
+}
+
+/// Checks a single-packet query or challenge by its type byte.
+#[inline(always)]
+fn detect_single(data: &[u8]) -> bool {
+	let valid = match data[4] {
+		b'T' => data[5..].starts_with(INFO_QUERY),
+		b'U' | b'V' | b'A' => data.len() == 9,
+		_ => false,
+	};
+	if !valid {
+		reject!("unknown_query");
+	}
+	valid
+}
+
+/// Checks a split fragment: Source's total, number, and size fields, or
+/// GoldSrc's packed number and total nibbles.
+#[inline(always)]
+fn detect_split(data: &[u8]) -> bool {
+	let source = data.len() >= 12 && {
+		let (total, number) = (data[8], data[9]);
+		let size = u16::from_le_bytes([data[10], data[11]]);
+		total >= 2 && number < total && (1..=MAX_SPLIT_SIZE).contains(&size)
+	};
+	let goldsrc = {
+		let (total, number) = (data[8] & 0x0F, data[8] >> 4);
+		total >= 2 && number < total
+	};
+	if !(source || goldsrc) {
+		reject!("invalid_split_header");
+		return false;
+	}
+	true
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_detect_info_query() {
+		assert!(detect(b"\xFF\xFF\xFF\xFFTSource Engine Query\x00"));
+		assert!(detect(
+			b"\xFF\xFF\xFF\xFFTSource Engine Query\x00\x4B\xA1\x3C\x09"
+		));
+	}
+
+	#[test]
+	fn test_detect_challenge_forms() {
+		assert!(detect(b"\xFF\xFF\xFF\xFFU\xFF\xFF\xFF\xFF"));
+		assert!(detect(b"\xFF\xFF\xFF\xFFV\x4B\xA1\x3C\x09"));
+		assert!(detect(b"\xFF\xFF\xFF\xFFA\x4B\xA1\x3C\x09"));
+	}
+
+	#[test]
+	fn test_detect_split_fragments() {
+		// Source: id, total 3, number 0, size 1248, then the inner header.
+		assert!(detect(
+			b"\xFE\xFF\xFF\xFF\x01\x00\x00\x00\x03\x00\xE0\x04\xFF\xFF\xFF\xFFE"
+		));
+		// GoldSrc: id, number 1 of 2 packed into one byte.
+		assert!(detect(b"\xFE\xFF\xFF\xFF\x01\x00\x00\x00\x12\x00\x01"));
+	}
+
+	#[test]
+	fn test_reject_other_queries() {
+		assert!(!detect(b"\xFF\xFF\xFF\xFFTSource Engine Quer"));
+		assert!(!detect(b"\xFF\xFF\xFF\xFFU\xFF\xFF\xFF\xFF\x00"));
+		assert!(!detect(b"\xFF\xFF\xFF\xFFgetchallenge"));
+		assert!(!detect(b"\xFE\xFF\xFF\xFF\x01\x00\x00\x00\x30\x00\x00\x00"));
+		assert!(!detect(b"GET / HTTP/1.1\r\n"));
+	}
+}
@@ -0,0 +1,175 @@
+/* src/protocols/spdy.rs */
+use crate::{DetectionStatus, ProtocolVersion};
+
+/// Control bit set in the first byte of every control frame.
+const CONTROL_BIT: u8 = 0x80;
+
+/// `SYN_STREAM` control frame type.
+const SYN_STREAM: u16 = 1;
+
+/// `SETTINGS` control frame type.
+const SETTINGS: u16 = 4;
+
+/// Length of the control frame header.
+const HEADER_LEN: usize = 8;
+
+/// Smallest `SYN_STREAM` body: stream ID, associated stream ID, priority and
+/// slot.
+const MIN_SYN_STREAM_LEN: usize = 10;
+
+/// Largest control frame accepted as a client's first frame.
+const MAX_FRAME_LEN: usize = 64 * 1024;
+
+/// Length of one `SETTINGS` entry (flags and ID, then the value).
+const SETTINGS_ENTRY_LEN: usize = 8;
+
+/// Most `SETTINGS` entries accepted.
+const MAX_SETTINGS: usize = 32;
+
+/// Probes for a SPDY/2 or SPDY/3 control frame opening a session.
+///
+/// Expects the control bit with version 2 or 3, then a `SYN_STREAM` (known
+/// flags, a body long enough for its fixed fields, and an odd client stream
+/// ID) or a `SETTINGS` frame (whose length fits its entry count).
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	match parse(data) {
+		Ok(version) => (DetectionStatus::Match, ProtocolVersion::Spdy(version)),
+		Err(status) => (status, ProtocolVersion::Unknown),
+	}
+}
+
+/// Checks the frame header and the fixed fields of the frame body.
+#[inline(always)]
+fn parse(data: &[u8]) -> Result<u8, DetectionStatus> {
+	let Some(&control) = data.first() else {
+		return Err(DetectionStatus::Incomplete);
+	};
+	if control != CONTROL_BIT {
+		reject!("not_control_frame");
+		return Err(DetectionStatus::NoMatch);
+	}
+	let Some(&version) = data.get(1) else {
+		return Err(DetectionStatus::Incomplete);
+	};
+	if !(2..=3).contains(&version) {
+		reject!("unsupported_version");
+		return Err(DetectionStatus::NoMatch);
+	}
+	let Some(header) = data.first_chunk::<HEADER_LEN>() else {
+		return Err(DetectionStatus::Incomplete);
+	};
+
+	let frame_type = u16::from_be_bytes([header[2], header[3]]);
+	let flags = header[4];
+	let length = usize::from(header[5]) << 16 | usize::from(header[6]) << 8 | usize::from(header[7]);
+	let body = &data[HEADER_LEN..];
+	match frame_type {
+		SYN_STREAM => {
+			// FLAG_FIN and FLAG_UNIDIRECTIONAL.
+			if flags & !0x03 != 0 || !(MIN_SYN_STREAM_LEN..=MAX_FRAME_LEN).contains(&length) {
+				reject!("invalid_syn_stream");
+				return Err(DetectionStatus::NoMatch);
+			}
+			if let Some(&stream_id) = body.first_chunk::<4>() {
+				let stream_id = u32::from_be_bytes(stream_id) & 0x7FFF_FFFF;
+				if stream_id % 2 == 0 {
+					reject!("not_client_stream");
+					return Err(DetectionStatus::NoMatch);
+				}
+			}
+		}
+		SETTINGS => {
+			// FLAG_SETTINGS_CLEAR_SETTINGS.
+			let entries = length.saturating_sub(4) / SETTINGS_ENTRY_LEN;
+			if flags & !0x01 != 0
+				|| length < 4
+				|| (length - 4) % SETTINGS_ENTRY_LEN != 0
+				|| entries > MAX_SETTINGS
+			{
+				reject!("invalid_settings");
+				return Err(DetectionStatus::NoMatch);
+			}
+			let count = body
+				.first_chunk::<4>()
+				.map(|&count| usize::try_from(u32::from_be_bytes(count)).ok());
+			if count.is_some_and(|count| count != Some(entries)) {
+				reject!("settings_count_mismatch");
+				return Err(DetectionStatus::NoMatch);
+			}
+		}
+		_ => {
+			reject!("unexpected_frame_type");
+			return Err(DetectionStatus::NoMatch);
+		}
+	}
+	Ok(version)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_detect_syn_stream() {
+		let frame = b"\x80\x03\x00\x01\x01\x00\x00\x2A\x00\x00\x00\x01\x00\x00\x00\x00\x60\x00\x38\xEA";
+		assert_eq!(
+			probe(frame),
+			(DetectionStatus::Match, ProtocolVersion::Spdy(3))
+		);
+		assert_eq!(
+			probe(b"\x80\x02\x00\x01\x00\x00\x00\x20").0,
+			DetectionStatus::Match
+		);
+	}
+
+	#[test]
+	fn test_detect_settings() {
+		let frame = b"\x80\x03\x00\x04\x00\x00\x00\x0C\x00\x00\x00\x01\x00\x00\x00\x04\x00\x00\x03\xE8";
+		assert_eq!(
+			probe(frame),
+			(DetectionStatus::Match, ProtocolVersion::Spdy(3))
+		);
+	}
+
+	#[test]
+	fn test_incomplete_header() {
+		assert_eq!(probe(b"\x80").0, DetectionStatus::Incomplete);
+		assert_eq!(probe(b"\x80\x03\x00\x01").0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	fn test_reject_other_frames() {
+		// Data frame, unknown version, GOAWAY.
+		assert_eq!(
+			probe(b"\x00\x00\x00\x01\x00\x00\x00\x10").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(
+			probe(b"\x80\x04\x00\x01\x00\x00\x00\x10").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(
+			probe(b"\x80\x03\x00\x07\x00\x00\x00\x08").0,
+			DetectionStatus::NoMatch
+		);
+		// Short SYN_STREAM, even stream ID, unknown flags.
+		assert_eq!(
+			probe(b"\x80\x03\x00\x01\x00\x00\x00\x04").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(
+			probe(b"\x80\x03\x00\x01\x00\x00\x00\x10\x00\x00\x00\x02").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(
+			probe(b"\x80\x03\x00\x01\x04\x00\x00\x10").0,
+			DetectionStatus::NoMatch
+		);
+		// SETTINGS whose count disagrees with the length.
+		assert_eq!(
+			probe(b"\x80\x03\x00\x04\x00\x00\x00\x0C\x00\x00\x00\x02").0,
+			DetectionStatus::NoMatch
+		);
+	}
+}
@@ -9,6 +9,7 @@ pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
 	}
 
 	if !data.starts_with(b"SSH-") {
+		reject!("missing_ssh_prefix");
 		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
 	}
 
@@ -23,6 +24,7 @@ pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
 	} else if b"SSH-1.99-".starts_with(data) {
 		return (DetectionStatus::Incomplete, ProtocolVersion::Unknown);
 	} else {
+		reject!("unknown_version");
 		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
 	};
 
@@ -34,6 +36,7 @@ pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
 			break;
 		}
 		if b != b'\r' && !(32..=126).contains(&b) {
+			reject!("non_printable_banner");
 			return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
 		}
 	}
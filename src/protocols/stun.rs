@@ -4,19 +4,23 @@
 #[inline(always)]
 pub(crate) fn detect(data: &[u8]) -> bool {
 	if data.len() < 20 {
+		reject!("too_short");
 		return false;
 	}
 
 	if data[4..8] != [0x21, 0x12, 0xA4, 0x42] {
+		reject!("magic_cookie_mismatch");
 		return false;
 	}
 
 	if (data[0] & 0xC0) != 0 {
+		reject!("message_type_invalid");
 		return false;
 	}
 
 	let msg_len = u16::from_be_bytes([data[2], data[3]]);
 	if !msg_len.is_multiple_of(4) {
+		reject!("message_length_unaligned");
 		return false;
 	}
 
@@ -0,0 +1,204 @@
+/* src/protocols/sunrpc.rs */
+use crate::{DetectionStatus, ProtocolMetadata, ProtocolVersion};
+
+/// `msg_type` of a call.
+const CALL: u32 = 0;
+
+/// The only RPC protocol version in use.
+const RPC_VERSION: u32 = 2;
+
+/// Length of the record mark that precedes messages over TCP.
+const RECORD_MARK_LEN: usize = 4;
+
+/// Record mark bit set on the last fragment of a record.
+const LAST_FRAGMENT: u32 = 0x8000_0000;
+
+/// Smallest call: the fixed header plus empty credentials and verifier.
+const MIN_CALL_LEN: u32 = 40;
+
+/// Largest fragment accepted over TCP.
+const MAX_FRAGMENT_LEN: u32 = 1 << 24;
+
+/// Highest program version accepted.
+const MAX_PROGRAM_VERSION: u32 = 10;
+
+/// Highest procedure number accepted.
+const MAX_PROCEDURE: u32 = 255;
+
+/// Longest credential or verifier body (RFC 5531).
+const MAX_AUTH_LEN: u32 = 400;
+
+/// Programs recognized: portmapper, rstatd, NFS, NIS (`ypserv`, `ypbind`,
+/// `yppasswdd`), mountd, rquotad, NLM, NSM status and NFS ACL.
+const PROGRAMS: [u32; 11] = [
+	100_000, 100_001, 100_003, 100_004, 100_005, 100_007, 100_009, 100_011, 100_021, 100_024, 100_227,
+];
+
+/// Probes for an ONC RPC (Sun RPC) call.
+///
+/// Accepts the bare call sent over UDP and the call behind a last-fragment
+/// record mark over TCP. The header must be a call with RPC version 2 to a
+/// known program, such as the portmapper (100000), NFS (100003) or mountd
+/// (100005), with a plausible version and procedure; credentials and
+/// verifier are checked as far as they are in view.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	let status = match call_offset(data).and_then(|offset| parse_call(&data[offset..])) {
+		Ok(_) => DetectionStatus::Match,
+		Err(status) => status,
+	};
+	(status, ProtocolVersion::Unknown)
+}
+
+/// Extracts the called program, its version and the procedure.
+#[inline(always)]
+pub(crate) fn metadata(data: &[u8]) -> ProtocolMetadata<'_> {
+	match call_offset(data).and_then(|offset| parse_call(&data[offset..])) {
+		Ok((program, version, procedure)) => ProtocolMetadata::SunRpc {
+			program,
+			version,
+			procedure,
+		},
+		Err(_) => ProtocolMetadata::None,
+	}
+}
+
+/// Returns the offset of the call: 0 when bare, or past the record mark.
+#[inline(always)]
+fn call_offset(data: &[u8]) -> Result<usize, DetectionStatus> {
+	let (Some(mark), Some(msg_type), Some(rpc_version)) =
+		(word(data, 0), word(data, 4), word(data, 8))
+	else {
+		return Err(DetectionStatus::Incomplete);
+	};
+	if msg_type == CALL && rpc_version == RPC_VERSION {
+		return Ok(0);
+	}
+	if mark & LAST_FRAGMENT == 0
+		|| !(MIN_CALL_LEN..=MAX_FRAGMENT_LEN).contains(&(mark & !LAST_FRAGMENT))
+	{
+		reject!("not_rpc_call");
+		return Err(DetectionStatus::NoMatch);
+	}
+	Ok(RECORD_MARK_LEN)
+}
+
+/// Checks the call header, credentials and verifier, returning the program,
+/// version and procedure.
+#[inline(always)]
+fn parse_call(data: &[u8]) -> Result<(u32, u32, u32), DetectionStatus> {
+	let header = |offset| word(data, offset).ok_or(DetectionStatus::Incomplete);
+	if header(4)? != CALL || header(8)? != RPC_VERSION {
+		reject!("not_rpc_call");
+		return Err(DetectionStatus::NoMatch);
+	}
+	let program = header(12)?;
+	if !PROGRAMS.contains(&program) {
+		reject!("unknown_program");
+		return Err(DetectionStatus::NoMatch);
+	}
+	let version = header(16)?;
+	let procedure = header(20)?;
+	if !(1..=MAX_PROGRAM_VERSION).contains(&version) || procedure > MAX_PROCEDURE {
+		reject!("invalid_procedure");
+		return Err(DetectionStatus::NoMatch);
+	}
+
+	// Credentials, then the verifier, each a flavor and an opaque body.
+	let mut offset = 24;
+	for _ in 0..2 {
+		let (Some(flavor), Some(len)) = (word(data, offset), word(data, offset + 4)) else {
+			break;
+		};
+		// AUTH_NONE, AUTH_SYS, AUTH_SHORT, AUTH_DH and RPCSEC_GSS.
+		if !matches!(flavor, 0..=3 | 6) || len > MAX_AUTH_LEN || flavor == 0 && len != 0 {
+			reject!("invalid_auth");
+			return Err(DetectionStatus::NoMatch);
+		}
+		offset += 8 + (len as usize).next_multiple_of(4);
+	}
+	Ok((program, version, procedure))
+}
+
+/// Reads the big-endian word at `offset`.
+#[inline(always)]
+fn word(data: &[u8], offset: usize) -> Option<u32> {
+	data
+		.get(offset..offset + 4)
+		.map(|bytes| u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Portmapper `GETPORT` for NFS v3 over TCP, sent over UDP.
+	const GETPORT_CALL: &[u8] = b"\x5E\x3C\x1A\x2B\x00\x00\x00\x00\x00\x00\x00\x02\x00\x01\x86\xA0\
+		\x00\x00\x00\x02\x00\x00\x00\x03\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\
+		\x00\x00\x00\x00\x00\x01\x86\xA3\x00\x00\x00\x03\x00\x00\x00\x06\x00\x00\x00\x00";
+
+	/// NFS v3 `GETATTR` with `AUTH_SYS` credentials, behind a TCP record
+	/// mark.
+	const GETATTR_CALL: &[u8] = b"\x80\x00\x00\x54\x1A\x2B\x3C\x4D\x00\x00\x00\x00\x00\x00\x00\x02\
+		\x00\x01\x86\xA3\x00\x00\x00\x03\x00\x00\x00\x01\x00\x00\x00\x01\x00\x00\x00\x20\
+		\x65\xF0\xA1\xB2\x00\x00\x00\x06client\x00\x00\x00\x00\x03\xE8\x00\x00\x03\xE8\
+		\x00\x00\x00\x01\x00\x00\x03\xE8\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x08\
+		\x01\x00\x07\x00\x02\x00\x00\x00";
+
+	#[test]
+	fn test_detect_udp_call() {
+		assert_eq!(
+			probe(GETPORT_CALL),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+		assert_eq!(
+			metadata(GETPORT_CALL),
+			ProtocolMetadata::SunRpc {
+				program: 100_000,
+				version: 2,
+				procedure: 3,
+			}
+		);
+	}
+
+	#[test]
+	fn test_detect_tcp_call() {
+		assert_eq!(probe(GETATTR_CALL).0, DetectionStatus::Match);
+		assert_eq!(
+			metadata(GETATTR_CALL),
+			ProtocolMetadata::SunRpc {
+				program: 100_003,
+				version: 3,
+				procedure: 1,
+			}
+		);
+	}
+
+	#[test]
+	fn test_incomplete_header() {
+		assert_eq!(probe(&GETPORT_CALL[..10]).0, DetectionStatus::Incomplete);
+		assert_eq!(probe(&GETATTR_CALL[..20]).0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	fn test_reject_other_messages() {
+		// Reply (msg_type 1) and a call to an unknown program.
+		let mut reply = [0u8; 56];
+		reply.copy_from_slice(GETPORT_CALL);
+		reply[7] = 1;
+		assert_eq!(probe(&reply).0, DetectionStatus::NoMatch);
+		let mut call = [0u8; 56];
+		call.copy_from_slice(GETPORT_CALL);
+		call[15] = 0x99;
+		assert_eq!(probe(&call).0, DetectionStatus::NoMatch);
+		// AUTH_NONE credentials with a body.
+		call.copy_from_slice(GETPORT_CALL);
+		call[31] = 8;
+		assert_eq!(probe(&call).0, DetectionStatus::NoMatch);
+		// Record mark without the last-fragment bit.
+		let mut record = [0u8; 88];
+		record.copy_from_slice(GETATTR_CALL);
+		record[0] = 0;
+		assert_eq!(probe(&record).0, DetectionStatus::NoMatch);
+	}
+}
@@ -0,0 +1,126 @@
+/* src/protocols/svn.rs */
+use crate::{DetectionStatus, ProtocolVersion};
+
+/// Opening of the server greeting after the outer parenthesis.
+const GREETING: &[u8] = b"success ( ";
+
+/// Capability every client since Subversion 1.0 lists first.
+const EDIT_PIPELINE: &[u8] = b"( edit-pipeline ";
+
+/// Highest protocol version accepted.
+const MAX_VERSION: u32 = 9;
+
+/// Most digits accepted in a version number.
+const MAX_DIGITS: usize = 3;
+
+/// Probes for the svnserve (`svn://`) protocol.
+///
+/// Recognizes the server greeting, `( success ( <minver> <maxver> ( ...`
+/// with a plausible version range, and the client's reply, `( <version> (
+/// edit-pipeline ...`. Both are space-separated S-expressions.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	let status = match parse(data) {
+		Ok(()) => DetectionStatus::Match,
+		Err(status) => status,
+	};
+	(status, ProtocolVersion::Unknown)
+}
+
+/// Checks the greeting or the client reply, depending on the first token.
+#[inline(always)]
+fn parse(data: &[u8]) -> Result<(), DetectionStatus> {
+	let rest = expect(data, b"( ")?;
+	let Some(&first) = rest.first() else {
+		return Err(DetectionStatus::Incomplete);
+	};
+	if first.is_ascii_digit() {
+		let (_, rest) = version(rest)?;
+		expect(rest, EDIT_PIPELINE)?;
+		return Ok(());
+	}
+	let rest = expect(rest, GREETING)?;
+	let (min, rest) = version(rest)?;
+	let (max, rest) = version(rest)?;
+	if min > max {
+		reject!("inverted_version_range");
+		return Err(DetectionStatus::NoMatch);
+	}
+	expect(rest, b"( ")?;
+	Ok(())
+}
+
+/// Matches `token` at the start of `data`, returning what follows it.
+#[inline(always)]
+fn expect<'a>(data: &'a [u8], token: &[u8]) -> Result<&'a [u8], DetectionStatus> {
+	let len = data.len().min(token.len());
+	if data[..len] != token[..len] {
+		reject!("unexpected_token");
+		return Err(DetectionStatus::NoMatch);
+	}
+	if len < token.len() {
+		return Err(DetectionStatus::Incomplete);
+	}
+	Ok(&data[len..])
+}
+
+/// Reads a version number and the space after it.
+#[inline(always)]
+fn version(data: &[u8]) -> Result<(u32, &[u8]), DetectionStatus> {
+	let digits = data.iter().take_while(|b| b.is_ascii_digit()).count();
+	if digits > MAX_DIGITS {
+		reject!("version_too_long");
+		return Err(DetectionStatus::NoMatch);
+	}
+	let Some(&next) = data.get(digits) else {
+		return Err(DetectionStatus::Incomplete);
+	};
+	let value = data[..digits]
+		.iter()
+		.fold(0, |acc, &b| acc * 10 + u32::from(b - b'0'));
+	if next != b' ' || !(1..=MAX_VERSION).contains(&value) {
+		reject!("invalid_version");
+		return Err(DetectionStatus::NoMatch);
+	}
+	Ok((value, &data[digits + 1..]))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_detect_server_greeting() {
+		let greeting = b"( success ( 2 2 ( ) ( edit-pipeline svndiff1 accepts-svndiff2 absent-entries commit-revprops depth log-revprops ) ) ) ";
+		assert_eq!(
+			probe(greeting),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+		assert_eq!(probe(b"( success ( 1 2 ( ").0, DetectionStatus::Match);
+	}
+
+	#[test]
+	fn test_detect_client_reply() {
+		let reply = b"( 2 ( edit-pipeline svndiff1 accepts-svndiff2 absent-entries depth mergeinfo log-revprops ) 24:svn://example.org/repo 10:SVN/1.14.2 ( ) ) ";
+		assert_eq!(probe(reply).0, DetectionStatus::Match);
+	}
+
+	#[test]
+	fn test_incomplete_greeting() {
+		assert_eq!(probe(b"(").0, DetectionStatus::Incomplete);
+		assert_eq!(probe(b"( succ").0, DetectionStatus::Incomplete);
+		assert_eq!(probe(b"( success ( 2 2").0, DetectionStatus::Incomplete);
+		assert_eq!(probe(b"( 2 ( edit-").0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	fn test_reject_other_payloads() {
+		// Failure response, inverted and zero versions.
+		assert_eq!(probe(b"( failure ( ( 210005 ").0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"( success ( 3 2 ( ").0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"( success ( 0 2 ( ").0, DetectionStatus::NoMatch);
+		// Lisp and a client reply without edit-pipeline.
+		assert_eq!(probe(b"(defun f (x) x)").0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"( 2 ( svndiff1 ").0, DetectionStatus::NoMatch);
+	}
+}
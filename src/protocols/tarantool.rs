@@ -0,0 +1,138 @@
+/* src/protocols/tarantool.rs */
+use crate::{DetectionStatus, ProtocolVersion};
+
+/// Opening of the greeting's first line.
+const PREFIX: &[u8] = b"Tarantool ";
+
+/// Length of each of the two greeting lines, newline included.
+const LINE_LEN: usize = 64;
+
+/// Longest version string accepted.
+const MAX_VERSION_LEN: usize = 40;
+
+/// Probes for the 128-byte greeting a Tarantool server sends on connect.
+///
+/// Expects a first line of `Tarantool <version> (<protocol>) ...` padded with
+/// spaces to 64 bytes, newline last, then a second line holding the base64
+/// salt padded the same way. The salt line lies past the default inspection
+/// window and is checked only as far as it is in view. The version (e.g.
+/// "2.10.4") is reported as the protocol version.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	match parse(data) {
+		Ok(version) => (DetectionStatus::Match, ProtocolVersion::Tarantool(version)),
+		Err(status) => (status, ProtocolVersion::Unknown),
+	}
+}
+
+/// Checks both greeting lines and returns the version string.
+#[inline(always)]
+fn parse(data: &[u8]) -> Result<&str, DetectionStatus> {
+	let len = data.len().min(PREFIX.len());
+	if data[..len] != PREFIX[..len] {
+		reject!("no_tarantool_prefix");
+		return Err(DetectionStatus::NoMatch);
+	}
+	let rest = &data[len..data.len().min(LINE_LEN)];
+	let version_len = rest.iter().take_while(|&&b| b != b' ').count();
+	let version = &rest[..version_len];
+	if version_len > MAX_VERSION_LEN
+		|| version.first().is_some_and(|b| !b.is_ascii_digit())
+		|| !version.iter().all(u8::is_ascii_graphic)
+	{
+		reject!("invalid_version");
+		return Err(DetectionStatus::NoMatch);
+	}
+	// The version ends at a space, followed by the parenthesized protocol.
+	match rest.get(version_len + 1) {
+		Some(b'(') if version_len > 0 => {}
+		None if data.len() < LINE_LEN => return Err(DetectionStatus::Incomplete),
+		_ => {
+			reject!("no_protocol_name");
+			return Err(DetectionStatus::NoMatch);
+		}
+	}
+	let Some(line) = data.get(..LINE_LEN) else {
+		return Err(DetectionStatus::Incomplete);
+	};
+	if line[LINE_LEN - 1] != b'\n'
+		|| !line[..LINE_LEN - 1]
+			.iter()
+			.all(|&b| b == b' ' || b.is_ascii_graphic())
+	{
+		reject!("invalid_first_line");
+		return Err(DetectionStatus::NoMatch);
+	}
+	if !is_salt_line(&data[LINE_LEN..data.len().min(2 * LINE_LEN)]) {
+		reject!("invalid_salt_line");
+		return Err(DetectionStatus::NoMatch);
+	}
+	core::str::from_utf8(version).map_err(|_| DetectionStatus::NoMatch)
+}
+
+/// Checks the visible part of the salt line: base64 characters, then spaces,
+/// and a newline in the last byte.
+#[inline(always)]
+fn is_salt_line(line: &[u8]) -> bool {
+	let (body, complete) = match line.split_last() {
+		Some((&b'\n', body)) if line.len() == LINE_LEN => (body, true),
+		_ if line.len() == LINE_LEN => return false,
+		_ => (line, false),
+	};
+	let salt_len = body
+		.iter()
+		.take_while(|&&b| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'/' | b'='))
+		.count();
+	(salt_len > 0 || body.is_empty() && !complete) && body[salt_len..].iter().all(|&b| b == b' ')
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Greeting of a Tarantool 2.10.4 instance, both lines padded to 64 bytes.
+	const GREETING: &[u8; 128] = b"Tarantool 2.10.4 (Binary) 3a1e5ba0-6c5f-4a4b-9d2c-0b8f6e4f9e21 \n\
+		Pm7nHJ0B3K2QsyvsyDZ1mEz9vOlU4XBKg9iC3rL0eFg=                   \n";
+
+	#[test]
+	fn test_detect_greeting() {
+		assert_eq!(
+			probe(GREETING),
+			(DetectionStatus::Match, ProtocolVersion::Tarantool("2.10.4"))
+		);
+		// Only the first line fits the default window.
+		assert_eq!(probe(&GREETING[..64]).0, DetectionStatus::Match);
+		assert_eq!(probe(&GREETING[..80]).0, DetectionStatus::Match);
+	}
+
+	#[test]
+	fn test_incomplete_greeting() {
+		assert_eq!(probe(b"Taran").0, DetectionStatus::Incomplete);
+		assert_eq!(probe(b"Tarantool 2.10").0, DetectionStatus::Incomplete);
+		assert_eq!(probe(&GREETING[..40]).0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	fn test_reject_other_payloads() {
+		assert_eq!(probe(b"Tarantula 2.1").0, DetectionStatus::NoMatch);
+		assert_eq!(
+			probe(b"Tarantool v2.10 (Binary)").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(
+			probe(b"Tarantool 2.10 is great").0,
+			DetectionStatus::NoMatch
+		);
+		// First line not ending in a newline.
+		let mut greeting = *GREETING;
+		greeting[63] = b' ';
+		assert_eq!(probe(&greeting).0, DetectionStatus::NoMatch);
+		// Salt line with a stray character or no newline.
+		let mut greeting = *GREETING;
+		greeting[110] = b'!';
+		assert_eq!(probe(&greeting).0, DetectionStatus::NoMatch);
+		let mut greeting = *GREETING;
+		greeting[127] = b' ';
+		assert_eq!(probe(&greeting).0, DetectionStatus::NoMatch);
+	}
+}
@@ -0,0 +1,241 @@
+/* src/protocols/tds.rs */
+use crate::{DetectionStatus, ProtocolMetadata, ProtocolVersion};
+
+/// Packet type of a PRELOGIN message.
+const PRELOGIN: u8 = 0x12;
+
+/// Packet type of a LOGIN7 message.
+const LOGIN7: u8 = 0x10;
+
+/// Length of the fixed part of a LOGIN7 record.
+const LOGIN7_FIXED_LEN: usize = 94;
+
+/// Largest packet a client sends before negotiating the packet size.
+const MAX_PACKET_LEN: usize = 4096;
+
+/// TDS versions a LOGIN7 record can request, as read little-endian.
+const TDS_VERSIONS: [(u32, &str); 8] = [
+	(0x7000_0000, "7.0"),
+	(0x7100_0000, "7.1"),
+	(0x7100_0001, "7.1"),
+	(0x7209_0002, "7.2"),
+	(0x730A_0003, "7.3"),
+	(0x730B_0003, "7.3"),
+	(0x7400_0004, "7.4"),
+	(0x0800_0000, "8.0"),
+];
+
+/// Option token of the VERSION option, which must come first.
+const VERSION_TOKEN: u8 = 0x00;
+
+/// Highest known PRELOGIN option token (NONCEOPT).
+const LAST_TOKEN: u8 = 0x07;
+
+/// Option list terminator.
+const TERMINATOR: u8 = 0xFF;
+
+/// Size of the TDS packet header.
+const HEADER_LEN: usize = 8;
+
+/// Probes for a TDS (Microsoft SQL Server) PRELOGIN or LOGIN7 packet.
+///
+/// Checks the packet header (type 0x12 or 0x10, end-of-message status, a
+/// length of at most 4096, SPID 0, window 0). For PRELOGIN it walks the
+/// option table: known tokens, VERSION first, each option's data inside the
+/// packet, and a 0xFF terminator. For LOGIN7 the record length must fill
+/// the packet and the requested TDS version, reported as the version, must
+/// be a known one.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	let packet_type = data[0];
+	if !matches!(packet_type, PRELOGIN | LOGIN7) || data.get(1).is_some_and(|&status| status != 0x01)
+	{
+		reject!("not_prelogin_or_login_packet");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	}
+	if data.len() < HEADER_LEN {
+		return (DetectionStatus::Incomplete, ProtocolVersion::Unknown);
+	}
+	let length = usize::from(u16::from_be_bytes([data[2], data[3]]));
+	if !(HEADER_LEN + 1..=MAX_PACKET_LEN).contains(&length) || data[4..6] != [0, 0] || data[7] != 0 {
+		reject!("invalid_packet_header");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	}
+	if packet_type == LOGIN7 {
+		return login7(data, length);
+	}
+
+	let payload_len = length - HEADER_LEN;
+	let payload = &data[HEADER_LEN..data.len().min(length)];
+	let mut pos = 0;
+	loop {
+		let Some(&token) = payload.get(pos) else {
+			return (DetectionStatus::Incomplete, ProtocolVersion::Unknown);
+		};
+		if token == TERMINATOR {
+			break;
+		}
+		if token > LAST_TOKEN || (pos == 0) != (token == VERSION_TOKEN) {
+			reject!("unexpected_option_token");
+			return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+		}
+		let Some(entry) = payload.get(pos + 1..pos + 5) else {
+			return (DetectionStatus::Incomplete, ProtocolVersion::Unknown);
+		};
+		let offset = usize::from(u16::from_be_bytes([entry[0], entry[1]]));
+		let len = usize::from(u16::from_be_bytes([entry[2], entry[3]]));
+		if offset + len > payload_len || (token == VERSION_TOKEN && len != 6) {
+			reject!("option_out_of_bounds");
+			return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+		}
+		pos += 5;
+	}
+	if pos == 0 {
+		reject!("missing_version_option");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	}
+	(DetectionStatus::Match, ProtocolVersion::Unknown)
+}
+
+/// Checks the LOGIN7 record length and the TDS version it requests.
+#[inline(always)]
+fn login7(data: &[u8], length: usize) -> (DetectionStatus, ProtocolVersion<'static>) {
+	let Some(fixed) = data.get(HEADER_LEN..HEADER_LEN + 8) else {
+		return (DetectionStatus::Incomplete, ProtocolVersion::Unknown);
+	};
+	let record_len = u32::from_le_bytes([fixed[0], fixed[1], fixed[2], fixed[3]]);
+	if usize::try_from(record_len).ok() != Some(length - HEADER_LEN)
+		|| length - HEADER_LEN < LOGIN7_FIXED_LEN
+	{
+		reject!("login_length_mismatch");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	}
+	let requested = u32::from_le_bytes([fixed[4], fixed[5], fixed[6], fixed[7]]);
+	let Some(&(_, version)) = TDS_VERSIONS.iter().find(|(known, _)| *known == requested) else {
+		reject!("unknown_tds_version");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	};
+	(DetectionStatus::Match, ProtocolVersion::Tds(version))
+}
+
+/// Extracts the client version from the VERSION option, if it is in view.
+#[inline(always)]
+pub(crate) fn metadata(data: &[u8]) -> ProtocolMetadata<'_> {
+	if data.first() != Some(&PRELOGIN) {
+		return ProtocolMetadata::None;
+	}
+	let Some(payload) = data.get(HEADER_LEN..) else {
+		return ProtocolMetadata::None;
+	};
+	if payload.first() != Some(&VERSION_TOKEN) || payload.len() < 5 {
+		return ProtocolMetadata::None;
+	}
+	let offset = usize::from(u16::from_be_bytes([payload[1], payload[2]]));
+	match payload.get(offset..offset + 6) {
+		Some(&[major, minor, build_hi, build_lo, sub_lo, sub_hi]) => ProtocolMetadata::Tds {
+			major,
+			minor,
+			build: u16::from_be_bytes([build_hi, build_lo]),
+			sub_build: u16::from_le_bytes([sub_lo, sub_hi]),
+		},
+		_ => ProtocolMetadata::None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// PRELOGIN from a client reporting version 15.0.2000.5.
+	const PRELOGIN_PACKET: &[u8] = &[
+		0x12, 0x01, 0x00, 0x2F, 0x00, 0x00, 0x01, 0x00, // header
+		0x00, 0x00, 0x1A, 0x00, 0x06, // VERSION
+		0x01, 0x00, 0x20, 0x00, 0x01, // ENCRYPTION
+		0x02, 0x00, 0x21, 0x00, 0x01, // INSTOPT
+		0x03, 0x00, 0x22, 0x00, 0x04, // THREADID
+		0x04, 0x00, 0x26, 0x00, 0x01, // MARS
+		0xFF, // terminator
+		0x0F, 0x00, 0x07, 0xD0, 0x05, 0x00, // version data
+		0x00, // encryption off
+		0x00, // instance
+		0x00, 0x00, 0x12, 0x34, // thread id
+		0x00, // MARS off
+	];
+
+	#[test]
+	fn test_detect_prelogin() {
+		assert_eq!(
+			probe(PRELOGIN_PACKET),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+	}
+
+	#[test]
+	fn test_detect_login7() {
+		// LOGIN7 header and the start of a 190-byte record requesting TDS 7.4.
+		let mut data = [0u8; 64];
+		data[..8].copy_from_slice(&[0x10, 0x01, 0x00, 0xC6, 0x00, 0x00, 0x01, 0x00]);
+		data[8..12].copy_from_slice(&190u32.to_le_bytes());
+		data[12..16].copy_from_slice(&[0x04, 0x00, 0x00, 0x74]);
+		data[16..20].copy_from_slice(&4096u32.to_le_bytes());
+		assert_eq!(
+			probe(&data),
+			(DetectionStatus::Match, ProtocolVersion::Tds("7.4"))
+		);
+		assert_eq!(metadata(&data), ProtocolMetadata::None);
+		data[12..16].copy_from_slice(&[0x02, 0x00, 0x09, 0x72]);
+		assert_eq!(probe(&data).1, ProtocolVersion::Tds("7.2"));
+	}
+
+	#[test]
+	fn test_reject_bad_login7() {
+		let mut data = [0u8; 64];
+		data[..8].copy_from_slice(&[0x10, 0x01, 0x00, 0xC6, 0x00, 0x00, 0x01, 0x00]);
+		data[8..12].copy_from_slice(&190u32.to_le_bytes());
+		data[12..16].copy_from_slice(&[0x04, 0x00, 0x00, 0x75]);
+		assert_eq!(probe(&data).0, DetectionStatus::NoMatch);
+		data[12..16].copy_from_slice(&[0x04, 0x00, 0x00, 0x74]);
+		data[8..12].copy_from_slice(&150u32.to_le_bytes());
+		assert_eq!(probe(&data).0, DetectionStatus::NoMatch);
+		assert_eq!(probe(&data[..12]).0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	fn test_metadata_reports_version() {
+		assert_eq!(
+			metadata(PRELOGIN_PACKET),
+			ProtocolMetadata::Tds {
+				major: 15,
+				minor: 0,
+				build: 2000,
+				sub_build: 5,
+			}
+		);
+		assert_eq!(metadata(&PRELOGIN_PACKET[..30]), ProtocolMetadata::None);
+	}
+
+	#[test]
+	fn test_incomplete_option_table() {
+		assert_eq!(probe(&PRELOGIN_PACKET[..5]).0, DetectionStatus::Incomplete);
+		assert_eq!(probe(&PRELOGIN_PACKET[..20]).0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	fn test_reject_bad_header() {
+		let mut data = PRELOGIN_PACKET.to_vec();
+		data[4] = 0x01; // SPID
+		assert_eq!(probe(&data).0, DetectionStatus::NoMatch);
+		assert_eq!(probe(&[0x12, 0x04, 0x00, 0x08]).0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"\x16\x03\x01\x00\xa5").0, DetectionStatus::NoMatch);
+	}
+
+	#[test]
+	fn test_reject_bad_options() {
+		let mut data = PRELOGIN_PACKET.to_vec();
+		data[8] = 0x01; // VERSION not first
+		assert_eq!(probe(&data).0, DetectionStatus::NoMatch);
+		let mut data = PRELOGIN_PACKET.to_vec();
+		data[15] = 0x40; // ENCRYPTION offset past the packet
+		assert_eq!(probe(&data).0, DetectionStatus::NoMatch);
+	}
+}
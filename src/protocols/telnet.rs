@@ -0,0 +1,111 @@
+/* src/protocols/telnet.rs */
+use crate::{DetectionStatus, ProtocolVersion};
+
+/// Interpret As Command: introduces every Telnet command.
+const IAC: u8 = 0xFF;
+
+/// Subnegotiation begin.
+const SB: u8 = 0xFA;
+
+/// Subnegotiation end.
+const SE: u8 = 0xF0;
+
+/// Option negotiation commands: `WILL`, `WONT`, `DO`, `DONT`.
+const NEGOTIATION: core::ops::RangeInclusive<u8> = 0xFB..=0xFE;
+
+/// Probes for Telnet option negotiation.
+///
+/// Expects the data to open with `IAC` followed by `WILL`, `WONT`, `DO` or
+/// `DONT` and an option, which virtually every Telnet server sends before
+/// its banner. Any further commands in view must also be well formed:
+/// negotiations, or subnegotiations terminated by `IAC SE`.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	(probe_status(data), ProtocolVersion::Unknown)
+}
+
+/// Walks the leading run of commands.
+#[inline(always)]
+fn probe_status(data: &[u8]) -> DetectionStatus {
+	match data {
+		[] | [IAC] => return DetectionStatus::Incomplete,
+		[IAC, command, ..] if NEGOTIATION.contains(command) => {}
+		_ => {
+			reject!("no_option_negotiation");
+			return DetectionStatus::NoMatch;
+		}
+	}
+	let mut pos = 0;
+	while let Some(&byte) = data.get(pos) {
+		if byte != IAC {
+			break;
+		}
+		match data.get(pos + 1) {
+			Some(command) if NEGOTIATION.contains(command) => {
+				if pos + 3 > data.len() {
+					return if pos == 0 {
+						DetectionStatus::Incomplete
+					} else {
+						DetectionStatus::Match
+					};
+				}
+				pos += 3;
+			}
+			Some(&SB) => {
+				let end = data[pos + 2..]
+					.windows(2)
+					.position(|pair| pair == [IAC, SE]);
+				match end {
+					Some(end) => pos += 2 + end + 2,
+					None => break,
+				}
+			}
+			// Other commands (e.g. `GA`, `NOP`) take no option.
+			Some(&command) if command >= SE => pos += 2,
+			Some(_) => {
+				reject!("invalid_command");
+				return DetectionStatus::NoMatch;
+			}
+			None => break,
+		}
+	}
+	DetectionStatus::Match
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_detect_server_negotiation() {
+		// WILL ECHO, WILL SUPPRESS-GO-AHEAD, DO TERMINAL-TYPE, DO NAWS.
+		let data = b"\xFF\xFB\x01\xFF\xFB\x03\xFF\xFD\x18\xFF\xFD\x1F";
+		assert_eq!(
+			probe(data),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+		// Negotiation followed by a login banner.
+		assert_eq!(probe(b"\xFF\xFD\x18\r\nlogin: ").0, DetectionStatus::Match);
+	}
+
+	#[test]
+	fn test_detect_subnegotiation() {
+		// DO TERMINAL-TYPE, then SB TERMINAL-TYPE SEND IAC SE.
+		let data = b"\xFF\xFD\x18\xFF\xFA\x18\x01\xFF\xF0\xFF\xFB\x01";
+		assert_eq!(probe(data).0, DetectionStatus::Match);
+	}
+
+	#[test]
+	fn test_incomplete_negotiation() {
+		assert_eq!(probe(b"\xFF").0, DetectionStatus::Incomplete);
+		assert_eq!(probe(b"\xFF\xFD").0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	fn test_reject_other_data() {
+		assert_eq!(probe(b"login: ").0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"\xFF\xF4\x00").0, DetectionStatus::NoMatch);
+		// A later IAC followed by a data byte is not a command.
+		assert_eq!(probe(b"\xFF\xFB\x01\xFF\x41").0, DetectionStatus::NoMatch);
+	}
+}
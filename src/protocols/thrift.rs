@@ -0,0 +1,255 @@
+/* src/protocols/thrift.rs */
+use crate::{DetectionStatus, ProtocolMetadata, ProtocolVersion};
+
+/// First byte of a strict binary-protocol message (version 0x8001).
+const BINARY_ID: u8 = 0x80;
+
+/// First byte of a compact-protocol message.
+const COMPACT_ID: u8 = 0x82;
+
+/// Compact protocol version in the low bits of the second byte.
+const COMPACT_VERSION: u8 = 1;
+
+/// Default maximum frame size of the framed transport.
+const MAX_FRAME_LEN: u32 = 16_384_000;
+
+/// Longest method name accepted.
+const MAX_NAME_LEN: u32 = 1024;
+
+/// A message header parsed up to the method name.
+struct Message<'a> {
+	/// Whether the compact protocol is used.
+	compact: bool,
+	/// Method name bytes in view.
+	name: &'a [u8],
+	/// Whether the whole name is in view.
+	name_complete: bool,
+	/// Length of the header through the end of the name.
+	header_len: usize,
+}
+
+/// Probes for Apache Thrift RPC messages.
+///
+/// Accepts the framed transport (a 4-byte big-endian frame length followed by
+/// a binary or compact message, with the frame long enough for the header)
+/// and unframed binary or compact messages. Message headers must carry a
+/// call, reply, exception, or oneway type and a printable method name.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	match parse(data) {
+		Ok(_) => (DetectionStatus::Match, ProtocolVersion::Unknown),
+		Err(status) => (status, ProtocolVersion::Unknown),
+	}
+}
+
+/// Reports binary vs compact encoding, framing, and the method name.
+#[inline(always)]
+pub(crate) fn metadata(data: &[u8]) -> ProtocolMetadata<'_> {
+	let Ok((framed, message)) = parse(data) else {
+		return ProtocolMetadata::None;
+	};
+	let method = Some(message.name)
+		.filter(|_| message.name_complete)
+		.and_then(|name| core::str::from_utf8(name).ok());
+	ProtocolMetadata::Thrift {
+		compact: message.compact,
+		framed,
+		method,
+	}
+}
+
+/// Parses the transport framing and the message header.
+#[inline(always)]
+fn parse(data: &[u8]) -> Result<(bool, Message<'_>), DetectionStatus> {
+	match data.first() {
+		Some(&BINARY_ID) => return binary(data).map(|message| (false, message)),
+		Some(&COMPACT_ID) => return compact(data).map(|message| (false, message)),
+		_ => {}
+	}
+	let Some(length) = data.first_chunk().map(|b| u32::from_be_bytes(*b)) else {
+		return Err(DetectionStatus::Incomplete);
+	};
+	if !(8..=MAX_FRAME_LEN).contains(&length) {
+		reject!("invalid_frame_length");
+		return Err(DetectionStatus::NoMatch);
+	}
+	let message = match data.get(4) {
+		None => return Err(DetectionStatus::Incomplete),
+		Some(&BINARY_ID) => binary(&data[4..])?,
+		Some(&COMPACT_ID) => compact(&data[4..])?,
+		Some(_) => {
+			reject!("unknown_protocol_id");
+			return Err(DetectionStatus::NoMatch);
+		}
+	};
+	if message.header_len > length as usize {
+		reject!("frame_shorter_than_header");
+		return Err(DetectionStatus::NoMatch);
+	}
+	Ok((true, message))
+}
+
+/// Parses a strict binary-protocol message header.
+#[inline(always)]
+fn binary(data: &[u8]) -> Result<Message<'_>, DetectionStatus> {
+	let Some(header) = data.first_chunk::<8>() else {
+		return Err(DetectionStatus::Incomplete);
+	};
+	if header[..3] != [BINARY_ID, 0x01, 0x00] || !(1..=4).contains(&header[3]) {
+		reject!("invalid_binary_header");
+		return Err(DetectionStatus::NoMatch);
+	}
+	let name_len = u32::from_be_bytes([header[4], header[5], header[6], header[7]]);
+	name(data, 8, name_len, false)
+}
+
+/// Parses a compact-protocol message header.
+#[inline(always)]
+fn compact(data: &[u8]) -> Result<Message<'_>, DetectionStatus> {
+	let Some(&type_and_version) = data.get(1) else {
+		return Err(DetectionStatus::Incomplete);
+	};
+	if type_and_version & 0x1F != COMPACT_VERSION || !(1..=4).contains(&(type_and_version >> 5)) {
+		reject!("invalid_compact_header");
+		return Err(DetectionStatus::NoMatch);
+	}
+	let (_, seq_len) = var_int(data, 2)?;
+	let (name_len, name_len_len) = var_int(data, 2 + seq_len)?;
+	name(data, 2 + seq_len + name_len_len, name_len, true)
+}
+
+/// Checks a method name of `len` bytes at `pos`.
+#[inline(always)]
+fn name(data: &[u8], pos: usize, len: u32, compact: bool) -> Result<Message<'_>, DetectionStatus> {
+	if !(1..=MAX_NAME_LEN).contains(&len) {
+		reject!("invalid_name_length");
+		return Err(DetectionStatus::NoMatch);
+	}
+	let end = pos + len as usize;
+	let name = &data[pos.min(data.len())..end.min(data.len())];
+	if name.is_empty() {
+		return Err(DetectionStatus::Incomplete);
+	}
+	if !name.iter().all(u8::is_ascii_graphic) {
+		reject!("non_printable_method_name");
+		return Err(DetectionStatus::NoMatch);
+	}
+	Ok(Message {
+		compact,
+		name,
+		name_complete: data.len() >= end,
+		header_len: end,
+	})
+}
+
+/// Reads an unsigned 32-bit varint at `pos` and returns it with its length.
+#[inline(always)]
+fn var_int(data: &[u8], pos: usize) -> Result<(u32, usize), DetectionStatus> {
+	let mut value = 0u32;
+	for i in 0..5 {
+		let Some(&byte) = data.get(pos + i) else {
+			return Err(DetectionStatus::Incomplete);
+		};
+		value |= u32::from(byte & 0x7F) << (7 * i);
+		if byte & 0x80 == 0 {
+			return Ok((value, i + 1));
+		}
+	}
+	reject!("varint_too_long");
+	Err(DetectionStatus::NoMatch)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Unframed binary call of `getUser`, sequence id 1, then a struct field.
+	const BINARY_CALL: &[u8] = b"\x80\x01\x00\x01\x00\x00\x00\x07getUser\x00\x00\x00\x01\x08\x00\x01";
+
+	/// Framed compact call of `ping`, sequence id 5.
+	const FRAMED_COMPACT: &[u8] = b"\x00\x00\x00\x08\x82\x21\x05\x04ping\x00";
+
+	#[test]
+	fn test_detect_unframed_binary() {
+		assert_eq!(
+			probe(BINARY_CALL),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+		assert_eq!(
+			metadata(BINARY_CALL),
+			ProtocolMetadata::Thrift {
+				compact: false,
+				framed: false,
+				method: Some("getUser"),
+			}
+		);
+	}
+
+	#[test]
+	fn test_detect_framed() {
+		assert_eq!(
+			metadata(FRAMED_COMPACT),
+			ProtocolMetadata::Thrift {
+				compact: true,
+				framed: true,
+				method: Some("ping"),
+			}
+		);
+		let mut framed = [0u8; 24];
+		framed[..4].copy_from_slice(&[0x00, 0x00, 0x00, 0x14]);
+		framed[4..].copy_from_slice(&BINARY_CALL[..20]);
+		assert_eq!(probe(&framed).0, DetectionStatus::Match);
+		assert_eq!(
+			metadata(&framed[..14]),
+			ProtocolMetadata::Thrift {
+				compact: false,
+				framed: true,
+				method: None,
+			}
+		);
+	}
+
+	#[test]
+	fn test_detect_unframed_compact() {
+		assert_eq!(
+			metadata(&FRAMED_COMPACT[4..]),
+			ProtocolMetadata::Thrift {
+				compact: true,
+				framed: false,
+				method: Some("ping"),
+			}
+		);
+	}
+
+	#[test]
+	fn test_incomplete_header() {
+		assert_eq!(probe(&BINARY_CALL[..6]).0, DetectionStatus::Incomplete);
+		assert_eq!(probe(&FRAMED_COMPACT[..7]).0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	fn test_reject_bad_headers() {
+		assert_eq!(
+			probe(b"\x80\x01\x00\x07\x00\x00\x00\x04ping").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(
+			probe(b"\x00\x00\x00\x08\x82\x22\x05\x04ping").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(
+			probe(b"\x80\x01\x00\x01\x00\x00\x00\x04pi\x01g").0,
+			DetectionStatus::NoMatch
+		);
+		// Frame too short to hold the method name.
+		assert_eq!(
+			probe(b"\x00\x00\x00\x08\x82\x21\x05\x10getUserPreferences").0,
+			DetectionStatus::NoMatch
+		);
+		// PostgreSQL StartupMessage.
+		assert_eq!(
+			probe(b"\x00\x00\x00\x29\x00\x03\x00\x00user").0,
+			DetectionStatus::NoMatch
+		);
+	}
+}
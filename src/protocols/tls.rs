@@ -1,6 +1,48 @@
 /* src/protocols/tls.rs */
+#[cfg(feature = "tor")]
+use crate::ProtocolMetadata;
 use crate::{DetectionStatus, ProtocolVersion};
 
+/// TLS extension type of server name indication.
+#[cfg(feature = "tor")]
+const EXTENSION_SERVER_NAME: u16 = 0;
+
+/// TLS extension type of ALPN.
+#[cfg(feature = "tor")]
+const EXTENSION_ALPN: u16 = 16;
+
+/// Cipher suite lists Tor clients lead with: OpenSSL's default TLS 1.3
+/// order, and the list of the v2 link handshake.
+#[cfg(feature = "tor")]
+const TOR_CIPHER_PREFIXES: [[u8; 6]; 2] = [
+	[0x13, 0x02, 0x13, 0x03, 0x13, 0x01],
+	[0xC0, 0x0A, 0xC0, 0x14, 0x00, 0x39],
+];
+
+/// Score of an SNI shaped like Tor's random `www.<base32>.com` host name.
+#[cfg(feature = "tor")]
+const TOR_SCORE_RANDOM_SNI: u8 = 40;
+
+/// Score of a `ClientHello` without SNI.
+#[cfg(feature = "tor")]
+const TOR_SCORE_NO_SNI: u8 = 20;
+
+/// Score of a cipher suite list starting like Tor's.
+#[cfg(feature = "tor")]
+const TOR_SCORE_CIPHERS: u8 = 25;
+
+/// Score of a `ClientHello` without ALPN, which browsers always send.
+#[cfg(feature = "tor")]
+const TOR_SCORE_NO_ALPN: u8 = 15;
+
+/// Score from which a `ClientHello` is reported as Tor-like.
+#[cfg(feature = "tor")]
+const TOR_THRESHOLD: u8 = 50;
+
+/// Highest confidence reported; a fingerprint alone never proves Tor.
+#[cfg(feature = "tor")]
+const TOR_MAX_CONFIDENCE: u8 = 80;
+
 /// Probes for TLS protocol and version.
 #[inline(always)]
 pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
@@ -20,10 +62,12 @@ pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
 	}
 
 	if !(0x14..=0x17).contains(&data[0]) {
+		reject!("content_type_invalid");
 		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
 	}
 
 	if data[1] != 0x03 {
+		reject!("major_version_invalid");
 		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
 	}
 
@@ -33,11 +77,19 @@ pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
 		0x02 => "1.1",
 		0x03 => "1.2",
 		0x04 => "1.3",
-		_ => return (DetectionStatus::NoMatch, ProtocolVersion::Unknown),
+		_ => {
+			reject!("minor_version_invalid");
+			return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+		}
 	};
 
 	let record_len = u16::from_be_bytes([data[3], data[4]]) as usize;
-	if record_len == 0 || record_len > 16384 {
+	if record_len == 0 {
+		reject!("record_length_zero");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	}
+	if record_len > 16384 {
+		reject!("record_length_too_large");
 		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
 	}
 
@@ -62,11 +114,180 @@ pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
 /// Helper to detect legacy `SSLv2` `ClientHello`.
 fn detect_sslv2(data: &[u8]) -> bool {
 	if data.len() < 11 {
+		reject!("sslv2_too_short");
 		return false;
 	}
 	if data[2] != 0x01 {
+		reject!("sslv2_not_client_hello");
 		return false;
 	}
 	let record_length = (u16::from(data[0] & 0x7F) << 8) | u16::from(data[1]);
 	record_length >= 9
 }
+
+/// Flags a `ClientHello` whose fingerprint resembles a Tor client's.
+///
+/// Scores a random-looking or absent SNI, Tor's leading cipher suites, and
+/// a missing ALPN extension. Returns [`ProtocolMetadata::TorSuspect`] once
+/// the score reaches the threshold, and [`ProtocolMetadata::None`] otherwise
+/// or when the extensions are not all in view.
+#[cfg(feature = "tor")]
+pub(crate) fn metadata(data: &[u8]) -> ProtocolMetadata<'_> {
+	match tor_score(data) {
+		Some(score) if score >= TOR_THRESHOLD => ProtocolMetadata::TorSuspect {
+			confidence: score.min(TOR_MAX_CONFIDENCE),
+		},
+		_ => ProtocolMetadata::None,
+	}
+}
+
+/// Walks a `ClientHello` record and sums the scores of its Tor-like traits.
+#[cfg(feature = "tor")]
+fn tor_score(data: &[u8]) -> Option<u8> {
+	if data.first() != Some(&0x16) || data.get(5) != Some(&0x01) {
+		return None;
+	}
+	// Record header, handshake header, client version and random.
+	let mut pos = 5 + 4 + 2 + 32;
+	pos += 1 + usize::from(*data.get(pos)?);
+	let suites_len = read_u16(data, pos)?;
+	let suites = data.get(pos + 2..pos + 2 + suites_len)?;
+	pos += 2 + suites_len;
+	pos += 1 + usize::from(*data.get(pos)?);
+	let extensions_len = read_u16(data, pos)?;
+	let mut extensions = data.get(pos + 2..pos + 2 + extensions_len)?;
+
+	let mut server_name = None;
+	let mut alpn = false;
+	while let [type_hi, type_lo, len_hi, len_lo, rest @ ..] = extensions {
+		let len = usize::from(u16::from_be_bytes([*len_hi, *len_lo]));
+		let body = rest.get(..len)?;
+		match u16::from_be_bytes([*type_hi, *type_lo]) {
+			EXTENSION_SERVER_NAME => server_name = Some(host_name(body)?),
+			EXTENSION_ALPN => alpn = true,
+			_ => {}
+		}
+		extensions = &rest[len..];
+	}
+
+	let mut score = match server_name {
+		None => TOR_SCORE_NO_SNI,
+		Some(name) if is_tor_host_name(name) => TOR_SCORE_RANDOM_SNI,
+		Some(_) => 0,
+	};
+	if TOR_CIPHER_PREFIXES
+		.iter()
+		.any(|prefix| suites.starts_with(prefix))
+	{
+		score += TOR_SCORE_CIPHERS;
+	}
+	if !alpn {
+		score += TOR_SCORE_NO_ALPN;
+	}
+	Some(score)
+}
+
+/// Extracts the host name from a server name extension body.
+#[cfg(feature = "tor")]
+fn host_name(body: &[u8]) -> Option<&[u8]> {
+	match body {
+		[_, _, 0x00, len_hi, len_lo, rest @ ..] => {
+			rest.get(..usize::from(u16::from_be_bytes([*len_hi, *len_lo])))
+		}
+		_ => None,
+	}
+}
+
+/// Checks for the `www.<4 to 25 base32 characters>.com` names Tor clients
+/// send as SNI.
+#[cfg(feature = "tor")]
+fn is_tor_host_name(name: &[u8]) -> bool {
+	name
+		.strip_prefix(b"www.")
+		.and_then(|name| name.strip_suffix(b".com"))
+		.is_some_and(|label| {
+			(4..=25).contains(&label.len())
+				&& label
+					.iter()
+					.all(|&b| b.is_ascii_lowercase() || (b'2'..=b'7').contains(&b))
+		})
+}
+
+/// Reads a big-endian 16-bit length at `pos`.
+#[cfg(feature = "tor")]
+fn read_u16(data: &[u8], pos: usize) -> Option<usize> {
+	let bytes = data.get(pos..pos + 2)?;
+	Some(usize::from(u16::from_be_bytes([bytes[0], bytes[1]])))
+}
+
+#[cfg(all(test, feature = "tor"))]
+mod tests {
+	use super::*;
+
+	/// Builds a TLS 1.2 `ClientHello` record into `out`, returning its length.
+	fn client_hello(suites: &[u8], extensions: &[u8], out: &mut [u8; 256]) -> usize {
+		let body_len = 2 + 32 + 1 + 2 + suites.len() + 2 + 2 + extensions.len();
+		let mut pos = 0;
+		let mut put = |bytes: &[u8]| {
+			out[pos..pos + bytes.len()].copy_from_slice(bytes);
+			pos += bytes.len();
+		};
+		put(&[0x16, 0x03, 0x01]);
+		put(&u16::try_from(body_len + 4).unwrap().to_be_bytes());
+		put(&[0x01, 0x00]);
+		put(&u16::try_from(body_len).unwrap().to_be_bytes());
+		put(&[0x03, 0x03]);
+		put(&[0x5A; 32]);
+		put(&[0x00]);
+		put(&u16::try_from(suites.len()).unwrap().to_be_bytes());
+		put(suites);
+		put(&[0x01, 0x00]);
+		put(&u16::try_from(extensions.len()).unwrap().to_be_bytes());
+		put(extensions);
+		pos
+	}
+
+	#[test]
+	fn test_flags_tor_client_hello() {
+		let suites = [0x13, 0x02, 0x13, 0x03, 0x13, 0x01, 0xC0, 0x2C];
+		let sni = b"\x00\x00\x00\x19\x00\x17\x00\x00\x14www.q3rxk7ab2fzd.com";
+		let mut out = [0u8; 256];
+		let len = client_hello(&suites, sni, &mut out);
+		assert_eq!(
+			metadata(&out[..len]),
+			ProtocolMetadata::TorSuspect { confidence: 80 }
+		);
+		// Without SNI the score still reaches the threshold.
+		let len = client_hello(&suites, b"", &mut out);
+		assert_eq!(
+			metadata(&out[..len]),
+			ProtocolMetadata::TorSuspect { confidence: 60 }
+		);
+	}
+
+	#[test]
+	fn test_ignores_browser_client_hello() {
+		let suites = [0x13, 0x01, 0x13, 0x02, 0x13, 0x03];
+		let extensions =
+			b"\x00\x00\x00\x10\x00\x0E\x00\x00\x0Bexample.com\x00\x10\x00\x05\x00\x03\x02h2";
+		let mut out = [0u8; 256];
+		let len = client_hello(&suites, extensions, &mut out);
+		assert_eq!(metadata(&out[..len]), ProtocolMetadata::None);
+	}
+
+	#[test]
+	fn test_ignores_truncated_client_hello() {
+		let suites = [0x13, 0x02, 0x13, 0x03, 0x13, 0x01];
+		let mut out = [0u8; 256];
+		let len = client_hello(&suites, b"", &mut out);
+		assert_eq!(metadata(&out[..len - 1]), ProtocolMetadata::None);
+	}
+
+	#[test]
+	fn test_tor_host_names() {
+		assert!(is_tor_host_name(b"www.q3rxk7ab2fzd.com"));
+		assert!(!is_tor_host_name(b"www.example1.com"));
+		assert!(!is_tor_host_name(b"www.abc.com"));
+		assert!(!is_tor_host_name(b"mail.q3rxk7ab2fzd.com"));
+	}
+}
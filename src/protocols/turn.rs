@@ -0,0 +1,184 @@
+/* src/protocols/turn.rs */
+use crate::{DetectionStatus, ProtocolVersion};
+
+/// STUN magic cookie.
+const MAGIC_COOKIE: [u8; 4] = [0x21, 0x12, 0xA4, 0x42];
+
+/// TURN methods (RFC 8656): Allocate, Refresh, Send, Data, CreatePermission,
+/// and ChannelBind.
+const TURN_METHODS: [u16; 6] = [0x003, 0x004, 0x006, 0x007, 0x008, 0x009];
+
+/// Send and Data, which only exist as indications.
+const INDICATION_METHODS: [u16; 2] = [0x006, 0x007];
+
+/// Class bits of an indication in a STUN message type.
+const INDICATION: u16 = 0x0010;
+
+/// Largest ChannelData length accepted when the message is cut off: relayed
+/// media fits an Ethernet MTU, and the cap keeps ASN.1 headers such as a
+/// Kerberos `AS-REQ` (`6A 81 B4 30 81 …`) from reading as ChannelData.
+const MAX_CHANNEL_DATA_LEN: usize = 1500;
+
+/// Probes for TURN (RFC 8656).
+///
+/// Matches STUN-framed messages that use a TURN method in a class it allows
+/// (Send and Data are indications, the others requests or responses), and
+/// ChannelData messages: a channel number in 0x4000-0x7FFF and a length that
+/// covers the rest of the data (up to 3 bytes of padding). ChannelData cut
+/// off by a full default inspection window still matches when its length is
+/// at most 1500 and the relayed payload starts like RTP, RTCP, DTLS, or STUN.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	if data.len() < 4 {
+		return (DetectionStatus::Incomplete, ProtocolVersion::Unknown);
+	}
+	if (0x40..=0x7F).contains(&data[0]) {
+		return probe_channel_data(data);
+	}
+	if data[0] & 0xC0 != 0 {
+		reject!("message_type_invalid");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	}
+	if data.len() < 8 {
+		return (DetectionStatus::Incomplete, ProtocolVersion::Unknown);
+	}
+	if data[4..8] != MAGIC_COOKIE {
+		reject!("magic_cookie_mismatch");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	}
+	if !u16::from_be_bytes([data[2], data[3]]).is_multiple_of(4) {
+		reject!("message_length_unaligned");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	}
+	let message_type = u16::from_be_bytes([data[0], data[1]]);
+	let method = method(message_type);
+	if !TURN_METHODS.contains(&method) {
+		reject!("not_turn_method");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	}
+	if INDICATION_METHODS.contains(&method) != (message_type & 0x0110 == INDICATION) {
+		reject!("invalid_method_class");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	}
+	(DetectionStatus::Match, ProtocolVersion::Unknown)
+}
+
+/// Checks a ChannelData header against the data that follows it.
+#[inline(always)]
+fn probe_channel_data(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	let length = usize::from(u16::from_be_bytes([data[2], data[3]]));
+	let end = 4 + length;
+	if data.len() >= end {
+		if data.len() <= end + 3 {
+			return (DetectionStatus::Match, ProtocolVersion::Unknown);
+		}
+		reject!("channel_data_length_mismatch");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	}
+	if length > MAX_CHANNEL_DATA_LEN {
+		reject!("channel_data_too_long");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	}
+	match data.get(4) {
+		// RTP/RTCP version 2, DTLS records, STUN messages.
+		Some(0x80..=0xBF | 0x14..=0x17 | 0x00..=0x01) if data.len() >= crate::MAX_INSPECT_BYTES => {
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		}
+		Some(0x80..=0xBF | 0x14..=0x17 | 0x00..=0x01) | None => {
+			(DetectionStatus::Incomplete, ProtocolVersion::Unknown)
+		}
+		Some(_) => {
+			reject!("unknown_relayed_payload");
+			(DetectionStatus::NoMatch, ProtocolVersion::Unknown)
+		}
+	}
+}
+
+/// Extracts the 12-bit method from a STUN message type, dropping the class bits.
+#[inline(always)]
+fn method(message_type: u16) -> u16 {
+	(message_type & 0x000F) | ((message_type & 0x00E0) >> 1) | ((message_type & 0x3E00) >> 2)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// STUN-framed message of the given type with no attributes.
+	fn message(message_type: u16) -> [u8; 20] {
+		let mut data = [0u8; 20];
+		data[..2].copy_from_slice(&message_type.to_be_bytes());
+		data[4..8].copy_from_slice(&MAGIC_COOKIE);
+		data
+	}
+
+	#[test]
+	fn test_detect_turn_methods() {
+		// Allocate request, Refresh success response, Send and Data indications.
+		for message_type in [0x0003, 0x0104, 0x0016, 0x0017] {
+			assert_eq!(
+				probe(&message(message_type)),
+				(DetectionStatus::Match, ProtocolVersion::Unknown)
+			);
+		}
+	}
+
+	#[test]
+	fn test_detect_channel_data() {
+		let mut data = [0u8; 16];
+		data[..4].copy_from_slice(&[0x40, 0x00, 0x00, 0x0A]);
+		data[4] = 0x55;
+		assert_eq!(probe(&data).0, DetectionStatus::Match);
+
+		let mut rtp = [0u8; 64];
+		rtp[..4].copy_from_slice(&[0x7F, 0xFF, 0x00, 0xAC]);
+		rtp[4] = 0x80;
+		assert_eq!(probe(&rtp).0, DetectionStatus::Match);
+	}
+
+	#[test]
+	fn test_reject_binding_request() {
+		assert_eq!(probe(&message(0x0001)).0, DetectionStatus::NoMatch);
+	}
+
+	#[test]
+	fn test_reject_invalid_method_class() {
+		// Send request, Allocate indication, Data success response.
+		for message_type in [0x0006, 0x0013, 0x0107] {
+			assert_eq!(probe(&message(message_type)).0, DetectionStatus::NoMatch);
+		}
+	}
+
+	#[test]
+	fn test_reject_channel_data_mismatch() {
+		let mut data = [0u8; 32];
+		data[..4].copy_from_slice(&[0x40, 0x01, 0x00, 0x04]);
+		assert_eq!(probe(&data).0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"GET / HTTP/1.1\r\n").0, DetectionStatus::NoMatch);
+	}
+
+	#[test]
+	fn test_reject_kerberos_as_req() {
+		// Channel 0x6A81 with a length of 0x3081, cut off by the window.
+		let mut as_req = [0u8; crate::MAX_INSPECT_BYTES];
+		as_req[..20].copy_from_slice(
+			b"\x6A\x81\xB4\x30\x81\xB1\xA1\x03\x02\x01\x05\xA2\x03\x02\x01\x0A\xA3\x15\x30\x13",
+		);
+		assert_eq!(probe(&as_req).0, DetectionStatus::NoMatch);
+		assert_eq!(probe(&as_req[..20]).0, DetectionStatus::NoMatch);
+	}
+
+	#[test]
+	fn test_incomplete_header() {
+		assert_eq!(probe(&[0x00, 0x03, 0x00]).0, DetectionStatus::Incomplete);
+		assert_eq!(
+			probe(&[0x40, 0x00, 0x01, 0x00]).0,
+			DetectionStatus::Incomplete
+		);
+		// RTP relayed in ChannelData, cut off before the window is full.
+		assert_eq!(
+			probe(&[0x40, 0x00, 0x00, 0xAC, 0x80, 0x60]).0,
+			DetectionStatus::Incomplete
+		);
+	}
+}
@@ -0,0 +1,80 @@
+/* src/protocols/vxlan.rs */
+use crate::{DetectionStatus, ProtocolMetadata, ProtocolVersion};
+
+/// Flags byte with only the I (valid VNI) bit set.
+const FLAGS: u8 = 0x08;
+
+/// Length of the VXLAN header.
+pub(crate) const HEADER_LEN: usize = 8;
+
+/// Length of the inner Ethernet header.
+const ETHERNET_HEADER_LEN: usize = 14;
+
+/// Probes for a VXLAN packet.
+///
+/// Expects the flags byte with only the I bit set, zero reserved fields around
+/// the 24-bit VNI, and room for the inner Ethernet header.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	let Some(header) = data.first_chunk::<HEADER_LEN>() else {
+		return (DetectionStatus::Incomplete, ProtocolVersion::Unknown);
+	};
+	if header[0] != FLAGS || header[1..4] != [0, 0, 0] || header[7] != 0 {
+		reject!("invalid_vxlan_header");
+		return (DetectionStatus::NoMatch, ProtocolVersion::Unknown);
+	}
+	if data.len() < HEADER_LEN + ETHERNET_HEADER_LEN {
+		return (DetectionStatus::Incomplete, ProtocolVersion::Unknown);
+	}
+	(DetectionStatus::Match, ProtocolVersion::Unknown)
+}
+
+/// Extracts the VXLAN network identifier.
+#[inline(always)]
+pub(crate) fn metadata(data: &[u8]) -> ProtocolMetadata<'_> {
+	match data.get(4..7) {
+		Some(&[a, b, c]) => ProtocolMetadata::Vxlan {
+			vni: u32::from_be_bytes([0, a, b, c]),
+		},
+		_ => ProtocolMetadata::None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// VNI 5001 carrying the start of an IPv4 Ethernet frame.
+	const PACKET: &[u8] = b"\x08\x00\x00\x00\x00\x13\x89\x00\
+		\x02\x42\xAC\x11\x00\x02\x02\x42\xAC\x11\x00\x03\x08\x00\x45\x00";
+
+	#[test]
+	fn test_detect_vxlan() {
+		assert_eq!(
+			probe(PACKET),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+		assert_eq!(metadata(PACKET), ProtocolMetadata::Vxlan { vni: 5001 });
+	}
+
+	#[test]
+	fn test_incomplete_packet() {
+		assert_eq!(probe(&PACKET[..6]).0, DetectionStatus::Incomplete);
+		assert_eq!(probe(&PACKET[..16]).0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	fn test_reject_bad_header() {
+		let mut data = PACKET.to_vec();
+		data[0] = 0x0C; // extra flag bit
+		assert_eq!(probe(&data).0, DetectionStatus::NoMatch);
+		let mut data = PACKET.to_vec();
+		data[7] = 0x01; // reserved byte
+		assert_eq!(probe(&data).0, DetectionStatus::NoMatch);
+		// DNS query with recursion desired.
+		assert_eq!(
+			probe(b"\x08\x00\x01\x00\x00\x01\x00\x00\x00\x00\x00\x00\x06google\x03com\x00").0,
+			DetectionStatus::NoMatch
+		);
+	}
+}
@@ -0,0 +1,354 @@
+/* src/protocols/websocket.rs */
+#[cfg(feature = "websocket")]
+use crate::{DetectionStatus, ProtocolVersion};
+use crate::{Protocol, ProtocolMetadata};
+
+/// WebSocket subprotocols that carry MQTT (`mqttv3.1` is the pre-standard
+/// name of MQTT 3.1).
+#[cfg(feature = "mqtt")]
+const MQTT_SUBPROTOCOLS: [&str; 2] = ["mqtt", "mqttv3.1"];
+
+/// Headers of a WebSocket upgrade request, from the header lines in view.
+struct Handshake<'a> {
+	/// `Upgrade` lists `websocket`.
+	upgrade: bool,
+	/// `Sec-WebSocket-Key` is present.
+	key: bool,
+	/// Value of `Sec-WebSocket-Protocol`.
+	subprotocols: Option<&'a str>,
+	/// The blank line ending the headers is in view.
+	complete: bool,
+}
+
+/// Reads the header lines fully in view after the request line.
+fn handshake(data: &[u8]) -> Handshake<'_> {
+	let mut handshake = Handshake {
+		upgrade: false,
+		key: false,
+		subprotocols: None,
+		complete: false,
+	};
+	let Some(end) = data.iter().rposition(|&b| b == b'\n') else {
+		return handshake;
+	};
+	for line in data[..end].split(|&b| b == b'\n').skip(1) {
+		let line = line.strip_suffix(b"\r").unwrap_or(line);
+		if line.is_empty() {
+			handshake.complete = true;
+			break;
+		}
+		let Some(colon) = line.iter().position(|&b| b == b':') else {
+			continue;
+		};
+		let (name, value) = (&line[..colon], line[colon + 1..].trim_ascii());
+		if name.eq_ignore_ascii_case(b"upgrade") {
+			handshake.upgrade = value
+				.split(|&b| b == b',')
+				.any(|token| token.trim_ascii().eq_ignore_ascii_case(b"websocket"));
+		} else if name.eq_ignore_ascii_case(b"sec-websocket-key") {
+			handshake.key = !value.is_empty();
+		} else if name.eq_ignore_ascii_case(b"sec-websocket-protocol") {
+			handshake.subprotocols = core::str::from_utf8(value).ok();
+		}
+	}
+	handshake
+}
+
+/// Extracts the subprotocols of a WebSocket upgrade request and the protocol
+/// they carry.
+///
+/// Only header lines fully in view are read. Returns
+/// [`ProtocolMetadata::None`] for requests without `Upgrade: websocket`.
+pub(crate) fn metadata(data: &[u8]) -> ProtocolMetadata<'_> {
+	let handshake = handshake(data);
+	if !handshake.upgrade {
+		return ProtocolMetadata::None;
+	}
+	ProtocolMetadata::WebSocket {
+		subprotocols: handshake.subprotocols,
+		inner: handshake.subprotocols.and_then(inner_protocol),
+	}
+}
+
+/// Maps offered WebSocket subprotocols to the protocol they carry.
+#[allow(unused_variables)]
+fn inner_protocol(subprotocols: &str) -> Option<Protocol> {
+	#[cfg(feature = "mqtt")]
+	if subprotocols.split(',').any(|token| {
+		MQTT_SUBPROTOCOLS
+			.iter()
+			.any(|mqtt| token.trim().eq_ignore_ascii_case(mqtt))
+	}) {
+		return Some(Protocol::Mqtt);
+	}
+	None
+}
+
+/// Probes for a WebSocket opening handshake or a frame sent mid-stream.
+///
+/// A client handshake is a `GET` request whose header lines in view carry
+/// `Upgrade: websocket` or a `Sec-WebSocket-Key`. Either one decides, so a
+/// handshake that sends them among its first headers is recognized within
+/// the default inspection window. Otherwise the data must start with a
+/// frame header whose first byte is plausible and whose length is minimally
+/// encoded. A masked (client) frame must be fully in view, followed by
+/// nothing or by another frame, unless its payload runs past a full default
+/// inspection window. Unmasked (server) frames must be fully in view, with
+/// a valid UTF-8 text payload.
+#[cfg(feature = "websocket")]
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	let status = if data.first() == Some(&b'G') {
+		probe_handshake(data)
+	} else {
+		probe_frame(data)
+	};
+	(status, ProtocolVersion::Unknown)
+}
+
+/// Checks for a `GET` request carrying either upgrade header.
+#[cfg(feature = "websocket")]
+#[inline(always)]
+fn probe_handshake(data: &[u8]) -> DetectionStatus {
+	if !data.starts_with(b"GET ") {
+		if b"GET ".starts_with(data) {
+			return DetectionStatus::Incomplete;
+		}
+		reject!("not_get_request");
+		return DetectionStatus::NoMatch;
+	}
+	let handshake = handshake(data);
+	if handshake.upgrade || handshake.key {
+		DetectionStatus::Match
+	} else if handshake.complete {
+		reject!("no_upgrade_headers");
+		DetectionStatus::NoMatch
+	} else {
+		DetectionStatus::Incomplete
+	}
+}
+
+/// Checks a frame header and, when in view, what follows its payload.
+#[cfg(feature = "websocket")]
+#[inline(always)]
+fn probe_frame(data: &[u8]) -> DetectionStatus {
+	match frame_end(data) {
+		Ok(None) => DetectionStatus::Match,
+		Ok(Some(end)) => match data.get(end) {
+			None => DetectionStatus::Match,
+			Some(&next) if is_frame_start(next) => DetectionStatus::Match,
+			Some(_) => {
+				reject!("trailing_bytes");
+				DetectionStatus::NoMatch
+			}
+		},
+		Err(status) => status,
+	}
+}
+
+/// Returns where the first frame ends, or `None` for a masked frame whose
+/// payload runs past a full default inspection window.
+#[cfg(feature = "websocket")]
+fn frame_end(data: &[u8]) -> Result<Option<usize>, DetectionStatus> {
+	let Some(&first) = data.first() else {
+		return Err(DetectionStatus::Incomplete);
+	};
+	if !is_frame_start(first) {
+		reject!("invalid_frame_start");
+		return Err(DetectionStatus::NoMatch);
+	}
+	let Some(&second) = data.get(1) else {
+		return Err(DetectionStatus::Incomplete);
+	};
+	let opcode = first & 0x0F;
+	let masked = second & 0x80 != 0;
+	let (len, mut header) = match second & 0x7F {
+		126 => {
+			let Some(len) = data.get(2..4) else {
+				return Err(DetectionStatus::Incomplete);
+			};
+			(u64::from(u16::from_be_bytes([len[0], len[1]])), 4)
+		}
+		127 => {
+			let Some(len) = data.get(2..10) else {
+				return Err(DetectionStatus::Incomplete);
+			};
+			let mut bytes = [0u8; 8];
+			bytes.copy_from_slice(len);
+			(u64::from_be_bytes(bytes), 10)
+		}
+		len => (u64::from(len), 2),
+	};
+	let minimal = match header {
+		4 => len >= 126,
+		10 => len > u64::from(u16::MAX) && len >> 63 == 0,
+		_ => true,
+	};
+	if !minimal || (opcode >= 0x08 && len > 125) {
+		reject!("invalid_payload_length");
+		return Err(DetectionStatus::NoMatch);
+	}
+	if masked {
+		header += 4;
+	}
+	if data.len() < header {
+		return Err(DetectionStatus::Incomplete);
+	}
+
+	let end = usize::try_from(len)
+		.ok()
+		.and_then(|len| len.checked_add(header));
+	let payload = end.and_then(|end| data.get(header..end));
+	match (payload, masked) {
+		(Some(_), true) => Ok(end),
+		(None, true) if data.len() >= crate::MAX_INSPECT_BYTES => Ok(None),
+		(None, _) => Err(DetectionStatus::Incomplete),
+		(Some(payload), false) => {
+			// Text is UTF-8 unless compressed (RSV1).
+			if opcode == 0x01 && first & 0x40 == 0 && core::str::from_utf8(payload).is_err() {
+				reject!("text_not_utf8");
+				return Err(DetectionStatus::NoMatch);
+			}
+			Ok(end)
+		}
+	}
+}
+
+/// Checks for a final text, binary or control frame, or the first fragment
+/// of a text or binary message; data frames may set RSV1 for
+/// permessage-deflate.
+#[cfg(feature = "websocket")]
+#[inline(always)]
+fn is_frame_start(byte: u8) -> bool {
+	matches!(
+		byte,
+		0x01 | 0x02 | 0x41 | 0x42 | 0x81 | 0x82 | 0xC1 | 0xC2 | 0x88 | 0x89 | 0x8A
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Client handshake from RFC 6455, section 1.2.
+	const HANDSHAKE: &[u8] = b"GET /chat HTTP/1.1\r\nHost: server.example.com\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\nOrigin: http://example.com\r\nSec-WebSocket-Protocol: chat, superchat\r\nSec-WebSocket-Version: 13\r\n\r\n";
+
+	#[test]
+	fn test_metadata_other_websocket() {
+		assert_eq!(
+			metadata(HANDSHAKE),
+			ProtocolMetadata::WebSocket {
+				subprotocols: Some("chat, superchat"),
+				inner: None,
+			}
+		);
+		// The subprotocol header is not fully in view yet.
+		let data = b"GET / HTTP/1.1\r\nUpgrade: websocket\r\nSec-WebSocket-Protocol: mq";
+		assert_eq!(
+			metadata(data),
+			ProtocolMetadata::WebSocket {
+				subprotocols: None,
+				inner: None,
+			}
+		);
+	}
+
+	#[test]
+	#[cfg(feature = "mqtt")]
+	fn test_metadata_mqtt_over_websocket() {
+		let data = b"GET /mqtt HTTP/1.1\r\nHost: broker\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Protocol: mqtt\r\n\r\n";
+		assert_eq!(
+			metadata(data),
+			ProtocolMetadata::WebSocket {
+				subprotocols: Some("mqtt"),
+				inner: Some(Protocol::Mqtt),
+			}
+		);
+		let data =
+			b"GET / HTTP/1.1\r\nupgrade: WebSocket\r\nsec-websocket-protocol: wamp, mqttv3.1\r\n";
+		assert_eq!(
+			metadata(data),
+			ProtocolMetadata::WebSocket {
+				subprotocols: Some("wamp, mqttv3.1"),
+				inner: Some(Protocol::Mqtt),
+			}
+		);
+	}
+
+	#[test]
+	fn test_metadata_plain_request() {
+		let data = b"GET / HTTP/1.1\r\nHost: example.com\r\nSec-WebSocket-Protocol: mqtt\r\n\r\n";
+		assert_eq!(metadata(data), ProtocolMetadata::None);
+	}
+
+	#[test]
+	#[cfg(feature = "websocket")]
+	fn test_detect_handshake() {
+		assert_eq!(
+			probe(HANDSHAKE),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+		assert_eq!(probe(&HANDSHAKE[..40]).0, DetectionStatus::Incomplete);
+		// Only the request line and `Upgrade` are in view.
+		assert_eq!(probe(&HANDSHAKE[..66]).0, DetectionStatus::Match);
+		assert_eq!(probe(b"GE").0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	#[cfg(feature = "websocket")]
+	fn test_reject_plain_get() {
+		assert_eq!(
+			probe(b"GET / HTTP/1.1\r\nHost: example.com\r\n\r\n").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(
+			probe(b"GET / HTTP/1.1\r\nUpgrade: h2c\r\nConnection: Upgrade\r\n\r\n").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(probe(b"GEM ").0, DetectionStatus::NoMatch);
+	}
+
+	#[test]
+	#[cfg(feature = "websocket")]
+	fn test_detect_frames() {
+		// Masked "Hello" from a client, and unmasked "Hello" from a server
+		// followed by a ping.
+		assert_eq!(
+			probe(b"\x81\x85\x37\xFA\x21\x3D\x7F\x9F\x4D\x51\x58").0,
+			DetectionStatus::Match
+		);
+		assert_eq!(probe(b"\x81\x05Hello\x89\x00").0, DetectionStatus::Match);
+		// Masked binary frame with a 16-bit length whose payload runs past
+		// the window.
+		let mut frame = [0u8; crate::MAX_INSPECT_BYTES];
+		frame[..8].copy_from_slice(b"\x82\xFE\x01\x00\x12\x34\x56\x78");
+		assert_eq!(probe(&frame).0, DetectionStatus::Match);
+		assert_eq!(probe(&frame[..9]).0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	#[cfg(feature = "websocket")]
+	fn test_incomplete_frames() {
+		assert_eq!(probe(b"\x81").0, DetectionStatus::Incomplete);
+		assert_eq!(probe(b"\x81\x85\x37").0, DetectionStatus::Incomplete);
+		assert_eq!(probe(b"\x81\x05Hel").0, DetectionStatus::Incomplete);
+		// RTCP receiver report: reads as a masked 73-byte text frame that the
+		// 32-byte datagram cannot hold.
+		let mut rr = [0u8; 32];
+		rr[..4].copy_from_slice(&[0x81, 0xC9, 0x00, 0x07]);
+		assert_eq!(probe(&rr).0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	#[cfg(feature = "websocket")]
+	fn test_reject_invalid_frames() {
+		// Unknown opcode, fragmented ping, non-minimal length, oversized
+		// close, invalid UTF-8 text, and trailing bytes after the frame.
+		assert_eq!(probe(b"\x83\x00").0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"\x09\x00").0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"\x82\x7E\x00\x10").0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"\x88\x7E\x00\x80").0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"\x81\x02\xC3\x28").0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"\x82\x00\x00\x00\x0A").0, DetectionStatus::NoMatch);
+	}
+}
@@ -0,0 +1,126 @@
+/* src/protocols/xmpp.rs */
+use crate::{DetectionStatus, ProtocolVersion};
+
+/// XML declaration that may precede the stream header.
+const XML_DECLARATION: &[u8] = b"<?xml";
+
+/// Opening of the stream header element.
+const STREAM_OPEN: &[u8] = b"<stream:stream";
+
+/// Namespaces that mark the stream header as XMPP.
+const NAMESPACES: [&[u8]; 2] = [b"jabber:", b"http://etherx.jabber.org/streams"];
+
+/// Longest XML declaration accepted.
+const MAX_DECLARATION_LEN: usize = 128;
+
+/// Probes for an XMPP stream header.
+///
+/// Expects an optional XML declaration, then `<stream:stream` followed by
+/// whitespace or the end of the tag. Once the whole start tag is in view,
+/// it must declare a `jabber:` or `http://etherx.jabber.org/streams`
+/// namespace.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	(probe_status(data), ProtocolVersion::Unknown)
+}
+
+/// Skips the XML declaration and checks the stream header.
+#[inline(always)]
+fn probe_status(data: &[u8]) -> DetectionStatus {
+	let mut rest = data;
+	if starts_with_partial(rest, XML_DECLARATION) {
+		if rest.len() < XML_DECLARATION.len() {
+			return DetectionStatus::Incomplete;
+		}
+		let Some(end) = rest.windows(2).position(|pair| pair == b"?>") else {
+			return if rest.len() < MAX_DECLARATION_LEN {
+				DetectionStatus::Incomplete
+			} else {
+				reject!("declaration_too_long");
+				DetectionStatus::NoMatch
+			};
+		};
+		rest = rest[end + 2..].trim_ascii_start();
+	}
+
+	if !starts_with_partial(rest, STREAM_OPEN) {
+		reject!("no_stream_header");
+		return DetectionStatus::NoMatch;
+	}
+	let Some(&next) = rest.get(STREAM_OPEN.len()) else {
+		return DetectionStatus::Incomplete;
+	};
+	if !next.is_ascii_whitespace() && next != b'>' {
+		reject!("not_stream_element");
+		return DetectionStatus::NoMatch;
+	}
+	let tag = &rest[STREAM_OPEN.len()..];
+	match tag.iter().position(|&b| b == b'>') {
+		Some(end) if !NAMESPACES.iter().any(|ns| contains(&tag[..end], ns)) => {
+			reject!("no_jabber_namespace");
+			DetectionStatus::NoMatch
+		}
+		_ => DetectionStatus::Match,
+	}
+}
+
+/// Checks whether `data` starts with `prefix`, or is a prefix of it.
+#[inline(always)]
+fn starts_with_partial(data: &[u8], prefix: &[u8]) -> bool {
+	let len = data.len().min(prefix.len());
+	data[..len] == prefix[..len] && !data.is_empty()
+}
+
+/// Checks whether `needle` occurs in `haystack`.
+#[inline(always)]
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+	haystack
+		.windows(needle.len())
+		.any(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_detect_client_stream() {
+		let data = b"<?xml version='1.0'?><stream:stream to='example.com' xmlns='jabber:client' xmlns:stream='http://etherx.jabber.org/streams' version='1.0'>";
+		assert_eq!(
+			probe(data),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+		// The namespaces lie beyond the default inspection window.
+		assert_eq!(probe(&data[..64]).0, DetectionStatus::Match);
+	}
+
+	#[test]
+	fn test_detect_stream_without_declaration() {
+		let data = b"<stream:stream xmlns='jabber:server' xmlns:stream='http://etherx.jabber.org/streams' to='example.net' version='1.0'>";
+		assert_eq!(probe(data).0, DetectionStatus::Match);
+	}
+
+	#[test]
+	fn test_incomplete_header() {
+		assert_eq!(probe(b"<?x").0, DetectionStatus::Incomplete);
+		assert_eq!(probe(b"<?xml version='1.0'").0, DetectionStatus::Incomplete);
+		assert_eq!(
+			probe(b"<?xml version='1.0'?>\n<str").0,
+			DetectionStatus::Incomplete
+		);
+	}
+
+	#[test]
+	fn test_reject_other_xml() {
+		assert_eq!(
+			probe(b"<?xml version='1.0'?><soap:Envelope>").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(probe(b"<stream:streaming>").0, DetectionStatus::NoMatch);
+		assert_eq!(
+			probe(b"<stream:stream xmlns='urn:example'>").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(probe(b"GET / HTTP/1.1\r\n").0, DetectionStatus::NoMatch);
+	}
+}
@@ -0,0 +1,161 @@
+/* src/protocols/zookeeper.rs */
+use crate::{DetectionStatus, ProtocolVersion};
+
+/// Length of a `ConnectRequest` after its length prefix: protocol version,
+/// last zxid seen, timeout, session ID and 16-byte password.
+const CONNECT_LEN: u32 = 44;
+
+/// Length of a `ConnectRequest` carrying the trailing read-only flag (3.4+).
+const CONNECT_READ_ONLY_LEN: u32 = 45;
+
+/// Length of the session password.
+const PASSWORD_LEN: u32 = 16;
+
+/// Session timeouts accepted, in milliseconds.
+const TIMEOUT_MS: core::ops::RangeInclusive<u32> = 100..=3_600_000;
+
+/// Four-letter admin commands.
+const FOUR_LETTER_WORDS: [&[u8; 4]; 17] = [
+	b"ruok", b"stat", b"srvr", b"conf", b"cons", b"crst", b"dump", b"envi", b"gtmk", b"isro",
+	b"mntr", b"reqs", b"srst", b"wchs", b"wchc", b"wchp", b"dirs",
+];
+
+/// Probes for a ZooKeeper client connection.
+///
+/// Recognizes the length-prefixed `ConnectRequest` (protocol version 0, a
+/// plausible session timeout and a 16-byte password, with or without the
+/// read-only flag) and four-letter admin commands such as `ruok`, `stat`
+/// and `srvr`, sent alone or followed by a newline.
+#[inline(always)]
+pub(crate) fn probe(data: &[u8]) -> (DetectionStatus, ProtocolVersion<'_>) {
+	let status = match data.first() {
+		None => DetectionStatus::Incomplete,
+		Some(0) => probe_connect(data),
+		Some(_) => probe_command(data),
+	};
+	(status, ProtocolVersion::Unknown)
+}
+
+/// Checks the fixed fields of a `ConnectRequest`.
+#[inline(always)]
+fn probe_connect(data: &[u8]) -> DetectionStatus {
+	let Some(&len) = data.first_chunk::<4>() else {
+		return DetectionStatus::Incomplete;
+	};
+	let len = u32::from_be_bytes(len);
+	if len != CONNECT_LEN && len != CONNECT_READ_ONLY_LEN {
+		reject!("unexpected_length");
+		return DetectionStatus::NoMatch;
+	}
+	let (Some(version), Some(timeout), Some(password_len)) =
+		(word(data, 4), word(data, 16), word(data, 28))
+	else {
+		return DetectionStatus::Incomplete;
+	};
+	if version != 0 {
+		reject!("unsupported_version");
+		return DetectionStatus::NoMatch;
+	}
+	if !TIMEOUT_MS.contains(&timeout) || password_len != PASSWORD_LEN {
+		reject!("implausible_connect");
+		return DetectionStatus::NoMatch;
+	}
+	match data.get(48) {
+		Some(&read_only) if len == CONNECT_READ_ONLY_LEN && read_only > 1 => {
+			reject!("invalid_read_only_flag");
+			DetectionStatus::NoMatch
+		}
+		_ => DetectionStatus::Match,
+	}
+}
+
+/// Checks for a four-letter word, alone or ending the line.
+#[inline(always)]
+fn probe_command(data: &[u8]) -> DetectionStatus {
+	let len = data.len().min(4);
+	if !FOUR_LETTER_WORDS
+		.iter()
+		.any(|word| word[..len] == data[..len])
+	{
+		reject!("unknown_command");
+		return DetectionStatus::NoMatch;
+	}
+	if len < 4 {
+		return DetectionStatus::Incomplete;
+	}
+	match &data[len..] {
+		b"" | b"\n" | b"\r\n" => DetectionStatus::Match,
+		b"\r" => DetectionStatus::Incomplete,
+		_ => {
+			reject!("trailing_data");
+			DetectionStatus::NoMatch
+		}
+	}
+}
+
+/// Reads the big-endian word at `offset`.
+#[inline(always)]
+fn word(data: &[u8], offset: usize) -> Option<u32> {
+	data
+		.get(offset..offset + 4)
+		.map(|bytes| u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// New session with a 30 s timeout and the read-only flag cleared.
+	const CONNECT_REQUEST: &[u8] =
+		b"\x00\x00\x00\x2D\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\
+		\x00\x00\x75\x30\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x10\
+		\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00";
+
+	#[test]
+	fn test_detect_connect_request() {
+		assert_eq!(
+			probe(CONNECT_REQUEST),
+			(DetectionStatus::Match, ProtocolVersion::Unknown)
+		);
+		// Without the read-only flag.
+		let mut request = [0u8; 48];
+		request.copy_from_slice(&CONNECT_REQUEST[..48]);
+		request[3] = 0x2C;
+		assert_eq!(probe(&request).0, DetectionStatus::Match);
+	}
+
+	#[test]
+	fn test_detect_four_letter_words() {
+		assert_eq!(probe(b"ruok").0, DetectionStatus::Match);
+		assert_eq!(probe(b"srvr\n").0, DetectionStatus::Match);
+		assert_eq!(probe(b"mntr\r\n").0, DetectionStatus::Match);
+	}
+
+	#[test]
+	fn test_incomplete_messages() {
+		assert_eq!(probe(b"\x00\x00").0, DetectionStatus::Incomplete);
+		assert_eq!(probe(&CONNECT_REQUEST[..20]).0, DetectionStatus::Incomplete);
+		assert_eq!(probe(b"ru").0, DetectionStatus::Incomplete);
+	}
+
+	#[test]
+	fn test_reject_other_payloads() {
+		// Zero timeout, then a nonzero protocol version.
+		let mut request = [0u8; 49];
+		request.copy_from_slice(CONNECT_REQUEST);
+		request[18] = 0;
+		request[19] = 0;
+		assert_eq!(probe(&request).0, DetectionStatus::NoMatch);
+		request.copy_from_slice(CONNECT_REQUEST);
+		request[7] = 1;
+		assert_eq!(probe(&request).0, DetectionStatus::NoMatch);
+		// Other lengths and text.
+		assert_eq!(
+			probe(b"\x00\x00\x00\x30\x00\x00").0,
+			DetectionStatus::NoMatch
+		);
+		assert_eq!(probe(b"status").0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"ruok please").0, DetectionStatus::NoMatch);
+		assert_eq!(probe(b"GET /").0, DetectionStatus::NoMatch);
+	}
+}
@@ -0,0 +1,269 @@
+/* src/rules.rs */
+
+use crate::{
+	Protocol, ProtocolDetector,
+	signature::{self, Check, Layer, Signature},
+};
+use std::fmt::Write as _;
+
+/// Exports the enabled protocols' signatures as Suricata rules, one per line.
+///
+/// Rule ids are assigned sequentially starting at `first_sid`. Each rule raises
+/// an alert named `guess <protocol>`; payload signatures are anchored with
+/// `offset`/`depth`, and port-only signatures (DNS, DHCP, NTP) match traffic
+/// to or from the well-known port.
+#[must_use]
+pub fn suricata<T>(detector: &ProtocolDetector<T>, first_sid: u32) -> String {
+	let mut out = String::new();
+	for (sid, (protocol, signature)) in (first_sid..).zip(signature::enabled(detector)) {
+		let name = protocol_name(protocol);
+		let _ = match *signature {
+			Signature::Payload(layer, checks) => {
+				let options: String = checks.iter().map(suricata_check).collect();
+				writeln!(
+					out,
+					"alert {} any any -> any any (msg:\"guess {name}\"; {options}sid:{sid}; rev:1;)",
+					layer_name(layer)
+				)
+			}
+			Signature::Port(layer, port) => writeln!(
+				out,
+				"alert {} any any <> any {port} (msg:\"guess {name}\"; sid:{sid}; rev:1;)",
+				layer_name(layer)
+			),
+		};
+	}
+	out
+}
+
+/// Exports the enabled protocols' signatures in Zeek signature language.
+///
+/// Each signature raises a `signature_match` event with the message
+/// `guess: <protocol>`. Port-only signatures are emitted twice, once per
+/// direction.
+#[must_use]
+pub fn zeek<T>(detector: &ProtocolDetector<T>) -> String {
+	let mut out = String::new();
+	let mut last = None;
+	let mut index = 0;
+	for (protocol, signature) in signature::enabled(detector) {
+		if last != Some(protocol) {
+			last = Some(protocol);
+			index = 0;
+		}
+		let name = protocol_name(protocol);
+		let mut emit = |condition: String| {
+			let _ = writeln!(
+				out,
+				"signature guess-{name}-{index} {{\n  ip-proto == {}\n  {condition}\n  event \"guess: {name}\"\n}}\n",
+				layer_name(signature_layer(signature))
+			);
+			index += 1;
+		};
+		match *signature {
+			Signature::Payload(_, checks) => emit(format!("payload /{}/", zeek_pattern(checks))),
+			Signature::Port(_, port) => {
+				emit(format!("dst-port == {port}"));
+				emit(format!("src-port == {port}"));
+			}
+		}
+	}
+	out
+}
+
+/// Renders one check as Suricata rule options.
+fn suricata_check(check: &Check) -> String {
+	let bytes = check_bytes(check.value, check.len);
+	if check.mask == full_mask(check.len) {
+		let printable = bytes
+			.iter()
+			.all(|b| (0x20..0x7F).contains(b) && !matches!(b, b'"' | b';' | b'\\' | b'|'));
+		let content = if printable {
+			String::from_utf8_lossy(&bytes).into_owned()
+		} else {
+			let hex: Vec<String> = bytes.iter().map(|b| format!("{b:02X}")).collect();
+			format!("|{}|", hex.join(" "))
+		};
+		format!(
+			"content:\"{content}\"; offset:{}; depth:{}; ",
+			check.offset, check.len
+		)
+	} else {
+		// `bitmask` shifts the masked value right by the mask's trailing zeros.
+		let shift = check.mask.trailing_zeros();
+		format!(
+			"byte_test:{},=,{},{},bitmask {:#x}; ",
+			check.len,
+			check.value >> shift,
+			check.offset,
+			check.mask
+		)
+	}
+}
+
+/// Builds an anchored Zeek payload regex for a set of checks.
+fn zeek_pattern(checks: &[Check]) -> String {
+	let mut sorted = checks.to_vec();
+	sorted.sort_by_key(|check| check.offset);
+
+	let mut out = String::from("^");
+	let mut pos = 0;
+	for check in sorted {
+		let gap = usize::from(check.offset).saturating_sub(pos);
+		if gap > 0 {
+			let _ = write!(out, "[\\x00-\\xff]{{{gap}}}");
+		}
+		let masks = check_bytes(check.mask, check.len);
+		let values = check_bytes(check.value, check.len);
+		for (mask, value) in masks.into_iter().zip(values) {
+			if mask == 0xFF {
+				push_zeek_byte(&mut out, value);
+			} else {
+				push_zeek_class(&mut out, mask, value);
+			}
+		}
+		pos = usize::from(check.offset) + usize::from(check.len);
+	}
+	out
+}
+
+/// Appends a single literal byte to a Zeek regex.
+fn push_zeek_byte(out: &mut String, byte: u8) {
+	if byte.is_ascii_alphanumeric() {
+		out.push(char::from(byte));
+	} else {
+		let _ = write!(out, "\\x{byte:02x}");
+	}
+}
+
+/// Appends a character class of every byte `b` with `b & mask == value`.
+fn push_zeek_class(out: &mut String, mask: u8, value: u8) {
+	out.push('[');
+	let mut byte = 0u16;
+	while byte <= 0xFF {
+		let start = byte as u8;
+		if start & mask != value {
+			byte += 1;
+			continue;
+		}
+		let mut end = start;
+		while end < 0xFF && (end + 1) & mask == value {
+			end += 1;
+		}
+		if start == end {
+			let _ = write!(out, "\\x{start:02x}");
+		} else {
+			let _ = write!(out, "\\x{start:02x}-\\x{end:02x}");
+		}
+		byte = u16::from(end) + 1;
+	}
+	out.push(']');
+}
+
+/// Returns the low `len` bytes of `word` in big-endian order.
+fn check_bytes(word: u32, len: u8) -> Vec<u8> {
+	word.to_be_bytes()[4 - usize::from(len)..].to_vec()
+}
+
+/// All-ones mask for a load of `len` bytes.
+fn full_mask(len: u8) -> u32 {
+	u32::MAX >> (32 - u32::from(len) * 8)
+}
+
+/// Transport of a signature.
+fn signature_layer(signature: &Signature) -> Layer {
+	match *signature {
+		Signature::Payload(layer, _) | Signature::Port(layer, _) => layer,
+	}
+}
+
+/// Lowercase transport keyword shared by Suricata and Zeek.
+fn layer_name(layer: Layer) -> &'static str {
+	match layer {
+		Layer::Tcp => "tcp",
+		Layer::Udp => "udp",
+	}
+}
+
+/// Lowercase protocol name used in rule messages and ids.
+fn protocol_name(protocol: Protocol) -> String {
+	format!("{protocol:?}").to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// ── Correct paths ──
+
+	#[test]
+	#[cfg(feature = "ssh")]
+	fn suricata_anchors_text_content() {
+		let detector = ProtocolDetector::builder().ssh().build();
+		assert_eq!(
+			suricata(&detector, 1_000_000),
+			"alert tcp any any -> any any (msg:\"guess ssh\"; content:\"SSH-\"; offset:0; depth:4; sid:1000000; rev:1;)\n"
+		);
+	}
+
+	#[test]
+	#[cfg(all(feature = "quic", feature = "ntp"))]
+	fn suricata_uses_bitmask_and_ports() {
+		let detector = ProtocolDetector::builder().quic().ntp().build();
+		let rules = suricata(&detector, 10);
+		let lines: Vec<&str> = rules.lines().collect();
+		assert_eq!(
+			lines,
+			[
+				"alert udp any any -> any any (msg:\"guess quic\"; byte_test:1,=,3,0,bitmask 0xc0; sid:10; rev:1;)",
+				"alert udp any any <> any 123 (msg:\"guess ntp\"; sid:11; rev:1;)",
+			]
+		);
+	}
+
+	#[test]
+	#[cfg(feature = "mysql")]
+	fn suricata_hex_encodes_binary_content() {
+		let detector = ProtocolDetector::builder().mysql().build();
+		assert!(
+			suricata(&detector, 1)
+				.contains("content:\"|00|\"; offset:3; depth:1; content:\"|0A|\"; offset:4; depth:1;")
+		);
+	}
+
+	#[test]
+	#[cfg(feature = "mysql")]
+	fn zeek_fills_gaps_before_offsets() {
+		let detector = ProtocolDetector::builder().mysql().build();
+		assert_eq!(
+			zeek(&detector),
+			"signature guess-mysql-0 {\n  ip-proto == tcp\n  payload /^[\\x00-\\xff]{3}\\x00\\x0a/\n  event \"guess: mysql\"\n}\n\n"
+		);
+	}
+
+	#[test]
+	#[cfg(feature = "quic")]
+	fn zeek_expands_masks_into_classes() {
+		let detector = ProtocolDetector::builder().quic().build();
+		assert!(zeek(&detector).contains("payload /^[\\xc0-\\xff]/"));
+	}
+
+	#[test]
+	#[cfg(feature = "dns")]
+	fn zeek_port_signatures_cover_both_directions() {
+		let detector = ProtocolDetector::builder().dns().build();
+		let sigs = zeek(&detector);
+		assert_eq!(sigs.matches("dst-port == 53").count(), 2);
+		assert_eq!(sigs.matches("src-port == 53").count(), 2);
+		assert!(sigs.contains("signature guess-dns-3 {"));
+	}
+
+	// ── Error paths ──
+
+	#[test]
+	fn no_protocols_yields_no_rules() {
+		let detector = ProtocolDetector::builder().build();
+		assert!(suricata(&detector, 1).is_empty());
+		assert!(zeek(&detector).is_empty());
+	}
+}
@@ -0,0 +1,215 @@
+/* src/rustls.rs */
+
+use crate::{
+	Protocol, ProtocolDetector,
+	net::{Sniffed, sniff_blocking},
+};
+use ::rustls::server::{Accepted, Acceptor};
+use std::io::{self, Read, Write};
+
+/// A TLS connection whose `ClientHello` has been read by rustls.
+#[derive(Debug)]
+pub struct TlsAccepted<IO> {
+	/// Parsed `ClientHello`, ready to be turned into a `ServerConnection`.
+	pub accepted: Accepted,
+	/// The connection; any sniffed bytes rustls has not consumed are replayed on read.
+	pub io: Sniffed<IO>,
+}
+
+/// Outcome of [`accept`].
+#[derive(Debug)]
+pub enum Accept<IO, R> {
+	/// The connection speaks TLS.
+	Tls(Box<TlsAccepted<IO>>),
+	/// The connection is not TLS; holds the plaintext callback's result.
+	Plain(R),
+}
+
+/// Sniffs a connection and hands TLS to a rustls [`Acceptor`], everything else to `plaintext`.
+///
+/// The bytes read during detection are replayed into the `Acceptor` first, so
+/// the `ClientHello` is never lost. Non-TLS connections are passed to
+/// `plaintext` as a [`Sniffed`] stream that replays the same bytes on read.
+///
+/// # Errors
+///
+/// Returns any I/O error raised by the connection, or `InvalidData` if rustls
+/// rejects the `ClientHello`; in that case the alert produced by rustls has
+/// already been written to the peer.
+pub fn accept<IO, T, R, F>(
+	detector: &ProtocolDetector<T>,
+	io: IO,
+	plaintext: F,
+) -> io::Result<Accept<IO, R>>
+where
+	IO: Read + Write,
+	F: FnOnce(Sniffed<IO>) -> R,
+{
+	let mut io = sniff_blocking(detector, io)?;
+	if !is_tls(io.protocol()) {
+		return Ok(Accept::Plain(plaintext(io)));
+	}
+
+	let mut acceptor = Acceptor::default();
+	loop {
+		if acceptor.read_tls(&mut io)? == 0 {
+			return Err(io::ErrorKind::UnexpectedEof.into());
+		}
+		match acceptor.accept() {
+			Ok(Some(accepted)) => return Ok(Accept::Tls(Box::new(TlsAccepted { accepted, io }))),
+			Ok(None) => {}
+			Err((err, mut alert)) => {
+				// Best effort: the handshake already failed, so a lost alert is not an error.
+				let _ = alert.write_all(io.get_mut());
+				return Err(io::Error::new(io::ErrorKind::InvalidData, err));
+			}
+		}
+	}
+}
+
+/// Returns `true` if the detected protocol is TLS.
+fn is_tls(protocol: Option<Protocol>) -> bool {
+	protocol == Some(Protocol::Tls)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::io::Cursor;
+
+	/// Builds a minimal TLS 1.3 `ClientHello` with an SNI extension.
+	fn client_hello(host: &str, with_signature_algorithms: bool) -> Vec<u8> {
+		let name = host.as_bytes();
+		let mut sni = Vec::new();
+		sni.extend_from_slice(&((name.len() + 3) as u16).to_be_bytes());
+		sni.push(0x00);
+		sni.extend_from_slice(&(name.len() as u16).to_be_bytes());
+		sni.extend_from_slice(name);
+
+		let mut extensions = Vec::new();
+		extensions.extend_from_slice(&[0x00, 0x00]);
+		extensions.extend_from_slice(&(sni.len() as u16).to_be_bytes());
+		extensions.extend_from_slice(&sni);
+		// supported_versions: TLS 1.3
+		extensions.extend_from_slice(&[0x00, 0x2b, 0x00, 0x03, 0x02, 0x03, 0x04]);
+		if with_signature_algorithms {
+			// signature_algorithms: rsa_pss_rsae_sha256
+			extensions.extend_from_slice(&[0x00, 0x0d, 0x00, 0x04, 0x00, 0x02, 0x08, 0x04]);
+		}
+
+		let mut body = vec![0x03, 0x03];
+		body.extend_from_slice(&[0x11; 32]);
+		body.push(0x00);
+		body.extend_from_slice(&[0x00, 0x02, 0x13, 0x01]);
+		body.extend_from_slice(&[0x01, 0x00]);
+		body.extend_from_slice(&(extensions.len() as u16).to_be_bytes());
+		body.extend_from_slice(&extensions);
+
+		let mut handshake = vec![0x01];
+		handshake.extend_from_slice(&(body.len() as u32).to_be_bytes()[1..]);
+		handshake.extend_from_slice(&body);
+
+		let mut record = vec![0x16, 0x03, 0x01];
+		record.extend_from_slice(&(handshake.len() as u16).to_be_bytes());
+		record.extend_from_slice(&handshake);
+		record
+	}
+
+	/// In-memory duplex stream: reads from `input`, collects writes in `output`.
+	#[derive(Debug)]
+	struct Duplex {
+		/// Bytes the peer sent.
+		input: Cursor<Vec<u8>>,
+		/// Bytes written back to the peer.
+		output: Vec<u8>,
+	}
+
+	impl Duplex {
+		/// Creates a stream that yields `input`.
+		fn new(input: &[u8]) -> Self {
+			Self {
+				input: Cursor::new(input.to_vec()),
+				output: Vec::new(),
+			}
+		}
+	}
+
+	impl Read for Duplex {
+		fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+			self.input.read(buf)
+		}
+	}
+
+	impl Write for Duplex {
+		fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+			self.output.extend_from_slice(buf);
+			Ok(buf.len())
+		}
+
+		fn flush(&mut self) -> io::Result<()> {
+			Ok(())
+		}
+	}
+
+	// ── Correct paths ──
+
+	#[test]
+	fn tls_connection_reaches_acceptor_with_client_hello_intact() {
+		let detector = ProtocolDetector::builder().tls().build();
+		let io = Duplex::new(&client_hello("example.com", true));
+		let Accept::Tls(tls) = accept(&detector, io, |_| ()).unwrap() else {
+			panic!("expected TLS");
+		};
+		assert_eq!(
+			tls.accepted.client_hello().server_name(),
+			Some("example.com")
+		);
+	}
+
+	#[test]
+	#[cfg(feature = "http")]
+	fn plaintext_connection_goes_to_callback_with_bytes_replayed() {
+		let detector = ProtocolDetector::builder().tls().http().build();
+		let io = Duplex::new(b"GET / HTTP/1.1\r\n\r\n");
+		let result = accept(&detector, io, |mut plain| {
+			let mut out = Vec::new();
+			plain.read_to_end(&mut out).unwrap();
+			(plain.protocol(), out)
+		})
+		.unwrap();
+		let Accept::Plain((protocol, bytes)) = result else {
+			panic!("expected plaintext");
+		};
+		assert_eq!(protocol, Some(Protocol::Http));
+		assert_eq!(bytes, b"GET / HTTP/1.1\r\n\r\n");
+	}
+
+	// ── Error paths ──
+
+	#[test]
+	fn unknown_traffic_goes_to_callback() {
+		let detector = ProtocolDetector::builder().build();
+		let io = Duplex::new(&[0x42; 16]);
+		let result = accept(&detector, io, |plain| plain.prefix().len()).unwrap();
+		assert!(matches!(result, Accept::Plain(16)));
+	}
+
+	#[test]
+	fn truncated_client_hello_is_eof() {
+		let detector = ProtocolDetector::builder().tls().build();
+		let hello = client_hello("example.com", true);
+		let io = Duplex::new(&hello[..20]);
+		let err = accept(&detector, io, |_| ()).unwrap_err();
+		assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+	}
+
+	#[test]
+	fn rejected_client_hello_sends_alert() {
+		let detector = ProtocolDetector::builder().tls().build();
+		let mut io = Duplex::new(&client_hello("example.com", false));
+		let err = accept(&detector, &mut io, |_| ()).unwrap_err();
+		assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+		// Alert record: content type 21.
+		assert_eq!(io.output.first(), Some(&0x15));
+	}
+}
@@ -0,0 +1,974 @@
+/* src/signature.rs */
+
+use crate::{Protocol, ProtocolDetector};
+
+/// Transport a signature applies to.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Layer {
+	/// TCP segments.
+	Tcp,
+	/// UDP datagrams.
+	Udp,
+}
+
+/// A masked big-endian comparison at a fixed payload offset.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Check {
+	/// Offset from the start of the transport payload.
+	pub(crate) offset: u16,
+	/// Load width in bytes: 1, 2, or 4.
+	pub(crate) len: u8,
+	/// Mask applied before comparing; all ones means an exact match.
+	pub(crate) mask: u32,
+	/// Expected value after masking.
+	pub(crate) value: u32,
+}
+
+/// One way a protocol can show up on the wire.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Signature {
+	/// Payload bytes that must all match.
+	Payload(Layer, &'static [Check]),
+	/// A well-known port, for protocols without a usable byte signature.
+	Port(Layer, u16),
+}
+
+/// Matches four ASCII bytes at the start of the payload.
+#[allow(dead_code)]
+const fn text(bytes: [u8; 4]) -> Check {
+	Check {
+		offset: 0,
+		len: 4,
+		mask: u32::MAX,
+		value: u32::from_be_bytes(bytes),
+	}
+}
+
+/// Matches a single byte under `mask`.
+#[allow(dead_code)]
+const fn byte(offset: u16, mask: u8, value: u8) -> Check {
+	Check {
+		offset,
+		len: 1,
+		mask: mask as u32,
+		value: value as u32,
+	}
+}
+
+/// Matches a 32-bit word exactly.
+#[allow(dead_code)]
+const fn word(offset: u16, value: u32) -> Check {
+	Check {
+		offset,
+		len: 4,
+		mask: u32::MAX,
+		value,
+	}
+}
+
+/// Builds TCP signatures from four-byte text prefixes.
+#[allow(unused_macros)]
+macro_rules! tcp_text {
+	($($prefix:literal),* $(,)?) => {
+		&[$(Signature::Payload(Layer::Tcp, &[text(*$prefix)])),*]
+	};
+	($($prefix:literal),* ; $($extra:expr),* $(,)?) => {
+		&[$(Signature::Payload(Layer::Tcp, &[text(*$prefix)]),)* $($extra),*]
+	};
+}
+
+/// Builds TCP and UDP signatures from four-byte text prefixes.
+#[allow(unused_macros)]
+macro_rules! any_text {
+	($($prefix:literal),* $(,)?) => {
+		&[
+			$(Signature::Payload(Layer::Tcp, &[text(*$prefix)]),)*
+			$(Signature::Payload(Layer::Udp, &[text(*$prefix)]),)*
+		]
+	};
+}
+
+#[cfg(feature = "http")]
+/// Common HTTP/1.x request methods, the HTTP/2 client preface and the
+/// server's opening SETTINGS frame. Other methods are not covered.
+const HTTP: &[Signature] = tcp_text![
+	b"GET ", b"POST", b"PUT ", b"DELE", b"HEAD", b"OPTI", b"CONN", b"TRAC", b"PATC", b"PRI ";
+	Signature::Payload(Layer::Tcp, &[byte(0, 0xFF, 0x00), byte(3, 0xFF, 0x04), word(5, 0)]),
+];
+
+#[cfg(feature = "tls")]
+/// TLS records (content types 0x14-0x17, major version 3) and SSLv2 hellos.
+const TLS: &[Signature] = &[
+	Signature::Payload(Layer::Tcp, &[byte(0, 0xFC, 0x14), byte(1, 0xFF, 0x03)]),
+	Signature::Payload(Layer::Tcp, &[byte(0, 0x80, 0x80), byte(2, 0xFF, 0x01)]),
+];
+
+#[cfg(feature = "ssh")]
+/// SSH identification string.
+const SSH: &[Signature] = tcp_text![b"SSH-"];
+
+#[cfg(feature = "dns")]
+/// DNS has no fixed payload bytes, so it is selected by port.
+const DNS: &[Signature] = &[
+	Signature::Port(Layer::Tcp, 53),
+	Signature::Port(Layer::Udp, 53),
+];
+
+#[cfg(feature = "quic")]
+/// QUIC long header.
+const QUIC: &[Signature] = &[Signature::Payload(Layer::Udp, &[byte(0, 0xC0, 0xC0)])];
+
+#[cfg(feature = "mysql")]
+/// `MySQL` handshake: sequence id 0 followed by protocol version 10.
+const MYSQL: &[Signature] = &[Signature::Payload(
+	Layer::Tcp,
+	&[byte(3, 0xFF, 0x00), byte(4, 0xFF, 0x0A)],
+)];
+
+#[cfg(feature = "postgres")]
+/// `PostgreSQL` startup message and `SSLRequest`.
+const POSTGRES: &[Signature] = &[
+	Signature::Payload(Layer::Tcp, &[word(4, 0x0003_0000)]),
+	Signature::Payload(Layer::Tcp, &[word(4, 0x04D2_162F)]),
+];
+
+#[cfg(feature = "redis")]
+/// RESP2 and RESP3 type markers.
+const REDIS: &[Signature] = &[
+	Signature::Payload(Layer::Tcp, &[byte(0, 0xFF, b'*')]),
+	Signature::Payload(Layer::Tcp, &[byte(0, 0xFF, b'$')]),
+	Signature::Payload(Layer::Tcp, &[byte(0, 0xFF, b'+')]),
+	Signature::Payload(Layer::Tcp, &[byte(0, 0xFF, b'-')]),
+	Signature::Payload(Layer::Tcp, &[byte(0, 0xFF, b':')]),
+	Signature::Payload(Layer::Tcp, &[byte(0, 0xFF, b'_')]),
+	Signature::Payload(Layer::Tcp, &[byte(0, 0xFF, b',')]),
+	Signature::Payload(Layer::Tcp, &[byte(0, 0xFF, b'#')]),
+	Signature::Payload(Layer::Tcp, &[byte(0, 0xFF, b'!')]),
+	Signature::Payload(Layer::Tcp, &[byte(0, 0xFF, b'=')]),
+	Signature::Payload(Layer::Tcp, &[byte(0, 0xFF, b'(')]),
+	Signature::Payload(Layer::Tcp, &[byte(0, 0xFF, b'%')]),
+	Signature::Payload(Layer::Tcp, &[byte(0, 0xFF, b'~')]),
+	Signature::Payload(Layer::Tcp, &[byte(0, 0xFF, b'>')]),
+];
+
+#[cfg(feature = "mqtt")]
+/// MQTT CONNECT packet type.
+const MQTT: &[Signature] = &[Signature::Payload(Layer::Tcp, &[byte(0, 0xFF, 0x10)])];
+
+#[cfg(feature = "smtp")]
+/// SMTP greeting and client commands.
+const SMTP: &[Signature] = tcp_text![
+	b"220 ", b"220-", b"EHLO", b"HELO", b"MAIL", b"RCPT", b"DATA", b"QUIT", b"STAR", b"VRFY",
+	b"EXPN",
+];
+
+#[cfg(feature = "pop3")]
+/// POP3 status lines and client commands.
+const POP3: &[Signature] = tcp_text![
+	b"+OK ", b"-ERR", b"USER", b"PASS", b"STAT", b"LIST", b"RETR", b"QUIT", b"CAPA",
+];
+
+#[cfg(feature = "imap")]
+/// IMAP untagged greeting; tagged commands have no fixed prefix, so the port is added.
+const IMAP: &[Signature] = &[
+	Signature::Payload(
+		Layer::Tcp,
+		&[Check {
+			offset: 0,
+			len: 2,
+			mask: 0xFFFF,
+			value: 0x2A20,
+		}],
+	),
+	Signature::Port(Layer::Tcp, 143),
+];
+
+#[cfg(feature = "ftp")]
+/// FTP greeting and client commands.
+const FTP: &[Signature] = tcp_text![
+	b"220 ", b"220-", b"USER", b"PASS", b"AUTH", b"SYST", b"FEAT", b"QUIT", b"PASV", b"EPSV",
+	b"TYPE", b"PWD ", b"PWD\r", b"PWD\n",
+];
+
+#[cfg(feature = "smb")]
+/// SMB1/SMB2 magic, bare or behind a NetBIOS session header.
+const SMB: &[Signature] = &[
+	Signature::Payload(Layer::Tcp, &[word(0, 0xFF53_4D42)]),
+	Signature::Payload(Layer::Tcp, &[word(0, 0xFE53_4D42)]),
+	Signature::Payload(Layer::Tcp, &[word(4, 0xFF53_4D42)]),
+	Signature::Payload(Layer::Tcp, &[word(4, 0xFE53_4D42)]),
+];
+
+#[cfg(feature = "stun")]
+/// STUN magic cookie.
+const STUN: &[Signature] = &[Signature::Payload(Layer::Udp, &[word(4, 0x2112_A442)])];
+
+#[cfg(feature = "sip")]
+/// SIP status line and request methods.
+const SIP: &[Signature] = any_text![
+	b"SIP/", b"INVI", b"INFO", b"ACK ", b"BYE ", b"CANC", b"OPTI", b"REGI", b"REFE", b"PRAC",
+	b"PUBL", b"UPDA", b"SUBS", b"NOTI", b"MESS",
+];
+
+#[cfg(feature = "rtsp")]
+/// RTSP status line and request methods.
+const RTSP: &[Signature] = any_text![
+	b"RTSP", b"OPTI", b"DESC", b"SETU", b"SET_", b"PLAY", b"PAUS", b"TEAR", b"GET_", b"REDI",
+	b"RECO", b"ANNO",
+];
+
+#[cfg(feature = "dhcp")]
+/// DHCP server and client ports.
+const DHCP: &[Signature] = &[
+	Signature::Port(Layer::Udp, 67),
+	Signature::Port(Layer::Udp, 68),
+];
+
+#[cfg(feature = "ntp")]
+/// NTP port.
+const NTP: &[Signature] = &[Signature::Port(Layer::Udp, 123)];
+
+#[cfg(feature = "grpc")]
+/// gRPC opens with the HTTP/2 client preface.
+const GRPC: &[Signature] = tcp_text![b"PRI "];
+
+#[cfg(feature = "nats")]
+/// NATS server banner and client commands.
+const NATS: &[Signature] = tcp_text![b"INFO", b"CONN", b"PING", b"SUB "];
+
+#[cfg(feature = "git")]
+/// Git pkt-line service requests and smart-HTTP advertisements.
+const GIT: &[Signature] = &[
+	Signature::Payload(Layer::Tcp, &[word(4, u32::from_be_bytes(*b"git-"))]),
+	Signature::Payload(Layer::Tcp, &[word(4, u32::from_be_bytes(*b"# se"))]),
+];
+
+#[cfg(feature = "ipp")]
+/// IPP requests are HTTP `POST`s.
+const IPP: &[Signature] = tcp_text![b"POST"];
+
+#[cfg(feature = "jetdirect")]
+/// PJL exit sequence or a bare PJL command.
+const JETDIRECT: &[Signature] = tcp_text![b"\x1b%-1", b"@PJL"];
+
+#[cfg(feature = "opcua")]
+/// OPC UA message types with their chunk markers.
+const OPCUA: &[Signature] = tcp_text![b"HELF", b"ACKF", b"OPNF", b"MSGF", b"MSGC", b"MSGA"];
+
+#[cfg(feature = "openvpn")]
+/// OpenVPN client hard reset, bare over UDP and length-prefixed over TCP.
+const OPENVPN: &[Signature] = &[
+	Signature::Payload(Layer::Udp, &[byte(0, 0xFF, 0x08)]),
+	Signature::Payload(Layer::Udp, &[byte(0, 0xFF, 0x38)]),
+	Signature::Payload(Layer::Udp, &[byte(0, 0xFF, 0x50)]),
+	Signature::Payload(Layer::Tcp, &[byte(2, 0xFF, 0x08)]),
+	Signature::Payload(Layer::Tcp, &[byte(2, 0xFF, 0x38)]),
+	Signature::Payload(Layer::Tcp, &[byte(2, 0xFF, 0x50)]),
+];
+
+#[cfg(feature = "bittorrent")]
+/// BitTorrent handshake: length 19 and the protocol string.
+const BITTORRENT: &[Signature] = tcp_text![b"\x13Bit"];
+
+#[cfg(feature = "dht")]
+/// Bencoded dictionary opening with a byte-string key.
+const DHT: &[Signature] = &[Signature::Payload(
+	Layer::Udp,
+	&[byte(0, 0xFF, b'd'), byte(1, 0xF0, 0x30)],
+)];
+
+#[cfg(feature = "mdns")]
+/// mDNS port.
+const MDNS: &[Signature] = &[Signature::Port(Layer::Udp, 5353)];
+
+#[cfg(feature = "netbios")]
+/// NetBIOS name service port and session requests.
+const NETBIOS: &[Signature] = &[
+	Signature::Port(Layer::Udp, 137),
+	Signature::Payload(Layer::Tcp, &[byte(0, 0xFF, 0x81), byte(4, 0xFF, 0x20)]),
+];
+
+#[cfg(feature = "turn")]
+/// TURN methods share the STUN magic cookie; ChannelData uses channels 0x4000-0x7FFF.
+const TURN: &[Signature] = &[
+	Signature::Payload(Layer::Udp, &[word(4, 0x2112_A442)]),
+	Signature::Payload(Layer::Udp, &[byte(0, 0xC0, 0x40)]),
+];
+
+#[cfg(feature = "tds")]
+/// TDS PRELOGIN or LOGIN7 packet with end-of-message status.
+const TDS: &[Signature] = &[
+	Signature::Payload(Layer::Tcp, &[byte(0, 0xFF, 0x12), byte(1, 0xFF, 0x01)]),
+	Signature::Payload(Layer::Tcp, &[byte(0, 0xFF, 0x10), byte(1, 0xFF, 0x01)]),
+];
+
+#[cfg(feature = "riak")]
+/// Riak PB frame: a length below 64 MiB.
+const RIAK: &[Signature] = &[Signature::Payload(Layer::Tcp, &[byte(0, 0xFC, 0x00)])];
+
+#[cfg(feature = "mysqlx")]
+/// X Protocol frame: a little-endian length below 64 MiB.
+const MYSQLX: &[Signature] = &[Signature::Payload(Layer::Tcp, &[byte(3, 0xF8, 0x00)])];
+
+#[cfg(feature = "minecraft")]
+/// Minecraft handshake; the VarInt length leaves no fixed offset, so match the default port.
+const MINECRAFT: &[Signature] = &[Signature::Port(Layer::Tcp, 25565)];
+
+#[cfg(feature = "source_query")]
+/// Source query single-packet (-1) or split (-2) header.
+const SOURCE_QUERY: &[Signature] = &[Signature::Payload(
+	Layer::Udp,
+	&[
+		byte(0, 0xFE, 0xFE),
+		byte(1, 0xFF, 0xFF),
+		byte(2, 0xFF, 0xFF),
+		byte(3, 0xFF, 0xFF),
+	],
+)];
+
+#[cfg(feature = "graphite")]
+/// Graphite plaintext lines start with a free-form metric name, so match the default port.
+const GRAPHITE: &[Signature] = &[
+	Signature::Port(Layer::Tcp, 2003),
+	Signature::Port(Layer::Udp, 2003),
+];
+
+#[cfg(feature = "influx")]
+/// Line protocol datagrams start with a free-form measurement name, so match the default UDP port.
+const INFLUX: &[Signature] = &[Signature::Port(Layer::Udp, 8089)];
+
+#[cfg(feature = "thrift")]
+/// Thrift has no fixed offset across framed and unframed messages, so match the common port.
+const THRIFT: &[Signature] = &[Signature::Port(Layer::Tcp, 9090)];
+
+#[cfg(feature = "ajp")]
+/// AJP packet magic in either direction.
+const AJP: &[Signature] = &[
+	Signature::Payload(Layer::Tcp, &[byte(0, 0xFF, 0x12), byte(1, 0xFF, 0x34)]),
+	Signature::Payload(Layer::Tcp, &[byte(0, 0xFF, b'A'), byte(1, 0xFF, b'B')]),
+];
+
+#[cfg(feature = "nvme_tcp")]
+/// NVMe/TCP ICReq common header with a 128-byte PDU length.
+const NVME_TCP: &[Signature] = &[Signature::Payload(
+	Layer::Tcp,
+	&[word(0, 0x0000_8000), word(4, 0x8000_0000)],
+)];
+
+#[cfg(feature = "dicom")]
+/// DICOM A-ASSOCIATE-RQ with protocol version 1.
+const DICOM: &[Signature] = &[Signature::Payload(
+	Layer::Tcp,
+	&[
+		byte(0, 0xFF, 0x01),
+		byte(1, 0xFF, 0x00),
+		byte(6, 0xFF, 0x00),
+		byte(7, 0xFF, 0x01),
+	],
+)];
+
+#[cfg(feature = "dnscrypt")]
+/// DNSCrypt shares the DNS port for certificates and uses random client magic, so match the default ports.
+const DNSCRYPT: &[Signature] = &[
+	Signature::Port(Layer::Udp, 443),
+	Signature::Port(Layer::Udp, 53),
+];
+
+#[cfg(feature = "vxlan")]
+/// VXLAN flags byte with only the I bit set and zero reserved bytes.
+const VXLAN: &[Signature] = &[Signature::Payload(
+	Layer::Udp,
+	&[
+		byte(0, 0xFF, 0x08),
+		byte(1, 0xFF, 0x00),
+		byte(2, 0xFF, 0x00),
+		byte(3, 0xFF, 0x00),
+		byte(7, 0xFF, 0x00),
+	],
+)];
+
+#[cfg(feature = "geneve")]
+/// GENEVE version 0 with an Ethernet, IPv4, or IPv6 protocol type.
+const GENEVE: &[Signature] = &[
+	Signature::Payload(
+		Layer::Udp,
+		&[
+			byte(0, 0xC0, 0x00),
+			byte(2, 0xFF, 0x65),
+			byte(3, 0xFF, 0x58),
+		],
+	),
+	Signature::Payload(
+		Layer::Udp,
+		&[
+			byte(0, 0xC0, 0x00),
+			byte(2, 0xFF, 0x08),
+			byte(3, 0xFF, 0x00),
+		],
+	),
+	Signature::Payload(
+		Layer::Udp,
+		&[
+			byte(0, 0xC0, 0x00),
+			byte(2, 0xFF, 0x86),
+			byte(3, 0xFF, 0xDD),
+		],
+	),
+];
+
+#[cfg(feature = "devp2p")]
+/// devp2p listens on one port for RLPx and discovery, and RLPx is encrypted, so match the default port.
+const DEVP2P: &[Signature] = &[
+	Signature::Port(Layer::Tcp, 30303),
+	Signature::Port(Layer::Udp, 30303),
+];
+
+#[cfg(feature = "jsonrpc")]
+/// JSON-RPC message (`{`, `[`) or LSP `Content-Length` header.
+const JSONRPC: &[Signature] = &[
+	Signature::Payload(Layer::Tcp, &[byte(0, 0xFF, b'{')]),
+	Signature::Payload(Layer::Tcp, &[byte(0, 0xFF, b'[')]),
+	Signature::Payload(Layer::Tcp, &[byte(0, 0xDF, b'C')]),
+];
+
+#[cfg(feature = "msgpack_rpc")]
+/// MessagePack-RPC request (`[0, ...]`) or notification (`[2, ...]`) envelope.
+const MSGPACK_RPC: &[Signature] = &[
+	Signature::Payload(Layer::Tcp, &[byte(0, 0xFF, 0x94), byte(1, 0xFF, 0x00)]),
+	Signature::Payload(Layer::Tcp, &[byte(0, 0xFF, 0x93), byte(1, 0xFF, 0x02)]),
+];
+
+#[cfg(feature = "lpd")]
+/// LPD daemon commands have no fixed bytes beyond the command code, so match the default port.
+const LPD: &[Signature] = &[Signature::Port(Layer::Tcp, 515)];
+
+#[cfg(feature = "http3")]
+/// QUIC version 1 and 2 Initial packets.
+const HTTP3: &[Signature] = &[
+	Signature::Payload(Layer::Udp, &[byte(0, 0xF0, 0xC0), word(1, 0x0000_0001)]),
+	Signature::Payload(Layer::Udp, &[byte(0, 0xF0, 0xD0), word(1, 0x6b33_43cf)]),
+];
+
+#[cfg(feature = "gquic")]
+/// gQUIC public header with the version flag, or a long header, followed by a `Q0` version tag.
+const GQUIC: &[Signature] = &[
+	Signature::Payload(
+		Layer::Udp,
+		&[
+			byte(0, 0x8B, 0x01),
+			byte(1, 0xFF, b'Q'),
+			byte(2, 0xFF, b'0'),
+		],
+	),
+	Signature::Payload(
+		Layer::Udp,
+		&[
+			byte(0, 0x8B, 0x09),
+			byte(9, 0xFF, b'Q'),
+			byte(10, 0xFF, b'0'),
+		],
+	),
+	Signature::Payload(
+		Layer::Udp,
+		&[
+			byte(0, 0xC0, 0xC0),
+			byte(1, 0xFF, b'Q'),
+			byte(2, 0xFF, b'0'),
+		],
+	),
+];
+
+#[cfg(feature = "spdy")]
+/// SPDY/2 or SPDY/3 `SYN_STREAM` or `SETTINGS` control frame.
+const SPDY: &[Signature] = &[
+	Signature::Payload(
+		Layer::Tcp,
+		&[
+			byte(0, 0xFF, 0x80),
+			byte(1, 0xFE, 0x02),
+			byte(2, 0xFF, 0x00),
+			byte(3, 0xFF, 0x01),
+		],
+	),
+	Signature::Payload(
+		Layer::Tcp,
+		&[
+			byte(0, 0xFF, 0x80),
+			byte(1, 0xFE, 0x02),
+			byte(2, 0xFF, 0x00),
+			byte(3, 0xFF, 0x04),
+		],
+	),
+];
+
+#[cfg(feature = "raknet")]
+/// RakNet open connection request followed by the offline-message magic.
+const RAKNET: &[Signature] = &[
+	Signature::Payload(
+		Layer::Udp,
+		&[
+			byte(0, 0xFF, 0x05),
+			word(1, 0x00FF_FF00),
+			word(5, 0xFEFE_FEFE),
+			word(9, 0xFDFD_FDFD),
+			word(13, 0x1234_5678),
+		],
+	),
+	Signature::Payload(
+		Layer::Udp,
+		&[
+			byte(0, 0xFF, 0x01),
+			word(9, 0x00FF_FF00),
+			word(13, 0xFEFE_FEFE),
+			word(17, 0xFDFD_FDFD),
+			word(21, 0x1234_5678),
+		],
+	),
+];
+
+#[cfg(feature = "mms")]
+/// TPKT and COTP data TPDU carrying an ISO session CONNECT.
+const MMS: &[Signature] = &[Signature::Payload(
+	Layer::Tcp,
+	&[
+		byte(0, 0xFF, 0x03),
+		byte(1, 0xFF, 0x00),
+		byte(4, 0xFF, 0x02),
+		byte(5, 0xFF, 0xF0),
+		byte(7, 0xFF, 0x0D),
+	],
+)];
+
+#[cfg(feature = "lorawan")]
+/// Semtech forwarder `PUSH_DATA` (version 1 or 2) followed by a JSON object;
+/// `PULL_DATA` has no body to match, so also match the default port.
+const LORAWAN: &[Signature] = &[
+	Signature::Payload(
+		Layer::Udp,
+		&[
+			byte(0, 0xFF, 0x01),
+			byte(3, 0xFF, 0x00),
+			byte(12, 0xFF, b'{'),
+		],
+	),
+	Signature::Payload(
+		Layer::Udp,
+		&[
+			byte(0, 0xFF, 0x02),
+			byte(3, 0xFF, 0x00),
+			byte(12, 0xFF, b'{'),
+		],
+	),
+	Signature::Port(Layer::Udp, 1700),
+];
+
+#[cfg(feature = "websocket")]
+/// WebSocket opening `GET` request, or a masked text or binary frame from a client.
+const WEBSOCKET: &[Signature] = &[
+	Signature::Payload(Layer::Tcp, &[text(*b"GET ")]),
+	Signature::Payload(Layer::Tcp, &[byte(0, 0xBF, 0x81), byte(1, 0x80, 0x80)]),
+	Signature::Payload(Layer::Tcp, &[byte(0, 0xBF, 0x82), byte(1, 0x80, 0x80)]),
+];
+
+#[cfg(feature = "telnet")]
+/// Telnet `IAC` followed by `WILL`, `WONT`, `DO` or `DONT`.
+const TELNET: &[Signature] = &[
+	Signature::Payload(Layer::Tcp, &[byte(0, 0xFF, 0xFF), byte(1, 0xFC, 0xFC)]),
+	Signature::Payload(Layer::Tcp, &[byte(0, 0xFF, 0xFF), byte(1, 0xFF, 0xFB)]),
+];
+
+#[cfg(feature = "rdp")]
+/// TPKT carrying an X.224 Connection Request with a zero destination reference.
+const RDP: &[Signature] = &[Signature::Payload(
+	Layer::Tcp,
+	&[
+		byte(0, 0xFF, 0x03),
+		byte(1, 0xFF, 0x00),
+		byte(5, 0xFF, 0xE0),
+		byte(6, 0xFF, 0x00),
+		byte(7, 0xFF, 0x00),
+	],
+)];
+
+#[cfg(feature = "ldap")]
+/// BER SEQUENCE with a one-byte length, a one-byte message ID and a `BindRequest`.
+const LDAP: &[Signature] = &[
+	Signature::Payload(
+		Layer::Tcp,
+		&[
+			byte(0, 0xFF, 0x30),
+			byte(2, 0xFF, 0x02),
+			byte(3, 0xFF, 0x01),
+			byte(5, 0xFF, 0x60),
+		],
+	),
+	Signature::Port(Layer::Tcp, 389),
+];
+
+#[cfg(feature = "kerberos")]
+/// Kerberos `AS-REQ` or `TGS-REQ`; the BER lengths leave no fixed offset for `pvno`, so match the KDC port.
+const KERBEROS: &[Signature] = &[
+	Signature::Port(Layer::Tcp, 88),
+	Signature::Port(Layer::Udp, 88),
+];
+
+#[cfg(feature = "irc")]
+/// IRC registration commands or a pre-registration notice; numeric replies follow a variable server prefix, so also match the default port.
+const IRC: &[Signature] = &[
+	Signature::Payload(Layer::Tcp, &[text(*b"NICK")]),
+	Signature::Payload(Layer::Tcp, &[text(*b"CAP ")]),
+	Signature::Payload(Layer::Tcp, &[text(*b"NOTI")]),
+	Signature::Port(Layer::Tcp, 6667),
+];
+
+#[cfg(feature = "xmpp")]
+/// XMPP stream header, with or without an XML declaration.
+const XMPP: &[Signature] = tcp_text!(b"<?xm", b"<str");
+
+#[cfg(feature = "amqp")]
+/// AMQP protocol header.
+const AMQP: &[Signature] = tcp_text!(b"AMQP");
+
+#[cfg(feature = "memcached")]
+/// Memcached ASCII commands.
+const MEMCACHED: &[Signature] = tcp_text!(b"get ", b"gets", b"set ", b"stat", b"vers", b"touc");
+
+#[cfg(feature = "socks4")]
+/// SOCKS4 `CONNECT` or `BIND` request.
+const SOCKS4: &[Signature] = &[
+	Signature::Payload(Layer::Tcp, &[byte(0, 0xFF, 0x04), byte(1, 0xFF, 0x01)]),
+	Signature::Payload(Layer::Tcp, &[byte(0, 0xFF, 0x04), byte(1, 0xFF, 0x02)]),
+];
+
+#[cfg(feature = "proxyproto")]
+/// PROXY protocol version 1 or version 2 header.
+const PROXYPROTO: &[Signature] = tcp_text!(b"PROX", b"\r\n\r\n");
+
+#[cfg(feature = "rtp")]
+/// RTP version 2 header.
+const RTP: &[Signature] = &[Signature::Payload(Layer::Udp, &[byte(0, 0xC0, 0x80)])];
+
+#[cfg(feature = "rtcp")]
+/// RTCP version 2 packet of type SR, RR, SDES, BYE or APP.
+const RTCP: &[Signature] = &[Signature::Payload(
+	Layer::Udp,
+	&[byte(0, 0xE0, 0x80), byte(1, 0xF8, 0xC8)],
+)];
+
+#[cfg(feature = "rtmp")]
+/// RTMP or RTMPE handshake version byte.
+const RTMP: &[Signature] = &[
+	Signature::Payload(Layer::Tcp, &[byte(0, 0xFF, 0x03)]),
+	Signature::Payload(Layer::Tcp, &[byte(0, 0xFF, 0x06)]),
+];
+
+#[cfg(feature = "s7")]
+/// TPKT carrying a COTP Connection Request or Data TPDU; port 102 is shared with MMS.
+const S7: &[Signature] = &[
+	Signature::Payload(
+		Layer::Tcp,
+		&[
+			byte(0, 0xFF, 0x03),
+			byte(1, 0xFF, 0x00),
+			byte(5, 0xFF, 0xE0),
+		],
+	),
+	Signature::Payload(
+		Layer::Tcp,
+		&[
+			byte(0, 0xFF, 0x03),
+			byte(1, 0xFF, 0x00),
+			byte(5, 0xFF, 0xF0),
+			byte(7, 0xFF, 0x32),
+		],
+	),
+];
+
+#[cfg(feature = "clickhouse")]
+/// ClickHouse native Hello: packet ID 0 and a short client name.
+const CLICKHOUSE: &[Signature] = &[Signature::Payload(
+	Layer::Tcp,
+	&[byte(0, 0xFF, 0x00), byte(1, 0x80, 0x00)],
+)];
+
+#[cfg(feature = "tarantool")]
+/// Tarantool server greeting.
+const TARANTOOL: &[Signature] = tcp_text!(b"Tara");
+
+#[cfg(feature = "svn")]
+/// svnserve greeting and client reply S-expressions.
+const SVN: &[Signature] = tcp_text!(b"( su", b"( 2 ");
+
+#[cfg(feature = "sunrpc")]
+/// ONC RPC call with RPC version 2, bare over UDP and behind the record mark over TCP.
+const SUNRPC: &[Signature] = &[
+	Signature::Payload(Layer::Udp, &[word(4, 0), word(8, 2)]),
+	Signature::Payload(Layer::Tcp, &[byte(0, 0x80, 0x80), word(8, 0), word(12, 2)]),
+];
+
+#[cfg(feature = "zookeeper")]
+/// ZooKeeper `ConnectRequest` (length 44 or 45, protocol version 0) and common four-letter words.
+const ZOOKEEPER: &[Signature] = tcp_text!(
+	b"ruok", b"stat", b"srvr", b"mntr";
+	Signature::Payload(Layer::Tcp, &[word(0, 44), word(4, 0)]),
+	Signature::Payload(Layer::Tcp, &[word(0, 45), word(4, 0)]),
+);
+
+#[cfg(feature = "mumble")]
+/// Mumble control channel is TLS on the wire and its UDP ping has no distinctive bytes, so match the default port.
+const MUMBLE: &[Signature] = &[
+	Signature::Port(Layer::Tcp, 64738),
+	Signature::Port(Layer::Udp, 64738),
+];
+
+#[cfg(feature = "ident")]
+/// Ident lines start with a decimal port number, so match the auth port.
+const IDENT: &[Signature] = &[Signature::Port(Layer::Tcp, 113)];
+
+#[cfg(feature = "scgi")]
+/// SCGI netstring length of two to five digits, then `:C` of `CONTENT_LENGTH`.
+const SCGI: &[Signature] = &[
+	Signature::Payload(
+		Layer::Tcp,
+		&[
+			byte(0, 0xF0, 0x30),
+			byte(2, 0xFF, b':'),
+			byte(3, 0xFF, b'C'),
+		],
+	),
+	Signature::Payload(
+		Layer::Tcp,
+		&[
+			byte(0, 0xF0, 0x30),
+			byte(3, 0xFF, b':'),
+			byte(4, 0xFF, b'C'),
+		],
+	),
+	Signature::Payload(
+		Layer::Tcp,
+		&[
+			byte(0, 0xF0, 0x30),
+			byte(4, 0xFF, b':'),
+			byte(5, 0xFF, b'C'),
+		],
+	),
+	Signature::Payload(
+		Layer::Tcp,
+		&[
+			byte(0, 0xF0, 0x30),
+			byte(5, 0xFF, b':'),
+			byte(6, 0xFF, b'C'),
+		],
+	),
+];
+
+#[cfg(feature = "icap")]
+/// ICAP request methods and status line.
+const ICAP: &[Signature] = tcp_text!(b"REQM", b"RESP", b"OPTI", b"ICAP");
+
+#[cfg(feature = "relp")]
+/// RELP session opening: transaction 1 carrying the `open` offer or its response.
+const RELP: &[Signature] = tcp_text!(b"1 op", b"1 rs");
+
+#[cfg(feature = "smpp")]
+/// SMPP bind requests: command ID 1, 2 or 9 with a zero status.
+const SMPP: &[Signature] = &[
+	Signature::Payload(Layer::Tcp, &[word(4, 1), word(8, 0)]),
+	Signature::Payload(Layer::Tcp, &[word(4, 2), word(8, 0)]),
+	Signature::Payload(Layer::Tcp, &[word(4, 9), word(8, 0)]),
+];
+
+#[cfg(feature = "h225")]
+/// H.225.0 SETUP: TPKT header, Q.931 discriminator, two-octet call reference and the SETUP message type.
+const H225: &[Signature] = &[Signature::Payload(
+	Layer::Tcp,
+	&[
+		byte(0, 0xFF, 0x03),
+		byte(1, 0xFF, 0x00),
+		byte(4, 0xFF, 0x08),
+		byte(5, 0xFF, 0x02),
+		byte(8, 0xFF, 0x05),
+	],
+)];
+
+/// Signatures used to prefilter `protocol`.
+pub(crate) fn signatures(protocol: Protocol) -> &'static [Signature] {
+	match protocol {
+		#[cfg(feature = "http")]
+		Protocol::Http => HTTP,
+		#[cfg(feature = "tls")]
+		Protocol::Tls => TLS,
+		#[cfg(feature = "ssh")]
+		Protocol::Ssh => SSH,
+		#[cfg(feature = "dns")]
+		Protocol::Dns => DNS,
+		#[cfg(feature = "quic")]
+		Protocol::Quic => QUIC,
+		#[cfg(feature = "mysql")]
+		Protocol::Mysql => MYSQL,
+		#[cfg(feature = "postgres")]
+		Protocol::Postgres => POSTGRES,
+		#[cfg(feature = "redis")]
+		Protocol::Redis => REDIS,
+		#[cfg(feature = "mqtt")]
+		Protocol::Mqtt => MQTT,
+		#[cfg(feature = "smtp")]
+		Protocol::Smtp => SMTP,
+		#[cfg(feature = "pop3")]
+		Protocol::Pop3 => POP3,
+		#[cfg(feature = "imap")]
+		Protocol::Imap => IMAP,
+		#[cfg(feature = "ftp")]
+		Protocol::Ftp => FTP,
+		#[cfg(feature = "smb")]
+		Protocol::Smb => SMB,
+		#[cfg(feature = "stun")]
+		Protocol::Stun => STUN,
+		#[cfg(feature = "sip")]
+		Protocol::Sip => SIP,
+		#[cfg(feature = "rtsp")]
+		Protocol::Rtsp => RTSP,
+		#[cfg(feature = "dhcp")]
+		Protocol::Dhcp => DHCP,
+		#[cfg(feature = "ntp")]
+		Protocol::Ntp => NTP,
+		#[cfg(feature = "grpc")]
+		Protocol::Grpc => GRPC,
+		#[cfg(feature = "nats")]
+		Protocol::Nats => NATS,
+		#[cfg(feature = "git")]
+		Protocol::Git => GIT,
+		#[cfg(feature = "ipp")]
+		Protocol::Ipp => IPP,
+		#[cfg(feature = "jetdirect")]
+		Protocol::JetDirect => JETDIRECT,
+		#[cfg(feature = "opcua")]
+		Protocol::OpcUa => OPCUA,
+		#[cfg(feature = "openvpn")]
+		Protocol::OpenVpn => OPENVPN,
+		#[cfg(feature = "bittorrent")]
+		Protocol::BitTorrent => BITTORRENT,
+		#[cfg(feature = "dht")]
+		Protocol::Dht => DHT,
+		#[cfg(feature = "mdns")]
+		Protocol::Mdns => MDNS,
+		#[cfg(feature = "netbios")]
+		Protocol::NetBios => NETBIOS,
+		#[cfg(feature = "turn")]
+		Protocol::Turn => TURN,
+		#[cfg(feature = "tds")]
+		Protocol::Tds => TDS,
+		#[cfg(feature = "riak")]
+		Protocol::Riak => RIAK,
+		#[cfg(feature = "mysqlx")]
+		Protocol::Mysqlx => MYSQLX,
+		#[cfg(feature = "minecraft")]
+		Protocol::Minecraft => MINECRAFT,
+		#[cfg(feature = "source_query")]
+		Protocol::SourceQuery => SOURCE_QUERY,
+		#[cfg(feature = "graphite")]
+		Protocol::Graphite => GRAPHITE,
+		#[cfg(feature = "influx")]
+		Protocol::Influx => INFLUX,
+		#[cfg(feature = "thrift")]
+		Protocol::Thrift => THRIFT,
+		#[cfg(feature = "ajp")]
+		Protocol::Ajp => AJP,
+		#[cfg(feature = "nvme_tcp")]
+		Protocol::NvmeTcp => NVME_TCP,
+		#[cfg(feature = "dicom")]
+		Protocol::Dicom => DICOM,
+		#[cfg(feature = "dnscrypt")]
+		Protocol::DnsCrypt => DNSCRYPT,
+		#[cfg(feature = "vxlan")]
+		Protocol::Vxlan => VXLAN,
+		#[cfg(feature = "geneve")]
+		Protocol::Geneve => GENEVE,
+		#[cfg(feature = "devp2p")]
+		Protocol::Devp2p => DEVP2P,
+		#[cfg(feature = "jsonrpc")]
+		Protocol::JsonRpc => JSONRPC,
+		#[cfg(feature = "msgpack_rpc")]
+		Protocol::MsgpackRpc => MSGPACK_RPC,
+		#[cfg(feature = "lpd")]
+		Protocol::Lpd => LPD,
+		#[cfg(feature = "http3")]
+		Protocol::Http3 => HTTP3,
+		#[cfg(feature = "gquic")]
+		Protocol::Gquic => GQUIC,
+		#[cfg(feature = "spdy")]
+		Protocol::Spdy => SPDY,
+		#[cfg(feature = "raknet")]
+		Protocol::RakNet => RAKNET,
+		#[cfg(feature = "mms")]
+		Protocol::Mms => MMS,
+		#[cfg(feature = "lorawan")]
+		Protocol::LoRaWan => LORAWAN,
+		#[cfg(feature = "websocket")]
+		Protocol::WebSocket => WEBSOCKET,
+		#[cfg(feature = "telnet")]
+		Protocol::Telnet => TELNET,
+		#[cfg(feature = "rdp")]
+		Protocol::Rdp => RDP,
+		#[cfg(feature = "ldap")]
+		Protocol::Ldap => LDAP,
+		#[cfg(feature = "kerberos")]
+		Protocol::Kerberos => KERBEROS,
+		#[cfg(feature = "irc")]
+		Protocol::Irc => IRC,
+		#[cfg(feature = "xmpp")]
+		Protocol::Xmpp => XMPP,
+		#[cfg(feature = "amqp")]
+		Protocol::Amqp => AMQP,
+		#[cfg(feature = "memcached")]
+		Protocol::Memcached => MEMCACHED,
+		#[cfg(feature = "socks4")]
+		Protocol::Socks4 => SOCKS4,
+		#[cfg(feature = "proxyproto")]
+		Protocol::ProxyProtocol => PROXYPROTO,
+		#[cfg(feature = "rtp")]
+		Protocol::Rtp => RTP,
+		#[cfg(feature = "rtcp")]
+		Protocol::Rtcp => RTCP,
+		#[cfg(feature = "rtmp")]
+		Protocol::Rtmp => RTMP,
+		#[cfg(feature = "s7")]
+		Protocol::S7 => S7,
+		#[cfg(feature = "clickhouse")]
+		Protocol::ClickHouse => CLICKHOUSE,
+		#[cfg(feature = "tarantool")]
+		Protocol::Tarantool => TARANTOOL,
+		#[cfg(feature = "svn")]
+		Protocol::Svn => SVN,
+		#[cfg(feature = "sunrpc")]
+		Protocol::SunRpc => SUNRPC,
+		#[cfg(feature = "zookeeper")]
+		Protocol::Zookeeper => ZOOKEEPER,
+		#[cfg(feature = "mumble")]
+		Protocol::Mumble => MUMBLE,
+		#[cfg(feature = "ident")]
+		Protocol::Ident => IDENT,
+		#[cfg(feature = "scgi")]
+		Protocol::Scgi => SCGI,
+		#[cfg(feature = "icap")]
+		Protocol::Icap => ICAP,
+		#[cfg(feature = "relp")]
+		Protocol::Relp => RELP,
+		#[cfg(feature = "smpp")]
+		Protocol::Smpp => SMPP,
+		#[cfg(feature = "h225")]
+		Protocol::H225 => H225,
+	}
+}
+
+/// Yields the signatures of every protocol enabled in `detector`.
+pub(crate) fn enabled<T>(
+	detector: &ProtocolDetector<T>,
+) -> impl Iterator<Item = (Protocol, &'static Signature)> + '_ {
+	Protocol::ALL
+		.iter()
+		.filter(|protocol| detector.enabled.contains(**protocol))
+		.flat_map(|&protocol| signatures(protocol).iter().map(move |sig| (protocol, sig)))
+}
@@ -0,0 +1,235 @@
+/* src/tower.rs */
+
+use crate::{
+	Protocol, ProtocolDetector, Unknown,
+	net::{Sniffed, sniff},
+};
+use std::{
+	future::Future,
+	pin::Pin,
+	sync::Arc,
+	task::{Context, Poll},
+};
+use tokio::io::AsyncRead;
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// Boxed error type used by the sniffing services.
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Boxed future returned by the sniffing services.
+pub type BoxFuture<T> = Pin<Box<dyn Future<Output = Result<T, BoxError>> + Send>>;
+
+/// Layer that classifies each accepted connection before passing it on.
+///
+/// The wrapped service receives a [`Sniffed`] connection which carries the
+/// detected protocol and replays the sniffed bytes on read.
+#[derive(Debug, Clone)]
+pub struct SniffLayer<T = Unknown> {
+	/// Shared detector used for every connection.
+	detector: Arc<ProtocolDetector<T>>,
+}
+
+impl<T> SniffLayer<T> {
+	/// Creates a layer from a detector.
+	#[must_use]
+	pub fn new(detector: ProtocolDetector<T>) -> Self {
+		Self {
+			detector: Arc::new(detector),
+		}
+	}
+}
+
+impl<S, T> Layer<S> for SniffLayer<T> {
+	type Service = SniffService<S, T>;
+
+	fn layer(&self, inner: S) -> Self::Service {
+		SniffService {
+			inner,
+			detector: Arc::clone(&self.detector),
+		}
+	}
+}
+
+/// Service produced by [`SniffLayer`].
+#[derive(Debug, Clone)]
+pub struct SniffService<S, T = Unknown> {
+	/// Service receiving the sniffed connection.
+	inner: S,
+	/// Shared detector used for every connection.
+	detector: Arc<ProtocolDetector<T>>,
+}
+
+impl<S, T, IO> Service<IO> for SniffService<S, T>
+where
+	S: Service<Sniffed<IO>> + Clone + Send + 'static,
+	S::Future: Send,
+	S::Error: Into<BoxError>,
+	T: Send + Sync + 'static,
+	IO: AsyncRead + Unpin + Send + 'static,
+{
+	type Response = S::Response;
+	type Error = BoxError;
+	type Future = BoxFuture<S::Response>;
+
+	fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+		self.inner.poll_ready(cx).map_err(Into::into)
+	}
+
+	fn call(&mut self, io: IO) -> Self::Future {
+		// Take the instance that was driven to readiness and leave a fresh clone behind.
+		let clone = self.inner.clone();
+		let mut inner = std::mem::replace(&mut self.inner, clone);
+		let detector = Arc::clone(&self.detector);
+		Box::pin(async move {
+			let sniffed = sniff(&detector, io).await?;
+			inner.call(sniffed).await.map_err(Into::into)
+		})
+	}
+}
+
+/// Dispatches sniffed connections to a service chosen by protocol.
+///
+/// Connections whose protocol has no route go to the fallback service.
+#[derive(Debug, Clone)]
+pub struct SniffRouter<S> {
+	/// Per-protocol services, checked in insertion order.
+	routes: Vec<(Protocol, S)>,
+	/// Service for unrouted or unidentified connections.
+	fallback: S,
+}
+
+impl<S> SniffRouter<S> {
+	/// Creates a router that sends everything to `fallback`.
+	#[must_use]
+	pub fn new(fallback: S) -> Self {
+		Self {
+			routes: Vec::new(),
+			fallback,
+		}
+	}
+
+	/// Routes connections detected as `protocol` to `service`.
+	#[must_use]
+	pub fn route(mut self, protocol: Protocol, service: S) -> Self {
+		self.routes.push((protocol, service));
+		self
+	}
+}
+
+impl<S, IO> Service<Sniffed<IO>> for SniffRouter<S>
+where
+	S: Service<Sniffed<IO>> + Clone + Send + 'static,
+	S::Future: Send,
+	S::Error: Into<BoxError>,
+	IO: Send + 'static,
+{
+	type Response = S::Response;
+	type Error = BoxError;
+	type Future = BoxFuture<S::Response>;
+
+	fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+		// Readiness is checked on the selected route in `call`.
+		Poll::Ready(Ok(()))
+	}
+
+	fn call(&mut self, io: Sniffed<IO>) -> Self::Future {
+		let mut service = self
+			.routes
+			.iter()
+			.find(|(protocol, _)| Some(*protocol) == io.protocol())
+			.map_or_else(|| self.fallback.clone(), |(_, service)| service.clone());
+		Box::pin(async move {
+			std::future::poll_fn(|cx| service.poll_ready(cx))
+				.await
+				.map_err(Into::into)?;
+			service.call(io).await.map_err(Into::into)
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::{convert::Infallible, future::poll_fn};
+	use tokio::io::{AsyncWriteExt, DuplexStream};
+
+	/// Service that reports the protocol it was handed.
+	#[derive(Clone, Debug)]
+	struct Echo(&'static str);
+
+	impl<IO: Send + 'static> Service<Sniffed<IO>> for Echo {
+		type Response = (&'static str, Option<Protocol>);
+		type Error = Infallible;
+		type Future = std::future::Ready<Result<Self::Response, Infallible>>;
+
+		fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+			Poll::Ready(Ok(()))
+		}
+
+		fn call(&mut self, io: Sniffed<IO>) -> Self::Future {
+			std::future::ready(Ok((self.0, io.protocol())))
+		}
+	}
+
+	/// Returns a server-side stream that already holds `data`.
+	async fn connection(data: &[u8]) -> DuplexStream {
+		let (mut client, server) = tokio::io::duplex(256);
+		client.write_all(data).await.unwrap();
+		drop(client);
+		server
+	}
+
+	/// Drives a service to readiness and calls it once.
+	async fn oneshot<S, R>(mut service: S, req: R) -> Result<S::Response, S::Error>
+	where
+		S: Service<R>,
+	{
+		poll_fn(|cx| service.poll_ready(cx)).await?;
+		service.call(req).await
+	}
+
+	// ── Correct paths ──
+
+	#[tokio::test]
+	#[cfg(feature = "http")]
+	async fn layer_attaches_detected_protocol() {
+		let detector = ProtocolDetector::builder().http().build();
+		let service = SniffLayer::new(detector).layer(Echo("inner"));
+		let io = connection(b"GET / HTTP/1.1\r\n").await;
+		let (name, protocol) = oneshot(service, io).await.unwrap();
+		assert_eq!(name, "inner");
+		assert_eq!(protocol, Some(Protocol::Http));
+	}
+
+	#[tokio::test]
+	#[cfg(all(feature = "http", feature = "ssh"))]
+	async fn router_dispatches_by_protocol() {
+		let detector = ProtocolDetector::builder().http().ssh().build();
+		let router = SniffRouter::new(Echo("fallback"))
+			.route(Protocol::Http, Echo("web"))
+			.route(Protocol::Ssh, Echo("shell"));
+		let service = SniffLayer::new(detector).layer(router);
+
+		let io = connection(b"SSH-2.0-OpenSSH_8.9\r\n").await;
+		let (name, _) = oneshot(service.clone(), io).await.unwrap();
+		assert_eq!(name, "shell");
+
+		let io = connection(b"GET / HTTP/1.1\r\n").await;
+		let (name, _) = oneshot(service, io).await.unwrap();
+		assert_eq!(name, "web");
+	}
+
+	// ── Error paths ──
+
+	#[tokio::test]
+	async fn unknown_traffic_goes_to_fallback() {
+		let detector = ProtocolDetector::builder().build();
+		let router = SniffRouter::new(Echo("fallback"));
+		let service = SniffLayer::new(detector).layer(router);
+		let io = connection(&[0x42; 32]).await;
+		let (name, protocol) = oneshot(service, io).await.unwrap();
+		assert_eq!(name, "fallback");
+		assert_eq!(protocol, None);
+	}
+}